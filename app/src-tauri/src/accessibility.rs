@@ -0,0 +1,30 @@
+//! Accessibility (input simulation) permission checks.
+//!
+//! On macOS, simulated key events are silently dropped (or `Enigo::new` fails with an
+//! unhelpful error) unless the app is trusted in System Settings → Privacy & Security →
+//! Accessibility. Other platforms have no equivalent gate, so they always report granted.
+
+/// Whether this process may synthesize keyboard input.
+pub fn is_accessibility_trusted() -> bool {
+    platform::is_trusted()
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXIsProcessTrusted() -> bool;
+    }
+
+    pub fn is_trusted() -> bool {
+        // SAFETY: no arguments, no preconditions; safe to call from any thread.
+        unsafe { AXIsProcessTrusted() }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    pub fn is_trusted() -> bool {
+        true
+    }
+}
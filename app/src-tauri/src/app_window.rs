@@ -0,0 +1,233 @@
+//! Best-effort lookup of another application's window bounds.
+//!
+//! Used to let the overlay follow an app's window (`overlay_follow_app`). Apps are matched
+//! case-insensitively by name: the executable file name (with or without extension) on
+//! Windows, the process name on macOS and the window class on Linux (X11 via xdotool).
+
+/// Outer bounds of a window. Physical pixels, except on macOS where System Events reports
+/// points (`logical` is set).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowBounds {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub logical: bool,
+}
+
+/// Bounds of the frontmost visible window of `app_name`, or `None` if the app isn't
+/// running, has no visible window, or the platform can't tell us.
+pub fn find_app_window(app_name: &str) -> Option<WindowBounds> {
+    let app_name = app_name.trim();
+    if app_name.is_empty() {
+        return None;
+    }
+    platform::find(app_name)
+}
+
+/// Whether a process path or name refers to `app_name`, ignoring case, directory and
+/// extension ("chrome" matches `C:\...\chrome.exe`).
+pub(crate) fn matches_app_name(candidate: &str, app_name: &str) -> bool {
+    let file = candidate.rsplit(['/', '\\']).next().unwrap_or(candidate);
+    let stem = file.rsplit_once('.').map_or(file, |(stem, _)| stem);
+    file.eq_ignore_ascii_case(app_name) || stem.eq_ignore_ascii_case(app_name)
+}
+
+/// Parse System Events' `{position, size}` output ("x, y, width, height").
+#[cfg_attr(not(any(target_os = "macos", test)), allow(dead_code))]
+fn parse_bounds_list(output: &str) -> Option<[f64; 4]> {
+    let values: Vec<f64> = output
+        .split(',')
+        .map(|part| part.trim().parse().ok())
+        .collect::<Option<_>>()?;
+    let bounds: [f64; 4] = values.try_into().ok()?;
+    (bounds[2] > 0.0 && bounds[3] > 0.0).then_some(bounds)
+}
+
+/// Parse `xdotool getwindowgeometry --shell` output (`X=`, `Y=`, `WIDTH=`, `HEIGHT=` lines).
+#[cfg_attr(not(any(target_os = "linux", test)), allow(dead_code))]
+fn parse_xdotool_geometry(output: &str) -> Option<[f64; 4]> {
+    let field = |name: &str| {
+        output.lines().find_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            (key.trim() == name).then(|| value.trim().parse::<f64>().ok())?
+        })
+    };
+    let bounds = [field("X")?, field("Y")?, field("WIDTH")?, field("HEIGHT")?];
+    (bounds[2] > 0.0 && bounds[3] > 0.0).then_some(bounds)
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{matches_app_name, WindowBounds};
+    use windows::core::BOOL;
+    use windows::Win32::Foundation::{HWND, LPARAM, RECT};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, GetWindowRect, GetWindowTextLengthW, GetWindowThreadProcessId, IsIconic,
+        IsWindowVisible,
+    };
+
+    struct Search<'a> {
+        app_name: &'a str,
+        found: Option<RECT>,
+    }
+
+    pub fn find(app_name: &str) -> Option<WindowBounds> {
+        unsafe extern "system" fn enum_proc(hwnd: HWND, lparam: LPARAM) -> BOOL {
+            // Safety: `find` passes a valid `Search` pointer via LPARAM.
+            let search = unsafe { &mut *(lparam.0 as *mut Search) };
+
+            unsafe {
+                // Skip hidden, minimized and untitled (helper/tool) windows.
+                if !IsWindowVisible(hwnd).as_bool()
+                    || IsIconic(hwnd).as_bool()
+                    || GetWindowTextLengthW(hwnd) == 0
+                {
+                    return BOOL(1);
+                }
+
+                let mut pid: u32 = 0;
+                GetWindowThreadProcessId(hwnd, Some(&mut pid));
+                let Some(path) = crate::windows_apps::query_process_path(pid) else {
+                    return BOOL(1);
+                };
+                if !matches_app_name(&path, search.app_name) {
+                    return BOOL(1);
+                }
+
+                let mut rect = RECT::default();
+                if GetWindowRect(hwnd, &mut rect).is_err()
+                    || rect.right <= rect.left
+                    || rect.bottom <= rect.top
+                {
+                    return BOOL(1);
+                }
+
+                // EnumWindows walks top-level windows in Z order, so the first match is the
+                // frontmost one.
+                search.found = Some(rect);
+                BOOL(0)
+            }
+        }
+
+        let mut search = Search {
+            app_name,
+            found: None,
+        };
+        // Stopping the enumeration early makes EnumWindows report an error; ignore it.
+        unsafe {
+            let _ = EnumWindows(Some(enum_proc), LPARAM((&mut search as *mut Search) as isize));
+        }
+
+        search.found.map(|rect| WindowBounds {
+            x: rect.left as f64,
+            y: rect.top as f64,
+            width: (rect.right - rect.left) as f64,
+            height: (rect.bottom - rect.top) as f64,
+            logical: false,
+        })
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{parse_bounds_list, WindowBounds};
+    use std::process::Command;
+
+    pub fn find(app_name: &str) -> Option<WindowBounds> {
+        let escaped = app_name.replace('\\', "\\\\").replace('"', "\\\"");
+        let script = format!(
+            "tell application \"System Events\" to get {{position, size}} of front window of (first process whose name is \"{}\")",
+            escaped
+        );
+        let output = Command::new("osascript").args(["-e", &script]).output().ok()?;
+
+        // Fails when the app isn't running or has no window.
+        if !output.status.success() {
+            return None;
+        }
+
+        let [x, y, width, height] = parse_bounds_list(&String::from_utf8_lossy(&output.stdout))?;
+        Some(WindowBounds {
+            x,
+            y,
+            width,
+            height,
+            logical: true,
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{parse_xdotool_geometry, WindowBounds};
+    use std::process::Command;
+
+    fn xdotool(args: &[&str]) -> Option<String> {
+        let output = Command::new("xdotool").args(args).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// X11 only (via xdotool); Wayland doesn't expose other clients' windows.
+    pub fn find(app_name: &str) -> Option<WindowBounds> {
+        let ids = xdotool(&["search", "--onlyvisible", "--class", app_name])?;
+        let ids: Vec<&str> = ids.split_whitespace().collect();
+
+        // Prefer the focused window when it belongs to the app.
+        let active = xdotool(&["getactivewindow"]);
+        let id = active
+            .as_deref()
+            .map(str::trim)
+            .filter(|active| ids.contains(active))
+            .or_else(|| ids.last().copied())?;
+
+        let geometry = xdotool(&["getwindowgeometry", "--shell", id])?;
+        let [x, y, width, height] = parse_xdotool_geometry(&geometry)?;
+        Some(WindowBounds {
+            x,
+            y,
+            width,
+            height,
+            logical: false,
+        })
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+mod platform {
+    use super::WindowBounds;
+
+    pub fn find(_app_name: &str) -> Option<WindowBounds> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_app_name() {
+        assert!(matches_app_name(r"C:\Program Files\Google\Chrome\chrome.exe", "chrome"));
+        assert!(matches_app_name(r"C:\Program Files\Google\Chrome\chrome.exe", "Chrome.exe"));
+        assert!(matches_app_name("/usr/bin/firefox", "Firefox"));
+        assert!(matches_app_name("Google Chrome", "google chrome"));
+        assert!(!matches_app_name(r"C:\Windows\explorer.exe", "chrome"));
+        assert!(!matches_app_name("chromedriver.exe", "chrome"));
+    }
+
+    #[test]
+    fn test_parse_window_geometry() {
+        assert_eq!(parse_bounds_list("120, 45, 1280, 800\n"), Some([120.0, 45.0, 1280.0, 800.0]));
+        assert_eq!(parse_bounds_list("-1440, 0, 800, 600"), Some([-1440.0, 0.0, 800.0, 600.0]));
+        assert_eq!(parse_bounds_list("120, 45, 0, 800"), None);
+        assert_eq!(parse_bounds_list(""), None);
+
+        let shell = "WINDOW=62914563\nX=100\nY=52\nWIDTH=1600\nHEIGHT=900\nSCREEN=0\n";
+        assert_eq!(parse_xdotool_geometry(shell), Some([100.0, 52.0, 1600.0, 900.0]));
+        assert_eq!(parse_xdotool_geometry("X=100\nY=52\n"), None);
+    }
+}
@@ -1,14 +1,40 @@
 use rodio::buffer::SamplesBuffer;
-use rodio::{Decoder, OutputStreamBuilder, Source};
+use rodio::{Decoder, OutputStream, OutputStreamBuilder, Source};
+use serde::Serialize;
+use std::collections::HashMap;
 use std::io::Cursor;
+use crate::cue_themes::CueClip;
+use cpal::traits::{DeviceTrait, HostTrait};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// Types of sounds that can be played
 #[derive(Debug, Clone, Copy)]
 pub enum SoundType {
     RecordingStart,
     RecordingStop,
+    /// Played when something went wrong (e.g. text output failed).
+    Error,
+    /// Played after switching output mode: `n` short pips, where `n` is the new mode's
+    /// 1-based position, so the user can tell modes apart without looking.
+    ModeChange(u8),
+    /// Played when a transcript came back empty, so silence isn't mistaken for a paste.
+    NoSpeech,
+}
+
+impl SoundType {
+    /// Parse a sound name as used by the frontend ("start", "stop", "error").
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "start" => Some(Self::RecordingStart),
+            "stop" => Some(Self::RecordingStop),
+            "error" => Some(Self::Error),
+            "no_speech" => Some(Self::NoSpeech),
+            _ => None,
+        }
+    }
 }
 
 /// User-selectable sound cue theme.
@@ -24,14 +50,98 @@ pub enum AudioCue {
 }
 
 impl AudioCue {
+    /// All cue themes, in the order shown in settings.
+    pub const ALL: [AudioCue; 4] = [
+        AudioCue::Tangerine,
+        AudioCue::Maraca,
+        AudioCue::Clave,
+        AudioCue::Tambourine,
+    ];
+
+    /// Stable settings value for this cue (inverse of `from_str`).
+    pub fn to_str(self) -> &'static str {
+        match self {
+            Self::Tangerine => "tangerine",
+            Self::Maraca => "maraca",
+            Self::Clave => "clave",
+            Self::Tambourine => "tambourine",
+        }
+    }
+
     pub fn from_str(s: &str) -> Self {
-        match s {
-            "tangerine" => Self::Tangerine,
-            "maraca" => Self::Maraca,
-            "clave" => Self::Clave,
-            "tambourine" => Self::Tambourine,
-            // Unknown values: default to Tangerine.
-            _ => Self::Tangerine,
+        Self::parse(s).unwrap_or(Self::Tangerine)
+    }
+
+    /// Strict counterpart of `from_str`: `None` for unknown values.
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|cue| cue.to_str() == s)
+    }
+
+    /// Name shown in settings.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Self::Tangerine => "Tangerine",
+            Self::Maraca => "Maraca",
+            Self::Clave => "Claves",
+            Self::Tambourine => "Tambourine",
+        }
+    }
+
+    /// One-line description of how the theme sounds.
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Tangerine => "Soft two-note chime, rising to start and falling to stop",
+            Self::Maraca => "Quick shaker rattles",
+            Self::Clave => "Dry woodblock taps",
+            Self::Tambourine => "The original tambourine recordings",
+        }
+    }
+}
+
+/// Which cue theme plays the start sound and which plays the stop sound.
+///
+/// A plain `AudioCue` (the `audio_cue` preset) converts into a selection that uses it for
+/// both; `audio_cue_start` / `audio_cue_stop` override one side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CueSelection {
+    pub start: AudioCue,
+    pub stop: AudioCue,
+}
+
+impl CueSelection {
+    /// Resolve the preset plus optional per-side overrides (unknown overrides are ignored).
+    pub fn from_settings(preset: &str, start: Option<&str>, stop: Option<&str>) -> Self {
+        let preset = AudioCue::from_str(preset);
+        Self {
+            start: start.and_then(AudioCue::parse).unwrap_or(preset),
+            stop: stop.and_then(AudioCue::parse).unwrap_or(preset),
+        }
+    }
+
+    /// The theme to play `sound_type` with. Error and mode-change sounds follow the start
+    /// cue's theme.
+    pub fn cue_for(self, sound_type: SoundType) -> AudioCue {
+        match sound_type {
+            SoundType::RecordingStop => self.stop,
+            _ => self.start,
+        }
+    }
+
+    /// Apply the `output_mode_cues` entry for the output mode `mode`, which replaces both
+    /// themes. Modes without a (valid) entry keep this selection.
+    pub fn for_output_mode(self, overrides: &HashMap<String, String>, mode: &str) -> Self {
+        overrides
+            .get(mode)
+            .and_then(|cue| AudioCue::parse(cue))
+            .map_or(self, Self::from)
+    }
+}
+
+impl From<AudioCue> for CueSelection {
+    fn from(cue: AudioCue) -> Self {
+        Self {
+            start: cue,
+            stop: cue,
         }
     }
 }
@@ -40,11 +150,237 @@ impl AudioCue {
 const START_SOUND: &[u8] = include_bytes!("assets/start.mp3");
 const STOP_SOUND: &[u8] = include_bytes!("assets/stop.mp3");
 
+/// Default window (ms) within which a second start/stop cue is dropped.
+pub const DEFAULT_CUE_DEBOUNCE_MS: u64 = 120;
+
+/// Current start/stop cue debounce window in ms (0 disables debouncing).
+static CUE_DEBOUNCE_MS: AtomicU64 = AtomicU64::new(DEFAULT_CUE_DEBOUNCE_MS);
+
+/// When the last start/stop cue was allowed to play.
+static LAST_CUE_AT: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Update the start/stop cue debounce window (from the `cue_debounce_ms` setting).
+pub fn set_cue_debounce_ms(ms: u64) {
+    CUE_DEBOUNCE_MS.store(ms, Ordering::Relaxed);
+}
+
+/// Returns true if a start/stop cue should be skipped because another one played within
+/// the debounce window. Fast toggling otherwise makes one recording's start cue overlap
+/// the previous recording's stop cue.
+///
+/// Only start/stop cues are debounced.
+fn should_debounce_cue(sound_type: SoundType) -> bool {
+    if !matches!(sound_type, SoundType::RecordingStart | SoundType::RecordingStop) {
+        return false;
+    }
+
+    let window = Duration::from_millis(CUE_DEBOUNCE_MS.load(Ordering::Relaxed));
+    if window.is_zero() {
+        return false;
+    }
+
+    let Ok(mut last) = LAST_CUE_AT.lock() else {
+        return false;
+    };
+
+    let now = Instant::now();
+    if let Some(prev) = *last {
+        if now.duration_since(prev) < window {
+            log::debug!(
+                "Skipping {:?} cue: previous cue played {}ms ago",
+                sound_type,
+                now.duration_since(prev).as_millis()
+            );
+            return true;
+        }
+    }
+
+    *last = Some(now);
+    false
+}
+
+/// Whether the stop cue should play for a recording that lasted `recorded`.
+///
+/// Recordings shorter than `min_recording_ms` (`min_recording_ms_for_stop_cue`, 0 = always
+/// play) are usually accidental hotkey taps, where a stop beep right after the start beep
+/// is just noise. An unknown duration always plays.
+pub fn stop_cue_wanted(recorded: Option<Duration>, min_recording_ms: u64) -> bool {
+    !recorded.is_some_and(|recorded| recorded < Duration::from_millis(min_recording_ms))
+}
+
+/// Whether an audio output device was present at the last check. Cues are skipped while
+/// this is false, so a headless/remote session doesn't fail (and warn) on every cue.
+static OUTPUT_AVAILABLE: AtomicBool = AtomicBool::new(true);
+
+/// Set once "no output device" has been logged, so skipped cues stay quiet.
+static OUTPUT_UNAVAILABLE_LOGGED: AtomicBool = AtomicBool::new(false);
+
+/// Whether cues can currently be played.
+pub fn is_output_available() -> bool {
+    OUTPUT_AVAILABLE.load(Ordering::Relaxed)
+}
+
+/// Name of the default output device, if there is one.
+pub fn default_output_device_name() -> Option<String> {
+    cpal::default_host()
+        .default_output_device()
+        .and_then(|device| device.name().ok())
+}
+
+/// Re-detect the default output device and return the new availability.
+///
+/// Called at startup, periodically by the device watcher, and when opening a stream fails.
+pub fn refresh_output_availability() -> bool {
+    let available = cpal::default_host().default_output_device().is_some();
+    let was_available = OUTPUT_AVAILABLE.swap(available, Ordering::Relaxed);
+    if available && !was_available {
+        log::info!("Audio output device connected; cues re-enabled");
+        OUTPUT_UNAVAILABLE_LOGGED.store(false, Ordering::Relaxed);
+    }
+    available
+}
+
+/// Set while output is paused (`set_output_paused`); start/stop/error cues are skipped.
+/// Previews still play since the user asked for them.
+static CUES_PAUSED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_cues_paused(paused: bool) {
+    CUES_PAUSED.store(paused, Ordering::Relaxed);
+}
+
+/// Decoded sounds of the active user cue theme (`active_cue_theme`), keyed by sound name.
+/// Cues with a clip here play it instead of the built-in sound; previews stay built-in.
+static THEME_CLIPS: RwLock<Option<HashMap<&'static str, CueClip>>> = RwLock::new(None);
+
+/// Replace the active user cue theme's clips (`None` = built-in sounds only).
+pub fn set_theme_clips(clips: Option<HashMap<&'static str, CueClip>>) {
+    *THEME_CLIPS.write().unwrap_or_else(|e| e.into_inner()) = clips;
+}
+
+/// The active user theme's clip for `sound_type`, if it has one.
+fn theme_clip(sound_type: SoundType) -> Option<CueClip> {
+    let name = match sound_type {
+        SoundType::RecordingStart => "start",
+        SoundType::RecordingStop => "stop",
+        SoundType::Error => "error",
+        SoundType::ModeChange(_) | SoundType::NoSpeech => return None,
+    };
+    THEME_CLIPS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .and_then(|clips| clips.get(name).cloned())
+}
+
+/// Crossfade (ms) between overlapping user-theme cues (`cue_crossfade_ms`); 0 = none.
+static CUE_CROSSFADE_MS: AtomicU64 = AtomicU64::new(0);
+
+/// When the user-theme clip currently playing was asked to fade out (`None` = not yet).
+type FadeOutTrigger = Arc<Mutex<Option<Instant>>>;
+
+/// Fade-out trigger of the user-theme clip currently playing, if any.
+static PLAYING_CLIP: Mutex<Option<FadeOutTrigger>> = Mutex::new(None);
+
+/// Update the user-theme cue crossfade (from the `cue_crossfade_ms` setting).
+pub fn set_cue_crossfade_ms(ms: u64) {
+    CUE_CROSSFADE_MS.store(ms, Ordering::Relaxed);
+}
+
+/// Gain of a clip being crossfaded out: 1.0 until its fade starts, then a linear ramp down
+/// to silence over `crossfade`.
+fn crossfade_out_gain(since_fade: Option<Duration>, crossfade: Duration) -> f32 {
+    match since_fade {
+        None => 1.0,
+        Some(_) if crossfade.is_zero() => 0.0,
+        Some(elapsed) => (1.0 - elapsed.as_secs_f32() / crossfade.as_secs_f32()).max(0.0),
+    }
+}
+
+/// Cue volume (`cue_volume`) as `f32` bits; 1.0 plays cues at their built-in level.
+static CUE_VOLUME: AtomicU32 = AtomicU32::new(0x3f80_0000);
+
+/// Update the cue volume (from the `cue_volume` setting), clamped to 0.0..=1.0.
+pub fn set_cue_volume(volume: f32) {
+    CUE_VOLUME.store(clamp_cue_volume(volume).to_bits(), Ordering::Relaxed);
+}
+
+/// `volume` limited to 0.0..=1.0; anything not finite plays at full volume.
+fn clamp_cue_volume(volume: f32) -> f32 {
+    if volume.is_finite() {
+        volume.clamp(0.0, 1.0)
+    } else {
+        1.0
+    }
+}
+
+pub fn cue_volume() -> f32 {
+    f32::from_bits(CUE_VOLUME.load(Ordering::Relaxed))
+}
+
+/// Output device cues play on (`cue_output_device`); `None` = the system default.
+static CUE_OUTPUT_DEVICE: RwLock<Option<String>> = RwLock::new(None);
+
+/// Update the cue output device (from the `cue_output_device` setting).
+pub fn set_cue_output_device(name: Option<String>) {
+    *CUE_OUTPUT_DEVICE.write().unwrap_or_else(|e| e.into_inner()) = name;
+}
+
+/// Names of the output devices cues can be sent to.
+pub fn output_device_names() -> Vec<String> {
+    use rodio::cpal::traits::{DeviceTrait, HostTrait};
+
+    rodio::cpal::default_host()
+        .output_devices()
+        .map(|devices| devices.filter_map(|device| device.name().ok()).collect())
+        .unwrap_or_default()
+}
+
+/// Open a stream on the configured cue output device. `None` when no device is configured
+/// or it isn't connected, in which case cues go to the default device.
+fn open_selected_output_stream() -> Option<Result<OutputStream, rodio::StreamError>> {
+    use rodio::cpal::traits::{DeviceTrait, HostTrait};
+
+    let name = CUE_OUTPUT_DEVICE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()?;
+    let device = rodio::cpal::default_host()
+        .output_devices()
+        .ok()?
+        .find(|device| device.name().is_ok_and(|n| n == name));
+    let Some(device) = device else {
+        log::debug!("Cue output device '{}' not connected; using the default", name);
+        return None;
+    };
+    Some(OutputStreamBuilder::from_device(device).and_then(|builder| builder.open_stream()))
+}
+
+/// Log (once until a device reappears) that cues are being skipped.
+fn note_output_unavailable() {
+    if !OUTPUT_UNAVAILABLE_LOGGED.swap(true, Ordering::Relaxed) {
+        log::info!("No audio output device; skipping cues until one is connected");
+    }
+}
+
+/// Length of a single mode-change pip and the silence between pips.
+const MODE_PIP_MS: u64 = 45;
+const MODE_PIP_GAP_MS: u64 = 50;
+
+/// Max pips in a mode-change cue: one per output mode, so every mode is distinguishable.
+const MAX_MODE_PIPS: u8 = crate::commands::text::OutputMode::ALL.len() as u8;
+
+/// Length of the no-speech cue (one soft low note, shared by all themes).
+const NO_SPEECH_CUE_MS: u64 = 160;
+
+fn mode_change_duration(count: u8) -> Duration {
+    let count = count.clamp(1, MAX_MODE_PIPS) as u64;
+    Duration::from_millis(count * MODE_PIP_MS + (count - 1) * MODE_PIP_GAP_MS)
+}
+
 /// Best-effort estimate of how long a cue will be audible.
 ///
 /// Used to avoid cutting off cues when we do side-effects (like system mute) shortly after
 /// starting playback.
-#[cfg_attr(not(test), allow(dead_code))]
 pub fn estimated_duration(sound_type: SoundType, cue: AudioCue) -> Duration {
     match cue {
         // For the legacy MP3 cue, use the decoder's total duration when available.
@@ -53,6 +389,10 @@ pub fn estimated_duration(sound_type: SoundType, cue: AudioCue) -> Duration {
             let sound_data = match sound_type {
                 SoundType::RecordingStart => START_SOUND,
                 SoundType::RecordingStop => STOP_SOUND,
+                // No legacy MP3 for errors; the synthesized Tangerine error cue is used.
+                SoundType::Error => return Duration::from_millis(90 + 25 + 140),
+                SoundType::ModeChange(count) => return mode_change_duration(count),
+                SoundType::NoSpeech => return Duration::from_millis(NO_SPEECH_CUE_MS),
             };
 
             Decoder::new(Cursor::new(sound_data))
@@ -66,47 +406,218 @@ pub fn estimated_duration(sound_type: SoundType, cue: AudioCue) -> Duration {
             // Start cue: two-note up-chime (shorter than the previous 3-note arpeggio).
             SoundType::RecordingStart => Duration::from_millis(170),
             SoundType::RecordingStop => Duration::from_millis(195),
+            SoundType::Error => Duration::from_millis(90 + 25 + 140),
+            SoundType::ModeChange(count) => mode_change_duration(count),
+            SoundType::NoSpeech => Duration::from_millis(NO_SPEECH_CUE_MS),
         },
         AudioCue::Maraca => match sound_type {
             SoundType::RecordingStart => Duration::from_millis(45 + 30 + 45 + 30 + 60),
             SoundType::RecordingStop => Duration::from_millis(55 + 35 + 45),
+            SoundType::Error => Duration::from_millis(40 + 25 + 40 + 25 + 40),
+            SoundType::ModeChange(count) => mode_change_duration(count),
+            SoundType::NoSpeech => Duration::from_millis(NO_SPEECH_CUE_MS),
         },
         AudioCue::Clave => match sound_type {
             SoundType::RecordingStart => Duration::from_millis(55 + 35 + 45),
             SoundType::RecordingStop => Duration::from_millis(80),
+            SoundType::Error => Duration::from_millis(70 + 40 + 70),
+            SoundType::ModeChange(count) => mode_change_duration(count),
+            SoundType::NoSpeech => Duration::from_millis(NO_SPEECH_CUE_MS),
         },
     }
 }
 
+/// What happened to a cue: played, deliberately skipped (and why), or failed.
+///
+/// `SoundDisabled` and `SuppressedDnd` are decided by callers before playback (they need
+/// settings); `diagnose_audio` reports them alongside the rest.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", content = "message", rename_all = "snake_case")]
+pub enum PlaybackOutcome {
+    Played,
+    /// `sound_enabled` is off.
+    SoundDisabled,
+    /// System Do Not Disturb (Focus, Windows quiet hours) with `respect_system_dnd` on.
+    SuppressedDnd,
+    /// Output is paused (`set_output_paused`).
+    SuppressedPaused,
+    /// The same kind of cue played within the debounce window.
+    Debounced,
+    NoOutputDevice,
+    /// The sound data couldn't be decoded.
+    DecodeError(String),
+    /// The output device was there but the stream couldn't be opened.
+    StreamError(String),
+}
+
+impl PlaybackOutcome {
+    /// The failure message, for outcomes that are errors rather than deliberate skips.
+    pub fn error(&self) -> Option<&str> {
+        match self {
+            PlaybackOutcome::DecodeError(message) | PlaybackOutcome::StreamError(message) => {
+                Some(message)
+            }
+            _ => None,
+        }
+    }
+}
+
 /// Play a sound effect (non-blocking)
-pub fn play_sound(sound_type: SoundType, cue: AudioCue) {
+pub fn play_sound(sound_type: SoundType, cues: impl Into<CueSelection>) {
+    let cues = cues.into();
     thread::spawn(move || {
-        if let Err(e) = play_sound_blocking(sound_type, cue) {
+        if let Some(e) = play_sound_blocking(sound_type, cues).error() {
             log::warn!("Failed to play sound: {}", e);
         }
     });
 }
 
+/// Play a cue and wait for it to finish. Start/stop cues are debounced (see
+/// `set_cue_debounce_ms`); a skipped cue returns immediately.
 pub(crate) fn play_sound_blocking(
     sound_type: SoundType,
-    cue: AudioCue,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let stream = OutputStreamBuilder::open_default_stream()?;
+    cues: impl Into<CueSelection>,
+) -> PlaybackOutcome {
+    if CUES_PAUSED.load(Ordering::Relaxed) {
+        return PlaybackOutcome::SuppressedPaused;
+    }
+    if should_debounce_cue(sound_type) {
+        return PlaybackOutcome::Debounced;
+    }
+
+    if let Some(clip) = theme_clip(sound_type) {
+        return play_clip_blocking(&clip);
+    }
+
+    play_sound_blocking_undebounced(sound_type, cues)
+}
+
+/// Open the cue output stream (the configured device, else the default), or the reason
+/// there's none to play into.
+fn open_output_stream() -> Result<OutputStream, PlaybackOutcome> {
+    if !is_output_available() {
+        note_output_unavailable();
+        return Err(PlaybackOutcome::NoOutputDevice);
+    }
+
+    match open_selected_output_stream() {
+        Some(Ok(stream)) => return Ok(stream),
+        Some(Err(e)) => log::warn!("Cue output device unusable, using the default: {}", e),
+        None => {}
+    }
+    match OutputStreamBuilder::open_default_stream() {
+        Ok(stream) => Ok(stream),
+        // The device may have gone away since the last check; if so, go quiet.
+        Err(_) if !refresh_output_availability() => {
+            note_output_unavailable();
+            Err(PlaybackOutcome::NoOutputDevice)
+        }
+        Err(e) => Err(PlaybackOutcome::StreamError(e.to_string())),
+    }
+}
+
+/// Play a user theme clip and wait for it to finish.
+fn play_clip_blocking(clip: &CueClip) -> PlaybackOutcome {
+    let stream = match open_output_stream() {
+        Ok(stream) => stream,
+        Err(outcome) => return outcome,
+    };
+
+    // Same tail padding as the built-in cues (see `play_sound_blocking_undebounced`).
+    const TAIL_PAD: Duration = Duration::from_millis(250);
+
+    let source = SamplesBuffer::new(clip.channels, clip.sample_rate, clip.samples.to_vec());
+    let volume = cue_volume();
+    let crossfade = Duration::from_millis(CUE_CROSSFADE_MS.load(Ordering::Relaxed));
+    if crossfade.is_zero() {
+        stream.mixer().add(source.amplify(volume));
+        thread::sleep(clip.duration() + TAIL_PAD);
+        return PlaybackOutcome::Played;
+    }
+
+    // Crossfade: a clip still playing fades out while this one fades in. A clip that
+    // doesn't overlap another starts at full volume.
+    let fade_out: FadeOutTrigger = Arc::new(Mutex::new(None));
+    let previous = PLAYING_CLIP
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .replace(fade_out.clone());
+    let overlapping = previous.is_some_and(|previous| {
+        previous
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_or_insert_with(Instant::now);
+        true
+    });
+    let source: Box<dyn Source + Send> = if overlapping {
+        Box::new(source.fade_in(crossfade))
+    } else {
+        Box::new(source)
+    };
+
+    let trigger = fade_out.clone();
+    stream.mixer().add(source.amplify(1.0).periodic_access(
+        Duration::from_millis(5),
+        move |source| {
+            let since_fade = trigger
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .map(|started| started.elapsed());
+            source.set_factor(crossfade_out_gain(since_fade, crossfade) * volume);
+        },
+    ));
+
+    // Dropping the stream stops playback, so wait out the clip, or just the fade if a
+    // newer cue took over.
+    let deadline = Instant::now() + clip.duration() + TAIL_PAD;
+    while Instant::now() < deadline {
+        let faded = fade_out
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_some_and(|started| started.elapsed() >= crossfade);
+        if faded {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    let mut playing = PLAYING_CLIP.lock().unwrap_or_else(|e| e.into_inner());
+    if playing.as_ref().is_some_and(|current| Arc::ptr_eq(current, &fade_out)) {
+        *playing = None;
+    }
+    PlaybackOutcome::Played
+}
+
+/// Like `play_sound_blocking`, but bypasses the debounce window.
+///
+/// Used by previews, which intentionally play start and stop back to back.
+pub(crate) fn play_sound_blocking_undebounced(
+    sound_type: SoundType,
+    cues: impl Into<CueSelection>,
+) -> PlaybackOutcome {
+    let cue = cues.into().cue_for(sound_type);
+    let stream = match open_output_stream() {
+        Ok(stream) => stream,
+        Err(outcome) => return outcome,
+    };
 
     // Some devices/backends take a moment to "wake" after being idle.
     // Since dropping `stream` stops playback, keep extra tail padding so we don't
     // clip the end of a cue (most noticeable on the first playback after idle).
     const TAIL_PAD: Duration = Duration::from_millis(250);
 
-    match cue {
+    match (cue, sound_type) {
         // Preserve the existing cue exactly (legacy MP3 assets).
-        AudioCue::Tambourine => {
+        (AudioCue::Tambourine, SoundType::RecordingStart | SoundType::RecordingStop) => {
             let sound_data = match sound_type {
                 SoundType::RecordingStart => START_SOUND,
-                SoundType::RecordingStop => STOP_SOUND,
+                _ => STOP_SOUND,
             };
             let cursor = Cursor::new(sound_data);
-            let decoded = Decoder::new(cursor)?.amplify(0.3);
+            let decoded = match Decoder::new(cursor) {
+                Ok(decoded) => decoded.amplify(0.3 * cue_volume()),
+                Err(e) => return PlaybackOutcome::DecodeError(e.to_string()),
+            };
 
             let duration = decoded
                 .total_duration()
@@ -119,12 +630,12 @@ pub(crate) fn play_sound_blocking(
         // New cues are synthesized at runtime (no extra audio assets needed).
         _ => {
             let (seq, duration) = build_synth_cue_source(sound_type, cue);
-            stream.mixer().add(seq);
+            stream.mixer().add(seq.amplify(cue_volume()));
             thread::sleep(duration + TAIL_PAD);
         }
     }
 
-    Ok(())
+    PlaybackOutcome::Played
 }
 
 fn build_synth_cue_source(sound_type: SoundType, cue: AudioCue) -> (SamplesBuffer, Duration) {
@@ -256,6 +767,38 @@ fn build_synth_cue_source(sound_type: SoundType, cue: AudioCue) -> (SamplesBuffe
         }
     }
 
+    /// Low, descending two-note chime used for errors. Returns the cue duration.
+    fn push_error_chime(samples: &mut Vec<f32>) -> Duration {
+        let d1 = Duration::from_millis(90);
+        let gap = Duration::from_millis(25);
+        let d2 = Duration::from_millis(140);
+
+        push_chime(samples, 392.00, d1, 0.20); // G4
+        push_silence(samples, gap);
+        push_chime(samples, 311.13, d2, 0.20); // Eb4
+
+        d1 + gap + d2
+    }
+
+    /// Short high pips for mode changes, shared by all themes. Returns the cue duration.
+    fn push_mode_pips(samples: &mut Vec<f32>, count: u8) -> Duration {
+        let count = count.clamp(1, MAX_MODE_PIPS);
+        for i in 0..count {
+            if i > 0 {
+                push_silence(samples, Duration::from_millis(MODE_PIP_GAP_MS));
+            }
+            push_chime(samples, 1318.51, Duration::from_millis(MODE_PIP_MS), 0.16); // E6
+        }
+        mode_change_duration(count)
+    }
+
+    /// A single soft, low note for "no speech detected", shared by all themes.
+    fn push_no_speech_chime(samples: &mut Vec<f32>) -> Duration {
+        let d = Duration::from_millis(NO_SPEECH_CUE_MS);
+        push_chime(samples, 440.00, d, 0.12); // A4
+        d
+    }
+
     // Build the cue explicitly per type so we can use more realistic synthesis.
     let mut samples: Vec<f32> = Vec::new();
     let mut duration = Duration::from_millis(0);
@@ -289,6 +832,15 @@ fn build_synth_cue_source(sound_type: SoundType, cue: AudioCue) -> (SamplesBuffe
 
                     duration = d1 + gap + d2;
                 }
+                SoundType::Error => {
+                    duration = push_error_chime(&mut samples);
+                }
+                SoundType::ModeChange(count) => {
+                    duration = push_mode_pips(&mut samples, count);
+                }
+                SoundType::NoSpeech => {
+                    duration = push_no_speech_chime(&mut samples);
+                }
             }
         }
 
@@ -316,6 +868,23 @@ fn build_synth_cue_source(sound_type: SoundType, cue: AudioCue) -> (SamplesBuffe
                     push_shaker(&mut samples, Duration::from_millis(45), 0.24, &mut seed);
                     duration = tick + gap + Duration::from_millis(45);
                 }
+                SoundType::Error => {
+                    // Three quick, quieter rattles: busier than stop so it reads as "wrong".
+                    let tick = Duration::from_millis(40);
+                    let gap = Duration::from_millis(25);
+                    push_shaker(&mut samples, tick, 0.30, &mut seed);
+                    push_silence(&mut samples, gap);
+                    push_shaker(&mut samples, tick, 0.26, &mut seed);
+                    push_silence(&mut samples, gap);
+                    push_shaker(&mut samples, tick, 0.22, &mut seed);
+                    duration = tick + gap + tick + gap + tick;
+                }
+                SoundType::ModeChange(count) => {
+                    duration = push_mode_pips(&mut samples, count);
+                }
+                SoundType::NoSpeech => {
+                    duration = push_no_speech_chime(&mut samples);
+                }
             }
         }
 
@@ -335,14 +904,149 @@ fn build_synth_cue_source(sound_type: SoundType, cue: AudioCue) -> (SamplesBuffe
                     push_woodblock(&mut samples, 1550.0, tap, 0.36, &mut seed);
                     duration = tap;
                 }
+                SoundType::Error => {
+                    // Two low, descending taps.
+                    let tap = Duration::from_millis(70);
+                    let gap = Duration::from_millis(40);
+                    push_woodblock(&mut samples, 900.0, tap, 0.36, &mut seed);
+                    push_silence(&mut samples, gap);
+                    push_woodblock(&mut samples, 780.0, tap, 0.34, &mut seed);
+                    duration = tap + gap + tap;
+                }
+                SoundType::ModeChange(count) => {
+                    duration = push_mode_pips(&mut samples, count);
+                }
+                SoundType::NoSpeech => {
+                    duration = push_no_speech_chime(&mut samples);
+                }
             }
         }
 
-        // Should never hit: Tambourine handled in play_sound_blocking.
-        // If it does, keep duration at the default 0.
-        AudioCue::Tambourine => {}
+        AudioCue::Tambourine => {
+            // Start/stop use the legacy MP3s (handled in play_sound_blocking); there is no
+            // legacy error sound, so reuse the Tangerine error chime.
+            // For start/stop, keep duration at the default 0.
+            match sound_type {
+                SoundType::Error => duration = push_error_chime(&mut samples),
+                SoundType::ModeChange(count) => duration = push_mode_pips(&mut samples, count),
+                SoundType::NoSpeech => duration = push_no_speech_chime(&mut samples),
+                SoundType::RecordingStart | SoundType::RecordingStop => {}
+            }
+        }
     }
 
     let seq = SamplesBuffer::new(CHANNELS, SAMPLE_RATE, samples);
     (seq, duration)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crossfade_out_gain_ramps_linearly() {
+        let crossfade = Duration::from_millis(100);
+        assert_eq!(crossfade_out_gain(None, crossfade), 1.0);
+        assert_eq!(crossfade_out_gain(Some(Duration::ZERO), crossfade), 1.0);
+        let quarter = crossfade_out_gain(Some(Duration::from_millis(25)), crossfade);
+        assert!((quarter - 0.75).abs() < 1e-6);
+        assert_eq!(crossfade_out_gain(Some(Duration::from_millis(250)), crossfade), 0.0);
+        assert_eq!(crossfade_out_gain(Some(Duration::ZERO), Duration::ZERO), 0.0);
+    }
+
+    #[test]
+    fn test_cue_volume_is_clamped() {
+        assert_eq!(clamp_cue_volume(0.4), 0.4);
+        assert_eq!(clamp_cue_volume(3.0), 1.0);
+        assert_eq!(clamp_cue_volume(-1.0), 0.0);
+        assert_eq!(clamp_cue_volume(f32::NAN), 1.0);
+        assert_eq!(clamp_cue_volume(f32::INFINITY), 1.0);
+    }
+
+    #[test]
+    fn test_playback_outcome_errors_and_serialization() {
+        assert_eq!(PlaybackOutcome::Played.error(), None);
+        assert_eq!(PlaybackOutcome::SuppressedDnd.error(), None);
+        assert_eq!(PlaybackOutcome::NoOutputDevice.error(), None);
+        let decode = PlaybackOutcome::DecodeError("bad frame".to_string());
+        assert_eq!(decode.error(), Some("bad frame"));
+
+        assert_eq!(
+            serde_json::to_value(&decode).unwrap(),
+            serde_json::json!({ "status": "decode_error", "message": "bad frame" })
+        );
+        assert_eq!(
+            serde_json::to_value(PlaybackOutcome::SuppressedPaused).unwrap(),
+            serde_json::json!({ "status": "suppressed_paused" })
+        );
+    }
+
+    #[test]
+    fn test_preset_sets_both_cues() {
+        let cues = CueSelection::from_settings("clave", None, None);
+        assert_eq!(cues, CueSelection::from(AudioCue::Clave));
+        assert_eq!(cues.cue_for(SoundType::RecordingStart), AudioCue::Clave);
+        assert_eq!(cues.cue_for(SoundType::RecordingStop), AudioCue::Clave);
+    }
+
+    #[test]
+    fn test_start_and_stop_cues_resolve_independently() {
+        let cues = CueSelection::from_settings("tangerine", None, Some("maraca"));
+        assert_eq!(cues.cue_for(SoundType::RecordingStart), AudioCue::Tangerine);
+        assert_eq!(cues.cue_for(SoundType::RecordingStop), AudioCue::Maraca);
+        assert_eq!(cues.cue_for(SoundType::Error), AudioCue::Tangerine);
+
+        let cues = CueSelection::from_settings("tangerine", Some("clave"), Some("bogus"));
+        assert_eq!(cues.start, AudioCue::Clave);
+        assert_eq!(cues.stop, AudioCue::Tangerine);
+    }
+
+    #[test]
+    fn test_output_mode_cue_override() {
+        let overrides = HashMap::from([
+            ("keystrokes_verified".to_string(), "clave".to_string()),
+            ("clipboard".to_string(), "bogus".to_string()),
+        ]);
+        let global = CueSelection::from_settings("tangerine", None, Some("maraca"));
+        assert_eq!(
+            global.for_output_mode(&overrides, "keystrokes_verified"),
+            CueSelection::from(AudioCue::Clave)
+        );
+        assert_eq!(global.for_output_mode(&overrides, "paste"), global);
+        assert_eq!(global.for_output_mode(&overrides, "clipboard"), global);
+    }
+
+    #[test]
+    fn test_stop_cue_skipped_for_short_recordings() {
+        let short = Some(Duration::from_millis(150));
+        assert!(stop_cue_wanted(short, 0));
+        assert!(!stop_cue_wanted(short, 300));
+        assert!(stop_cue_wanted(Some(Duration::from_millis(300)), 300));
+        assert!(stop_cue_wanted(None, 300));
+    }
+
+    #[test]
+    fn test_audio_cue_parse_is_strict() {
+        for cue in AudioCue::ALL {
+            assert_eq!(AudioCue::parse(cue.to_str()), Some(cue));
+        }
+        assert_eq!(AudioCue::parse("bogus"), None);
+        assert_eq!(AudioCue::from_str("bogus"), AudioCue::Tangerine);
+    }
+
+    #[test]
+    fn test_audio_cue_metadata_is_complete() {
+        let mut names: Vec<&str> = AudioCue::ALL.iter().map(|cue| cue.display_name()).collect();
+        names.dedup();
+        assert_eq!(names.len(), AudioCue::ALL.len());
+        assert!(AudioCue::ALL.iter().all(|cue| !cue.description().is_empty()));
+        assert_eq!(AudioCue::Clave.display_name(), "Claves");
+    }
+
+    #[test]
+    fn test_every_output_mode_gets_distinct_pips() {
+        let last = crate::commands::text::OutputMode::ALL.len() as u8;
+        assert!(mode_change_duration(last) > mode_change_duration(last - 1));
+        assert_eq!(mode_change_duration(last + 1), mode_change_duration(last));
+    }
+}
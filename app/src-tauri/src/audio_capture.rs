@@ -12,7 +12,7 @@ use hound::{WavSpec, WavWriter};
 use serde::{Deserialize, Serialize};
 use std::io::Cursor;
 use std::sync::mpsc;
-use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex as StdMutex};
 use std::thread::{self, JoinHandle};
 
@@ -225,8 +225,80 @@ fn apply_noise_gate_interleaved(
     out
 }
 
+/// Default `silence_trim_threshold_dbfs`: quieter than speech, louder than a typical
+/// mic's noise floor.
+pub const DEFAULT_SILENCE_TRIM_THRESHOLD_DBFS: f32 = -50.0;
+
+/// Default `silence_trim_max_ms`.
+pub const DEFAULT_SILENCE_TRIM_MAX_MS: u32 = 2_000;
+
+/// Silence kept before the first and after the last loud frame, so soft word onsets and
+/// tails (fricatives, trailing breaths) aren't clipped.
+const SILENCE_TRIM_PAD_MS: u32 = 150;
+
+/// Leading/trailing silence trim, applied at stop-time before any other processing.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SilenceTrimConfig {
+    /// Frames whose peak (across channels) is below this dBFS level count as silence.
+    pub threshold_dbfs: f32,
+    /// Most audio cut from each end, in milliseconds.
+    pub max_trim_ms: u32,
+}
+
+impl Default for SilenceTrimConfig {
+    fn default() -> Self {
+        Self {
+            threshold_dbfs: DEFAULT_SILENCE_TRIM_THRESHOLD_DBFS,
+            max_trim_ms: DEFAULT_SILENCE_TRIM_MAX_MS,
+        }
+    }
+}
+
+/// Cut leading and trailing silence from interleaved samples.
+///
+/// Keeps `SILENCE_TRIM_PAD_MS` of silence around the audible part and never cuts more than
+/// `max_trim_ms` from either end. A recording with no frame above the threshold is returned
+/// whole: deciding it's empty is the quiet-audio gate's job.
+fn trim_silence_interleaved(
+    samples: &[f32],
+    sample_rate: u32,
+    channels: u16,
+    cfg: SilenceTrimConfig,
+) -> &[f32] {
+    let channels = channels.max(1) as usize;
+    let frames = samples.len() / channels;
+    let threshold_dbfs = if cfg.threshold_dbfs.is_finite() {
+        cfg.threshold_dbfs.clamp(-75.0, -20.0)
+    } else {
+        DEFAULT_SILENCE_TRIM_THRESHOLD_DBFS
+    };
+    let threshold_amp = db_to_amp(threshold_dbfs);
+    let loud = |frame: usize| {
+        samples[frame * channels..(frame + 1) * channels]
+            .iter()
+            .any(|s| s.abs() >= threshold_amp)
+    };
+
+    let Some(first) = (0..frames).find(|&frame| loud(frame)) else {
+        return samples;
+    };
+    let last = (first..frames).rev().find(|&frame| loud(frame)).unwrap_or(first);
+
+    let ms_to_frames = |ms: u32| (u64::from(ms) * u64::from(sample_rate) / 1000) as usize;
+    let pad = ms_to_frames(SILENCE_TRIM_PAD_MS);
+    let max_trim = ms_to_frames(cfg.max_trim_ms);
+
+    let start = first.saturating_sub(pad).min(max_trim);
+    let end = (last + 1 + pad).min(frames).max(frames.saturating_sub(max_trim));
+    // A partial trailing frame (shouldn't happen) is kept when nothing is cut from the end.
+    let end_sample = if end == frames { samples.len() } else { end * channels };
+    &samples[start * channels..end_sample]
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct AudioEncodeConfig {
+    /// If set, trim leading/trailing silence before anything else.
+    pub silence_trim: Option<SilenceTrimConfig>,
     /// If set, apply a noise gate with the given threshold.
     pub noise_gate_threshold_dbfs: Option<f32>,
     /// Convert the captured audio to mono before WAV encoding.
@@ -246,6 +318,7 @@ pub struct AudioEncodeConfig {
 impl Default for AudioEncodeConfig {
     fn default() -> Self {
         Self {
+            silence_trim: None,
             noise_gate_threshold_dbfs: None,
             downmix_to_mono: true,
             resample_to_16khz: false,
@@ -290,6 +363,36 @@ pub enum AudioCaptureError {
     ThreadError(String),
 }
 
+/// Longest lead-in or pre-roll `recording_start_delay_ms` may ask for, either way.
+pub const MAX_RECORDING_START_DELAY_MS: i64 = 2_000;
+
+/// How a recording's audio begins relative to the moment capture is started
+/// (`recording_start_delay_ms`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RecordingStart {
+    /// Keep everything from the moment the stream starts.
+    #[default]
+    Immediate,
+    /// Drop this many milliseconds once the stream starts (a lead-in).
+    Delayed(u32),
+    /// Keep a rolling buffer of this many milliseconds running between recordings and start
+    /// each recording with it, so words spoken just before the hotkey aren't clipped.
+    PreRoll(u32),
+}
+
+impl RecordingStart {
+    /// Positive delays drop audio, negative ones pre-roll; both are capped at
+    /// `MAX_RECORDING_START_DELAY_MS`.
+    pub fn from_delay_ms(delay_ms: i64) -> Self {
+        let ms = delay_ms.clamp(-MAX_RECORDING_START_DELAY_MS, MAX_RECORDING_START_DELAY_MS);
+        match ms.cmp(&0) {
+            std::cmp::Ordering::Greater => Self::Delayed(ms as u32),
+            std::cmp::Ordering::Less => Self::PreRoll(ms.unsigned_abs() as u32),
+            std::cmp::Ordering::Equal => Self::Immediate,
+        }
+    }
+}
+
 /// Audio buffer that accumulates samples during recording
 #[derive(Debug, Clone)]
 pub struct AudioBuffer {
@@ -297,6 +400,8 @@ pub struct AudioBuffer {
     sample_rate: u32,
     channels: u16,
     max_duration_secs: f32,
+    // Incoming samples still to be dropped for a lead-in delay.
+    discard_remaining: usize,
 }
 
 impl AudioBuffer {
@@ -308,12 +413,15 @@ impl AudioBuffer {
             sample_rate,
             channels,
             max_duration_secs,
+            discard_remaining: 0,
         }
     }
 
     /// Append samples to the buffer
     pub fn append(&mut self, new_samples: &[f32]) {
-        self.samples.extend_from_slice(new_samples);
+        let skip = self.discard_remaining.min(new_samples.len());
+        self.discard_remaining -= skip;
+        self.samples.extend_from_slice(&new_samples[skip..]);
 
         // Trim if exceeds max duration
         let max_samples =
@@ -324,6 +432,33 @@ impl AudioBuffer {
         }
     }
 
+    /// Append `len` zero samples, e.g. in place of muted input.
+    pub fn append_silence(&mut self, len: usize) {
+        let skip = self.discard_remaining.min(len);
+        self.discard_remaining -= skip;
+        let new_len = self.samples.len() + (len - skip);
+        self.samples.resize(new_len, 0.0);
+
+        let max_samples =
+            (self.sample_rate as f32 * self.max_duration_secs * self.channels as f32) as usize;
+        if self.samples.len() > max_samples {
+            let drain_count = self.samples.len() - max_samples;
+            self.samples.drain(0..drain_count);
+        }
+    }
+
+    /// Drop the next `ms` of appended audio instead of keeping it.
+    pub fn discard_next_ms(&mut self, ms: u32) {
+        let frames = self.sample_rate as u64 * ms as u64 / 1000;
+        self.discard_remaining = frames as usize * self.channels as usize;
+    }
+
+    /// Change how much audio the buffer keeps. Samples already held stay (up to the new
+    /// limit, on the next append), which is how a pre-roll buffer becomes a recording.
+    pub fn set_max_duration_secs(&mut self, max_duration_secs: f32) {
+        self.max_duration_secs = max_duration_secs;
+    }
+
     /// Clear all samples from the buffer
     #[cfg_attr(not(test), allow(dead_code))]
     pub fn clear(&mut self) {
@@ -421,10 +556,27 @@ impl AudioBuffer {
             None
         };
 
+        // Cut dead air first, so everything after works on (and uploads) less audio.
+        let samples = match cfg.silence_trim {
+            Some(trim) => {
+                let trimmed =
+                    trim_silence_interleaved(&self.samples, self.sample_rate, self.channels, trim);
+                if trimmed.len() < self.samples.len() {
+                    log::debug!(
+                        "Silence trim: kept {} of {} samples",
+                        trimmed.len(),
+                        self.samples.len()
+                    );
+                }
+                trimmed
+            }
+            None => &self.samples[..],
+        };
+
         let mut processed_samples = if cfg.downmix_to_mono {
-            downmix_interleaved_to_mono(&self.samples, self.channels as usize)
+            downmix_interleaved_to_mono(samples, self.channels as usize)
         } else {
-            self.samples.to_vec()
+            samples.to_vec()
         };
 
         let mut out_sample_rate = self.sample_rate;
@@ -521,6 +673,41 @@ pub struct AudioLevelStats {
     pub peak: f32,
 }
 
+/// Peak below this (dBFS) over a whole recording means the mic is likely too quiet.
+const QUIET_RECORDING_PEAK_DBFS: f32 = -30.0;
+
+/// Floor used instead of -inf dBFS for silent recordings (keeps the JSON numeric).
+const LEVEL_DBFS_FLOOR: f32 = -120.0;
+
+/// Level summary of a finished recording, so the UI can warn about a very quiet mic.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct RecordingLevelStats {
+    pub duration_secs: f32,
+    /// Peak (max absolute) amplitude in [0, 1].
+    pub peak: f32,
+    /// RMS (average) amplitude in [0, 1].
+    pub rms: f32,
+    pub peak_dbfs: f32,
+    pub rms_dbfs: f32,
+    /// True when the peak never reached `QUIET_RECORDING_PEAK_DBFS`.
+    pub too_quiet: bool,
+}
+
+impl From<AudioLevelStats> for RecordingLevelStats {
+    fn from(stats: AudioLevelStats) -> Self {
+        let peak_dbfs = amp_to_dbfs(stats.peak).max(LEVEL_DBFS_FLOOR);
+        let rms_dbfs = amp_to_dbfs(stats.rms).max(LEVEL_DBFS_FLOOR);
+        Self {
+            duration_secs: stats.duration_secs,
+            peak: stats.peak,
+            rms: stats.rms,
+            peak_dbfs,
+            rms_dbfs,
+            too_quiet: peak_dbfs < QUIET_RECORDING_PEAK_DBFS,
+        }
+    }
+}
+
 fn detect_speech_presence(samples: &[f32], sample_rate: u32, channels: u16) -> bool {
     if samples.is_empty() {
         return false;
@@ -593,6 +780,32 @@ impl SharedAudioLevelMeter {
     }
 }
 
+/// A cheap-to-clone handle for reading why capture gave up on the stream mid-recording
+/// (after the reconnect attempts in the capture thread failed).
+#[derive(Clone, Default)]
+pub struct SharedCaptureFailure {
+    inner: Arc<StdMutex<Option<String>>>,
+}
+
+impl SharedCaptureFailure {
+    /// Take the failure message, if capture failed since the last call.
+    pub fn take(&self) -> Option<String> {
+        self.inner.lock().ok().and_then(|mut failure| failure.take())
+    }
+
+    fn set(&self, message: String) {
+        if let Ok(mut failure) = self.inner.lock() {
+            *failure = Some(message);
+        }
+    }
+
+    fn clear(&self) {
+        if let Ok(mut failure) = self.inner.lock() {
+            *failure = None;
+        }
+    }
+}
+
 #[derive(Debug)]
 struct AudioWaveformMeter {
     seq: AtomicU64,
@@ -611,6 +824,15 @@ impl Default for AudioWaveformMeter {
 }
 
 impl AudioWaveformMeter {
+    /// Flatten every bucket, as for a silent chunk.
+    fn clear(&self) {
+        for bin in 0..WAVEFORM_BINS {
+            self.min_bits[bin].store(0f32.to_bits(), Ordering::Relaxed);
+            self.max_bits[bin].store(0f32.to_bits(), Ordering::Relaxed);
+        }
+        self.seq.fetch_add(1, Ordering::Relaxed);
+    }
+
     fn snapshot(&self) -> AudioWaveformSnapshot {
         let seq = self.seq.load(Ordering::Relaxed);
         let mut mins = Vec::with_capacity(WAVEFORM_BINS);
@@ -726,14 +948,179 @@ impl Default for VadAutoStopConfig {
     }
 }
 
+/// Capture format the user asked for (`audio_capture_sample_rate` /
+/// `audio_capture_channels`). `None` fields keep the device default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CapturePreference {
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u16>,
+}
+
+impl CapturePreference {
+    fn is_set(&self) -> bool {
+        self.sample_rate.is_some() || self.channels.is_some()
+    }
+}
+
+/// Capture format negotiated for the most recent recording (`get_capture_config`).
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptureConfig {
+    pub device_name: Option<String>,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub sample_format: String,
+    /// The device's own default format, for comparison.
+    pub default_sample_rate: u32,
+    pub default_channels: u16,
+    pub requested_sample_rate: Option<u32>,
+    pub requested_channels: Option<u16>,
+    /// A format was requested but the device doesn't support it, so its default was used.
+    pub fell_back_to_default: bool,
+}
+
+/// A device-supported capture format range, reduced to what negotiation looks at.
+#[derive(Debug, Clone, Copy)]
+struct FormatRange {
+    channels: u16,
+    min_sample_rate: u32,
+    max_sample_rate: u32,
+    /// Same sample format as the device default, which is preferred when there's a choice.
+    default_sample_format: bool,
+}
+
+/// Index of the range that can capture at `sample_rate` with `channels`, if any.
+fn pick_format_range(ranges: &[FormatRange], sample_rate: u32, channels: u16) -> Option<usize> {
+    let fits = |range: &FormatRange| {
+        range.channels == channels
+            && (range.min_sample_rate..=range.max_sample_rate).contains(&sample_rate)
+    };
+    ranges
+        .iter()
+        .position(|range| fits(range) && range.default_sample_format)
+        .or_else(|| ranges.iter().position(fits))
+}
+
+/// The device's default input config, or the requested format when one is set and the
+/// device supports it. Returns whether a requested format had to be dropped.
+fn negotiate_input_config(
+    device: &cpal::Device,
+    default: cpal::SupportedStreamConfig,
+    preference: CapturePreference,
+) -> (cpal::SupportedStreamConfig, bool) {
+    if !preference.is_set() {
+        return (default, false);
+    }
+    let sample_rate = preference.sample_rate.unwrap_or(default.sample_rate().0);
+    let channels = preference.channels.unwrap_or(default.channels());
+
+    let ranges: Vec<cpal::SupportedStreamConfigRange> = match device.supported_input_configs() {
+        // Only formats `build_capture_stream` can read.
+        Ok(configs) => configs
+            .filter(|range| {
+                matches!(
+                    range.sample_format(),
+                    SampleFormat::F32 | SampleFormat::I16 | SampleFormat::U16
+                )
+            })
+            .collect(),
+        Err(e) => {
+            log::warn!("Couldn't list supported input configs ({}); using device default", e);
+            return (default, true);
+        }
+    };
+    let summaries: Vec<FormatRange> = ranges
+        .iter()
+        .map(|range| FormatRange {
+            channels: range.channels(),
+            min_sample_rate: range.min_sample_rate().0,
+            max_sample_rate: range.max_sample_rate().0,
+            default_sample_format: range.sample_format() == default.sample_format(),
+        })
+        .collect();
+
+    match pick_format_range(&summaries, sample_rate, channels) {
+        Some(index) => (
+            ranges[index]
+                .clone()
+                .with_sample_rate(cpal::SampleRate(sample_rate)),
+            false,
+        ),
+        None => {
+            log::warn!(
+                "Input device doesn't support {} Hz / {} channel(s); using its default {} Hz / {} channel(s)",
+                sample_rate,
+                channels,
+                default.sample_rate().0,
+                default.channels()
+            );
+            (default, true)
+        }
+    }
+}
+
 /// Handle to a running audio capture session
 struct CaptureHandle {
     command_tx: mpsc::Sender<CaptureCommand>,
     #[cfg_attr(not(test), allow(dead_code))]
     event_rx: mpsc::Receiver<AudioCaptureEvent>,
+    /// Whether captured audio goes through VAD (see `run_vad_loop`). Clear while the stream
+    /// is a pre-roll standby.
+    vad_active: Arc<AtomicBool>,
     thread_handle: JoinHandle<Result<(), AudioCaptureError>>,
 }
 
+impl CaptureHandle {
+    /// Stop the capture thread and wait for it to exit.
+    fn shutdown(self) {
+        // Send stop command (ignore error if thread already stopped)
+        let _ = self.command_tx.send(CaptureCommand::Stop);
+        // Wait for thread to finish (with timeout in case of issues)
+        let _ = self.thread_handle.join();
+    }
+}
+
+/// A capture stream that's been started, and the format it was opened with.
+struct OpenCapture {
+    buffer: Arc<StdMutex<AudioBuffer>>,
+    handle: CaptureHandle,
+    sample_rate: u32,
+    channels: u16,
+    device_name: Option<String>,
+    config: CaptureConfig,
+}
+
+/// Capture kept running between recordings to fill a pre-roll buffer.
+struct PreRollStandby {
+    /// Device asked for when the standby was opened (`None` = system default).
+    requested_device: Option<String>,
+    preference: CapturePreference,
+    pre_roll_ms: u32,
+    capture: OpenCapture,
+}
+
+impl PreRollStandby {
+    /// Whether this standby can start a recording with the given settings. A standby whose
+    /// stream gave up (its thread exited) never can.
+    fn serves(
+        &self,
+        requested_device: Option<&str>,
+        preference: CapturePreference,
+        pre_roll_ms: u32,
+    ) -> bool {
+        self.requested_device.as_deref() == requested_device
+            && self.preference == preference
+            && self.pre_roll_ms == pre_roll_ms
+            && !self.capture.handle.thread_handle.is_finished()
+    }
+}
+
+/// The device name to look for, or `None` for the system default.
+fn requested_input_device(input_device_name: Option<&str>) -> Option<&str> {
+    input_device_name
+        .map(str::trim)
+        .filter(|s| !s.is_empty() && *s != "default")
+}
+
 /// Thread-safe audio capture manager
 ///
 /// This runs audio capture in a separate thread to avoid Send/Sync issues
@@ -750,6 +1137,20 @@ pub struct AudioCapture {
 
     // Most recent realtime waveform buckets (for true waveform rendering).
     waveform_meter: Arc<AudioWaveformMeter>,
+
+    // Name of the device used by the most recent `start_with_device_name`.
+    active_device_name: Option<String>,
+
+    // Set by the capture thread when the stream died and couldn't be rebuilt.
+    capture_failure: SharedCaptureFailure,
+
+    // Requested capture format, and what the most recent recording actually got.
+    capture_preference: CapturePreference,
+    capture_config: Option<CaptureConfig>,
+
+    // Lead-in/pre-roll for new recordings, and the standby stream feeding the pre-roll.
+    recording_start: RecordingStart,
+    pre_roll: Option<PreRollStandby>,
 }
 
 impl AudioCapture {
@@ -763,6 +1164,12 @@ impl AudioCapture {
             vad_config: VadAutoStopConfig::default(),
             level_meter: Arc::new(AudioLevelMeter::default()),
             waveform_meter: Arc::new(AudioWaveformMeter::default()),
+            active_device_name: None,
+            capture_failure: SharedCaptureFailure::default(),
+            capture_preference: CapturePreference::default(),
+            capture_config: None,
+            recording_start: RecordingStart::default(),
+            pre_roll: None,
         }
     }
 
@@ -776,9 +1183,20 @@ impl AudioCapture {
             vad_config,
             level_meter: Arc::new(AudioLevelMeter::default()),
             waveform_meter: Arc::new(AudioWaveformMeter::default()),
+            active_device_name: None,
+            capture_failure: SharedCaptureFailure::default(),
+            capture_preference: CapturePreference::default(),
+            capture_config: None,
+            recording_start: RecordingStart::default(),
+            pre_roll: None,
         }
     }
 
+    /// Name of the input device used by the most recent recording, if any.
+    pub fn active_device_name(&self) -> Option<&str> {
+        self.active_device_name.as_deref()
+    }
+
     /// Get the most recent realtime input level snapshot.
     ///
     /// This is updated continuously while recording (per CPAL callback). When not recording,
@@ -800,11 +1218,96 @@ impl AudioCapture {
         }
     }
 
+    pub fn shared_capture_failure(&self) -> SharedCaptureFailure {
+        self.capture_failure.clone()
+    }
+
     /// Update VAD configuration
     pub fn set_vad_config(&mut self, config: VadAutoStopConfig) {
         self.vad_config = config;
     }
 
+    /// Request a capture format for future recordings. Unsupported formats fall back to the
+    /// device default.
+    pub fn set_capture_preference(&mut self, preference: CapturePreference) {
+        self.capture_preference = preference;
+    }
+
+    /// Set how future recordings start. Switching away from pre-roll closes the standby
+    /// stream; switching to it takes effect at the next `arm_pre_roll`.
+    pub fn set_recording_start(&mut self, start: RecordingStart) {
+        self.recording_start = start;
+        if !matches!(start, RecordingStart::PreRoll(_)) {
+            self.stop_pre_roll();
+        }
+    }
+
+    /// With pre-roll configured and no recording running, make sure a standby stream is
+    /// filling the pre-roll buffer from `input_device_name`.
+    ///
+    /// This keeps the microphone open while idle. Failures are only logged; the next
+    /// recording then opens its own stream as usual.
+    pub fn arm_pre_roll(&mut self, input_device_name: Option<&str>) {
+        let RecordingStart::PreRoll(pre_roll_ms) = self.recording_start else {
+            self.stop_pre_roll();
+            return;
+        };
+        if self.capture_handle.is_some() {
+            return;
+        }
+
+        let requested = requested_input_device(input_device_name);
+        if self
+            .pre_roll
+            .as_ref()
+            .is_some_and(|standby| standby.serves(requested, self.capture_preference, pre_roll_ms))
+        {
+            return;
+        }
+        self.stop_pre_roll();
+
+        match self.open_capture(pre_roll_ms as f32 / 1000.0, requested, false) {
+            Ok(capture) => {
+                log::info!("Pre-roll capture armed ({}ms)", pre_roll_ms);
+                self.pre_roll = Some(PreRollStandby {
+                    requested_device: requested.map(str::to_string),
+                    preference: self.capture_preference,
+                    pre_roll_ms,
+                    capture,
+                });
+            }
+            Err(e) => log::warn!("Couldn't start pre-roll capture: {}", e),
+        }
+    }
+
+    /// Close the pre-roll standby stream, if one is running.
+    fn stop_pre_roll(&mut self) {
+        if let Some(standby) = self.pre_roll.take() {
+            log::info!("Stopping pre-roll capture");
+            standby.capture.handle.shutdown();
+        }
+    }
+
+    /// Take the standby stream for a recording from `requested`, if it's running with the
+    /// current settings. A standby that doesn't fit is closed.
+    fn take_pre_roll(&mut self, requested: Option<&str>) -> Option<OpenCapture> {
+        let RecordingStart::PreRoll(pre_roll_ms) = self.recording_start else {
+            return None;
+        };
+        let standby = self.pre_roll.take()?;
+        if standby.serves(requested, self.capture_preference, pre_roll_ms) {
+            Some(standby.capture)
+        } else {
+            standby.capture.handle.shutdown();
+            None
+        }
+    }
+
+    /// Capture format negotiated for the most recent recording, if any.
+    pub fn capture_config(&self) -> Option<&CaptureConfig> {
+        self.capture_config.as_ref()
+    }
+
     /// Get the current VAD configuration
     #[cfg_attr(not(test), allow(dead_code))]
     pub fn vad_config(&self) -> &VadAutoStopConfig {
@@ -832,65 +1335,97 @@ impl AudioCapture {
         // Stop any existing recording
         self.stop();
 
-        // Get device info first (on main thread)
-        let host = cpal::default_host();
-
-        let desired_name = input_device_name
-            .map(str::trim)
-            .filter(|s| !s.is_empty() && *s != "default");
-
-        let mut selected: Option<cpal::Device> = None;
-        if let Some(name) = desired_name {
-            if let Ok(devices) = host.input_devices() {
-                for d in devices {
-                    let Ok(n) = d.name() else { continue };
-                    if n == name {
-                        selected = Some(d);
-                        break;
-                    }
+        let requested = requested_input_device(input_device_name);
+        let capture = match self.take_pre_roll(requested) {
+            Some(capture) => {
+                if let Ok(mut buffer) = capture.buffer.lock() {
+                    buffer.set_max_duration_secs(max_duration_secs);
+                    log::info!(
+                        "Recording starts with {:.0}ms of pre-roll",
+                        buffer.duration_secs() * 1000.0
+                    );
                 }
-            }
-        }
-
-        let device = match selected {
-            Some(d) => {
-                log::info!("Using selected input device: {}", desired_name.unwrap_or("<unknown>"));
-                d
+                // VAD starts with the recording, so speech from before the hotkey can't end
+                // it right away.
+                while capture.handle.event_rx.try_recv().is_ok() {}
+                capture.handle.vad_active.store(true, Ordering::Relaxed);
+                self.capture_failure.clear();
+                capture
             }
             None => {
-                if let Some(name) = desired_name {
-                    log::warn!(
-                        "Selected input device '{}' not found; falling back to default input device",
-                        name
-                    );
+                self.stop_pre_roll();
+                let capture = self.open_capture(max_duration_secs, requested, true)?;
+                if let RecordingStart::Delayed(ms) = self.recording_start {
+                    if let Ok(mut buffer) = capture.buffer.lock() {
+                        buffer.discard_next_ms(ms);
+                    }
                 }
-                host.default_input_device()
-                    .ok_or(AudioCaptureError::NoInputDevice)?
+                capture
             }
         };
 
-        let config = device
+        self.buffer = capture.buffer;
+        self.sample_rate = capture.sample_rate;
+        self.channels = capture.channels;
+        self.active_device_name = capture.device_name;
+        self.capture_config = Some(capture.config);
+        self.capture_handle = Some(capture.handle);
+
+        log::info!("Audio capture started");
+        Ok(())
+    }
+
+    /// Open `requested` (or the system default) in the preferred format and start capturing
+    /// into a fresh buffer holding up to `max_duration_secs`. With `vad_active` clear, VAD
+    /// waits until it's set on the handle.
+    fn open_capture(
+        &self,
+        max_duration_secs: f32,
+        requested: Option<&str>,
+        vad_active: bool,
+    ) -> Result<OpenCapture, AudioCaptureError> {
+        // Get device info first (on main thread)
+        let host = cpal::default_host();
+
+        let (device, _is_preferred) =
+            resolve_preferred_input_device(&host, requested).ok_or(AudioCaptureError::NoInputDevice)?;
+        let device_name = device.name().ok();
+
+        let default_config = device
             .default_input_config()
             .map_err(|e| AudioCaptureError::DeviceConfig(e.to_string()))?;
+        let (config, fell_back_to_default) =
+            negotiate_input_config(&device, default_config.clone(), self.capture_preference);
 
-        self.sample_rate = config.sample_rate().0;
-        self.channels = config.channels();
+        let sample_rate = config.sample_rate().0;
+        let channels = config.channels();
+        let capture_config = CaptureConfig {
+            device_name: device_name.clone(),
+            sample_rate,
+            channels,
+            sample_format: format!("{:?}", config.sample_format()),
+            default_sample_rate: default_config.sample_rate().0,
+            default_channels: default_config.channels(),
+            requested_sample_rate: self.capture_preference.sample_rate,
+            requested_channels: self.capture_preference.channels,
+            fell_back_to_default,
+        };
 
         log::info!(
             "Audio config: {} Hz, {} channels, {:?}",
-            self.sample_rate,
-            self.channels,
+            sample_rate,
+            channels,
             config.sample_format()
         );
 
         // Create new buffer with correct params
-        self.buffer = Arc::new(StdMutex::new(AudioBuffer::new(
-            self.sample_rate,
-            self.channels,
+        let buffer = Arc::new(StdMutex::new(AudioBuffer::new(
+            sample_rate,
+            channels,
             max_duration_secs,
         )));
 
-        let buffer_clone = self.buffer.clone();
+        let buffer_clone = buffer.clone();
         let meter = self.level_meter.clone();
         let waveform_meter = self.waveform_meter.clone();
         let (command_tx, command_rx) = mpsc::channel();
@@ -898,7 +1433,10 @@ impl AudioCapture {
         let sample_format = config.sample_format();
         let stream_config: cpal::StreamConfig = config.into();
         let vad_config = self.vad_config.clone();
-        let sample_rate = self.sample_rate;
+        let vad_active = Arc::new(AtomicBool::new(vad_active));
+        let thread_vad_active = vad_active.clone();
+        let capture_failure = self.capture_failure.clone();
+        capture_failure.clear();
 
         // Spawn capture thread
         let thread_handle = thread::spawn(move || {
@@ -912,18 +1450,25 @@ impl AudioCapture {
                 command_rx,
                 event_tx,
                 vad_config,
+                thread_vad_active,
                 sample_rate,
+                capture_failure,
             )
         });
 
-        self.capture_handle = Some(CaptureHandle {
-            command_tx,
-            event_rx,
-            thread_handle,
-        });
-
-        log::info!("Audio capture started");
-        Ok(())
+        Ok(OpenCapture {
+            buffer,
+            handle: CaptureHandle {
+                command_tx,
+                event_rx,
+                vad_active,
+                thread_handle,
+            },
+            sample_rate,
+            channels,
+            device_name,
+            config: capture_config,
+        })
     }
 
     /// Stop recording and return the captured audio as WAV bytes
@@ -944,7 +1489,6 @@ impl AudioCapture {
     }
 
     /// Stop recording and return the captured audio as WAV bytes along with level stats.
-    #[cfg_attr(not(test), allow(dead_code))]
     pub fn stop_and_get_wav_with_stats(
         &mut self,
     ) -> Result<(Vec<u8>, AudioLevelStats), AudioCaptureError> {
@@ -1023,6 +1567,7 @@ impl AudioCapture {
 
         // "Before": as-captured (no downmix/resample/filters/gates).
         let (before_wav, _before_diag) = buffer.to_wav_bytes_with_config(AudioEncodeConfig {
+            silence_trim: None,
             noise_gate_threshold_dbfs: None,
             downmix_to_mono: false,
             resample_to_16khz: false,
@@ -1042,10 +1587,7 @@ impl AudioCapture {
     pub fn stop(&mut self) {
         if let Some(handle) = self.capture_handle.take() {
             log::info!("Stopping audio capture");
-            // Send stop command (ignore error if thread already stopped)
-            let _ = handle.command_tx.send(CaptureCommand::Stop);
-            // Wait for thread to finish (with timeout in case of issues)
-            let _ = handle.thread_handle.join();
+            handle.shutdown();
         }
     }
 
@@ -1105,10 +1647,80 @@ impl Default for AudioCapture {
 impl Drop for AudioCapture {
     fn drop(&mut self) {
         self.stop();
+        self.stop_pre_roll();
+    }
+}
+
+/// How many times a stream that errored mid-recording (e.g. a USB mic hiccup) is rebuilt
+/// before capture gives up.
+const MAX_STREAM_RECONNECTS: u32 = 2;
+
+/// Base delay before rebuilding a failed stream; attempt `n` waits `n` times this.
+const STREAM_RECONNECT_DELAY_MS: u64 = 250;
+
+/// A rebuilt stream that runs this long without errors earns back the full reconnect
+/// budget, so a mic that glitches now and then over a long recording isn't given up on.
+const STREAM_HEALTHY_RESET: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Backoff before reconnect attempt `attempt` (1-based).
+fn stream_reconnect_delay(attempt: u32) -> std::time::Duration {
+    std::time::Duration::from_millis(STREAM_RECONNECT_DELAY_MS * attempt.max(1) as u64)
+}
+
+/// Reconnect attempts used since the stream was last healthy.
+#[derive(Debug, Default)]
+struct ReconnectBudget {
+    used: u32,
+    /// When the current stream was (re)built after an error.
+    rebuilt_at: Option<std::time::Instant>,
+}
+
+impl ReconnectBudget {
+    /// Reset the count once the rebuilt stream has run for `STREAM_HEALTHY_RESET`.
+    fn note_running(&mut self, now: std::time::Instant) {
+        if self
+            .rebuilt_at
+            .is_some_and(|at| now.duration_since(at) >= STREAM_HEALTHY_RESET)
+        {
+            self.used = 0;
+            self.rebuilt_at = None;
+        }
+    }
+
+    /// Use one attempt; returns its 1-based number, or `None` when the budget is spent.
+    fn spend(&mut self) -> Option<u32> {
+        if self.used >= MAX_STREAM_RECONNECTS {
+            return None;
+        }
+        self.used += 1;
+        Some(self.used)
+    }
+
+    fn rebuilt(&mut self, now: std::time::Instant) {
+        self.rebuilt_at = Some(now);
+    }
+}
+
+/// Wait up to `timeout` for a stop command. Returns true if capture should end (stop
+/// received or the owner went away).
+fn stop_requested_within(
+    command_rx: &mpsc::Receiver<CaptureCommand>,
+    timeout: std::time::Duration,
+) -> bool {
+    match command_rx.recv_timeout(timeout) {
+        Ok(CaptureCommand::Stop) => true,
+        Err(mpsc::RecvTimeoutError::Timeout) => false,
+        Err(mpsc::RecvTimeoutError::Disconnected) => true,
     }
 }
 
 /// Run the audio capture in a dedicated thread
+///
+/// If the stream reports an error mid-recording, it's rebuilt on the same device up to
+/// `MAX_STREAM_RECONNECTS` times (audio captured so far is kept); the count resets once a
+/// rebuilt stream stays healthy. After that the failure is recorded in `capture_failure`
+/// and capture ends.
+#[allow(clippy::too_many_arguments)]
 fn run_capture_thread(
     device: cpal::Device,
     config: cpal::StreamConfig,
@@ -1119,13 +1731,11 @@ fn run_capture_thread(
     command_rx: mpsc::Receiver<CaptureCommand>,
     event_tx: mpsc::Sender<AudioCaptureEvent>,
     vad_config: VadAutoStopConfig,
+    vad_active: Arc<AtomicBool>,
     sample_rate: u32,
+    capture_failure: SharedCaptureFailure,
 ) -> Result<(), AudioCaptureError> {
-    use cpal::Sample;
-
-    let err_fn = |err| {
-        log::error!("Audio stream error: {}", err);
-    };
+    let (stream_error_tx, stream_error_rx) = mpsc::channel::<String>();
 
     // Create a channel for passing samples to the VAD processing thread
     let (vad_samples_tx, vad_samples_rx): (mpsc::Sender<Vec<f32>>, mpsc::Receiver<Vec<f32>>) =
@@ -1136,40 +1746,181 @@ fn run_capture_thread(
         let event_tx_clone = event_tx.clone();
         let vad_cfg = vad_config.vad_config.clone();
         Some(thread::spawn(move || {
-            let mut processor = VadFrameProcessor::new(vad_cfg, sample_rate);
-            log::info!("VAD processor initialized for {} Hz audio in dedicated thread", sample_rate);
-
-            loop {
-                match vad_samples_rx.recv_timeout(std::time::Duration::from_millis(100)) {
-                    Ok(samples) => {
-                        for event in processor.process(&samples) {
-                            let capture_event = match event {
-                                VadEvent::SpeechStart { .. } => AudioCaptureEvent::SpeechStart,
-                                VadEvent::SpeechEnd => AudioCaptureEvent::SpeechEnd,
-                                VadEvent::None => continue,
-                            };
-                            let _ = event_tx_clone.send(capture_event);
-                        }
-                    }
-                    Err(mpsc::RecvTimeoutError::Timeout) => continue,
-                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
-                }
-            }
+            run_vad_loop(vad_samples_rx, event_tx_clone, &vad_active, || {
+                let mut processor = VadFrameProcessor::new(vad_cfg.clone(), sample_rate);
+                log::info!("VAD processor initialized for {} Hz audio", sample_rate);
+                move |samples: &[f32]| processor.process(samples)
+            })
         }))
     } else {
         None
     };
 
+    let build = || {
+        build_capture_stream(
+            &device,
+            &config,
+            sample_format,
+            &buffer,
+            &meter,
+            &waveform_meter,
+            vad_config.enabled.then(|| vad_samples_tx.clone()),
+            stream_error_tx.clone(),
+        )
+    };
+
+    let mut stream = Some(build()?);
+    let mut reconnects = ReconnectBudget::default();
+    let mut pending_error: Option<String> = None;
+
+    // Wait for stop command, rebuilding the stream if it errors.
+    loop {
+        if stop_requested_within(&command_rx, std::time::Duration::from_millis(100)) {
+            break;
+        }
+
+        let Some(error) = pending_error.take().or_else(|| stream_error_rx.try_recv().ok()) else {
+            reconnects.note_running(std::time::Instant::now());
+            continue;
+        };
+        // One glitch often reports several errors; they all describe the same stream.
+        while stream_error_rx.try_recv().is_ok() {}
+        stream = None;
+
+        let Some(attempt) = reconnects.spend() else {
+            log::error!(
+                "Audio stream failed after {} reconnect attempts; giving up: {}",
+                MAX_STREAM_RECONNECTS,
+                error
+            );
+            capture_failure.set(error);
+            break;
+        };
+        let delay = stream_reconnect_delay(attempt);
+        log::warn!(
+            "Audio stream error ({}); rebuilding stream in {}ms (attempt {}/{})",
+            error,
+            delay.as_millis(),
+            attempt,
+            MAX_STREAM_RECONNECTS
+        );
+        if stop_requested_within(&command_rx, delay) {
+            break;
+        }
+
+        match build() {
+            Ok(rebuilt) => {
+                log::info!("Audio stream rebuilt");
+                reconnects.rebuilt(std::time::Instant::now());
+                stream = Some(rebuilt);
+            }
+            Err(e) => pending_error = Some(e.to_string()),
+        }
+    }
+    drop(stream);
+
+    // Drop the VAD sender to signal the VAD thread to stop
+    drop(vad_samples_tx);
+
+    // Wait for VAD thread to finish
+    if let Some(handle) = vad_handle {
+        let _ = handle.join();
+    }
+
+    Ok(())
+}
+
+/// Feed captured samples to a VAD processor and forward its events until the stream drops
+/// its sender.
+///
+/// Samples are discarded until `active` is set, and the processor is only created then. A
+/// pre-roll standby runs with it clear, so speech from before the hotkey never reaches the
+/// processor and can't fire a `SpeechEnd` that stops the new recording.
+fn run_vad_loop<P: FnMut(&[f32]) -> Vec<VadEvent>>(
+    samples_rx: mpsc::Receiver<Vec<f32>>,
+    event_tx: mpsc::Sender<AudioCaptureEvent>,
+    active: &AtomicBool,
+    mut new_processor: impl FnMut() -> P,
+) {
+    let mut processor = None;
+    loop {
+        match samples_rx.recv_timeout(std::time::Duration::from_millis(100)) {
+            Ok(samples) => {
+                if !active.load(Ordering::Relaxed) {
+                    continue;
+                }
+                let process = processor.get_or_insert_with(&mut new_processor);
+                for event in process(&samples) {
+                    let capture_event = match event {
+                        VadEvent::SpeechStart { .. } => AudioCaptureEvent::SpeechStart,
+                        VadEvent::SpeechEnd => AudioCaptureEvent::SpeechEnd,
+                        VadEvent::None => continue,
+                    };
+                    let _ = event_tx.send(capture_event);
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => continue,
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+}
+
+/// While set, every capture stream replaces its input with silence (see `set_input_muted`).
+static INPUT_MUTED: AtomicBool = AtomicBool::new(false);
+
+/// Mute or unmute the microphone without stopping capture: while muted, the stream keeps
+/// running but the buffer, meters and VAD receive silence, so unmuting resumes instantly.
+/// Returns whether the state changed.
+pub fn set_input_muted(muted: bool) -> bool {
+    INPUT_MUTED.swap(muted, Ordering::Relaxed) != muted
+}
+
+pub fn is_input_muted() -> bool {
+    INPUT_MUTED.load(Ordering::Relaxed)
+}
+
+/// Build and start the input stream, feeding the buffer, meters and (optionally) VAD.
+///
+/// Stream errors reported by cpal after start are sent to `error_tx`.
+#[allow(clippy::too_many_arguments)]
+fn build_capture_stream(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    sample_format: SampleFormat,
+    buffer: &Arc<StdMutex<AudioBuffer>>,
+    meter: &Arc<AudioLevelMeter>,
+    waveform_meter: &Arc<AudioWaveformMeter>,
+    vad_tx: Option<mpsc::Sender<Vec<f32>>>,
+    error_tx: mpsc::Sender<String>,
+) -> Result<cpal::Stream, AudioCaptureError> {
+    use cpal::Sample;
+
+    let err_fn = move |err: cpal::StreamError| {
+        log::error!("Audio stream error: {}", err);
+        let _ = error_tx.send(err.to_string());
+    };
+
     let stream = match sample_format {
         SampleFormat::F32 => {
             let buffer = buffer.clone();
             let meter = meter.clone();
             let waveform_meter = waveform_meter.clone();
-            let vad_tx = if vad_config.enabled { Some(vad_samples_tx.clone()) } else { None };
+            let vad_tx = vad_tx.clone();
             let channels = config.channels as usize;
             device.build_input_stream(
-                &config,
+                config,
                 move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                    // Muted input is recorded as silence but never reaches the VAD, so
+                    // auto-stop doesn't mistake the mute for the speaker going quiet.
+                    if is_input_muted() {
+                        meter.update(0.0, 0.0);
+                        waveform_meter.clear();
+                        if let Ok(mut buf) = buffer.lock() {
+                            buf.append_silence(data.len());
+                        }
+                        return;
+                    }
+
                     // Realtime meter (cheap math, no allocations).
                     let mut peak: f32 = 0.0;
                     let mut sum_sq: f64 = 0.0;
@@ -1211,17 +1962,18 @@ fn run_capture_thread(
             let buffer = buffer.clone();
             let meter = meter.clone();
             let waveform_meter = waveform_meter.clone();
-            let vad_tx = if vad_config.enabled { Some(vad_samples_tx.clone()) } else { None };
+            let vad_tx = vad_tx.clone();
             let channels = config.channels as usize;
             device.build_input_stream(
-                &config,
+                config,
                 move |data: &[i16], _: &cpal::InputCallbackInfo| {
+                    let muted = is_input_muted();
                     let mut peak: f32 = 0.0;
                     let mut sum_sq: f64 = 0.0;
                     let samples: Vec<f32> = data
                         .iter()
                         .map(|&s| {
-                            let f = s.to_float_sample();
+                            let f = if muted { 0.0 } else { s.to_float_sample() };
                             let a = f.abs();
                             if a > peak {
                                 peak = a;
@@ -1242,8 +1994,8 @@ fn run_capture_thread(
                         buf.append(&samples);
                     }
 
-                    // Send samples to VAD thread if enabled
-                    if let Some(ref tx) = vad_tx {
+                    // Send samples to VAD thread if enabled (never while muted)
+                    if let Some(tx) = vad_tx.as_ref().filter(|_| !muted) {
                         let mono = if channels > 1 {
                             downmix_interleaved_chunk_to_mono(&samples, channels)
                         } else {
@@ -1260,17 +2012,18 @@ fn run_capture_thread(
             let buffer = buffer.clone();
             let meter = meter.clone();
             let waveform_meter = waveform_meter.clone();
-            let vad_tx = if vad_config.enabled { Some(vad_samples_tx.clone()) } else { None };
+            let vad_tx = vad_tx.clone();
             let channels = config.channels as usize;
             device.build_input_stream(
-                &config,
+                config,
                 move |data: &[u16], _: &cpal::InputCallbackInfo| {
+                    let muted = is_input_muted();
                     let mut peak: f32 = 0.0;
                     let mut sum_sq: f64 = 0.0;
                     let samples: Vec<f32> = data
                         .iter()
                         .map(|&s| {
-                            let f = s.to_float_sample();
+                            let f = if muted { 0.0 } else { s.to_float_sample() };
                             let a = f.abs();
                             if a > peak {
                                 peak = a;
@@ -1291,8 +2044,8 @@ fn run_capture_thread(
                         buf.append(&samples);
                     }
 
-                    // Send samples to VAD thread if enabled
-                    if let Some(ref tx) = vad_tx {
+                    // Send samples to VAD thread if enabled (never while muted)
+                    if let Some(tx) = vad_tx.as_ref().filter(|_| !muted) {
                         let mono = if channels > 1 {
                             downmix_interleaved_chunk_to_mono(&samples, channels)
                         } else {
@@ -1318,40 +2071,165 @@ fn run_capture_thread(
         .play()
         .map_err(|e| AudioCaptureError::StreamStart(e.to_string()))?;
 
-    // Wait for stop command
-    loop {
-        match command_rx.recv_timeout(std::time::Duration::from_millis(100)) {
-            Ok(CaptureCommand::Stop) => break,
-            Err(mpsc::RecvTimeoutError::Timeout) => continue,
-            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+    Ok(stream)
+}
+
+/// Input device as exposed to the settings UI.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InputDeviceInfo {
+    /// Friendly CPAL device name (what the user sees).
+    pub name: String,
+    /// Normalized identifier that stays the same when the OS re-enumerates the device
+    /// (e.g. Windows renaming "Microphone (USB Audio)" to "Microphone (2- USB Audio)").
+    pub id: String,
+}
+
+/// Drop empty and duplicate device names while preserving the original order.
+///
+/// Some hosts expose the same device through multiple APIs, which shows up as duplicate
+/// entries in the settings dropdown.
+fn dedupe_device_names(names: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    names
+        .into_iter()
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty() && seen.insert(n.clone()))
+        .collect()
+}
+
+/// Normalize a device name into a stable identifier.
+///
+/// Lowercases, collapses whitespace and strips Windows' "N- " re-enumeration prefix inside
+/// parentheses.
+pub fn normalize_device_id(name: &str) -> String {
+    let lower = name.trim().to_lowercase();
+    let mut out = String::with_capacity(lower.len());
+    let mut rest = lower.as_str();
+
+    while let Some(idx) = rest.find('(') {
+        out.push_str(&rest[..=idx]);
+        rest = &rest[idx + 1..];
+
+        let digits = rest.chars().take_while(|c| c.is_ascii_digit()).count();
+        if digits > 0 && rest[digits..].starts_with("- ") {
+            rest = &rest[digits + 2..];
         }
     }
+    out.push_str(rest);
 
-    // Drop the VAD sender to signal the VAD thread to stop
-    drop(vad_samples_tx);
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
 
-    // Wait for VAD thread to finish
-    if let Some(handle) = vad_handle {
-        let _ = handle.join();
+/// Resolve a stored device name against the currently available names.
+///
+/// Fallback ladder: exact match, then case-insensitive / normalized-id match, then
+/// substring match (either direction). Returns the index into `available`.
+fn match_device_name(stored: &str, available: &[String]) -> Option<usize> {
+    let stored = stored.trim();
+    if stored.is_empty() {
+        return None;
     }
 
-    // Stream is dropped here, stopping capture
-    Ok(())
+    if let Some(i) = available.iter().position(|n| n == stored) {
+        return Some(i);
+    }
+
+    let stored_id = normalize_device_id(stored);
+    if let Some(i) = available
+        .iter()
+        .position(|n| n.eq_ignore_ascii_case(stored) || normalize_device_id(n) == stored_id)
+    {
+        return Some(i);
+    }
+
+    // Very short names would match almost anything.
+    if stored_id.chars().count() < 3 {
+        return None;
+    }
+
+    available.iter().position(|n| {
+        let id = normalize_device_id(n);
+        id.contains(&stored_id) || stored_id.contains(&id)
+    })
+}
+
+/// Find the CPAL input device best matching a stored device name.
+pub fn resolve_input_device(host: &cpal::Host, stored: &str) -> Option<cpal::Device> {
+    let devices: Vec<cpal::Device> = host.input_devices().ok()?.collect();
+    let names: Vec<String> = devices
+        .iter()
+        .map(|d| d.name().unwrap_or_default())
+        .collect();
+
+    let idx = match_device_name(stored, &names)?;
+    if names[idx] != stored {
+        log::info!(
+            "Resolved input device '{}' to '{}' via fuzzy match",
+            stored,
+            names[idx]
+        );
+    }
+    devices.into_iter().nth(idx)
+}
+
+/// The device after `current` in `available`, wrapping around, for `cycle_input_device`.
+///
+/// `current` is matched like a stored selection, so a re-enumerated name still finds its
+/// place. No selection (the system default) or one that's gone starts at the first device.
+#[cfg_attr(not(any(desktop, test)), allow(dead_code))]
+pub fn next_input_device(current: Option<&str>, available: &[String]) -> Option<String> {
+    let next = match current.and_then(|current| match_device_name(current, available)) {
+        Some(idx) => (idx + 1) % available.len(),
+        None => 0,
+    };
+    available.get(next).cloned()
+}
+
+/// Resolve the user's preferred input device, falling back to the system default only when
+/// the preferred device is genuinely gone.
+///
+/// Called on every recording start, so a USB mic that was unplugged and plugged back in
+/// (possibly re-enumerated under a slightly different name) is picked up again without the
+/// user re-selecting it. Returns the device and whether it is the preferred one.
+pub fn resolve_preferred_input_device(
+    host: &cpal::Host,
+    preferred: Option<&str>,
+) -> Option<(cpal::Device, bool)> {
+    if let Some(name) = preferred {
+        if let Some(device) = resolve_input_device(host, name) {
+            log::info!("Using selected input device: {}", name);
+            return Some((device, true));
+        }
+
+        log::warn!(
+            "Selected input device '{}' not found; falling back to default input device",
+            name
+        );
+    }
+
+    host.default_input_device().map(|d| (d, false))
 }
 
-/// Get the list of available input devices
+/// Get the list of available input devices (deduplicated, in host order)
 #[cfg_attr(not(test), allow(dead_code))]
 pub fn list_input_devices() -> Vec<String> {
     let host = cpal::default_host();
     host.input_devices()
-        .map(|devices| {
-            devices
-                .filter_map(|d| d.name().ok())
-                .collect()
-        })
+        .map(|devices| dedupe_device_names(devices.filter_map(|d| d.name().ok())))
         .unwrap_or_default()
 }
 
+/// Get available input devices with their stable identifiers.
+pub fn list_input_device_infos() -> Vec<InputDeviceInfo> {
+    list_input_devices()
+        .into_iter()
+        .map(|name| InputDeviceInfo {
+            id: normalize_device_id(&name),
+            name,
+        })
+        .collect()
+}
+
 /// Get information about the default input device
 #[cfg_attr(not(test), allow(dead_code))]
 pub fn get_default_input_device_info() -> Option<(String, u32, u16)> {
@@ -1366,6 +2244,170 @@ pub fn get_default_input_device_info() -> Option<(String, u32, u16)> {
 mod tests {
     use super::*;
 
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_dedupe_device_names_preserves_order() {
+        let deduped = dedupe_device_names(names(&["Mic A", "Mic B", "Mic A", " ", "Mic C", "Mic B"]));
+        assert_eq!(deduped, names(&["Mic A", "Mic B", "Mic C"]));
+    }
+
+    #[test]
+    fn test_normalize_device_id_strips_reenumeration_prefix() {
+        assert_eq!(
+            normalize_device_id("Microphone (2- USB Audio Device)"),
+            "microphone (usb audio device)"
+        );
+        assert_eq!(
+            normalize_device_id("  Microphone   (USB Audio Device) "),
+            "microphone (usb audio device)"
+        );
+        // Digits not followed by "- " are kept.
+        assert_eq!(normalize_device_id("Mic (2ch)"), "mic (2ch)");
+    }
+
+    #[test]
+    fn test_match_device_name_ladder() {
+        let available = names(&[
+            "MacBook Pro Microphone",
+            "Microphone (2- USB Audio Device)",
+            "microphone array",
+        ]);
+
+        // Exact match wins even when a case-insensitive match exists earlier.
+        assert_eq!(match_device_name("microphone array", &available), Some(2));
+
+        // Case-insensitive.
+        assert_eq!(match_device_name("macbook pro microphone", &available), Some(0));
+
+        // Re-enumerated Windows name (normalized id).
+        assert_eq!(match_device_name("Microphone (USB Audio Device)", &available), Some(1));
+
+        // Substring, either direction.
+        assert_eq!(match_device_name("USB Audio", &available), Some(1));
+        assert_eq!(
+            match_device_name("MacBook Pro Microphone (Built-in)", &available),
+            Some(0)
+        );
+
+        // No match / too short / empty.
+        assert_eq!(match_device_name("Bluetooth Headset", &available), None);
+        assert_eq!(match_device_name("mi", &available), None);
+        assert_eq!(match_device_name("", &available), None);
+    }
+
+    /// `lead` s of near-silence (noise at -66 dBFS), `tone` s at -6 dBFS, `tail` s of
+    /// near-silence, mono at 16 kHz.
+    fn padded_tone(lead: f32, tone: f32, tail: f32) -> Vec<f32> {
+        let segment = |secs: f32, amp: f32| {
+            (0..(secs * 16_000.0) as usize).map(move |i| if i % 2 == 0 { amp } else { -amp })
+        };
+        segment(lead, 0.0005)
+            .chain(segment(tone, 0.5))
+            .chain(segment(tail, 0.0005))
+            .collect()
+    }
+
+    #[test]
+    fn test_trim_silence_keeps_padded_speech() {
+        let samples = padded_tone(1.0, 0.5, 1.0);
+        let trimmed = trim_silence_interleaved(&samples, 16_000, 1, SilenceTrimConfig::default());
+        let pad = (SILENCE_TRIM_PAD_MS * 16) as usize;
+        assert_eq!(trimmed.len(), 8_000 + 2 * pad);
+        assert_eq!(trimmed[pad], 0.5);
+        assert_eq!(trimmed[pad - 1].abs(), 0.0005);
+    }
+
+    #[test]
+    fn test_trim_silence_respects_max_trim_and_all_silent_input() {
+        let samples = padded_tone(3.0, 0.5, 3.0);
+        let cfg = SilenceTrimConfig {
+            max_trim_ms: 500,
+            ..Default::default()
+        };
+        let trimmed = trim_silence_interleaved(&samples, 16_000, 1, cfg);
+        assert_eq!(trimmed.len(), samples.len() - 2 * 8_000);
+
+        // Nothing above the threshold: left for the quiet-audio gate.
+        let silent = padded_tone(1.0, 0.0, 1.0);
+        let trimmed = trim_silence_interleaved(&silent, 16_000, 1, SilenceTrimConfig::default());
+        assert_eq!(trimmed.len(), silent.len());
+
+        // A higher threshold treats the quiet tone as silence too.
+        let quiet = padded_tone(0.0, 0.5, 0.0).iter().map(|s| s * 0.01).collect::<Vec<_>>();
+        let cfg = SilenceTrimConfig {
+            threshold_dbfs: -30.0,
+            ..Default::default()
+        };
+        assert_eq!(trim_silence_interleaved(&quiet, 16_000, 1, cfg).len(), quiet.len());
+    }
+
+    #[test]
+    fn test_trim_silence_stereo_uses_loudest_channel() {
+        // 1s silence, 0.5s with sound in the right channel only, 1s silence.
+        let frames = |secs: f32, right: f32| {
+            (0..(secs * 8_000.0) as usize).flat_map(move |_| [0.0, right])
+        };
+        let samples: Vec<f32> = frames(1.0, 0.0)
+            .chain(frames(0.5, 0.5))
+            .chain(frames(1.0, 0.0))
+            .collect();
+        let trimmed = trim_silence_interleaved(&samples, 8_000, 2, SilenceTrimConfig::default());
+        let pad_frames = (SILENCE_TRIM_PAD_MS * 8) as usize;
+        assert_eq!(trimmed.len(), 2 * (4_000 + 2 * pad_frames));
+        assert_eq!(trimmed[2 * pad_frames + 1], 0.5);
+    }
+
+    #[test]
+    fn test_set_input_muted_reports_changes() {
+        assert!(!is_input_muted());
+        assert!(set_input_muted(true));
+        assert!(is_input_muted());
+        assert!(!set_input_muted(true));
+        assert!(set_input_muted(false));
+        assert!(!is_input_muted());
+    }
+
+    #[test]
+    fn test_next_input_device_wraps_around() {
+        let available = names(&["Headset", "Microphone (2- USB Audio Device)", "Desk Mic"]);
+        let next = |current| next_input_device(current, &available);
+
+        assert_eq!(next(Some("Headset")).as_deref(), Some("Microphone (2- USB Audio Device)"));
+        assert_eq!(next(Some("Desk Mic")).as_deref(), Some("Headset"));
+        // Re-enumerated name of the selected device.
+        assert_eq!(next(Some("Microphone (USB Audio Device)")).as_deref(), Some("Desk Mic"));
+        // System default or an unplugged device: start at the top.
+        assert_eq!(next(None).as_deref(), Some("Headset"));
+        assert_eq!(next(Some("Bluetooth Headphones")).as_deref(), Some("Headset"));
+
+        assert_eq!(next_input_device(Some("Headset"), &[]), None);
+    }
+
+    #[test]
+    fn test_pick_format_range_prefers_default_sample_format() {
+        let range = |channels, min_sample_rate, max_sample_rate, default_sample_format| FormatRange {
+            channels,
+            min_sample_rate,
+            max_sample_rate,
+            default_sample_format,
+        };
+        let ranges = [
+            range(2, 44_100, 48_000, true),
+            range(1, 8_000, 48_000, false),
+            range(1, 16_000, 16_000, true),
+        ];
+
+        assert_eq!(pick_format_range(&ranges, 16_000, 1), Some(2));
+        assert_eq!(pick_format_range(&ranges, 22_050, 1), Some(1));
+        assert_eq!(pick_format_range(&ranges, 48_000, 2), Some(0));
+        // Unsupported: the caller falls back to the device default.
+        assert_eq!(pick_format_range(&ranges, 16_000, 2), None);
+        assert_eq!(pick_format_range(&ranges, 96_000, 1), None);
+    }
+
     #[test]
     fn test_audio_buffer_creation() {
         let buffer = AudioBuffer::new(16000, 1, 60.0);
@@ -1381,6 +2423,18 @@ mod tests {
         assert_eq!(buffer.len(), 3);
     }
 
+    #[test]
+    fn test_audio_buffer_append_silence() {
+        let mut buffer = AudioBuffer::new(1000, 1, 1.0);
+        buffer.discard_next_ms(100);
+        buffer.append_silence(150);
+        assert_eq!(buffer.len(), 50);
+        buffer.append(&[0.5; 10]);
+        buffer.append_silence(2000);
+        assert_eq!(buffer.len(), 1000);
+        assert!(buffer.samples.iter().all(|&s| s == 0.0));
+    }
+
     #[test]
     fn test_audio_buffer_clear() {
         let mut buffer = AudioBuffer::new(16000, 1, 60.0);
@@ -1410,4 +2464,134 @@ mod tests {
         // Should be trimmed to 1 second
         assert_eq!(buffer.len(), 1000);
     }
+
+    #[test]
+    fn test_recording_start_from_delay() {
+        assert_eq!(RecordingStart::from_delay_ms(0), RecordingStart::Immediate);
+        assert_eq!(RecordingStart::from_delay_ms(250), RecordingStart::Delayed(250));
+        assert_eq!(RecordingStart::from_delay_ms(-300), RecordingStart::PreRoll(300));
+        assert_eq!(RecordingStart::from_delay_ms(-60_000), RecordingStart::PreRoll(2_000));
+    }
+
+    #[test]
+    fn test_audio_buffer_lead_in_and_pre_roll() {
+        // Lead-in: the first 100ms (of stereo audio) are dropped across appends.
+        let mut buffer = AudioBuffer::new(1000, 2, 60.0);
+        buffer.discard_next_ms(100);
+        buffer.append(&[0.5; 150]);
+        assert!(buffer.is_empty());
+        buffer.append(&[0.5; 150]);
+        assert_eq!(buffer.len(), 100);
+
+        // Pre-roll: a 0.5s rolling buffer keeps its tail when it becomes a recording.
+        let mut buffer = AudioBuffer::new(1000, 1, 0.5);
+        buffer.append(&[0.1; 2000]);
+        assert_eq!(buffer.len(), 500);
+        buffer.set_max_duration_secs(60.0);
+        buffer.append(&[0.2; 1000]);
+        assert_eq!(buffer.len(), 1500);
+    }
+
+    #[test]
+    fn test_recording_level_stats_flags_quiet_recordings() {
+        let quiet = RecordingLevelStats::from(AudioLevelStats {
+            duration_secs: 2.0,
+            rms: 0.001,
+            peak: 0.01,
+        });
+        assert!(quiet.too_quiet);
+        assert!((quiet.peak_dbfs - -40.0).abs() < 0.01);
+
+        let normal = RecordingLevelStats::from(AudioLevelStats {
+            duration_secs: 2.0,
+            rms: 0.1,
+            peak: 0.5,
+        });
+        assert!(!normal.too_quiet);
+
+        let silent = RecordingLevelStats::from(AudioLevelStats {
+            duration_secs: 1.0,
+            rms: 0.0,
+            peak: 0.0,
+        });
+        assert!(silent.too_quiet);
+        assert_eq!(silent.rms_dbfs, LEVEL_DBFS_FLOOR);
+    }
+
+    #[test]
+    fn test_stream_reconnect_backoff_and_stop() {
+        assert_eq!(stream_reconnect_delay(1).as_millis(), STREAM_RECONNECT_DELAY_MS as u128);
+        assert!(stream_reconnect_delay(2) > stream_reconnect_delay(1));
+
+        let (tx, rx) = mpsc::channel();
+        let wait = std::time::Duration::from_millis(5);
+        assert!(!stop_requested_within(&rx, wait));
+        tx.send(CaptureCommand::Stop).unwrap();
+        assert!(stop_requested_within(&rx, wait));
+        drop(tx);
+        assert!(stop_requested_within(&rx, wait));
+    }
+
+    #[test]
+    fn test_reconnect_budget_resets_after_healthy_stream() {
+        let start = std::time::Instant::now();
+        let mut budget = ReconnectBudget::default();
+        assert_eq!(budget.spend(), Some(1));
+        budget.rebuilt(start);
+        assert_eq!(budget.spend(), Some(2));
+        budget.rebuilt(start);
+        assert_eq!(budget.spend(), None);
+
+        // A short healthy stretch doesn't refill the budget; a long one does.
+        budget.note_running(start + STREAM_HEALTHY_RESET / 2);
+        assert_eq!(budget.spend(), None);
+        budget.note_running(start + STREAM_HEALTHY_RESET);
+        assert_eq!(budget.spend(), Some(1));
+    }
+
+    #[test]
+    fn test_vad_starts_fresh_when_recording_takes_pre_roll() {
+        // Rendezvous channel: a send returns once the VAD loop has taken the chunk, i.e.
+        // after it finished with the one before.
+        let (samples_tx, samples_rx) = mpsc::sync_channel::<Vec<f32>>(0);
+        let (event_tx, event_rx) = mpsc::channel();
+        let active = Arc::new(AtomicBool::new(false));
+        let created = Arc::new(AtomicU32::new(0));
+        let vad = {
+            let (active, created) = (active.clone(), created.clone());
+            thread::spawn(move || {
+                run_vad_loop(samples_rx, event_tx, &active, || {
+                    created.fetch_add(1, Ordering::SeqCst);
+                    // Speech starts with loud audio and ends at the next quiet chunk.
+                    let mut speaking = false;
+                    move |samples: &[f32]| {
+                        let loud = samples.iter().any(|s| s.abs() > 0.5);
+                        let event = match (speaking, loud) {
+                            (false, true) => VadEvent::SpeechStart { pre_roll: Vec::new() },
+                            (true, false) => VadEvent::SpeechEnd,
+                            _ => VadEvent::None,
+                        };
+                        speaking = loud;
+                        vec![event]
+                    }
+                })
+            })
+        };
+
+        // Standby: the user is already talking before the hotkey.
+        samples_tx.send(vec![0.9; 16]).unwrap();
+        samples_tx.send(Vec::new()).unwrap();
+        assert_eq!(created.load(Ordering::SeqCst), 0);
+
+        // The recording takes the stream; a pause right away must not end it.
+        active.store(true, Ordering::Relaxed);
+        samples_tx.send(vec![0.0; 16]).unwrap();
+        samples_tx.send(vec![0.9; 16]).unwrap();
+        drop(samples_tx);
+        vad.join().unwrap();
+
+        let events: Vec<AudioCaptureEvent> = event_rx.try_iter().collect();
+        assert!(matches!(events[..], [AudioCaptureEvent::SpeechStart]));
+        assert_eq!(created.load(Ordering::SeqCst), 1);
+    }
 }
@@ -0,0 +1,232 @@
+//! Clipboard writes that mustn't hang an output: restores run one at a time on a worker
+//! thread and are given up on after a timeout, and any newer write supersedes a restore
+//! that hasn't run yet.
+
+use crate::clipboard_snapshot::{self, ClipboardSnapshot};
+use crate::commands::error::CommandError;
+use arboard::Clipboard;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+/// Longest an output waits for the clipboard restore. A slower write (a hung clipboard owner,
+/// an app choking on huge content) is left to finish on the restore worker.
+const CLIPBOARD_RESTORE_TIMEOUT_MS: u64 = 1_000;
+
+/// Minimal clipboard write surface, so the restore logic can be unit tested without a
+/// real clipboard.
+pub(crate) trait ClipboardWrite {
+    fn write_text(&mut self, text: &str) -> Result<(), CommandError>;
+}
+
+/// Clipboard writes made on the restore worker and given up on after
+/// `CLIPBOARD_RESTORE_TIMEOUT_MS`, for restores: the output already succeeded, so a hung
+/// clipboard must not hang it. `concealed` hides the write from clipboard managers
+/// (`clipboard_manager_friendly`).
+pub(crate) struct TimedClipboard {
+    concealed: bool,
+}
+
+impl TimedClipboard {
+    pub(crate) fn new(concealed: bool) -> Self {
+        Self { concealed }
+    }
+
+    pub(crate) fn plain() -> Self {
+        Self::new(false)
+    }
+}
+
+impl ClipboardWrite for TimedClipboard {
+    fn write_text(&mut self, text: &str) -> Result<(), CommandError> {
+        let text = text.to_string();
+        let concealed = self.concealed;
+        write_within(Duration::from_millis(CLIPBOARD_RESTORE_TIMEOUT_MS), move || {
+            let mut clipboard = Clipboard::new()?;
+            if concealed {
+                set_concealed_text(&mut clipboard, &text)
+            } else {
+                clipboard.set_text(text)
+            }
+        })
+        .map_err(CommandError::clipboard)
+    }
+}
+
+/// Bumped by every queued restore, every restore given up on, and every clipboard write an
+/// output makes (`supersede_clipboard_restores`). The restore worker skips a job queued
+/// under an older value, so a restore delayed by a hung clipboard can't later overwrite
+/// newer content.
+static CLIPBOARD_RESTORE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// A write queued on the restore worker by `write_within`.
+struct ClipboardRestoreJob {
+    generation: u64,
+    write: Box<dyn FnOnce() -> Result<(), arboard::Error> + Send>,
+    reply: mpsc::Sender<Result<(), arboard::Error>>,
+}
+
+static CLIPBOARD_RESTORE_WORKER: OnceLock<Mutex<mpsc::Sender<ClipboardRestoreJob>>> =
+    OnceLock::new();
+
+/// The single thread that runs restore writes, one at a time and in order.
+fn clipboard_restore_worker() -> mpsc::Sender<ClipboardRestoreJob> {
+    CLIPBOARD_RESTORE_WORKER
+        .get_or_init(|| {
+            let (tx, rx) = mpsc::channel::<ClipboardRestoreJob>();
+            let spawned = thread::Builder::new()
+                .name("clipboard-restore".to_string())
+                .spawn(move || {
+                    for job in rx {
+                        let current = CLIPBOARD_RESTORE_GENERATION.load(Ordering::SeqCst);
+                        let result = if current == job.generation {
+                            (job.write)()
+                        } else {
+                            log::debug!("Skipping clipboard restore superseded by a newer write");
+                            Err(arboard::Error::Unknown {
+                                description: "superseded by a newer clipboard write".to_string(),
+                            })
+                        };
+                        let _ = job.reply.send(result);
+                    }
+                });
+            if let Err(e) = spawned {
+                log::warn!("Failed to start the clipboard restore worker: {}", e);
+            }
+            Mutex::new(tx)
+        })
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Invalidate restores that haven't run yet; called before an output writes the clipboard.
+pub(crate) fn supersede_clipboard_restores() {
+    CLIPBOARD_RESTORE_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Queue `write` on the restore worker and wait at most `timeout` for it. On timeout the job
+/// is invalidated (if it hasn't started, it never runs) and an error is returned.
+fn write_within(
+    timeout: Duration,
+    write: impl FnOnce() -> Result<(), arboard::Error> + Send + 'static,
+) -> Result<(), arboard::Error> {
+    let generation = CLIPBOARD_RESTORE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let (reply, rx) = mpsc::channel();
+    let job = ClipboardRestoreJob {
+        generation,
+        write: Box::new(write),
+        reply,
+    };
+    if clipboard_restore_worker().send(job).is_err() {
+        return Err(arboard::Error::Unknown {
+            description: "clipboard restore worker is not running".to_string(),
+        });
+    }
+    rx.recv_timeout(timeout).unwrap_or_else(|e| {
+        // Leave a newer job's generation alone; it invalidated this one already.
+        let _ = CLIPBOARD_RESTORE_GENERATION.compare_exchange(
+            generation,
+            generation + 1,
+            Ordering::SeqCst,
+            Ordering::SeqCst,
+        );
+        Err(arboard::Error::Unknown {
+            description: match e {
+                mpsc::RecvTimeoutError::Timeout => {
+                    format!("timed out after {}ms", timeout.as_millis())
+                }
+                mpsc::RecvTimeoutError::Disconnected => "clipboard write panicked".to_string(),
+            },
+        })
+    })
+}
+
+/// Put a full clipboard snapshot back on the restore worker, giving up after
+/// `CLIPBOARD_RESTORE_TIMEOUT_MS` like the text restores.
+pub(crate) fn restore_snapshot_within(
+    snapshot: &ClipboardSnapshot,
+    concealed: bool,
+) -> Result<(), String> {
+    let snapshot = snapshot.clone();
+    write_within(Duration::from_millis(CLIPBOARD_RESTORE_TIMEOUT_MS), move || {
+        clipboard_snapshot::restore(&snapshot, concealed)
+            .map_err(|description| arboard::Error::Unknown { description })
+    })
+    .map_err(|e| e.to_string())
+}
+
+/// Set clipboard text flagged as transient so clipboard managers (Maccy, Ditto, Klipper,
+/// Windows clipboard history) skip it: `ExcludeClipboardContentFromMonitorProcessing` on
+/// Windows, the nspasteboard.org concealed marker on macOS and KDE's password-manager hint
+/// on Linux. Managers that ignore these conventions still see the write.
+pub(crate) fn set_concealed_text(
+    clipboard: &mut Clipboard,
+    text: &str,
+) -> Result<(), arboard::Error> {
+    let set = clipboard.set();
+    #[cfg(target_os = "windows")]
+    let set = {
+        use arboard::SetExtWindows;
+        set.exclude_from_monitoring()
+            .exclude_from_history()
+            .exclude_from_cloud()
+    };
+    #[cfg(target_os = "macos")]
+    let set = {
+        use arboard::SetExtApple;
+        set.exclude_from_history()
+    };
+    #[cfg(target_os = "linux")]
+    let set = {
+        use arboard::SetExtLinux;
+        set.exclude_from_history()
+    };
+    set.text(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    #[test]
+    fn test_hung_write_is_given_up_on() {
+        let started = Instant::now();
+        let result = write_within(Duration::from_millis(50), || {
+            thread::sleep(Duration::from_millis(300));
+            Ok(())
+        });
+        assert!(matches!(result, Err(arboard::Error::Unknown { .. })));
+        assert!(started.elapsed() < Duration::from_secs(1));
+        // Queued behind the hung write; runs once it finishes.
+        assert!(write_within(Duration::from_secs(2), || Ok(())).is_ok());
+    }
+
+    #[test]
+    fn test_abandoned_restore_never_writes() {
+        let (release, hold) = mpsc::channel::<()>();
+        let blocker = write_within(Duration::from_millis(10), move || {
+            let _ = hold.recv_timeout(Duration::from_secs(2));
+            Ok(())
+        });
+        assert!(blocker.is_err());
+
+        // Given up on while still queued behind the blocker.
+        let stale_ran = Arc::new(AtomicBool::new(false));
+        let ran = stale_ran.clone();
+        let stale = write_within(Duration::from_millis(10), move || {
+            ran.store(true, Ordering::SeqCst);
+            Ok(())
+        });
+        assert!(stale.is_err());
+
+        drop(release);
+        assert!(write_within(Duration::from_secs(3), || Ok(())).is_ok());
+        assert!(!stale_ran.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,267 @@
+//! Full clipboard capture/restore around a paste (Windows).
+//!
+//! arboard only round-trips plain text, so restoring after a paste drops every other format
+//! the previous owner offered (CF_UNICODETEXT vs CF_TEXT/CF_LOCALE, RTF, HTML, Excel's
+//! Biff/XML Spreadsheet, ...). Pasting into Excel after dictating then behaves differently
+//! than before. On Windows we snapshot each memory-backed format's bytes before overwriting
+//! and put them all back afterwards. Other platforms return `None` and callers keep using
+//! the text-only restore.
+
+/// Everything the clipboard held before we overwrote it.
+#[derive(Debug, Clone)]
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub struct ClipboardSnapshot {
+    formats: Vec<(u32, Vec<u8>)>,
+}
+
+impl ClipboardSnapshot {
+    /// Total size of the captured data.
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    fn total_bytes(&self) -> usize {
+        self.formats.iter().map(|(_, data)| data.len()).sum()
+    }
+}
+
+/// Capture every restorable clipboard format, or `None` if the clipboard is empty, couldn't
+/// be read, holds more than `max_bytes`, or the platform isn't supported.
+pub fn capture(max_bytes: usize) -> Option<ClipboardSnapshot> {
+    platform::capture(max_bytes)
+}
+
+/// Replace the clipboard contents with `snapshot`. With `conceal`, the restore is flagged so
+/// clipboard monitors (Ditto, Windows clipboard history) don't record it as a new entry.
+pub fn restore(snapshot: &ClipboardSnapshot, conceal: bool) -> Result<(), String> {
+    platform::restore(snapshot, conceal)
+}
+
+/// Formats whose data is a GDI/metafile handle or owner-drawn rather than an HGLOBAL, so
+/// their bytes can't be copied out and back in. Everything else is plain global memory.
+#[cfg_attr(not(any(target_os = "windows", test)), allow(dead_code))]
+fn is_restorable_format(format: u32) -> bool {
+    const CF_BITMAP: u32 = 2;
+    const CF_METAFILEPICT: u32 = 3;
+    const CF_PALETTE: u32 = 9;
+    const CF_ENHMETAFILE: u32 = 14;
+    const CF_OWNERDISPLAY: u32 = 0x0080;
+    const CF_DSPBITMAP: u32 = 0x0082;
+    const CF_DSPMETAFILEPICT: u32 = 0x0083;
+    const CF_DSPENHMETAFILE: u32 = 0x008E;
+    const CF_GDIOBJFIRST: u32 = 0x0300;
+    const CF_GDIOBJLAST: u32 = 0x03FF;
+
+    !matches!(
+        format,
+        CF_BITMAP
+            | CF_METAFILEPICT
+            | CF_PALETTE
+            | CF_ENHMETAFILE
+            | CF_OWNERDISPLAY
+            | CF_DSPBITMAP
+            | CF_DSPMETAFILEPICT
+            | CF_DSPENHMETAFILE
+            | CF_GDIOBJFIRST..=CF_GDIOBJLAST
+    )
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{is_restorable_format, ClipboardSnapshot};
+    use std::thread;
+    use std::time::Duration;
+    use windows::core::w;
+    use windows::Win32::Foundation::{HANDLE, HGLOBAL};
+    use windows::Win32::System::DataExchange::{
+        CloseClipboard, EmptyClipboard, EnumClipboardFormats, GetClipboardData, OpenClipboard,
+        RegisterClipboardFormatW, SetClipboardData,
+    };
+    use windows::Win32::System::Memory::{
+        GlobalAlloc, GlobalFree, GlobalLock, GlobalSize, GlobalUnlock, GMEM_MOVEABLE, GMEM_ZEROINIT,
+    };
+
+    /// Another app may hold the clipboard open briefly (e.g. a clipboard manager reacting to
+    /// our own write), so opening is retried a few times.
+    const OPEN_ATTEMPTS: u32 = 5;
+    const OPEN_RETRY_MS: u64 = 20;
+
+    /// Closes the clipboard on drop so every early return releases it.
+    struct OpenClipboardGuard;
+
+    impl OpenClipboardGuard {
+        fn open() -> Result<Self, String> {
+            let mut last_error = String::new();
+            for attempt in 0..OPEN_ATTEMPTS {
+                if attempt > 0 {
+                    thread::sleep(Duration::from_millis(OPEN_RETRY_MS));
+                }
+                // SAFETY: no owner window; the guard closes the clipboard again.
+                match unsafe { OpenClipboard(None) } {
+                    Ok(()) => return Ok(Self),
+                    Err(e) => last_error = e.to_string(),
+                }
+            }
+            Err(format!("OpenClipboard failed: {}", last_error))
+        }
+    }
+
+    impl Drop for OpenClipboardGuard {
+        fn drop(&mut self) {
+            // SAFETY: only constructed after a successful OpenClipboard on this thread.
+            let _ = unsafe { CloseClipboard() };
+        }
+    }
+
+    /// Copy the bytes of one HGLOBAL-backed clipboard format.
+    ///
+    /// SAFETY: the clipboard must be open on this thread.
+    unsafe fn read_format(format: u32) -> Option<Vec<u8>> {
+        let handle = GetClipboardData(format).ok()?;
+        let hglobal = HGLOBAL(handle.0);
+        let size = GlobalSize(hglobal);
+        if size == 0 {
+            return None;
+        }
+        let ptr = GlobalLock(hglobal) as *const u8;
+        if ptr.is_null() {
+            return None;
+        }
+        let data = std::slice::from_raw_parts(ptr, size).to_vec();
+        let _ = GlobalUnlock(hglobal);
+        Some(data)
+    }
+
+    pub fn capture(max_bytes: usize) -> Option<ClipboardSnapshot> {
+        let _guard = match OpenClipboardGuard::open() {
+            Ok(guard) => guard,
+            Err(e) => {
+                log::debug!("Clipboard snapshot skipped: {}", e);
+                return None;
+            }
+        };
+
+        let mut snapshot = ClipboardSnapshot {
+            formats: Vec::new(),
+        };
+        let mut format = 0;
+        loop {
+            // SAFETY: the clipboard is open (guard).
+            format = unsafe { EnumClipboardFormats(format) };
+            if format == 0 {
+                break;
+            }
+            if !is_restorable_format(format) {
+                log::debug!("Clipboard format {} can't be restored; skipping", format);
+                continue;
+            }
+            // SAFETY: the clipboard is open (guard).
+            if let Some(data) = unsafe { read_format(format) } {
+                snapshot.formats.push((format, data));
+            }
+            if snapshot.total_bytes() > max_bytes {
+                log::info!(
+                    "Previous clipboard exceeds {} bytes; it will not be restored after paste",
+                    max_bytes
+                );
+                return None;
+            }
+        }
+
+        (!snapshot.formats.is_empty()).then_some(snapshot)
+    }
+
+    /// Add the `ExcludeClipboardContentFromMonitorProcessing` marker to the clipboard we just
+    /// filled. Monitors only check that the format is present. Must be called while the
+    /// clipboard is open and owned by us; best-effort.
+    fn mark_excluded_from_monitoring() {
+        // SAFETY: the caller holds the clipboard open as owner; the block is ours until
+        // SetClipboardData succeeds, and freed if it fails.
+        unsafe {
+            let format = RegisterClipboardFormatW(w!("ExcludeClipboardContentFromMonitorProcessing"));
+            if format == 0 {
+                return;
+            }
+            let Ok(hglobal) = GlobalAlloc(GMEM_MOVEABLE | GMEM_ZEROINIT, std::mem::size_of::<u32>()) else {
+                return;
+            };
+            if SetClipboardData(format, Some(HANDLE(hglobal.0))).is_err() {
+                log::debug!("Couldn't mark restored clipboard as excluded from monitoring");
+                let _ = GlobalFree(Some(hglobal));
+            }
+        }
+    }
+
+    pub fn restore(snapshot: &ClipboardSnapshot, conceal: bool) -> Result<(), String> {
+        let _guard = OpenClipboardGuard::open()?;
+
+        // SAFETY: the clipboard is open (guard); EmptyClipboard makes us the owner, which
+        // SetClipboardData requires.
+        unsafe { EmptyClipboard() }.map_err(|e| format!("EmptyClipboard failed: {}", e))?;
+
+        let mut restored = 0;
+        for (format, data) in &snapshot.formats {
+            // SAFETY: we allocate and fill the block ourselves; on success ownership moves to
+            // the system, on failure we free it.
+            unsafe {
+                let Ok(hglobal) = GlobalAlloc(GMEM_MOVEABLE, data.len()) else {
+                    continue;
+                };
+                let ptr = GlobalLock(hglobal) as *mut u8;
+                if ptr.is_null() {
+                    let _ = GlobalFree(Some(hglobal));
+                    continue;
+                }
+                std::ptr::copy_nonoverlapping(data.as_ptr(), ptr, data.len());
+                let _ = GlobalUnlock(hglobal);
+
+                if SetClipboardData(*format, Some(HANDLE(hglobal.0))).is_ok() {
+                    restored += 1;
+                } else {
+                    log::debug!("SetClipboardData failed for format {}", format);
+                    let _ = GlobalFree(Some(hglobal));
+                }
+            }
+        }
+
+        if restored == 0 {
+            return Err("No clipboard formats could be restored".to_string());
+        }
+        if conceal {
+            mark_excluded_from_monitoring();
+        }
+        log::debug!(
+            "Restored {} of {} clipboard formats",
+            restored,
+            snapshot.formats.len()
+        );
+        Ok(())
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use super::ClipboardSnapshot;
+
+    pub fn capture(_max_bytes: usize) -> Option<ClipboardSnapshot> {
+        None
+    }
+
+    pub fn restore(_snapshot: &ClipboardSnapshot, _conceal: bool) -> Result<(), String> {
+        Err("Full clipboard restore is only available on Windows".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restorable_formats() {
+        // CF_TEXT, CF_UNICODETEXT, CF_LOCALE, CF_HDROP and registered formats (RTF, HTML).
+        for format in [1, 13, 16, 15, 0xC000, 0xC0FF] {
+            assert!(is_restorable_format(format), "{}", format);
+        }
+        // Handle-based formats.
+        for format in [2, 3, 9, 14, 0x0080, 0x0082, 0x0300, 0x03FF] {
+            assert!(!is_restorable_format(format), "{}", format);
+        }
+    }
+}
@@ -1,29 +1,110 @@
+use super::error::CommandError;
 use crate::audio::{self, AudioCue, SoundType};
 use crate::audio_capture;
+use crate::cue_themes;
+use crate::pipeline::SharedPipeline;
+use crate::tts;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::thread;
 use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+#[cfg(desktop)]
+use tauri_plugin_store::StoreExt;
+
+/// Default gap between the start and stop sounds in the preview sequence.
+const DEFAULT_PREVIEW_GAP_MS: u64 = 140;
+
+/// Upper bound for the preview gap so a typo can't park the preview thread for minutes.
+const MAX_PREVIEW_GAP_MS: u64 = 5_000;
+
+/// How often the output device watcher re-checks for an audio output device.
+const OUTPUT_DEVICE_POLL_INTERVAL_MS: u64 = 5_000;
+
+/// Gap between cues in `preview_all_cues`.
+const PREVIEW_ALL_GAP_MS: u64 = 350;
+
+/// Longest mic test `capture_test_audio` will record.
+const MAX_TEST_CAPTURE_SECS: u32 = 30;
+
+/// File (in the app data dir) the mic test recording is written to, replacing the last one.
+const TEST_CAPTURE_FILE: &str = "mic-test.wav";
+
+/// Most `set_silence_trim` will cut from each end of a recording.
+const MAX_SILENCE_TRIM_MS: u32 = 10_000;
+
+/// Settings an audio profile bundles: cue selection, cue volume and cue output device.
+#[cfg(desktop)]
+const AUDIO_PROFILE_SETTINGS: &[&str] = &[
+    "sound_enabled",
+    "audio_cue",
+    "audio_cue_start",
+    "audio_cue_stop",
+    "output_mode_cues",
+    "active_cue_theme",
+    "cue_volume",
+    "cue_output_device",
+];
+
+/// Longest audio profile name, in characters.
+#[cfg(desktop)]
+const MAX_AUDIO_PROFILE_NAME_CHARS: usize = 64;
+
+/// Bumped to cancel an in-flight `preview_all_cues` sequence.
+static PREVIEW_GENERATION: AtomicU64 = AtomicU64::new(0);
 
 /// Play the selected cue once as a short preview.
 ///
 /// Frontend passes the cue string (e.g. "tangerine"). Unknown values fall back to Tangerine.
+/// By default the start and stop sounds are played back to back; pass `sound` ("start",
+/// "stop", "error" or "no_speech") to audition a single sound instead. `gap_ms` sets the pause between
+/// start and stop (default 140ms, clamped to 5s) to match a real workflow's timing.
 #[tauri::command]
-pub async fn play_audio_cue_preview(cue: String) -> Result<(), String> {
+pub async fn play_audio_cue_preview(
+    cue: String,
+    sound: Option<String>,
+    gap_ms: Option<u64>,
+) -> Result<(), CommandError> {
     let cue = AudioCue::from_str(&cue);
 
+    if let Some(sound) = sound {
+        let sound_type = SoundType::from_str(&sound)
+            .ok_or_else(|| CommandError::InvalidArgument(format!("Invalid sound type: {}", sound)))?;
+
+        log::info!("Previewing audio cue: {:?} ({:?})", cue, sound_type);
+        thread::spawn(move || {
+            let outcome = audio::play_sound_blocking_undebounced(sound_type, cue);
+            if let Some(e) = outcome.error() {
+                log::warn!("Failed to play preview sound: {}", e);
+            }
+        });
+        return Ok(());
+    }
+
+    let gap_ms = gap_ms
+        .unwrap_or(DEFAULT_PREVIEW_GAP_MS)
+        .min(MAX_PREVIEW_GAP_MS);
+
     // Preview both sounds so it's obvious which pair will be used during real recording.
-    log::info!("Previewing audio cue: {:?} (start then stop)", cue);
+    log::info!(
+        "Previewing audio cue: {:?} (start then stop, {}ms gap)",
+        cue,
+        gap_ms
+    );
 
     // Run the preview sequence off-thread so we don't block the command handler.
     thread::spawn(move || {
-        if let Err(e) = audio::play_sound_blocking(SoundType::RecordingStart, cue) {
+        let outcome = audio::play_sound_blocking_undebounced(SoundType::RecordingStart, cue);
+        if let Some(e) = outcome.error() {
             log::warn!("Failed to play preview start sound: {}", e);
             return;
         }
 
         // A small deliberate gap so users can clearly distinguish start vs stop.
-        thread::sleep(Duration::from_millis(140));
+        thread::sleep(Duration::from_millis(gap_ms));
 
-        if let Err(e) = audio::play_sound_blocking(SoundType::RecordingStop, cue) {
+        let outcome = audio::play_sound_blocking_undebounced(SoundType::RecordingStop, cue);
+        if let Some(e) = outcome.error() {
             log::warn!("Failed to play preview stop sound: {}", e);
         }
     });
@@ -31,6 +112,776 @@ pub async fn play_audio_cue_preview(cue: String) -> Result<(), String> {
     Ok(())
 }
 
+/// A cue theme as listed by `list_audio_cues`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AudioCueInfo {
+    /// Settings value (`audio_cue`, `audio_cue_start`, ...).
+    pub id: &'static str,
+    pub display_name: &'static str,
+    pub description: &'static str,
+    pub start_ms: u64,
+    pub stop_ms: u64,
+    /// Whether the theme plays sound files the user has to provide. Every built-in theme
+    /// is bundled, so this is false for all of them today.
+    pub requires_user_files: bool,
+}
+
+/// All cue themes in settings order, with what the UI needs to describe them.
+///
+/// Derived from `AudioCue::ALL`, so the frontend doesn't have to hardcode the list.
+#[tauri::command]
+pub fn list_audio_cues() -> Vec<AudioCueInfo> {
+    AudioCue::ALL
+        .into_iter()
+        .map(|cue| AudioCueInfo {
+            id: cue.to_str(),
+            display_name: cue.display_name(),
+            description: cue.description(),
+            start_ms: audio::estimated_duration(SoundType::RecordingStart, cue).as_millis() as u64,
+            stop_ms: audio::estimated_duration(SoundType::RecordingStop, cue).as_millis() as u64,
+            requires_user_files: false,
+        })
+        .collect()
+}
+
+/// Play the start sound of every cue theme in sequence.
+///
+/// Emits `cue-preview-playing` (cue name) before each cue so the UI can highlight it, and
+/// `cue-preview-finished` at the end (also when cancelled via `stop_cue_preview`).
+#[tauri::command]
+pub fn preview_all_cues(app: AppHandle) {
+    let generation = PREVIEW_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let is_current = move || PREVIEW_GENERATION.load(Ordering::SeqCst) == generation;
+
+    thread::spawn(move || {
+        for (i, cue) in AudioCue::ALL.into_iter().enumerate() {
+            if !is_current() {
+                log::info!("Cue preview sequence cancelled");
+                break;
+            }
+
+            if i > 0 {
+                thread::sleep(Duration::from_millis(PREVIEW_ALL_GAP_MS));
+                if !is_current() {
+                    log::info!("Cue preview sequence cancelled");
+                    break;
+                }
+            }
+
+            log::info!("Previewing audio cue: {:?} (start)", cue);
+            let _ = app.emit("cue-preview-playing", cue.to_str());
+
+            let outcome = audio::play_sound_blocking_undebounced(SoundType::RecordingStart, cue);
+            if let Some(e) = outcome.error() {
+                log::warn!("Failed to play preview for {:?}: {}", cue, e);
+            }
+        }
+
+        let _ = app.emit("cue-preview-finished", ());
+    });
+}
+
+/// Cancel an in-flight `preview_all_cues` sequence after the currently playing cue.
+#[tauri::command]
+pub fn stop_cue_preview() {
+    PREVIEW_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Speak `text` through the OS text-to-speech engine without waiting for it to finish.
+///
+/// Used for `tts_confirmations` previews. Text is trimmed and capped at 200 chars.
+#[tauri::command]
+pub fn speak_confirmation(text: String) -> Result<(), CommandError> {
+    if text.trim().is_empty() {
+        return Err(CommandError::InvalidArgument("Nothing to speak".to_string()));
+    }
+    tts::speak(text);
+    Ok(())
+}
+
+/// Report from `diagnose_audio`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AudioDiagnosis {
+    /// What happened to the test cue.
+    pub outcome: audio::PlaybackOutcome,
+    pub output_device: Option<String>,
+    pub sound_enabled: bool,
+    pub respect_system_dnd: bool,
+    /// The OS Do Not Disturb state, `None` where the platform can't tell.
+    pub system_dnd_active: Option<bool>,
+    /// Active user cue theme (`active_cue_theme`), if any.
+    pub cue_theme: Option<String>,
+}
+
+/// Play the start cue the way a recording would and report why it did or didn't play,
+/// together with the settings and device state that decide it. For "no sound" reports.
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn diagnose_audio(app: AppHandle) -> Result<AudioDiagnosis, CommandError> {
+    let setting = |key: &str, default: bool| -> bool {
+        app.store("settings.json")
+            .ok()
+            .and_then(|store| store.get(key))
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or(default)
+    };
+    let sound_enabled = setting("sound_enabled", true);
+    let respect_system_dnd = setting("respect_system_dnd", false);
+    let system_dnd_active = crate::system_dnd::is_dnd_active();
+    let cues = crate::get_cue_selection(&app);
+
+    let outcome = if !sound_enabled {
+        audio::PlaybackOutcome::SoundDisabled
+    } else if respect_system_dnd && system_dnd_active == Some(true) {
+        audio::PlaybackOutcome::SuppressedDnd
+    } else {
+        audio::refresh_output_availability();
+        tauri::async_runtime::spawn_blocking(move || {
+            audio::play_sound_blocking(SoundType::RecordingStart, cues)
+        })
+        .await
+        .map_err(CommandError::internal)?
+    };
+    log::info!("Audio diagnosis: {:?}", outcome);
+
+    Ok(AudioDiagnosis {
+        outcome,
+        output_device: audio::default_output_device_name(),
+        sound_enabled,
+        respect_system_dnd,
+        system_dnd_active,
+        cue_theme: active_cue_theme(&app),
+    })
+}
+
+// Stub for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn diagnose_audio(_app: AppHandle) -> Result<AudioDiagnosis, CommandError> {
+    Err(CommandError::Internal(
+        "Audio diagnostics are not supported on this platform".to_string(),
+    ))
+}
+
+/// Persist a per-sound cue override. `None` clears it so the sound follows `audio_cue` again.
+#[cfg(desktop)]
+fn set_cue_override(app: &AppHandle, key: &str, cue: Option<String>) -> Result<(), CommandError> {
+    let cue = cue
+        .map(|c| {
+            AudioCue::parse(&c)
+                .ok_or_else(|| CommandError::InvalidArgument(format!("Unknown audio cue: {}", c)))
+        })
+        .transpose()?;
+
+    let store = app
+        .store("settings.json")
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+    store.set(key, serde_json::json!(cue.map(AudioCue::to_str)));
+    store
+        .save()
+        .map_err(|e| CommandError::Storage(e.to_string()))
+}
+
+/// Choose the cue theme for the recording start sound independently of the stop sound.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn set_start_cue(app: AppHandle, cue: Option<String>) -> Result<(), CommandError> {
+    set_cue_override(&app, "audio_cue_start", cue)
+}
+
+/// Choose the cue theme for the recording stop sound independently of the start sound.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn set_stop_cue(app: AppHandle, cue: Option<String>) -> Result<(), CommandError> {
+    set_cue_override(&app, "audio_cue_stop", cue)
+}
+
+/// Choose the cue theme used while outputting in `mode` (e.g. a subtle click for keystroke
+/// modes, the full chime for paste). `None` clears the override so the mode follows the
+/// global cue again. Stored in `output_mode_cues`.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn set_mode_cue_override(
+    app: AppHandle,
+    mode: String,
+    cue: Option<String>,
+) -> Result<(), CommandError> {
+    use crate::commands::text::OutputMode;
+    use std::collections::HashMap;
+
+    let mode = OutputMode::parse(&mode)
+        .ok_or_else(|| CommandError::InvalidArgument(format!("Unknown output mode: {}", mode)))?;
+    let cue = cue
+        .map(|c| {
+            AudioCue::parse(&c)
+                .ok_or_else(|| CommandError::InvalidArgument(format!("Unknown audio cue: {}", c)))
+        })
+        .transpose()?;
+
+    let store = app
+        .store("settings.json")
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+    let mut overrides: HashMap<String, String> = store
+        .get("output_mode_cues")
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+    match cue {
+        Some(cue) => overrides.insert(mode.to_str().to_string(), cue.to_str().to_string()),
+        None => overrides.remove(mode.to_str()),
+    };
+    store.set("output_mode_cues", serde_json::json!(overrides));
+    store
+        .save()
+        .map_err(|e| CommandError::Storage(e.to_string()))
+}
+
+// Stubs for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn set_mode_cue_override(
+    _app: AppHandle,
+    _mode: String,
+    _cue: Option<String>,
+) -> Result<(), CommandError> {
+    Err(CommandError::Internal(
+        "Audio cues are not supported on this platform".to_string(),
+    ))
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn set_start_cue(_app: AppHandle, _cue: Option<String>) -> Result<(), CommandError> {
+    Err(CommandError::Internal(
+        "Audio cues are not supported on this platform".to_string(),
+    ))
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn set_stop_cue(_app: AppHandle, _cue: Option<String>) -> Result<(), CommandError> {
+    Err(CommandError::Internal(
+        "Audio cues are not supported on this platform".to_string(),
+    ))
+}
+
+/// Directory installed cue themes live in (`cue-themes` in the app data dir).
+#[cfg(desktop)]
+fn cue_themes_dir(app: &AppHandle) -> Result<std::path::PathBuf, CommandError> {
+    use tauri::Manager;
+
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| CommandError::Storage(e.to_string()))?
+        .join(cue_themes::THEMES_DIR))
+}
+
+#[cfg(desktop)]
+fn active_cue_theme(app: &AppHandle) -> Option<String> {
+    app.store("settings.json")
+        .ok()
+        .and_then(|store| store.get("active_cue_theme"))
+        .and_then(|v| serde_json::from_value(v).ok())
+}
+
+/// Install a cue theme from a folder containing `start.wav`, `stop.wav` and optionally
+/// `error.wav`. Every file must decode (and be at most 5s long) before anything is copied.
+/// `name` defaults to the folder name; installing over an existing theme replaces it.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn install_cue_theme(
+    app: AppHandle,
+    path: String,
+    name: Option<String>,
+) -> Result<cue_themes::CueThemeInfo, CommandError> {
+    let themes_dir = cue_themes_dir(&app)?;
+    let mut info = cue_themes::install(&themes_dir, std::path::Path::new(&path), name.as_deref())
+        .map_err(CommandError::InvalidArgument)?;
+    log::info!("Installed cue theme '{}' ({:?})", info.name, info.sounds);
+
+    // Reinstalling the active theme should be heard right away.
+    if active_cue_theme(&app).as_deref() == Some(info.name.as_str()) {
+        info.active = true;
+        let clips = cue_themes::load(&themes_dir, &info.name).map_err(CommandError::Storage)?;
+        audio::set_theme_clips(Some(clips));
+    }
+    Ok(info)
+}
+
+/// Installed cue themes, sorted by name, with the active one flagged.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn list_cue_themes(app: AppHandle) -> Result<Vec<cue_themes::CueThemeInfo>, CommandError> {
+    let active = active_cue_theme(&app);
+    Ok(cue_themes::list(&cue_themes_dir(&app)?, active.as_deref()))
+}
+
+/// Play an installed cue theme's sounds instead of the built-in start/stop/error cues, or go
+/// back to the built-in cues with `None`. Stored in `active_cue_theme`.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn set_active_cue_theme(app: AppHandle, name: Option<String>) -> Result<(), CommandError> {
+    let clips = name
+        .as_deref()
+        .map(|name| {
+            cue_themes::load(&cue_themes_dir(&app)?, name).map_err(CommandError::InvalidArgument)
+        })
+        .transpose()?;
+
+    let store = app
+        .store("settings.json")
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+    store.set("active_cue_theme", serde_json::json!(name));
+    store
+        .save()
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+
+    audio::set_theme_clips(clips);
+    Ok(())
+}
+
+/// Load the saved `active_cue_theme` at startup. A theme that was removed or no longer
+/// decodes is logged and the built-in cues are used.
+#[cfg(desktop)]
+pub fn apply_active_cue_theme(app: &AppHandle) {
+    let Some(name) = active_cue_theme(app) else {
+        return;
+    };
+    match cue_themes_dir(app).and_then(|dir| {
+        cue_themes::load(&dir, &name).map_err(CommandError::InvalidArgument)
+    }) {
+        Ok(clips) => {
+            log::info!("Using cue theme '{}'", name);
+            audio::set_theme_clips(Some(clips));
+        }
+        Err(e) => log::warn!("Cue theme '{}' unavailable, using built-in cues: {}", name, e),
+    }
+}
+
+/// A cue setting that doesn't point at a playable sound.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CueSettingIssue {
+    /// Setting key; `output_mode_cues.<mode>` for a single per-mode override.
+    pub key: String,
+    pub value: serde_json::Value,
+    pub reason: String,
+}
+
+/// What `validate_cue_config` found.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CueConfigReport {
+    /// Every installed theme folder, healthy or not.
+    pub themes: Vec<cue_themes::ThemeCheck>,
+    pub settings: Vec<CueSettingIssue>,
+    /// No broken themes and no bad settings; `prune_missing_cue_files` would do nothing.
+    pub healthy: bool,
+}
+
+/// What `prune_missing_cue_files` changed.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CuePruneReport {
+    /// Theme folders deleted because a required sound file was missing.
+    pub removed_themes: Vec<String>,
+    /// Theme folders left in place although they can't be played (a file doesn't decode or
+    /// the folder name is invalid); the user has to fix or delete them.
+    pub broken_themes: Vec<cue_themes::ThemeCheck>,
+    /// Settings reverted to a built-in cue (keys as in `CueSettingIssue`).
+    pub reset_settings: Vec<String>,
+}
+
+/// Cue settings that name an unknown built-in cue, or an active theme that isn't in
+/// `themes` or doesn't load.
+#[cfg(desktop)]
+fn cue_setting_issues(
+    app: &AppHandle,
+    themes: &[cue_themes::ThemeCheck],
+) -> Result<Vec<CueSettingIssue>, CommandError> {
+    use std::collections::HashMap;
+
+    let store = app
+        .store("settings.json")
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+    let mut issues = Vec::new();
+    let mut flag = |key: String, value: serde_json::Value, reason: &str| {
+        issues.push(CueSettingIssue {
+            key,
+            value,
+            reason: reason.to_string(),
+        })
+    };
+
+    for key in ["audio_cue", "audio_cue_start", "audio_cue_stop"] {
+        let Some(value) = store.get(key) else {
+            continue;
+        };
+        // The per-sound overrides are null when they follow `audio_cue`.
+        if value.is_null() && key != "audio_cue" {
+            continue;
+        }
+        if value.as_str().and_then(AudioCue::parse).is_none() {
+            flag(key.to_string(), value, "not a built-in cue");
+        }
+    }
+
+    let mode_cues: HashMap<String, String> = store
+        .get("output_mode_cues")
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+    for (mode, cue) in mode_cues {
+        if AudioCue::parse(&cue).is_none() {
+            let key = format!("output_mode_cues.{}", mode);
+            flag(key, serde_json::json!(cue), "not a built-in cue");
+        }
+    }
+
+    if let Some(name) = active_cue_theme(app) {
+        let reason = match themes.iter().find(|theme| theme.name == name) {
+            None => Some("theme is not installed"),
+            Some(theme) => theme.error.as_deref(),
+        };
+        if let Some(reason) = reason {
+            flag("active_cue_theme".to_string(), serde_json::json!(name), reason);
+        }
+    }
+    Ok(issues)
+}
+
+/// Check that every installed cue theme's files still exist and decode, and that the cue
+/// settings point at something playable. Changes nothing; see `prune_missing_cue_files`.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn validate_cue_config(app: AppHandle) -> Result<CueConfigReport, CommandError> {
+    let themes = cue_themes::check_installed(&cue_themes_dir(&app)?);
+    let settings = cue_setting_issues(&app, &themes)?;
+    let healthy = settings.is_empty() && themes.iter().all(|theme| theme.error.is_none());
+    Ok(CueConfigReport {
+        themes,
+        settings,
+        healthy,
+    })
+}
+
+/// Delete cue theme folders missing a required sound, report the other broken ones, and
+/// revert cue settings that no longer point at a playable sound: `active_cue_theme` (so a
+/// broken theme is deactivated) and the per-sound overrides go back to null (the built-in
+/// cues), `audio_cue` to Tangerine, and bad per-mode overrides are dropped.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn prune_missing_cue_files(app: AppHandle) -> Result<CuePruneReport, CommandError> {
+    use std::collections::HashMap;
+
+    let themes_dir = cue_themes_dir(&app)?;
+    let removed_themes =
+        cue_themes::remove_incomplete(&themes_dir).map_err(CommandError::Storage)?;
+    let remaining = cue_themes::check_installed(&themes_dir);
+    let issues = cue_setting_issues(&app, &remaining)?;
+    let broken_themes: Vec<cue_themes::ThemeCheck> = remaining
+        .into_iter()
+        .filter(|theme| theme.error.is_some())
+        .collect();
+
+    let store = app
+        .store("settings.json")
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+    let mut mode_cues: HashMap<String, String> = store
+        .get("output_mode_cues")
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+    for issue in &issues {
+        match issue.key.strip_prefix("output_mode_cues.") {
+            Some(mode) => {
+                mode_cues.remove(mode);
+                store.set("output_mode_cues", serde_json::json!(mode_cues));
+            }
+            None if issue.key == "audio_cue" => {
+                store.set("audio_cue", serde_json::json!(AudioCue::Tangerine.to_str()));
+            }
+            None => store.set(issue.key.as_str(), serde_json::Value::Null),
+        }
+    }
+    store
+        .save()
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+
+    if issues.iter().any(|issue| issue.key == "active_cue_theme") {
+        audio::set_theme_clips(None);
+    }
+
+    let reset_settings: Vec<String> = issues.into_iter().map(|issue| issue.key).collect();
+    log::info!(
+        "Pruned cue config: removed themes {:?}, kept broken themes {:?}, reset settings {:?}",
+        removed_themes,
+        broken_themes.iter().map(|theme| &theme.name).collect::<Vec<_>>(),
+        reset_settings
+    );
+    Ok(CuePruneReport {
+        removed_themes,
+        broken_themes,
+        reset_settings,
+    })
+}
+
+/// Load `cue_volume`, `cue_output_device`, `cue_debounce_ms` and `cue_crossfade_ms` into
+/// the cue player. Called at startup and by the commands that change them (audio profiles,
+/// settings reset and import).
+#[cfg(desktop)]
+pub fn apply_cue_settings(app: &AppHandle) {
+    let Ok(store) = app.store("settings.json") else {
+        return;
+    };
+    let volume = store
+        .get("cue_volume")
+        .and_then(|v| v.as_f64())
+        .unwrap_or(1.0);
+    audio::set_cue_volume(volume as f32);
+    audio::set_cue_output_device(
+        store
+            .get("cue_output_device")
+            .and_then(|v| serde_json::from_value(v).ok()),
+    );
+    let millis = |key: &str, default: u64| {
+        store.get(key).and_then(|v| v.as_u64()).unwrap_or(default)
+    };
+    let debounce_ms = millis("cue_debounce_ms", audio::DEFAULT_CUE_DEBOUNCE_MS);
+    audio::set_cue_debounce_ms(debounce_ms.min(2_000));
+    audio::set_cue_crossfade_ms(millis("cue_crossfade_ms", 0).min(1_000));
+}
+
+/// A named snapshot of the cue settings (see `AUDIO_PROFILE_SETTINGS`), stored in
+/// `audio_profiles` as `{name: {setting: value}}`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AudioProfile {
+    pub name: String,
+    pub settings: serde_json::Map<String, serde_json::Value>,
+}
+
+#[cfg(desktop)]
+type StoredAudioProfiles =
+    std::collections::BTreeMap<String, serde_json::Map<String, serde_json::Value>>;
+
+#[cfg(desktop)]
+fn stored_audio_profiles<R: tauri::Runtime>(
+    store: &tauri_plugin_store::Store<R>,
+) -> StoredAudioProfiles {
+    store
+        .get("audio_profiles")
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Save the current cue selection, cue volume and cue output device as profile `name`,
+/// replacing a profile of the same name.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn save_audio_profile(app: AppHandle, name: String) -> Result<AudioProfile, CommandError> {
+    let name = name.trim().to_string();
+    if name.is_empty() || name.chars().count() > MAX_AUDIO_PROFILE_NAME_CHARS {
+        return Err(CommandError::InvalidArgument(format!(
+            "Audio profile names must be 1-{} characters",
+            MAX_AUDIO_PROFILE_NAME_CHARS
+        )));
+    }
+
+    let store = app
+        .store("settings.json")
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+    let settings: serde_json::Map<String, serde_json::Value> = AUDIO_PROFILE_SETTINGS
+        .iter()
+        .filter_map(|key| store.get(*key).map(|value| (key.to_string(), value)))
+        .collect();
+
+    let mut profiles = stored_audio_profiles(&store);
+    profiles.insert(name.clone(), settings.clone());
+    store.set("audio_profiles", serde_json::json!(profiles));
+    store
+        .save()
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+
+    log::info!("Saved audio profile '{}'", name);
+    Ok(AudioProfile { name, settings })
+}
+
+/// Saved audio profiles, sorted by name.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn list_audio_profiles(app: AppHandle) -> Result<Vec<AudioProfile>, CommandError> {
+    let store = app
+        .store("settings.json")
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+    Ok(stored_audio_profiles(&store)
+        .into_iter()
+        .map(|(name, settings)| AudioProfile { name, settings })
+        .collect())
+}
+
+/// Switch to audio profile `name`: write all of its settings in one save and emit a single
+/// `audio-profile-applied` event with the values applied.
+///
+/// Every value (and the profile's cue theme) is checked first; if any is invalid nothing
+/// changes. Settings the profile doesn't have are left as they are.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn apply_audio_profile(app: AppHandle, name: String) -> Result<AudioProfile, CommandError> {
+    use crate::commands::settings::{validate_setting_value, SettingValidation};
+
+    let store = app
+        .store("settings.json")
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+    let profile = stored_audio_profiles(&store)
+        .remove(name.trim())
+        .ok_or_else(|| CommandError::InvalidArgument(format!("No audio profile '{}'", name)))?;
+    let defaults = crate::default_settings().map_err(CommandError::internal)?;
+
+    let mut settings = serde_json::Map::new();
+    for (key, value) in profile {
+        if !AUDIO_PROFILE_SETTINGS.contains(&key.as_str()) {
+            continue;
+        }
+        let default = defaults.iter().find(|(known, _)| *known == key).map(|(_, v)| v);
+        let value = match validate_setting_value(&key, &value, default) {
+            SettingValidation::Valid => value,
+            SettingValidation::Repaired { value, .. } => value,
+            SettingValidation::Invalid(reason) => {
+                return Err(CommandError::InvalidArgument(format!(
+                    "Audio profile '{}' has an invalid {}: {}",
+                    name, key, reason
+                )));
+            }
+        };
+        settings.insert(key, value);
+    }
+
+    // `Some(clips)` when the profile sets the theme; loaded up front so a missing theme
+    // fails before anything is written.
+    let theme_clips = match settings.get("active_cue_theme") {
+        Some(serde_json::Value::String(theme)) => Some(Some(
+            cue_themes::load(&cue_themes_dir(&app)?, theme)
+                .map_err(CommandError::InvalidArgument)?,
+        )),
+        Some(_) => Some(None),
+        None => None,
+    };
+
+    for (key, value) in &settings {
+        store.set(key.as_str(), value.clone());
+    }
+    store
+        .save()
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+
+    if let Some(clips) = theme_clips {
+        audio::set_theme_clips(clips);
+    }
+    apply_cue_settings(&app);
+
+    let profile = AudioProfile {
+        name: name.trim().to_string(),
+        settings,
+    };
+    log::info!("Applied audio profile '{}'", profile.name);
+    let _ = app.emit("audio-profile-applied", &profile);
+    Ok(profile)
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn install_cue_theme(
+    _app: AppHandle,
+    _path: String,
+    _name: Option<String>,
+) -> Result<cue_themes::CueThemeInfo, CommandError> {
+    Err(CommandError::Internal(
+        "Cue themes are not supported on this platform".to_string(),
+    ))
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn list_cue_themes(_app: AppHandle) -> Result<Vec<cue_themes::CueThemeInfo>, CommandError> {
+    Err(CommandError::Internal(
+        "Cue themes are not supported on this platform".to_string(),
+    ))
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn set_active_cue_theme(_app: AppHandle, _name: Option<String>) -> Result<(), CommandError> {
+    Err(CommandError::Internal(
+        "Cue themes are not supported on this platform".to_string(),
+    ))
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn validate_cue_config(_app: AppHandle) -> Result<CueConfigReport, CommandError> {
+    Err(CommandError::Internal(
+        "Cue themes are not supported on this platform".to_string(),
+    ))
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn prune_missing_cue_files(_app: AppHandle) -> Result<CuePruneReport, CommandError> {
+    Err(CommandError::Internal(
+        "Cue themes are not supported on this platform".to_string(),
+    ))
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn save_audio_profile(_app: AppHandle, _name: String) -> Result<AudioProfile, CommandError> {
+    Err(CommandError::Internal(
+        "Audio profiles are not supported on this platform".to_string(),
+    ))
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn list_audio_profiles(_app: AppHandle) -> Result<Vec<AudioProfile>, CommandError> {
+    Err(CommandError::Internal(
+        "Audio profiles are not supported on this platform".to_string(),
+    ))
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn apply_audio_profile(_app: AppHandle, _name: String) -> Result<AudioProfile, CommandError> {
+    Err(CommandError::Internal(
+        "Audio profiles are not supported on this platform".to_string(),
+    ))
+}
+
+/// Whether an audio output device is available for cues, so the UI can disable cue settings.
+#[tauri::command]
+pub fn is_audio_output_available() -> bool {
+    audio::is_output_available()
+}
+
+/// Output devices cues can be sent to (`cue_output_device`).
+#[tauri::command]
+pub fn list_audio_output_devices() -> Vec<String> {
+    audio::output_device_names()
+}
+
+/// Detect the output device now and keep re-checking in the background, emitting
+/// `audio-output-availability-changed` (bool) when a device appears or disappears.
+pub fn spawn_output_device_watcher(app: AppHandle) {
+    let mut last = audio::refresh_output_availability();
+    if !last {
+        log::info!("No audio output device at startup; cues disabled");
+    }
+
+    thread::spawn(move || loop {
+        thread::sleep(Duration::from_millis(OUTPUT_DEVICE_POLL_INTERVAL_MS));
+
+        let available = audio::refresh_output_availability();
+        if available != last {
+            last = available;
+            let _ = app.emit("audio-output-availability-changed", available);
+        }
+    });
+}
+
 /// List available audio input devices as seen by the backend (CPAL).
 ///
 /// This is the authoritative device list for recording and the backend-driven overlay waveform.
@@ -39,8 +890,275 @@ pub fn list_audio_input_devices() -> Vec<String> {
     audio_capture::list_input_devices()
 }
 
+/// List available audio input devices with a stable identifier per device.
+///
+/// The `id` survives OS re-enumeration (e.g. a USB mic reconnected to another port), so the
+/// UI can keep its selection across reconnects.
+#[tauri::command]
+pub fn list_audio_input_devices_with_ids() -> Vec<audio_capture::InputDeviceInfo> {
+    audio_capture::list_input_device_infos()
+}
+
 /// Get the backend default audio input device name (CPAL default), if available.
 #[tauri::command]
 pub fn get_default_audio_input_device_name() -> Option<String> {
     audio_capture::get_default_input_device_info().map(|(name, _sr, _ch)| name)
 }
+
+/// Capture format of the backend default input device.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DefaultInputDeviceInfo {
+    pub name: String,
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+/// Get the backend default audio input device name and native capture format, if available.
+#[tauri::command]
+pub fn get_default_audio_input_device_info() -> Option<DefaultInputDeviceInfo> {
+    audio_capture::get_default_input_device_info().map(|(name, sample_rate, channels)| {
+        DefaultInputDeviceInfo {
+            name,
+            sample_rate,
+            channels,
+        }
+    })
+}
+
+/// Select the next input device in `list_audio_input_devices` (wrapping around), persist
+/// it as `selected_mic_id`, and apply it to the pipeline for the next recording.
+///
+/// Emits `input-device-selected` with the device name and, with `tts_confirmations` on,
+/// speaks it. Returns the new device name.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn cycle_input_device(
+    app: AppHandle,
+    pipeline: State<'_, SharedPipeline>,
+) -> Result<String, CommandError> {
+    let store = app
+        .store("settings.json")
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+    // NOTE: The frontend setting key is historically named `selected_mic_id`.
+    let current: Option<String> = store
+        .get("selected_mic_id")
+        .and_then(|v| serde_json::from_value(v).ok())
+        .filter(|id: &String| !id.trim().is_empty() && id != "default");
+
+    let available = audio_capture::list_input_devices();
+    let next = audio_capture::next_input_device(current.as_deref(), &available)
+        .ok_or_else(|| CommandError::Internal("No input devices found".to_string()))?;
+
+    store.set("selected_mic_id", serde_json::json!(next));
+    store
+        .save()
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+    pipeline
+        .update_config(crate::pipeline::PipelineConfig {
+            input_device_name: Some(next.clone()),
+            ..pipeline.config()
+        })
+        .map_err(CommandError::from)?;
+
+    log::info!(
+        "Input device cycled: {} -> {}",
+        current.as_deref().unwrap_or("default"),
+        next
+    );
+    let _ = app.emit("input-device-selected", &next);
+
+    let tts_confirmations: bool = store
+        .get("tts_confirmations")
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or(false);
+    if tts_confirmations {
+        tts::speak(next.clone());
+    }
+
+    Ok(next)
+}
+
+// Stub for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn cycle_input_device(
+    _app: AppHandle,
+    _pipeline: State<'_, SharedPipeline>,
+) -> Result<String, CommandError> {
+    Err(CommandError::Internal(
+        "Input device selection is not supported on this platform".to_string(),
+    ))
+}
+
+/// Mute or unmute the microphone without stopping capture. While muted a running recording
+/// keeps its stream open but captures silence, so unmuting is instant. Emits
+/// `input-mute-changed` (`{ muted }`) when the state changes.
+#[tauri::command]
+pub fn set_input_muted(app: AppHandle, muted: bool) {
+    if audio_capture::set_input_muted(muted) {
+        log::info!("Input {}", if muted { "muted" } else { "unmuted" });
+        let _ = app.emit("input-mute-changed", serde_json::json!({ "muted": muted }));
+    }
+}
+
+/// Whether the microphone is muted with `set_input_muted`.
+#[tauri::command]
+pub fn is_input_muted() -> bool {
+    audio_capture::is_input_muted()
+}
+
+/// Turn stop-time silence trim on or off, persist it (`silence_trim_enabled`,
+/// `silence_trim_threshold_dbfs`, `silence_trim_max_ms`), and apply it to the pipeline from
+/// the next recording. Omitted values keep their stored setting.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn set_silence_trim(
+    app: AppHandle,
+    pipeline: State<'_, SharedPipeline>,
+    enabled: bool,
+    threshold_dbfs: Option<f32>,
+    max_trim_ms: Option<u32>,
+) -> Result<(), CommandError> {
+    if let Some(threshold) = threshold_dbfs {
+        if !threshold.is_finite() || !(-75.0..=-20.0).contains(&threshold) {
+            return Err(CommandError::InvalidArgument(format!(
+                "Silence threshold {} dBFS is outside -75..=-20",
+                threshold
+            )));
+        }
+    }
+    if max_trim_ms.is_some_and(|ms| ms > MAX_SILENCE_TRIM_MS) {
+        return Err(CommandError::InvalidArgument(format!(
+            "Silence trim is limited to {} ms per end",
+            MAX_SILENCE_TRIM_MS
+        )));
+    }
+
+    let store = app
+        .store("settings.json")
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+    store.set("silence_trim_enabled", serde_json::json!(enabled));
+    if let Some(threshold) = threshold_dbfs {
+        store.set("silence_trim_threshold_dbfs", serde_json::json!(threshold));
+    }
+    if let Some(ms) = max_trim_ms {
+        store.set("silence_trim_max_ms", serde_json::json!(ms));
+    }
+    store
+        .save()
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+
+    let silence_trim = crate::silence_trim_config(&app);
+    pipeline
+        .update_config(crate::pipeline::PipelineConfig {
+            silence_trim,
+            ..pipeline.config()
+        })
+        .map_err(CommandError::from)?;
+    log::info!("Silence trim set: {:?}", silence_trim);
+    Ok(())
+}
+
+// Stub for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn set_silence_trim(
+    _app: AppHandle,
+    _pipeline: State<'_, SharedPipeline>,
+    _enabled: bool,
+    _threshold_dbfs: Option<f32>,
+    _max_trim_ms: Option<u32>,
+) -> Result<(), CommandError> {
+    Err(CommandError::Internal(
+        "Silence trim is not supported on this platform".to_string(),
+    ))
+}
+
+/// Capture format negotiated for the current/most recent recording: the sample rate and
+/// channel count actually captured, next to the device default and any requested format
+/// (`audio_capture_sample_rate` / `audio_capture_channels`). `None` before the first
+/// recording.
+#[tauri::command]
+pub fn get_capture_config(
+    pipeline: State<'_, SharedPipeline>,
+) -> Option<audio_capture::CaptureConfig> {
+    pipeline.capture_config()
+}
+
+/// Result of a mic test: where the WAV was saved and how loud it was.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TestCaptureResult {
+    pub path: String,
+    /// Device actually recorded from (the selected mic, or the default if it's missing).
+    pub device: Option<String>,
+    pub levels: audio_capture::RecordingLevelStats,
+}
+
+/// Record `seconds` (clamped to 30) from the selected input device and save it as
+/// `mic-test.wav` in the app data dir, so a user can listen to what the mic captures
+/// without involving the transcription server. The raw capture is saved (no noise gate).
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn capture_test_audio(
+    app: AppHandle,
+    seconds: u32,
+) -> Result<TestCaptureResult, CommandError> {
+    use tauri::Manager;
+
+    if seconds == 0 {
+        return Err(CommandError::InvalidArgument(
+            "Test capture duration must be at least 1 second".to_string(),
+        ));
+    }
+    let seconds = seconds.min(MAX_TEST_CAPTURE_SECS);
+
+    // NOTE: The frontend setting key is historically named `selected_mic_id`.
+    let input_device_name: Option<String> = app
+        .store("settings.json")
+        .ok()
+        .and_then(|store| store.get("selected_mic_id"))
+        .and_then(|v| serde_json::from_value(v).ok());
+
+    let path = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| CommandError::Storage(e.to_string()))?
+        .join(TEST_CAPTURE_FILE);
+
+    let (wav_bytes, stats, device) = tauri::async_runtime::spawn_blocking(move || {
+        let mut capture = audio_capture::AudioCapture::new();
+        capture.start_with_device_name(seconds as f32 + 1.0, input_device_name.as_deref())?;
+        log::info!("Mic test: recording {}s", seconds);
+        thread::sleep(Duration::from_secs(u64::from(seconds)));
+        let device = capture.active_device_name().map(str::to_string);
+        let (wav_bytes, stats) = capture.stop_and_get_wav_with_stats()?;
+        Ok::<_, audio_capture::AudioCaptureError>((wav_bytes, stats, device))
+    })
+    .await
+    .map_err(CommandError::internal)?
+    .map_err(CommandError::internal)?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| CommandError::Storage(e.to_string()))?;
+    }
+    std::fs::write(&path, wav_bytes).map_err(|e| CommandError::Storage(e.to_string()))?;
+    log::info!("Mic test saved to {}", path.display());
+
+    Ok(TestCaptureResult {
+        path: path.to_string_lossy().to_string(),
+        device,
+        levels: stats.into(),
+    })
+}
+
+// Stub for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn capture_test_audio(
+    _app: AppHandle,
+    _seconds: u32,
+) -> Result<TestCaptureResult, CommandError> {
+    Err(CommandError::Internal(
+        "Mic test is not supported on this platform".to_string(),
+    ))
+}
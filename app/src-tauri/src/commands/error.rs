@@ -0,0 +1,140 @@
+//! Structured error type shared by Tauri commands.
+//!
+//! Serializes as `{ "code": "clipboard_unavailable", "message": "..." }` so the frontend can
+//! branch on a stable `code` instead of pattern-matching human-readable strings.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+#[serde(tag = "code", content = "message", rename_all = "snake_case")]
+pub enum CommandError {
+    /// The system clipboard couldn't be opened or written (often locked by another app).
+    #[error("Clipboard unavailable: {0}")]
+    ClipboardUnavailable(String),
+
+    /// Simulated keyboard input failed (permissions, no display, ...).
+    #[error("Input injection failed: {0}")]
+    InputInjectionFailed(String),
+
+    /// A backend server/provider couldn't be reached.
+    #[error("Server unreachable: {0}")]
+    ServerUnreachable(String),
+
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
+    #[error("Window not found: {0}")]
+    WindowNotFound(String),
+
+    /// A window exists but showing/hiding/moving/resizing it failed.
+    #[error("Window operation failed: {0}")]
+    WindowOperationFailed(String),
+
+    /// The OS hasn't granted a permission we need (e.g. macOS Accessibility for typing).
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
+    /// Another output is already being typed/pasted and `output_concurrency` is `reject`.
+    #[error("Output busy: {0}")]
+    OutputBusy(String),
+
+    /// Keystroke output was abandoned because the user started typing before it began.
+    #[error("Output interrupted: {0}")]
+    OutputInterrupted(String),
+
+    /// Reading or writing persisted data (history, settings) failed.
+    #[error("Storage error: {0}")]
+    Storage(String),
+
+    #[error("Internal error: {0}")]
+    Internal(String),
+}
+
+impl CommandError {
+    pub fn clipboard(err: impl std::fmt::Display) -> Self {
+        Self::ClipboardUnavailable(err.to_string())
+    }
+
+    pub fn input(err: impl std::fmt::Display) -> Self {
+        Self::InputInjectionFailed(err.to_string())
+    }
+
+    pub fn window(err: impl std::fmt::Display) -> Self {
+        Self::WindowOperationFailed(err.to_string())
+    }
+
+    pub fn internal(err: impl std::fmt::Display) -> Self {
+        Self::Internal(err.to_string())
+    }
+
+    /// Whether the same operation might succeed if tried again shortly (a locked clipboard,
+    /// a flaky input backend, an unreachable server), as opposed to bad input or a missing
+    /// permission that will fail the same way every time.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            Self::ClipboardUnavailable(_) | Self::InputInjectionFailed(_) | Self::ServerUnreachable(_)
+        )
+    }
+}
+
+impl From<reqwest::Error> for CommandError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_connect() || err.is_timeout() {
+            Self::ServerUnreachable(err.to_string())
+        } else {
+            Self::Internal(err.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serializes_as_code_and_message() {
+        let err = CommandError::ClipboardUnavailable("locked".to_string());
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "code": "clipboard_unavailable", "message": "locked" })
+        );
+    }
+
+    #[test]
+    fn test_serde_round_trip_all_variants() {
+        let variants = [
+            CommandError::ClipboardUnavailable("a".into()),
+            CommandError::InputInjectionFailed("b".into()),
+            CommandError::ServerUnreachable("c".into()),
+            CommandError::InvalidArgument("d".into()),
+            CommandError::WindowNotFound("e".into()),
+            CommandError::WindowOperationFailed("f".into()),
+            CommandError::OutputInterrupted("i".into()),
+            CommandError::Storage("g".into()),
+            CommandError::Internal("h".into()),
+        ];
+
+        for err in variants {
+            let json = serde_json::to_string(&err).unwrap();
+            let back: CommandError = serde_json::from_str(&json).unwrap();
+            assert_eq!(back, err);
+        }
+    }
+
+    #[test]
+    fn test_is_transient() {
+        assert!(CommandError::clipboard("locked").is_transient());
+        assert!(CommandError::input("enigo").is_transient());
+        assert!(!CommandError::PermissionDenied("accessibility".into()).is_transient());
+        assert!(!CommandError::InvalidArgument("mode".into()).is_transient());
+        assert!(!CommandError::OutputBusy("busy".into()).is_transient());
+    }
+
+    #[test]
+    fn test_display_includes_message() {
+        let err = CommandError::InvalidArgument("Invalid overlay mode: foo".to_string());
+        assert_eq!(err.to_string(), "Invalid argument: Invalid overlay mode: foo");
+    }
+}
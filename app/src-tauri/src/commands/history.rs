@@ -1,5 +1,13 @@
-use crate::history::{HistoryEntry, HistoryStorage};
-use tauri::{AppHandle, State};
+use super::error::CommandError;
+use crate::history::{
+    HistoryEntry, HistoryFilter, HistoryPage, HistorySearchResult, HistorySession,
+    HistoryStorage, HistoryStorageInfo, TranscriptionMetadata, TrimStrategy,
+    DEFAULT_SESSION_GAP_MINUTES, MAX_FUZZY_RESULTS,
+};
+use super::text::{
+    file_output_contents, file_output_dir, validate_output_file_path, FileOutputResult, LineEnding,
+};
+use tauri::{AppHandle, Emitter, State};
 
 #[cfg(desktop)]
 use tauri_plugin_store::StoreExt;
@@ -25,15 +33,146 @@ fn get_max_saved_recordings(app: &AppHandle) -> usize {
     }
 }
 
+fn get_max_history_age_days(app: &AppHandle) -> u64 {
+    #[cfg(desktop)]
+    {
+        return app
+            .store("settings.json")
+            .ok()
+            .and_then(|store| store.get("max_history_age_days"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0);
+    }
+
+    #[cfg(not(desktop))]
+    {
+        let _ = app;
+        0
+    }
+}
+
+/// The `file_output_line_ending` setting, shared with the file output mode.
+fn get_file_output_line_ending(app: &AppHandle) -> LineEnding {
+    #[cfg(desktop)]
+    {
+        let value = app
+            .store("settings.json")
+            .ok()
+            .and_then(|store| store.get("file_output_line_ending"))
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default();
+        return LineEnding::from_str(&value);
+    }
+
+    #[cfg(not(desktop))]
+    {
+        let _ = app;
+        LineEnding::from_str("native")
+    }
+}
+
+/// Sync the `trim_strategy` setting into storage. Call before adding entries.
+pub(crate) fn apply_history_trim_strategy(app: &AppHandle, history: &HistoryStorage) {
+    #[cfg(desktop)]
+    {
+        let strategy = app
+            .store("settings.json")
+            .ok()
+            .and_then(|store| store.get("trim_strategy"))
+            .and_then(|v| v.as_str().map(TrimStrategy::from_str))
+            .unwrap_or_default();
+        history.set_trim_strategy(strategy);
+    }
+
+    #[cfg(not(desktop))]
+    {
+        let _ = (app, history);
+    }
+}
+
+/// Sync the `history_session_gap_minutes` setting into storage. Call before adding entries.
+pub(crate) fn apply_history_session_gap(app: &AppHandle, history: &HistoryStorage) {
+    #[cfg(desktop)]
+    {
+        let minutes = app
+            .store("settings.json")
+            .ok()
+            .and_then(|store| store.get("history_session_gap_minutes"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_SESSION_GAP_MINUTES);
+        history.set_session_gap_minutes(minutes);
+    }
+
+    #[cfg(not(desktop))]
+    {
+        let _ = (app, history);
+    }
+}
+
+/// Sync the `max_history_age_days` setting into storage and drop expired entries.
+///
+/// Returns the number of entries removed.
+pub(crate) fn apply_max_history_age(app: &AppHandle, history: &HistoryStorage) -> usize {
+    history.set_max_age_days(get_max_history_age_days(app));
+
+    match history.prune_expired() {
+        Ok(removed) => removed.len(),
+        Err(e) => {
+            log::warn!("Failed to prune history by max age: {}", e);
+            0
+        }
+    }
+}
+
+/// Sync the `redact_patterns` and `redact_builtin_patterns` settings into the redactor
+/// shared by history, request logs and the output audit log.
+///
+/// Cheap when nothing changed (patterns are only recompiled on change), so it's called
+/// before each write rather than tracking settings changes.
+pub(crate) fn apply_history_redaction(app: &AppHandle, history: &HistoryStorage) {
+    #[cfg(desktop)]
+    {
+        let store = app.store("settings.json").ok();
+        let patterns: Vec<String> = store
+            .as_ref()
+            .and_then(|store| store.get("redact_patterns"))
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default();
+        let builtins = store
+            .as_ref()
+            .and_then(|store| store.get("redact_builtin_patterns"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        history.set_redaction(&patterns, builtins);
+    }
+
+    #[cfg(not(desktop))]
+    {
+        let _ = (app, history);
+    }
+}
+
 /// Add a new entry to the dictation history
+///
+/// `metadata` (duration/confidence/model) is optional so existing callers keep working.
+/// Pass the same `idempotency_key` when retrying an add for the same utterance; within a
+/// short window the existing entry is returned instead of creating a duplicate.
 #[tauri::command]
 pub async fn add_history_entry(
     app: AppHandle,
     text: String,
+    metadata: Option<TranscriptionMetadata>,
+    idempotency_key: Option<String>,
     history: State<'_, HistoryStorage>,
-) -> Result<HistoryEntry, String> {
+) -> Result<HistoryEntry, CommandError> {
     let max = get_max_saved_recordings(&app);
-    history.add_entry(text, max)
+    history.set_max_age_days(get_max_history_age_days(&app));
+    apply_history_redaction(&app, &history);
+    apply_history_trim_strategy(&app, &history);
+    apply_history_session_gap(&app, &history);
+    history
+        .add_entry_with_metadata(text, metadata, idempotency_key, max)
+        .map_err(CommandError::Storage)
 }
 
 /// Get dictation history entries
@@ -41,8 +180,104 @@ pub async fn add_history_entry(
 pub async fn get_history(
     limit: Option<usize>,
     history: State<'_, HistoryStorage>,
-) -> Result<Vec<HistoryEntry>, String> {
-    history.get_all(limit)
+) -> Result<Vec<HistoryEntry>, CommandError> {
+    history.get_all(limit).map_err(CommandError::Storage)
+}
+
+/// Query history with a combined filter (text, date range, source app; all optional, AND
+/// semantics). Returns one page of matches, newest first, with the total match count.
+#[tauri::command]
+pub async fn query_history(
+    filter: HistoryFilter,
+    history: State<'_, HistoryStorage>,
+) -> Result<HistoryPage, CommandError> {
+    history.query(&filter).map_err(CommandError::Storage)
+}
+
+/// Get history grouped into dictation sessions, newest first.
+///
+/// A new session starts when more than `history_session_gap_minutes` passed since the
+/// previous entry. `limit` caps the number of sessions returned.
+#[tauri::command]
+pub async fn get_history_sessions(
+    app: AppHandle,
+    limit: Option<usize>,
+    history: State<'_, HistoryStorage>,
+) -> Result<Vec<HistorySession>, CommandError> {
+    apply_history_session_gap(&app, &history);
+    history.get_sessions(limit).map_err(CommandError::Storage)
+}
+
+/// Search history with typo-tolerant fuzzy matching.
+///
+/// Results are ordered best match first and include their score. `limit` defaults to and is
+/// capped at 50.
+#[tauri::command]
+pub async fn fuzzy_search_history(
+    query: String,
+    limit: Option<usize>,
+    history: State<'_, HistoryStorage>,
+) -> Result<Vec<HistorySearchResult>, CommandError> {
+    history
+        .fuzzy_search(&query, limit.unwrap_or(MAX_FUZZY_RESULTS))
+        .map_err(CommandError::Storage)
+}
+
+/// Copy a history entry's text to the clipboard without pasting it.
+///
+/// Emits `output-mode-resolved` like any other clipboard output, so the UI can confirm the
+/// copy the same way.
+#[tauri::command]
+pub async fn copy_history_entry(
+    app: AppHandle,
+    id: String,
+    history: State<'_, HistoryStorage>,
+) -> Result<(), CommandError> {
+    use super::text::{copy_to_clipboard, OutputMode, OutputModeResolution, ResolutionReason};
+
+    let entry = history
+        .get(&id)
+        .map_err(CommandError::Storage)?
+        .ok_or_else(|| CommandError::InvalidArgument(format!("No history entry with id {}", id)))?;
+
+    copy_to_clipboard(&entry.text)?;
+
+    let resolution = OutputModeResolution::new(
+        OutputMode::Clipboard,
+        OutputMode::Clipboard,
+        &ResolutionReason::Requested,
+    );
+    let _ = app.emit("output-mode-resolved", &resolution);
+    Ok(())
+}
+
+/// Save one history entry's text to `path` (inside the `file_output_dir` folder; overwritten
+/// if it exists), as UTF-8 with the `file_output_line_ending` line ending and a final line
+/// break.
+#[tauri::command]
+pub async fn export_history_entry(
+    app: AppHandle,
+    id: String,
+    path: String,
+    history: State<'_, HistoryStorage>,
+) -> Result<FileOutputResult, CommandError> {
+    let entry = history
+        .get(&id)
+        .map_err(CommandError::Storage)?
+        .ok_or_else(|| CommandError::InvalidArgument(format!("No history entry with id {}", id)))?;
+
+    let path = validate_output_file_path(&path, &file_output_dir(&app)?)?;
+    let contents = file_output_contents(&entry.text, get_file_output_line_ending(&app));
+    std::fs::write(&path, &contents).map_err(|e| {
+        CommandError::Storage(format!("Failed to write {}: {}", path.display(), e))
+    })?;
+
+    log::info!("Exported history entry {} to {}", id, path.display());
+    Ok(FileOutputResult {
+        path: path.display().to_string(),
+        bytes_written: contents.len(),
+        appended: false,
+    })
 }
 
 /// Delete a history entry by ID
@@ -50,12 +285,73 @@ pub async fn get_history(
 pub async fn delete_history_entry(
     id: String,
     history: State<'_, HistoryStorage>,
-) -> Result<bool, String> {
-    history.delete(&id)
+) -> Result<bool, CommandError> {
+    history.delete(&id).map_err(CommandError::Storage)
+}
+
+/// Delete multiple history entries in a single pass.
+///
+/// IDs that don't exist are ignored. Returns the number of entries actually deleted.
+#[tauri::command]
+pub async fn delete_history_entries(
+    ids: Vec<String>,
+    history: State<'_, HistoryStorage>,
+) -> Result<usize, CommandError> {
+    history.delete_many(&ids).map_err(CommandError::Storage)
+}
+
+/// Remove unpinned entries older than `max_history_age_days` (0 = keep forever).
+///
+/// Returns the number of entries removed.
+#[tauri::command]
+pub async fn prune_history(
+    app: AppHandle,
+    history: State<'_, HistoryStorage>,
+) -> Result<usize, CommandError> {
+    history.set_max_age_days(get_max_history_age_days(&app));
+    let removed = history.prune_expired().map_err(CommandError::Storage)?;
+    Ok(removed.len())
+}
+
+/// Pin or unpin a history entry. Pinned entries are never removed by age-based pruning or
+/// retention.
+#[tauri::command]
+pub async fn set_history_entry_pinned(
+    id: String,
+    pinned: bool,
+    history: State<'_, HistoryStorage>,
+) -> Result<bool, CommandError> {
+    history.set_pinned(&id, pinned).map_err(CommandError::Storage)
 }
 
 /// Clear all history entries
 #[tauri::command]
-pub async fn clear_history(history: State<'_, HistoryStorage>) -> Result<(), String> {
-    history.clear()
+pub async fn clear_history(history: State<'_, HistoryStorage>) -> Result<(), CommandError> {
+    history.clear().map_err(CommandError::Storage)
+}
+
+/// Entry count and size of the history file on disk.
+#[tauri::command]
+pub async fn get_history_storage_info(
+    history: State<'_, HistoryStorage>,
+) -> Result<HistoryStorageInfo, CommandError> {
+    history.storage_info().map_err(CommandError::Storage)
+}
+
+/// Rewrite the history file without pretty-printing and drop the backup copy.
+///
+/// Returns the storage info after compaction.
+#[tauri::command]
+pub async fn compact_history(
+    history: State<'_, HistoryStorage>,
+) -> Result<HistoryStorageInfo, CommandError> {
+    history.compact().map_err(CommandError::Storage)
+}
+
+/// Write any pending (debounced) history changes to disk immediately.
+///
+/// The frontend should call this before exporting or reading `history.json` directly.
+#[tauri::command]
+pub async fn flush_history(history: State<'_, HistoryStorage>) -> Result<(), CommandError> {
+    history.flush().map_err(CommandError::Storage)
 }
@@ -1,5 +1,7 @@
-//! Tauri commands for request logging.
+//! Tauri commands for request logging and the recent app log.
 
+use super::error::CommandError;
+use crate::log_buffer::{self, LogLine};
 use crate::request_log::{
     RequestLog, RequestLogStore, RequestLogsRetentionConfig, RequestLogsRetentionMode,
 };
@@ -68,3 +70,17 @@ pub fn clear_request_logs(app: AppHandle) {
         store.clear();
     }
 }
+
+/// Recent app log lines (oldest first) from the in-memory ring buffer, optionally only
+/// those at `level` ("error", "warn", "info", "debug", "trace") or more severe.
+#[tauri::command]
+pub fn get_recent_logs(level: Option<String>) -> Result<Vec<LogLine>, CommandError> {
+    let min_level = level
+        .map(|level| {
+            level.parse::<log::Level>().map_err(|_| {
+                CommandError::InvalidArgument(format!("Unknown log level: {}", level))
+            })
+        })
+        .transpose()?;
+    Ok(log_buffer::recent(min_level))
+}
@@ -1,5 +1,6 @@
 pub mod audio;
 pub mod config;
+pub mod error;
 pub mod history;
 pub mod llm;
 pub mod logs;
@@ -1,3 +1,9 @@
+use super::error::CommandError;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Manager};
 
 #[cfg(desktop)]
@@ -12,63 +18,549 @@ fn get_setting_from_store<T: serde::de::DeserializeOwned>(app: &AppHandle, key:
         .unwrap_or(default)
 }
 
-fn set_widget_position_impl(app: &AppHandle, position: &str) -> Result<(), String> {
-    let Some(window) = app.get_webview_window("overlay") else {
-        return Err("Overlay window not found".to_string());
+/// The overlay window, or `WindowNotFound` if it has been closed (see `recreate_overlay`).
+fn overlay_window(app: &AppHandle) -> Result<tauri::WebviewWindow, CommandError> {
+    app.get_webview_window("overlay")
+        .ok_or_else(|| CommandError::WindowNotFound("overlay".to_string()))
+}
+
+/// Backend-driven visual state of the overlay.
+///
+/// Emitted as the `overlay-state` event so the overlay's color/animation always matches what
+/// the backend is actually doing (the frontend can't see e.g. output failures on its own).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverlayState {
+    Idle,
+    Recording,
+    Transcribing,
+    Error,
+}
+
+/// Broadcast the overlay state to all windows.
+pub fn emit_overlay_state(app: &AppHandle, state: OverlayState) {
+    log::debug!("Overlay state -> {:?}", state);
+    let _ = app.emit("overlay-state", state);
+}
+
+/// Default max length (chars, including the ellipsis) of the post-output preview snippet.
+const DEFAULT_OVERLAY_PREVIEW_MAX_CHARS: usize = 40;
+
+/// The tail of `text` that fits in `max_chars` characters, for the post-output preview.
+///
+/// Counts `char`s rather than bytes so multibyte text is never split mid-sequence. When the
+/// text is cut, the snippet starts at a word boundary and is prefixed with "…". Returns
+/// `None` for blank text.
+pub fn overlay_preview_snippet(text: &str, max_chars: usize) -> Option<String> {
+    let text = text.trim();
+    if text.is_empty() || max_chars == 0 {
+        return None;
+    }
+
+    let total = text.chars().count();
+    if total <= max_chars {
+        return Some(text.to_string());
+    }
+
+    // Reserve one char for the ellipsis, then find the byte offset of the cut.
+    let keep = max_chars.saturating_sub(1);
+    let (cut, _) = text.char_indices().nth(total - keep)?;
+    let tail = &text[cut..];
+
+    // If we landed mid-word, skip to the next word (unless the tail is a single word).
+    let starts_mid_word = !text[..cut].ends_with(char::is_whitespace);
+    let tail = if starts_mid_word {
+        match tail.find(char::is_whitespace) {
+            Some(ws) => tail[ws..].trim_start(),
+            None => tail,
+        }
+    } else {
+        tail
     };
 
+    Some(format!("…{}", tail.trim_start()))
+}
+
+/// Emit `overlay-preview-text` with the end of the just-output transcript, if enabled via
+/// the `overlay_preview_enabled` setting. The frontend owns display and timeout.
+#[cfg(desktop)]
+pub fn emit_overlay_preview_text(app: &AppHandle, text: &str) {
+    if !get_setting_from_store(app, "overlay_preview_enabled", false) {
+        return;
+    }
+
+    let max_chars: usize = get_setting_from_store(
+        app,
+        "overlay_preview_max_chars",
+        DEFAULT_OVERLAY_PREVIEW_MAX_CHARS,
+    );
+
+    if let Some(snippet) = overlay_preview_snippet(text, max_chars) {
+        let _ = app.emit("overlay-preview-text", snippet);
+    }
+}
+
+/// Manually override the overlay state (e.g. to clear a stuck error indicator).
+#[tauri::command]
+pub fn set_overlay_state(app: AppHandle, state: OverlayState) -> Result<(), CommandError> {
+    emit_overlay_state(&app, state);
+    Ok(())
+}
+
+fn set_widget_position_impl(app: &AppHandle, position: &str) -> Result<(), CommandError> {
+    let window = overlay_window(app)?;
+
     let monitor = window
         .current_monitor()
-        .map_err(|e| e.to_string())?
-        .ok_or("No monitor found")?;
+        .map_err(CommandError::window)?
+        .ok_or_else(|| CommandError::window("No monitor found"))?;
 
+    place_overlay_on_monitor(&window, &monitor, position)
+}
+
+/// Offset of a window of size `window` placed at preset `position` inside an area of size
+/// `area`, `margin` away from the edges.
+fn preset_offset(
+    position: &str,
+    area: (f64, f64),
+    window: (f64, f64),
+    margin: f64,
+) -> Result<(f64, f64), CommandError> {
+    let (area_width, area_height) = area;
+    let (window_width, window_height) = window;
+    Ok(match position {
+        "top-left" => (margin, margin),
+        "top-center" => ((area_width - window_width) / 2.0, margin),
+        "top-right" => (area_width - window_width - margin, margin),
+        "center" => (
+            (area_width - window_width) / 2.0,
+            (area_height - window_height) / 2.0,
+        ),
+        "bottom-left" => (margin, area_height - window_height - margin),
+        "bottom-center" => (
+            (area_width - window_width) / 2.0,
+            area_height - window_height - margin,
+        ),
+        "bottom-right" => (
+            area_width - window_width - margin,
+            area_height - window_height - margin,
+        ),
+        _ => {
+            return Err(CommandError::InvalidArgument(format!(
+                "Invalid widget position: {}",
+                position
+            )))
+        }
+    })
+}
+
+/// Move the overlay to a preset position on the given monitor.
+fn place_overlay_on_monitor(
+    window: &tauri::WebviewWindow,
+    monitor: &tauri::Monitor,
+    position: &str,
+) -> Result<(), CommandError> {
     let screen_size = monitor.size();
     let scale = monitor.scale_factor();
     let screen_width = screen_size.width as f64 / scale;
     let screen_height = screen_size.height as f64 / scale;
 
     // Get current window size
-    let window_size = window.outer_size().map_err(|e| e.to_string())?;
+    let window_size = window.outer_size().map_err(CommandError::window)?;
     let window_width = window_size.width as f64 / scale;
     let window_height = window_size.height as f64 / scale;
 
     // Calculate margins (pixels from edge)
     let margin = 50.0;
 
-    let (x, y) = match position {
-        "top-left" => (margin, margin),
-        "top-center" => ((screen_width - window_width) / 2.0, margin),
-        "top-right" => (screen_width - window_width - margin, margin),
-        "center" => (
-            (screen_width - window_width) / 2.0,
-            (screen_height - window_height) / 2.0,
-        ),
-        "bottom-left" => (margin, screen_height - window_height - margin),
-        "bottom-center" => (
-            (screen_width - window_width) / 2.0,
-            screen_height - window_height - margin,
-        ),
-        "bottom-right" => (
-            screen_width - window_width - margin,
-            screen_height - window_height - margin,
-        ),
-        _ => return Err(format!("Invalid widget position: {}", position)),
-    };
+    let (x, y) = preset_offset(
+        position,
+        (screen_width, screen_height),
+        (window_width, window_height),
+        margin,
+    )?;
+
+    // Offset by the monitor's origin so presets land on this monitor rather than the primary.
+    let origin = monitor.position();
+    let x = x + origin.x as f64 / scale;
+    let y = y + origin.y as f64 / scale;
 
     window
         .set_position(tauri::Position::Logical(tauri::LogicalPosition { x, y }))
-        .map_err(|e| e.to_string())?;
+        .map_err(CommandError::window)?;
 
     log::info!("Widget position set to {} at ({}, {})", position, x, y);
     Ok(())
 }
 
+/// How often the display watcher polls the monitor layout.
+#[cfg(desktop)]
+const MONITOR_POLL_INTERVAL_MS: u64 = 2_000;
+
+/// Payload of the `overlay-relocated` event.
+#[derive(Debug, Clone, Serialize)]
+pub struct OverlayRelocated {
+    /// Name of the monitor the overlay was moved onto, if the OS reports one.
+    pub monitor: Option<String>,
+    pub position: String,
+}
+
+/// Snapshot of the monitor layout used to detect display changes.
+#[cfg(desktop)]
+fn monitor_layout(app: &AppHandle) -> Vec<(Option<String>, i32, i32, u32, u32)> {
+    app.available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|m| {
+            (
+                m.name().cloned(),
+                m.position().x,
+                m.position().y,
+                m.size().width,
+                m.size().height,
+            )
+        })
+        .collect()
+}
+
+/// Whether the overlay's center still lies on one of the connected monitors.
+#[cfg(desktop)]
+fn overlay_is_on_a_monitor(app: &AppHandle, window: &tauri::WebviewWindow) -> bool {
+    let (Ok(pos), Ok(size)) = (window.outer_position(), window.outer_size()) else {
+        // Can't tell; leave the window alone rather than yanking it around.
+        return true;
+    };
+    let cx = pos.x as i64 + size.width as i64 / 2;
+    let cy = pos.y as i64 + size.height as i64 / 2;
+
+    app.available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .any(|m| {
+            let (mx, my) = (m.position().x as i64, m.position().y as i64);
+            let (mw, mh) = (m.size().width as i64, m.size().height as i64);
+            cx >= mx && cx < mx + mw && cy >= my && cy < my + mh
+        })
+}
+
+/// Move the overlay onto the primary monitor (or the first available one) at the saved
+/// preset position and emit `overlay-relocated`.
+#[cfg(desktop)]
+fn relocate_orphaned_overlay(app: &AppHandle) -> Result<(), CommandError> {
+    let window = overlay_window(app)?;
+
+    let monitor = match app.primary_monitor().map_err(CommandError::window)? {
+        Some(monitor) => monitor,
+        None => app
+            .available_monitors()
+            .map_err(CommandError::window)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| CommandError::window("No monitor found"))?,
+    };
+
+    let position: String =
+        get_setting_from_store(app, "widget_position", "bottom-center".to_string());
+    place_overlay_on_monitor(&window, &monitor, &position)?;
+
+    log::info!(
+        "Overlay was off-screen after a display change; moved to {:?}",
+        monitor.name()
+    );
+    let _ = app.emit(
+        "overlay-relocated",
+        OverlayRelocated {
+            monitor: monitor.name().cloned(),
+            position,
+        },
+    );
+    Ok(())
+}
+
+/// Watch for display changes (e.g. undocking) and re-home the overlay when the monitor it
+/// was on disappears.
+///
+/// Tauri has no cross-platform "displays changed" event, so this polls the monitor layout
+/// and only checks the overlay when the layout actually changes.
+#[cfg(desktop)]
+pub fn spawn_monitor_watcher(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut last_layout = monitor_layout(&app);
+        loop {
+            std::thread::sleep(std::time::Duration::from_millis(MONITOR_POLL_INTERVAL_MS));
+
+            let layout = monitor_layout(&app);
+            if layout == last_layout || layout.is_empty() {
+                continue;
+            }
+            log::info!("Display layout changed ({} monitor(s))", layout.len());
+            last_layout = layout;
+
+            let Some(window) = app.get_webview_window("overlay") else {
+                continue;
+            };
+            if overlay_is_on_a_monitor(&app, &window) {
+                continue;
+            }
+            if let Err(e) = relocate_orphaned_overlay(&app) {
+                log::warn!("Failed to relocate overlay after display change: {}", e);
+            }
+        }
+    });
+}
+
+/// App whose window the overlay follows (`overlay_follow_app`), if any.
+#[cfg(desktop)]
+static FOLLOW_APP: Mutex<Option<String>> = Mutex::new(None);
+
+/// How often the followed app's window bounds are polled.
+#[cfg(desktop)]
+const FOLLOW_APP_POLL_INTERVAL_MS: u64 = 250;
+
+/// Gap (logical px) between the overlay and the followed window's edges.
+#[cfg(desktop)]
+const FOLLOW_APP_MARGIN: f64 = 24.0;
+
+/// Move the overlay to the saved preset position within `bounds` instead of the monitor.
+#[cfg(desktop)]
+fn place_overlay_in_window(
+    window: &tauri::WebviewWindow,
+    bounds: &crate::app_window::WindowBounds,
+    position: &str,
+) -> Result<(), CommandError> {
+    let size = window.outer_size().map_err(CommandError::window)?;
+    let scale = window.scale_factor().map_err(CommandError::window)?;
+
+    // Work in the units the bounds came in.
+    let (overlay_size, margin) = if bounds.logical {
+        (
+            (size.width as f64 / scale, size.height as f64 / scale),
+            FOLLOW_APP_MARGIN,
+        )
+    } else {
+        (
+            (size.width as f64, size.height as f64),
+            FOLLOW_APP_MARGIN * scale,
+        )
+    };
+    let (dx, dy) = preset_offset(position, (bounds.width, bounds.height), overlay_size, margin)?;
+    let (x, y) = (bounds.x + dx, bounds.y + dy);
+
+    let target = if bounds.logical {
+        tauri::Position::Logical(tauri::LogicalPosition { x, y })
+    } else {
+        tauri::Position::Physical(tauri::PhysicalPosition {
+            x: x.round() as i32,
+            y: y.round() as i32,
+        })
+    };
+    window.set_position(target).map_err(CommandError::window)
+}
+
+/// Keep the overlay positioned on the followed app's window (see `set_overlay_follow_app`).
+///
+/// Polls the window's bounds while the overlay is visible and only moves the overlay when
+/// they change. When the app has no visible window anymore, the overlay goes back to its
+/// static preset position.
+#[cfg(desktop)]
+pub fn spawn_follow_app_watcher(app: AppHandle) {
+    let saved: Option<String> = get_setting_from_store(&app, "overlay_follow_app", None);
+    if let Ok(mut target) = FOLLOW_APP.lock() {
+        *target = saved.filter(|name| !name.trim().is_empty());
+    }
+
+    std::thread::spawn(move || {
+        // Bounds the overlay was last placed against, and for which app.
+        let mut placed: Option<(String, crate::app_window::WindowBounds)> = None;
+        loop {
+            std::thread::sleep(std::time::Duration::from_millis(FOLLOW_APP_POLL_INTERVAL_MS));
+
+            let Some(target) = FOLLOW_APP.lock().ok().and_then(|target| target.clone()) else {
+                placed = None;
+                continue;
+            };
+            let Some(window) = app.get_webview_window("overlay") else {
+                continue;
+            };
+            // Showing the overlay may snap it to the preset position, so re-place it after.
+            if !window.is_visible().unwrap_or(false) {
+                placed = None;
+                continue;
+            }
+
+            match crate::app_window::find_app_window(&target) {
+                Some(bounds) => {
+                    if placed.as_ref() == Some(&(target.clone(), bounds)) {
+                        continue;
+                    }
+                    let position: String =
+                        get_setting_from_store(&app, "widget_position", "bottom-center".to_string());
+                    if let Err(e) = place_overlay_in_window(&window, &bounds, &position) {
+                        log::debug!("Failed to move overlay onto {}: {}", target, e);
+                    }
+                    placed = Some((target, bounds));
+                }
+                None => {
+                    if placed.take().is_some() {
+                        log::info!("{} has no visible window; overlay back at its preset position", target);
+                        if let Err(e) = snap_overlay_to_saved_position(&app) {
+                            log::warn!("Failed to restore overlay position: {}", e);
+                        }
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Make the overlay follow `app_name`'s window, positioned by `widget_position` relative to
+/// that window instead of the screen. `None` (or an empty name) goes back to the static
+/// position.
+///
+/// Apps are matched by executable name on Windows (e.g. "chrome"), process name on macOS
+/// (e.g. "Google Chrome") and window class on Linux/X11.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn set_overlay_follow_app(app: AppHandle, app_name: Option<String>) -> Result<(), CommandError> {
+    let app_name = app_name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty());
+
+    let store = app
+        .store("settings.json")
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+    store.set("overlay_follow_app", serde_json::json!(app_name));
+    store
+        .save()
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+
+    if let Ok(mut target) = FOLLOW_APP.lock() {
+        *target = app_name.clone();
+    }
+    match app_name {
+        Some(name) => log::info!("Overlay following {}", name),
+        None => {
+            log::info!("Overlay no longer following an app");
+            snap_overlay_to_saved_position(&app)?;
+        }
+    }
+    Ok(())
+}
+
+// Stub for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn set_overlay_follow_app(_app: AppHandle, _app_name: Option<String>) -> Result<(), CommandError> {
+    Ok(())
+}
+
+/// Default time between `overlay-ping` events, and the bounds it's clamped to.
+pub const DEFAULT_OVERLAY_HEARTBEAT_INTERVAL_MS: u64 = 5_000;
+const OVERLAY_HEARTBEAT_INTERVAL_RANGE_MS: (u64, u64) = (1_000, 60_000);
+
+/// Default wait for the `overlay_pong` answer before the overlay is reloaded.
+pub const DEFAULT_OVERLAY_HEARTBEAT_TIMEOUT_MS: u64 = 3_000;
+const MIN_OVERLAY_HEARTBEAT_TIMEOUT_MS: u64 = 500;
+
+/// Sequence number of the latest ping the overlay answered.
+static LAST_OVERLAY_PONG: AtomicU64 = AtomicU64::new(0);
+
+/// Clamp the configured heartbeat interval and timeout. The timeout never exceeds the
+/// interval, so at most one ping is outstanding.
+#[cfg_attr(not(any(desktop, test)), allow(dead_code))]
+fn heartbeat_timings(interval_ms: u64, timeout_ms: u64) -> (u64, u64) {
+    let (min, max) = OVERLAY_HEARTBEAT_INTERVAL_RANGE_MS;
+    let interval_ms = interval_ms.clamp(min, max);
+    let timeout_ms = timeout_ms.clamp(MIN_OVERLAY_HEARTBEAT_TIMEOUT_MS, interval_ms);
+    (interval_ms, timeout_ms)
+}
+
+/// Watch for a hung overlay webview and reload it.
+///
+/// While the overlay is visible, sends it `overlay-ping` (with a sequence number) every
+/// `overlay_heartbeat_interval_ms`; the overlay answers with `overlay_pong`. If no answer
+/// arrives within `overlay_heartbeat_timeout_ms`, the webview is reloaded. Settings are
+/// re-read each round, so toggling `overlay_heartbeat_enabled` (off by default) applies
+/// without a restart. A reload never happens mid-recording or mid-transcription, where a
+/// missed pong is more likely a busy webview than a hung one.
+#[cfg(desktop)]
+pub fn spawn_overlay_heartbeat(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut seq: u64 = 0;
+        loop {
+            let (interval_ms, timeout_ms) = heartbeat_timings(
+                get_setting_from_store(
+                    &app,
+                    "overlay_heartbeat_interval_ms",
+                    DEFAULT_OVERLAY_HEARTBEAT_INTERVAL_MS,
+                ),
+                get_setting_from_store(
+                    &app,
+                    "overlay_heartbeat_timeout_ms",
+                    DEFAULT_OVERLAY_HEARTBEAT_TIMEOUT_MS,
+                ),
+            );
+            std::thread::sleep(std::time::Duration::from_millis(interval_ms - timeout_ms));
+
+            if !get_setting_from_store(&app, "overlay_heartbeat_enabled", false) {
+                std::thread::sleep(std::time::Duration::from_millis(timeout_ms));
+                continue;
+            }
+            // A hidden overlay has nothing to animate; don't reload it behind the user's back.
+            let Some(window) = app
+                .get_webview_window("overlay")
+                .filter(|window| window.is_visible().unwrap_or(false))
+            else {
+                std::thread::sleep(std::time::Duration::from_millis(timeout_ms));
+                continue;
+            };
+
+            seq += 1;
+            let _ = window.emit("overlay-ping", seq);
+            std::thread::sleep(std::time::Duration::from_millis(timeout_ms));
+
+            if LAST_OVERLAY_PONG.load(Ordering::SeqCst) >= seq {
+                continue;
+            }
+            let busy = app
+                .try_state::<crate::pipeline::SharedPipeline>()
+                .map(|pipeline| pipeline.state())
+                .is_some_and(|state| {
+                    matches!(
+                        state,
+                        crate::pipeline::PipelineState::Recording
+                            | crate::pipeline::PipelineState::Transcribing
+                            | crate::pipeline::PipelineState::Rewriting
+                    )
+                });
+            if busy {
+                log::debug!("Overlay missed heartbeat {} while the pipeline is busy", seq);
+                continue;
+            }
+            log::warn!(
+                "Overlay did not answer heartbeat {} within {}ms; reloading it",
+                seq,
+                timeout_ms
+            );
+            if let Err(e) = window.reload() {
+                log::error!("Failed to reload overlay: {}", e);
+            }
+        }
+    });
+}
+
+/// Answer to an `overlay-ping` heartbeat (see `spawn_overlay_heartbeat`).
+#[tauri::command]
+pub fn overlay_pong(seq: u64) {
+    LAST_OVERLAY_PONG.fetch_max(seq, Ordering::SeqCst);
+}
+
 /// Best-effort: snap the overlay window back to the saved preset position.
 ///
 /// Intended for cases where the overlay is not always visible (recording-only/never) and
 /// the user may have dragged it away since the last time it was shown.
 #[cfg(desktop)]
-pub fn snap_overlay_to_saved_position(app: &AppHandle) -> Result<(), String> {
+pub fn snap_overlay_to_saved_position(app: &AppHandle) -> Result<(), CommandError> {
     let position: String =
         get_setting_from_store(app, "widget_position", "bottom-center".to_string());
     set_widget_position_impl(app, position.as_str())
@@ -77,113 +569,484 @@ pub fn snap_overlay_to_saved_position(app: &AppHandle) -> Result<(), String> {
 /// Show the overlay window and, if the current mode is not "always", reset the window
 /// back to the saved preset position.
 #[cfg(desktop)]
-pub fn show_overlay_with_reset_if_not_always(app: &AppHandle) -> Result<(), String> {
+pub fn show_overlay_with_reset_if_not_always(app: &AppHandle) -> Result<(), CommandError> {
     let overlay_mode: String =
         get_setting_from_store(app, "overlay_mode", "recording_only".to_string());
 
-    if overlay_mode != "always" {
+    // Don't yank the window out from under the user's cursor mid-drag.
+    if overlay_mode != "always" && !overlay_drag_in_progress() {
         if let Err(e) = snap_overlay_to_saved_position(app) {
             log::warn!("Failed to snap overlay position on show: {}", e);
         }
     }
 
-    if let Some(window) = app.get_webview_window("overlay") {
-        window.show().map_err(|e| e.to_string())?;
+    set_overlay_visible(app, true)
+}
+
+/// How long the overlay frontend gets to animate out before the backend hides the window.
+const OVERLAY_HIDE_FALLBACK_MS: u64 = 220;
+
+/// The most recent show/hide request. Every request bumps `seq`, so a delayed hide (the
+/// animate-out fallback, or the frontend's `hide_overlay` after its exit animation) can tell
+/// whether a newer show arrived meanwhile and must not be undone.
+struct VisibilityIntent {
+    seq: u64,
+    visible: bool,
+}
+
+impl VisibilityIntent {
+    /// Record a new request, returning its sequence number.
+    fn request(&mut self, visible: bool) -> u64 {
+        self.seq += 1;
+        self.visible = visible;
+        self.seq
     }
 
-    Ok(())
+    /// A hide numbered `seq` may only run if it's still the latest request.
+    fn allows_hide(&self, seq: u64) -> bool {
+        self.seq == seq && !self.visible
+    }
+}
+
+static VISIBILITY_INTENT: Mutex<VisibilityIntent> = Mutex::new(VisibilityIntent {
+    seq: 0,
+    visible: false,
+});
+
+/// Show or hide the overlay now; this becomes the latest intent.
+///
+/// The intent lock is held while the show/hide is dispatched so window operations reach
+/// the event loop in the same order as the intents.
+pub fn set_overlay_visible(app: &AppHandle, visible: bool) -> Result<(), CommandError> {
+    let mut intent = VISIBILITY_INTENT
+        .lock()
+        .map_err(|_| CommandError::internal("Overlay visibility lock poisoned"))?;
+    intent.request(visible);
+    apply_overlay_visibility(app, visible)
 }
 
+/// Hide the overlay only if no show/hide was requested after the hide numbered `seq`.
+fn hide_overlay_if_latest(app: &AppHandle, seq: u64) -> Result<(), CommandError> {
+    let intent = VISIBILITY_INTENT
+        .lock()
+        .map_err(|_| CommandError::internal("Overlay visibility lock poisoned"))?;
+    if !intent.allows_hide(seq) {
+        log::debug!(
+            "Skipping stale overlay hide #{} (latest #{}, visible = {})",
+            seq,
+            intent.seq,
+            intent.visible
+        );
+        return Ok(());
+    }
+    apply_overlay_visibility(app, false)
+}
+
+fn apply_overlay_visibility(app: &AppHandle, visible: bool) -> Result<(), CommandError> {
+    let window = overlay_window(app)?;
+    if visible {
+        window.show().map_err(CommandError::window)
+    } else {
+        window.hide().map_err(CommandError::window)
+    }
+}
+
+/// Ask the overlay frontend to animate out (`overlay-hide-requested`, carrying the hide's
+/// sequence number for `hide_overlay`), then hide the window after a short fallback delay
+/// in case the frontend isn't listening.
+///
+/// The fallback hide is skipped if a newer show/hide was requested or `overlay_mode` is no
+/// longer `required_mode` by then.
+pub fn request_overlay_hide(app: &AppHandle, required_mode: &'static str) {
+    let seq = match VISIBILITY_INTENT.lock() {
+        Ok(mut intent) => intent.request(false),
+        Err(_) => return,
+    };
+    let _ = app.emit("overlay-hide-requested", seq);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(OVERLAY_HIDE_FALLBACK_MS)).await;
+        let current_mode: String =
+            get_setting_from_store(&app, "overlay_mode", "recording_only".to_string());
+        if current_mode == required_mode {
+            let _ = hide_overlay_if_latest(&app, seq);
+        }
+    });
+}
+
+/// Default floor for overlay width/height (logical px).
+const DEFAULT_OVERLAY_MIN_SIZE: f64 = 48.0;
+
+/// Read the `overlay_min_size` setting, falling back to the default (with a warning) for
+/// values that aren't positive or can't fit on the overlay's current monitor, so a bad
+/// setting never blocks resizing.
+fn get_overlay_min_size(app: &AppHandle, window: &tauri::WebviewWindow) -> f64 {
+    #[cfg(desktop)]
+    let min_size: f64 = get_setting_from_store(app, "overlay_min_size", DEFAULT_OVERLAY_MIN_SIZE);
+    #[cfg(not(desktop))]
+    let min_size = {
+        let _ = app;
+        DEFAULT_OVERLAY_MIN_SIZE
+    };
+
+    if !min_size.is_finite() || min_size <= 0.0 {
+        log::warn!(
+            "Invalid overlay_min_size {}; using {}",
+            min_size,
+            DEFAULT_OVERLAY_MIN_SIZE
+        );
+        return DEFAULT_OVERLAY_MIN_SIZE;
+    }
+
+    if let Ok(Some(monitor)) = window.current_monitor() {
+        let scale = monitor.scale_factor();
+        let screen_width = monitor.size().width as f64 / scale;
+        let screen_height = monitor.size().height as f64 / scale;
+
+        if min_size > screen_width || min_size > screen_height {
+            log::warn!(
+                "overlay_min_size {} exceeds monitor size {}x{}; using {}",
+                min_size,
+                screen_width,
+                screen_height,
+                DEFAULT_OVERLAY_MIN_SIZE
+            );
+            return DEFAULT_OVERLAY_MIN_SIZE;
+        }
+    }
+
+    min_size
+}
+
+/// Logical size last requested through `resize_overlay`, re-applied when the overlay lands
+/// on a display with a different scale factor.
+static OVERLAY_LOGICAL_SIZE: Mutex<Option<(f64, f64)>> = Mutex::new(None);
+
+#[tauri::command]
+pub async fn resize_overlay(app: AppHandle, width: f64, height: f64) -> Result<(), CommandError> {
+    resize_overlay_or_defer(&app, width, height)
+}
+
+/// A drag counts as finished once the overlay hasn't moved for this long (the OS doesn't
+/// report the mouse-up that ends a native window drag).
+const DRAG_SETTLE_MS: u64 = 200;
+
+/// How often a deferred resize checks whether the drag has finished.
+const DRAG_POLL_MS: u64 = 50;
+
+/// Whether the user is dragging the overlay, plus a resize that arrived meanwhile.
+///
+/// A resize recomputes the position from the window's current geometry, which is stale
+/// mid-drag and makes the window jump. So resizes during a drag are held back, and the
+/// latest one is applied from the settled position once the drag ends.
+struct DragTracker {
+    /// Drag start or the last move during it; `None` when not dragging.
+    last_activity: Option<Instant>,
+    pending_resize: Option<(f64, f64)>,
+    flush_scheduled: bool,
+}
+
+enum ResizeDecision {
+    Apply,
+    /// Held until the drag ends. `schedule_flush` is set for the first deferred resize,
+    /// whose caller starts the thread that applies it.
+    Deferred { schedule_flush: bool },
+}
+
+enum FlushPoll {
+    Dragging,
+    Settled(Option<(f64, f64)>),
+}
+
+impl DragTracker {
+    fn start(&mut self, now: Instant) {
+        self.last_activity = Some(now);
+    }
+
+    fn moved(&mut self, now: Instant) {
+        if self.in_progress(now) {
+            self.last_activity = Some(now);
+        }
+    }
+
+    fn in_progress(&mut self, now: Instant) -> bool {
+        match self.last_activity {
+            Some(at) if now.duration_since(at) < Duration::from_millis(DRAG_SETTLE_MS) => true,
+            Some(_) => {
+                self.last_activity = None;
+                false
+            }
+            None => false,
+        }
+    }
+
+    fn request_resize(&mut self, size: (f64, f64), now: Instant) -> ResizeDecision {
+        if !self.in_progress(now) {
+            // Newer than anything still pending.
+            self.pending_resize = None;
+            return ResizeDecision::Apply;
+        }
+        self.pending_resize = Some(size);
+        ResizeDecision::Deferred {
+            schedule_flush: !std::mem::replace(&mut self.flush_scheduled, true),
+        }
+    }
+
+    fn poll_flush(&mut self, now: Instant) -> FlushPoll {
+        if self.in_progress(now) {
+            return FlushPoll::Dragging;
+        }
+        self.flush_scheduled = false;
+        FlushPoll::Settled(self.pending_resize.take())
+    }
+}
+
+static DRAG_TRACKER: Mutex<DragTracker> = Mutex::new(DragTracker {
+    last_activity: None,
+    pending_resize: None,
+    flush_scheduled: false,
+});
+
+/// Called by the overlay right before it starts a native window drag.
 #[tauri::command]
-pub async fn resize_overlay(app: AppHandle, width: f64, height: f64) -> Result<(), String> {
+pub fn overlay_drag_started() {
+    if let Ok(mut tracker) = DRAG_TRACKER.lock() {
+        tracker.start(Instant::now());
+    }
+}
+
+/// Feed overlay `Moved` window events into the drag tracker.
+pub fn note_overlay_moved() {
+    if let Ok(mut tracker) = DRAG_TRACKER.lock() {
+        tracker.moved(Instant::now());
+    }
+}
+
+fn overlay_drag_in_progress() -> bool {
+    DRAG_TRACKER
+        .lock()
+        .map(|mut tracker| tracker.in_progress(Instant::now()))
+        .unwrap_or(false)
+}
+
+/// Resize now, or after the drag in progress has settled (see `DragTracker`).
+fn resize_overlay_or_defer(app: &AppHandle, width: f64, height: f64) -> Result<(), CommandError> {
+    let decision = match DRAG_TRACKER.lock() {
+        Ok(mut tracker) => tracker.request_resize((width, height), Instant::now()),
+        Err(_) => ResizeDecision::Apply,
+    };
+    match decision {
+        ResizeDecision::Apply => resize_overlay_impl(app, width, height),
+        ResizeDecision::Deferred { schedule_flush } => {
+            log::debug!("Overlay is being dragged; deferring resize to {}x{}", width, height);
+            if schedule_flush {
+                spawn_deferred_resize(app.clone());
+            }
+            Ok(())
+        }
+    }
+}
+
+fn spawn_deferred_resize(app: AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(Duration::from_millis(DRAG_POLL_MS));
+        let poll = match DRAG_TRACKER.lock() {
+            Ok(mut tracker) => tracker.poll_flush(Instant::now()),
+            Err(_) => return,
+        };
+        let FlushPoll::Settled(pending) = poll else {
+            continue;
+        };
+        if let Some((width, height)) = pending {
+            let app_for_resize = app.clone();
+            let _ = app.run_on_main_thread(move || {
+                if let Err(e) = resize_overlay_impl(&app_for_resize, width, height) {
+                    log::warn!("Failed to apply overlay resize deferred by drag: {}", e);
+                }
+            });
+        }
+        return;
+    });
+}
+
+/// Whether a window whose inner size is `physical` at `scale` no longer matches the
+/// intended logical size (more than half a logical pixel off in either dimension).
+fn differs_from_logical_size(intended: (f64, f64), physical: (u32, u32), scale: f64) -> bool {
+    if scale <= 0.0 {
+        return false;
+    }
+    let width = physical.0 as f64 / scale;
+    let height = physical.1 as f64 / scale;
+    (width - intended.0).abs() > 0.5 || (height - intended.1).abs() > 0.5
+}
+
+/// Keep the overlay's apparent size when it moves between displays with different DPI.
+///
+/// The OS rescales the window's physical size by its own rules on a scale-factor change,
+/// which on some setups makes the overlay jump in size. Re-applies the last logical size
+/// from `resize_overlay` (including its min-size and clamping logic). Deferred to the main
+/// loop so it runs after the platform has applied its own suggested size.
+pub fn handle_overlay_scale_change(app: &AppHandle, scale_factor: f64, new_inner_size: (u32, u32)) {
+    let Some(intended) = OVERLAY_LOGICAL_SIZE.lock().ok().and_then(|size| *size) else {
+        return;
+    };
+    if !differs_from_logical_size(intended, new_inner_size, scale_factor) {
+        return;
+    }
+
+    log::info!(
+        "Overlay scale factor changed to {}; re-applying {}x{} logical size",
+        scale_factor,
+        intended.0,
+        intended.1
+    );
+    let app_for_resize = app.clone();
+    let _ = app.run_on_main_thread(move || {
+        if let Err(e) = resize_overlay_or_defer(&app_for_resize, intended.0, intended.1) {
+            log::warn!("Failed to re-apply overlay size after scale change: {}", e);
+        }
+    });
+}
+
+fn resize_overlay_impl(app: &AppHandle, width: f64, height: f64) -> Result<(), CommandError> {
+    if let Ok(mut size) = OVERLAY_LOGICAL_SIZE.lock() {
+        *size = Some((width, height));
+    }
+
+    let window = overlay_window(app)?;
     // Enforce minimum dimensions to prevent invisible window
-    let min_size = 48.0;
+    let min_size = get_overlay_min_size(app, &window);
     let width = width.max(min_size);
     let height = height.max(min_size);
 
-    if let Some(window) = app.get_webview_window("overlay") {
-        // We position using *outer* geometry (position + size), because that's what the OS
-        // uses for window placement. On Windows/macOS the outer size includes decorations,
-        // so using the requested logical size directly can cause subtle drift.
-        let prev = if let (Ok(pos), Ok(outer_size), Ok(inner_size)) =
-            (window.outer_position(), window.outer_size(), window.inner_size())
-        {
-            let scale = window.scale_factor().unwrap_or(1.0);
-            let x = pos.x as f64 / scale;
-            let y = pos.y as f64 / scale;
-            let w = outer_size.width as f64 / scale;
-            let h = outer_size.height as f64 / scale;
-            let inner_w = inner_size.width as f64 / scale;
-            let inner_h = inner_size.height as f64 / scale;
-            Some((x, y, w, h, inner_w, inner_h, scale))
-        } else {
-            None
-        };
+    // We position using *outer* geometry (position + size), because that's what the OS
+    // uses for window placement. On Windows/macOS the outer size includes decorations,
+    // so using the requested logical size directly can cause subtle drift.
+    let prev = if let (Ok(pos), Ok(outer_size), Ok(inner_size)) =
+        (window.outer_position(), window.outer_size(), window.inner_size())
+    {
+        let scale = window.scale_factor().unwrap_or(1.0);
+        let x = pos.x as f64 / scale;
+        let y = pos.y as f64 / scale;
+        let w = outer_size.width as f64 / scale;
+        let h = outer_size.height as f64 / scale;
+        let inner_w = inner_size.width as f64 / scale;
+        let inner_h = inner_size.height as f64 / scale;
+        Some((x, y, w, h, inner_w, inner_h, scale))
+    } else {
+        None
+    };
 
-        // Set the new size
-        window
-            .set_size(tauri::Size::Logical(tauri::LogicalSize { width, height }))
-            .map_err(|e| e.to_string())?;
-
-        // For the fixed collapsed/expanded toggle sizes (56x56 <-> 264x56), keep the window
-        // center fixed so the expanded state grows out from the collapsed widget's location.
-        // Avoid clamping in this path: users prefer slight off-screen over "push away" drift.
-        let is_fixed_toggle_size = (height - 56.0).abs() < 0.5
-            && ((width - 56.0).abs() < 0.5 || (width - 264.0).abs() < 0.5);
-
-        if let Some((prev_x, prev_y, prev_outer_w, prev_outer_h, prev_inner_w, prev_inner_h, scale)) =
-            prev
-        {
-            // Try to use the actual outer size after resize (most accurate).
-            // Fall back to estimating using the previous decoration delta if needed.
-            let (new_outer_w, new_outer_h) = match window.outer_size() {
-                Ok(sz) => (sz.width as f64 / scale, sz.height as f64 / scale),
-                Err(_) => {
-                    // Estimate decoration delta using the pre-resize outer vs inner sizes.
-                    let decor_w = (prev_outer_w - prev_inner_w).max(0.0);
-                    let decor_h = (prev_outer_h - prev_inner_h).max(0.0);
-                    (width + decor_w, height + decor_h)
-                }
-            };
+    // Set the new size
+    window
+        .set_size(tauri::Size::Logical(tauri::LogicalSize { width, height }))
+        .map_err(CommandError::window)?;
 
-            let mut x;
-            let mut y;
+    // For the fixed collapsed/expanded toggle sizes (56x56 <-> 264x56), keep the window
+    // center fixed so the expanded state grows out from the collapsed widget's location.
+    // Avoid clamping in this path: users prefer slight off-screen over "push away" drift.
+    let is_fixed_toggle_size = (height - 56.0).abs() < 0.5
+        && ((width - 56.0).abs() < 0.5 || (width - 264.0).abs() < 0.5);
 
-            if is_fixed_toggle_size {
-                let cx = prev_x + prev_outer_w / 2.0;
-                let cy = prev_y + prev_outer_h / 2.0;
-                x = cx - new_outer_w / 2.0;
-                y = cy - new_outer_h / 2.0;
-            } else {
-                // Default: preserve top-left and clamp to screen bounds.
-                x = prev_x;
-                y = prev_y;
-
-                if let Ok(Some(monitor)) = window.current_monitor() {
-                    let screen_size = monitor.size();
-                    let scale = monitor.scale_factor();
-                    let screen_width = screen_size.width as f64 / scale;
-                    let screen_height = screen_size.height as f64 / scale;
-
-                    let margin = 12.0;
-                    let max_x = (screen_width - new_outer_w - margin).max(margin);
-                    let max_y = (screen_height - new_outer_h - margin).max(margin);
-
-                    x = x.clamp(margin, max_x);
-                    y = y.clamp(margin, max_y);
-                }
+    if let Some((prev_x, prev_y, prev_outer_w, prev_outer_h, prev_inner_w, prev_inner_h, scale)) =
+        prev
+    {
+        // Try to use the actual outer size after resize (most accurate).
+        // Fall back to estimating using the previous decoration delta if needed.
+        let (new_outer_w, new_outer_h) = match window.outer_size() {
+            Ok(sz) => (sz.width as f64 / scale, sz.height as f64 / scale),
+            Err(_) => {
+                // Estimate decoration delta using the pre-resize outer vs inner sizes.
+                let decor_w = (prev_outer_w - prev_inner_w).max(0.0);
+                let decor_h = (prev_outer_h - prev_inner_h).max(0.0);
+                (width + decor_w, height + decor_h)
             }
+        };
+
+        let mut x;
+        let mut y;
 
-            window
-                .set_position(tauri::Position::Logical(tauri::LogicalPosition { x, y }))
-                .map_err(|e| e.to_string())?;
+        if is_fixed_toggle_size {
+            let cx = prev_x + prev_outer_w / 2.0;
+            let cy = prev_y + prev_outer_h / 2.0;
+            x = cx - new_outer_w / 2.0;
+            y = cy - new_outer_h / 2.0;
+        } else {
+            // Default: preserve top-left and clamp to screen bounds.
+            x = prev_x;
+            y = prev_y;
+
+            if let Ok(Some(monitor)) = window.current_monitor() {
+                let screen_size = monitor.size();
+                let scale = monitor.scale_factor();
+                let screen_width = screen_size.width as f64 / scale;
+                let screen_height = screen_size.height as f64 / scale;
+
+                let margin = 12.0;
+                let max_x = (screen_width - new_outer_w - margin).max(margin);
+                let max_y = (screen_height - new_outer_h - margin).max(margin);
+
+                x = x.clamp(margin, max_x);
+                y = y.clamp(margin, max_y);
+            }
         }
+
+        window
+            .set_position(tauri::Position::Logical(tauri::LogicalPosition { x, y }))
+            .map_err(CommandError::window)?;
     }
     Ok(())
 }
 
+/// Width/height of an overlay size preset (logical px).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OverlayPresetSize {
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Built-in size presets. `dot` and `compact` match the overlay's collapsed/expanded toggle
+/// sizes, so switching between them keeps the widget centered.
+fn builtin_overlay_preset(name: &str) -> Option<OverlayPresetSize> {
+    let (width, height) = match name {
+        "dot" => (56.0, 56.0),
+        "compact" => (264.0, 56.0),
+        "expanded" => (360.0, 96.0),
+        _ => return None,
+    };
+    Some(OverlayPresetSize { width, height })
+}
+
+/// Resize the overlay to a named preset.
+///
+/// User-defined presets in the `overlay_presets` setting (`{ "name": { "width", "height" } }`)
+/// take precedence over the built-in `dot`, `compact` and `expanded`.
 #[tauri::command]
-pub async fn show_overlay(app: AppHandle) -> Result<(), String> {
+pub async fn set_overlay_preset(app: AppHandle, name: String) -> Result<(), CommandError> {
+    #[cfg(desktop)]
+    let custom: std::collections::HashMap<String, OverlayPresetSize> =
+        get_setting_from_store(&app, "overlay_presets", Default::default());
+    #[cfg(not(desktop))]
+    let custom: std::collections::HashMap<String, OverlayPresetSize> = Default::default();
+
+    let preset = custom
+        .get(&name)
+        .copied()
+        .or_else(|| builtin_overlay_preset(&name))
+        .ok_or_else(|| CommandError::InvalidArgument(format!("Unknown overlay preset: {}", name)))?;
+
+    log::info!(
+        "Applying overlay preset '{}' ({}x{})",
+        name,
+        preset.width,
+        preset.height
+    );
+    resize_overlay_or_defer(&app, preset.width, preset.height)
+}
+
+#[tauri::command]
+pub async fn show_overlay(app: AppHandle) -> Result<(), CommandError> {
     #[cfg(desktop)]
     {
         return show_overlay_with_reset_if_not_always(&app);
@@ -191,75 +1054,600 @@ pub async fn show_overlay(app: AppHandle) -> Result<(), String> {
 
     #[cfg(not(desktop))]
     {
-        if let Some(window) = app.get_webview_window("overlay") {
-            window.show().map_err(|e| e.to_string())?;
-        }
-        Ok(())
+        set_overlay_visible(&app, true)
     }
 }
 
+/// Hide the overlay. Pass the `seq` from `overlay-hide-requested` when hiding in response
+/// to it, so a hide that lost a race with a newer show is ignored.
 #[tauri::command]
-pub async fn hide_overlay(app: AppHandle) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("overlay") {
-        window.hide().map_err(|e| e.to_string())?;
+pub async fn hide_overlay(app: AppHandle, seq: Option<u64>) -> Result<(), CommandError> {
+    match seq {
+        Some(seq) => hide_overlay_if_latest(&app, seq),
+        None => set_overlay_visible(&app, false),
     }
-    Ok(())
 }
 
 /// Set overlay mode: "always", "never", or "recording_only"
 #[tauri::command]
-pub async fn set_overlay_mode(app: AppHandle, mode: String) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("overlay") {
-        match mode.as_str() {
-            "always" => {
-                window.show().map_err(|e| e.to_string())?;
-            }
-            "never" => {
-                // Ask the frontend to animate out before we hide.
-                let _ = app.emit("overlay-hide-requested", ());
-
-                // Fallback hide (in case the overlay UI isn't ready to handle the event).
-                let window_clone = window.clone();
-                let app_clone = app.clone();
-                tauri::async_runtime::spawn(async move {
-                    tokio::time::sleep(std::time::Duration::from_millis(220)).await;
-                    let current_mode: String = get_setting_from_store(
-                        &app_clone,
-                        "overlay_mode",
-                        "recording_only".to_string(),
-                    );
-                    if current_mode == "never" {
-                        let _ = window_clone.hide();
-                    }
-                });
-            }
-            "recording_only" => {
-                // Hide initially, will be shown when recording starts
-                let _ = app.emit("overlay-hide-requested", ());
-                let window_clone = window.clone();
-                let app_clone = app.clone();
-                tauri::async_runtime::spawn(async move {
-                    tokio::time::sleep(std::time::Duration::from_millis(220)).await;
-                    let current_mode: String = get_setting_from_store(
-                        &app_clone,
-                        "overlay_mode",
-                        "recording_only".to_string(),
-                    );
-                    if current_mode == "recording_only" {
-                        let _ = window_clone.hide();
-                    }
-                });
-            }
-            _ => {
-                return Err(format!("Invalid overlay mode: {}", mode));
-            }
+pub async fn set_overlay_mode(app: AppHandle, mode: String) -> Result<(), CommandError> {
+    if !matches!(mode.as_str(), "always" | "never" | "recording_only") {
+        return Err(CommandError::InvalidArgument(format!(
+            "Invalid overlay mode: {}",
+            mode
+        )));
+    }
+    overlay_window(&app)?;
+
+    match mode.as_str() {
+        "always" => set_overlay_visible(&app, true)?,
+        // Ask the frontend to animate out before we hide.
+        "never" => request_overlay_hide(&app, "never"),
+        // Hide initially, will be shown when recording starts
+        _ => request_overlay_hide(&app, "recording_only"),
+    }
+    Ok(())
+}
+
+/// Rebuild the overlay window if it's gone (closed by the OS, or its webview crashed).
+///
+/// Returns `false` if the overlay already existed. The new window is placed at the saved
+/// position and shown or hidden according to `overlay_mode`. Async because creating a
+/// window from a synchronous command can deadlock on Windows.
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn recreate_overlay(app: AppHandle) -> Result<bool, CommandError> {
+    if app.get_webview_window("overlay").is_some() {
+        return Ok(false);
+    }
+
+    // This command runs on a worker thread, but windows must be created on the main thread
+    // (macOS requires it).
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let handle = app.clone();
+    app.run_on_main_thread(move || {
+        let _ = tx.send(crate::create_overlay_window(&handle).map(|_| ()));
+    })
+    .map_err(CommandError::window)?;
+    rx.await
+        .map_err(|_| CommandError::internal("Overlay creation was dropped"))?
+        .map_err(CommandError::window)?;
+    log::info!("Recreated the overlay window");
+
+    let overlay_mode: String =
+        get_setting_from_store(&app, "overlay_mode", "recording_only".to_string());
+    set_overlay_visible(&app, overlay_mode == "always")?;
+    Ok(true)
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn recreate_overlay(_app: AppHandle) -> Result<bool, CommandError> {
+    Err(CommandError::Internal(
+        "The overlay is not supported on this platform".to_string(),
+    ))
+}
+
+/// Lock or unlock the overlay position.
+///
+/// Dragging is started by the overlay frontend (`startDragging`), so the lock is enforced
+/// there: it checks `is_overlay_draggable` / listens for `overlay-draggable-changed` and
+/// skips starting a drag when locked. Show/hide, resize and preset positioning via commands
+/// keep working while locked.
+///
+/// This is independent of click-through: click-through ignores *all* mouse input (so
+/// dragging is moot), while the drag lock only blocks moving the window and still lets
+/// clicks through to the overlay's buttons.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn set_overlay_draggable(app: AppHandle, enabled: bool) -> Result<(), CommandError> {
+    let store = app
+        .store("settings.json")
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+    store.set("overlay_draggable", serde_json::json!(enabled));
+    store
+        .save()
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+
+    log::info!("Overlay drag {}", if enabled { "unlocked" } else { "locked" });
+    let _ = app.emit("overlay-draggable-changed", enabled);
+    Ok(())
+}
+
+/// Whether the user may drag the overlay window (see `set_overlay_draggable`).
+#[cfg(desktop)]
+#[tauri::command]
+pub fn is_overlay_draggable(app: AppHandle) -> bool {
+    get_setting_from_store(&app, "overlay_draggable", true)
+}
+
+// Stubs for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn set_overlay_draggable(_app: AppHandle, _enabled: bool) -> Result<(), CommandError> {
+    Ok(())
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn is_overlay_draggable(_app: AppHandle) -> bool {
+    true
+}
+
+/// Keep the overlay above other windows (default on) and persist the preference.
+///
+/// On macOS the overlay is an NSPanel: "on" uses the screen-saver level so it also stays
+/// above fullscreen apps, "off" drops it to the normal window level.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn set_overlay_always_on_top(app: AppHandle, enabled: bool) -> Result<(), CommandError> {
+    let store = app
+        .store("settings.json")
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+    store.set("overlay_always_on_top", serde_json::json!(enabled));
+    store
+        .save()
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+
+    apply_overlay_always_on_top(&app, enabled);
+    Ok(())
+}
+
+/// Apply the always-on-top preference to the overlay window. A missing window is only
+/// logged; the saved preference is used when the overlay is created.
+#[cfg(desktop)]
+pub fn apply_overlay_always_on_top(app: &AppHandle, enabled: bool) {
+    let Some(window) = app.get_webview_window("overlay") else {
+        log::info!("Overlay window not found; always-on-top will apply when it is created");
+        return;
+    };
+
+    if let Err(e) = window.set_always_on_top(enabled) {
+        log::warn!("Failed to set overlay always-on-top: {}", e);
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use tauri_nspanel::{ManagerExt, PanelLevel};
+        match app.get_webview_panel("overlay") {
+            Ok(panel) => panel.set_level(if enabled {
+                PanelLevel::ScreenSaver.value()
+            } else {
+                PanelLevel::Normal.value()
+            }),
+            Err(_) => log::debug!("Overlay is not a panel; window always-on-top only"),
+        }
+    }
+
+    log::info!("Overlay always-on-top {}", if enabled { "on" } else { "off" });
+}
+
+// Stub for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn set_overlay_always_on_top(_app: AppHandle, _enabled: bool) -> Result<(), CommandError> {
+    Ok(())
+}
+
+/// Largest accepted overlay corner radius (logical px).
+pub(crate) const MAX_OVERLAY_CORNER_RADIUS: f64 = 64.0;
+
+/// Default overlay corner radius (logical px), matching the overlay's built-in styling.
+pub const DEFAULT_OVERLAY_CORNER_RADIUS: f64 = 16.0;
+
+/// Outline of the overlay window's content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverlayShape {
+    /// Fully rounded ends; `corner_radius` is ignored.
+    Pill,
+    /// Rounded corners of `corner_radius`.
+    Rounded,
+    /// Sharp corners (radius 0).
+    Square,
+}
+
+impl OverlayShape {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "pill" => Some(Self::Pill),
+            "rounded" => Some(Self::Rounded),
+            "square" => Some(Self::Square),
+            _ => None,
         }
     }
+}
+
+/// Overlay shape settings, emitted as `overlay-shape` for the frontend to apply.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OverlayShapeConfig {
+    pub shape: OverlayShape,
+    pub corner_radius: f64,
+}
+
+impl OverlayShapeConfig {
+    /// Validate user input. `square` always stores radius 0.
+    pub fn new(shape: &str, corner_radius: f64) -> Result<Self, CommandError> {
+        let shape = OverlayShape::parse(shape).ok_or_else(|| {
+            CommandError::InvalidArgument(format!(
+                "Invalid overlay shape: {} (expected pill, rounded or square)",
+                shape
+            ))
+        })?;
+
+        // Also rejects NaN.
+        if !(0.0..=MAX_OVERLAY_CORNER_RADIUS).contains(&corner_radius) {
+            return Err(CommandError::InvalidArgument(format!(
+                "Overlay corner radius must be between 0 and {}, got {}",
+                MAX_OVERLAY_CORNER_RADIUS, corner_radius
+            )));
+        }
+
+        let corner_radius = if shape == OverlayShape::Square {
+            0.0
+        } else {
+            corner_radius
+        };
+        Ok(Self {
+            shape,
+            corner_radius,
+        })
+    }
+}
+
+/// Persist the overlay shape and broadcast it as `overlay-shape`.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn set_overlay_shape(
+    app: AppHandle,
+    shape: String,
+    corner_radius: f64,
+) -> Result<OverlayShapeConfig, CommandError> {
+    let config = OverlayShapeConfig::new(&shape, corner_radius)?;
+
+    let store = app
+        .store("settings.json")
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+    store.set("overlay_shape", serde_json::json!(config.shape));
+    store.set("overlay_corner_radius", serde_json::json!(config.corner_radius));
+    store
+        .save()
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+
+    log::info!(
+        "Overlay shape -> {:?} (radius {})",
+        config.shape,
+        config.corner_radius
+    );
+    let _ = app.emit("overlay-shape", config);
+    Ok(config)
+}
+
+/// The saved overlay shape, for the overlay to apply on startup. Invalid stored values fall
+/// back to the default rounded shape.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn get_overlay_shape(app: AppHandle) -> OverlayShapeConfig {
+    let shape: String = get_setting_from_store(&app, "overlay_shape", "rounded".to_string());
+    let corner_radius: f64 =
+        get_setting_from_store(&app, "overlay_corner_radius", DEFAULT_OVERLAY_CORNER_RADIUS);
+    OverlayShapeConfig::new(&shape, corner_radius).unwrap_or(OverlayShapeConfig {
+        shape: OverlayShape::Rounded,
+        corner_radius: DEFAULT_OVERLAY_CORNER_RADIUS,
+    })
+}
+
+// Stubs for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn set_overlay_shape(
+    _app: AppHandle,
+    shape: String,
+    corner_radius: f64,
+) -> Result<OverlayShapeConfig, CommandError> {
+    OverlayShapeConfig::new(&shape, corner_radius)
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn get_overlay_shape(_app: AppHandle) -> OverlayShapeConfig {
+    OverlayShapeConfig {
+        shape: OverlayShape::Rounded,
+        corner_radius: DEFAULT_OVERLAY_CORNER_RADIUS,
+    }
+}
+
+/// A saved overlay placement (see `save_overlay_layout`).
+///
+/// Position is in physical desktop coordinates so it round-trips exactly across monitors
+/// with different scale factors; size is logical like the rest of the overlay API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverlayLayout {
+    pub x: i32,
+    pub y: i32,
+    pub width: f64,
+    pub height: f64,
+    /// Name of the monitor the overlay was on, if the OS reports one.
+    pub monitor: Option<String>,
+}
+
+#[cfg(desktop)]
+type OverlayLayouts = std::collections::BTreeMap<String, OverlayLayout>;
+
+#[cfg(desktop)]
+fn write_overlay_layouts(app: &AppHandle, layouts: &OverlayLayouts) -> Result<(), CommandError> {
+    let store = app
+        .store("settings.json")
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+    store.set("overlay_layouts", serde_json::json!(layouts));
+    store
+        .save()
+        .map_err(|e| CommandError::Storage(e.to_string()))
+}
+
+/// Save the overlay's current position, size and monitor under `name` (overwrites).
+#[cfg(desktop)]
+#[tauri::command]
+pub fn save_overlay_layout(app: AppHandle, name: String) -> Result<OverlayLayout, CommandError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(CommandError::InvalidArgument(
+            "Layout name must not be empty".to_string(),
+        ));
+    }
+
+    let window = overlay_window(&app)?;
+
+    let position = window.outer_position().map_err(CommandError::window)?;
+    let size = window.inner_size().map_err(CommandError::window)?;
+    let scale = window.scale_factor().map_err(CommandError::window)?;
+    let monitor = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .and_then(|m| m.name().cloned());
+
+    let layout = OverlayLayout {
+        x: position.x,
+        y: position.y,
+        width: size.width as f64 / scale,
+        height: size.height as f64 / scale,
+        monitor,
+    };
+
+    let mut layouts: OverlayLayouts = get_setting_from_store(&app, "overlay_layouts", Default::default());
+    layouts.insert(name.clone(), layout.clone());
+    write_overlay_layouts(&app, &layouts)?;
+
+    log::info!("Saved overlay layout '{}': {:?}", name, layout);
+    Ok(layout)
+}
+
+/// Restore a layout saved with `save_overlay_layout`.
+///
+/// If the layout's monitor is no longer connected, the size is still applied and the
+/// overlay is placed at the saved preset position on the primary monitor instead.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn apply_overlay_layout(app: AppHandle, name: String) -> Result<(), CommandError> {
+    let layouts: OverlayLayouts = get_setting_from_store(&app, "overlay_layouts", Default::default());
+    let layout = layouts
+        .get(name.trim())
+        .cloned()
+        .ok_or_else(|| CommandError::InvalidArgument(format!("Unknown overlay layout: {}", name)))?;
+
+    let window = overlay_window(&app)?;
+
+    let min_size = get_overlay_min_size(&app, &window);
+    window
+        .set_size(tauri::Size::Logical(tauri::LogicalSize {
+            width: layout.width.max(min_size),
+            height: layout.height.max(min_size),
+        }))
+        .map_err(CommandError::window)?;
+
+    let monitors = app.available_monitors().map_err(CommandError::window)?;
+    let monitor_present = match &layout.monitor {
+        Some(saved) => monitors.iter().any(|m| m.name() == Some(saved)),
+        // Nothing to check against; trust the coordinates.
+        None => true,
+    };
+
+    if monitor_present {
+        window
+            .set_position(tauri::Position::Physical(tauri::PhysicalPosition {
+                x: layout.x,
+                y: layout.y,
+            }))
+            .map_err(CommandError::window)?;
+        log::info!("Applied overlay layout '{}'", name);
+    } else {
+        log::warn!(
+            "Monitor {:?} for overlay layout '{}' is not connected; using the primary monitor",
+            layout.monitor,
+            name
+        );
+        relocate_orphaned_overlay(&app)?;
+    }
+
     Ok(())
 }
 
+/// Names of saved overlay layouts, sorted.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn list_overlay_layouts(app: AppHandle) -> Vec<String> {
+    let layouts: OverlayLayouts = get_setting_from_store(&app, "overlay_layouts", Default::default());
+    layouts.into_keys().collect()
+}
+
+// Stubs for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn save_overlay_layout(_app: AppHandle, _name: String) -> Result<OverlayLayout, CommandError> {
+    Err(CommandError::Internal(
+        "Overlay layouts are not supported on this platform".to_string(),
+    ))
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn apply_overlay_layout(_app: AppHandle, _name: String) -> Result<(), CommandError> {
+    Err(CommandError::Internal(
+        "Overlay layouts are not supported on this platform".to_string(),
+    ))
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn list_overlay_layouts(_app: AppHandle) -> Vec<String> {
+    Vec::new()
+}
+
 /// Set overlay widget position on screen
 #[tauri::command]
-pub async fn set_widget_position(app: AppHandle, position: String) -> Result<(), String> {
+pub async fn set_widget_position(app: AppHandle, position: String) -> Result<(), CommandError> {
     set_widget_position_impl(&app, position.as_str())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_offset_within_area() {
+        let area = (1000.0, 600.0);
+        let window = (200.0, 50.0);
+        assert_eq!(preset_offset("top-left", area, window, 24.0).unwrap(), (24.0, 24.0));
+        assert_eq!(preset_offset("center", area, window, 24.0).unwrap(), (400.0, 275.0));
+        assert_eq!(preset_offset("bottom-right", area, window, 24.0).unwrap(), (776.0, 526.0));
+        assert!(preset_offset("middle", area, window, 24.0).is_err());
+    }
+
+    #[test]
+    fn test_heartbeat_timings_are_clamped() {
+        assert_eq!(heartbeat_timings(5_000, 3_000), (5_000, 3_000));
+        assert_eq!(heartbeat_timings(0, 0), (1_000, 500));
+        assert_eq!(heartbeat_timings(600_000, 100_000), (60_000, 60_000));
+        // The timeout can't outlast the interval.
+        assert_eq!(heartbeat_timings(2_000, 10_000), (2_000, 2_000));
+    }
+
+    #[test]
+    fn test_differs_from_logical_size_across_scales() {
+        // 264x56 logical: correct on a 2x display, doubled-up after moving to a 1x display.
+        assert!(!differs_from_logical_size((264.0, 56.0), (528, 112), 2.0));
+        assert!(differs_from_logical_size((264.0, 56.0), (528, 112), 1.0));
+        assert!(!differs_from_logical_size((264.0, 56.0), (396, 84), 1.5));
+        // Rounding to whole physical pixels is not a mismatch.
+        assert!(!differs_from_logical_size((263.7, 56.0), (396, 84), 1.5));
+        assert!(!differs_from_logical_size((264.0, 56.0), (0, 0), 0.0));
+    }
+
+    #[test]
+    fn test_preview_snippet_short_text_unchanged() {
+        assert_eq!(overlay_preview_snippet("  hello world ", 40).as_deref(), Some("hello world"));
+        assert_eq!(overlay_preview_snippet("   ", 40), None);
+    }
+
+    #[test]
+    fn test_preview_snippet_cuts_on_word_boundary() {
+        let snippet = overlay_preview_snippet("the quick brown fox jumps", 12).unwrap();
+        assert_eq!(snippet, "…fox jumps");
+        assert!(snippet.chars().count() <= 12);
+    }
+
+    #[test]
+    fn test_preview_snippet_handles_multibyte() {
+        let snippet = overlay_preview_snippet("café crème brûlée très bon", 12).unwrap();
+        assert_eq!(snippet, "…très bon");
+
+        // A single long word of multibyte chars: cut mid-word rather than return nothing.
+        let snippet = overlay_preview_snippet("日本語のテキストです", 5).unwrap();
+        assert_eq!(snippet, "…ストです");
+    }
+
+    #[test]
+    fn test_overlay_shape_config_validation() {
+        let config = OverlayShapeConfig::new("pill", 12.0).unwrap();
+        assert_eq!(config.shape, OverlayShape::Pill);
+        assert_eq!(config.corner_radius, 12.0);
+
+        // Square corners are always sharp.
+        assert_eq!(OverlayShapeConfig::new("square", 20.0).unwrap().corner_radius, 0.0);
+
+        assert!(matches!(
+            OverlayShapeConfig::new("circle", 8.0),
+            Err(CommandError::InvalidArgument(_))
+        ));
+        assert!(OverlayShapeConfig::new("rounded", -1.0).is_err());
+        assert!(OverlayShapeConfig::new("rounded", f64::NAN).is_err());
+        assert!(OverlayShapeConfig::new("rounded", MAX_OVERLAY_CORNER_RADIUS + 1.0).is_err());
+    }
+
+    #[test]
+    fn test_overlay_shape_serializes_lowercase() {
+        let config = OverlayShapeConfig::new("rounded", 16.0).unwrap();
+        assert_eq!(
+            serde_json::to_value(config).unwrap(),
+            serde_json::json!({ "shape": "rounded", "corner_radius": 16.0 })
+        );
+    }
+
+    #[test]
+    fn test_stale_hide_loses_to_newer_show() {
+        let mut intent = VisibilityIntent {
+            seq: 0,
+            visible: false,
+        };
+
+        let hide = intent.request(false);
+        assert!(intent.allows_hide(hide));
+
+        // A show during the exit animation wins over the late hide.
+        intent.request(true);
+        assert!(!intent.allows_hide(hide));
+
+        // So does a later hide (only the newest hide acts).
+        let newer_hide = intent.request(false);
+        assert!(!intent.allows_hide(hide));
+        assert!(intent.allows_hide(newer_hide));
+    }
+
+    #[test]
+    fn test_resize_during_drag_is_deferred_until_settled() {
+        let mut tracker = DragTracker {
+            last_activity: None,
+            pending_resize: None,
+            flush_scheduled: false,
+        };
+        let t0 = Instant::now();
+        let ms = |n: u64| t0 + Duration::from_millis(n);
+
+        assert!(matches!(tracker.request_resize((56.0, 56.0), t0), ResizeDecision::Apply));
+
+        tracker.start(t0);
+        assert!(matches!(
+            tracker.request_resize((264.0, 56.0), ms(10)),
+            ResizeDecision::Deferred { schedule_flush: true }
+        ));
+        // A second resize mid-drag replaces the first; the flush is already scheduled.
+        assert!(matches!(
+            tracker.request_resize((360.0, 96.0), ms(20)),
+            ResizeDecision::Deferred { schedule_flush: false }
+        ));
+
+        // Still moving: the drag keeps the resize on hold.
+        tracker.moved(ms(150));
+        assert!(matches!(tracker.poll_flush(ms(300)), FlushPoll::Dragging));
+
+        // No movement for the settle time: the latest resize is applied, once.
+        assert!(matches!(
+            tracker.poll_flush(ms(150 + DRAG_SETTLE_MS)),
+            FlushPoll::Settled(Some((w, h))) if w == 360.0 && h == 96.0
+        ));
+        // Moves after the drag (our own repositioning) don't restart it.
+        tracker.moved(ms(500));
+        assert!(matches!(tracker.request_resize((56.0, 56.0), ms(500)), ResizeDecision::Apply));
+    }
+}
@@ -3,13 +3,17 @@
 //! These commands expose the recording pipeline functionality to the frontend,
 //! enabling voice dictation directly from the Tauri app.
 
-use crate::audio_capture::{AudioCaptureDiagnostics, VadAutoStopConfig};
-use crate::pipeline::{LlmOutcome, PipelineConfig, PipelineError, PipelineState, SharedPipeline};
+use crate::audio_capture::{AudioCaptureDiagnostics, RecordingLevelStats, VadAutoStopConfig};
+use crate::pipeline::{
+    LlmOutcome, PipelineConfig, PipelineError, PipelineState, RecordingState, SharedPipeline,
+    TranscriptionLatency,
+};
 use crate::recordings::{RecordingStore, RecordingsStats};
 use crate::request_log::RequestLogStore;
 use crate::history::{HistoryStorage, RequestModelInfo};
 use chrono::{Duration as ChronoDuration, Utc};
 use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Manager, State};
 
@@ -155,6 +159,10 @@ fn get_transcription_retention_delete_recordings(app: &AppHandle) -> bool {
 }
 
 pub(crate) fn apply_transcription_retention(app: &AppHandle) {
+    if let Some(history) = app.try_state::<HistoryStorage>() {
+        super::history::apply_max_history_age(app, &history);
+    }
+
     let Some(retention) = get_transcription_retention_duration(app) else {
         return;
     };
@@ -186,14 +194,16 @@ pub(crate) fn apply_transcription_retention(app: &AppHandle) {
             }
         }
     }
-
-    let _ = app.emit("history-changed", ());
 }
 
-/// Tauri-compatible error type for commands
+/// Tauri-compatible error type for commands.
+///
+/// Serializes as `{ "code": ..., "message": ... }`, the same shape as
+/// `commands::error::CommandError`, so the frontend reads every command error the same way.
 #[derive(Debug, serde::Serialize)]
 pub struct CommandError {
     pub message: String,
+    #[serde(rename = "code")]
     pub error_type: String,
 }
 
@@ -211,6 +221,7 @@ impl From<PipelineError> for CommandError {
             PipelineError::Cancelled => "cancelled",
             PipelineError::Timeout(_) => "timeout",
             PipelineError::RecordingTooLarge(_, _) => "size",
+            PipelineError::Unreachable(_) => "network",
         };
         Self {
             message: err.to_string(),
@@ -219,6 +230,18 @@ impl From<PipelineError> for CommandError {
     }
 }
 
+/// Keeps the shared error's `code` and `message` as they'd serialize from a text command.
+impl From<super::error::CommandError> for CommandError {
+    fn from(err: super::error::CommandError) -> Self {
+        let json = serde_json::to_value(&err).unwrap_or_default();
+        let field = |key: &str| json[key].as_str().map(str::to_string);
+        Self {
+            message: field("message").unwrap_or_else(|| err.to_string()),
+            error_type: field("code").unwrap_or_else(|| "unknown".to_string()),
+        }
+    }
+}
+
 impl From<String> for CommandError {
     fn from(message: String) -> Self {
         Self {
@@ -377,18 +400,20 @@ pub async fn pipeline_stop_and_transcribe(
                 None
             },
             llm_model: config.llm_config.model.clone(),
+            source_app: crate::windows_apps::get_foreground_process_path(),
         }
     };
 
     // Create an in-progress history entry so the History view shows a running request.
     if let Some(req_id) = active_request_id.as_deref() {
         if let Some(history) = app.try_state::<HistoryStorage>() {
+            super::history::apply_history_trim_strategy(&app, &history);
+            super::history::apply_history_session_gap(&app, &history);
             let _ = history.add_request_entry(
                 req_id.to_string(),
                 model_info,
                 max_saved_recordings,
             );
-            let _ = app.emit("history-changed", ());
         }
     }
 
@@ -469,7 +494,6 @@ pub async fn pipeline_stop_and_transcribe(
             if let Some(req_id) = active_request_id.as_deref() {
                 if let Some(history) = app.try_state::<HistoryStorage>() {
                     let _ = history.complete_request_error(req_id, e.to_string());
-                    let _ = app.emit("history-changed", ());
                 }
             }
 
@@ -576,8 +600,8 @@ pub async fn pipeline_stop_and_transcribe(
     // Update history entry with success text
     if let Some(req_id) = active_request_id.as_deref() {
         if let Some(history) = app.try_state::<HistoryStorage>() {
+            super::history::apply_history_redaction(&app, &history);
             let _ = history.complete_request_success(req_id, final_text.clone());
-            let _ = app.emit("history-changed", ());
         }
     }
 
@@ -634,17 +658,20 @@ pub async fn pipeline_retry_transcription(
             None
         },
         llm_model: config.llm_config.model.clone(),
+        // Retries are started from our own window, so there's no target app to record.
+        source_app: None,
     };
 
     // Create a history entry for the retry attempt.
     if let Some(req_id) = new_request_id.as_deref() {
         if let Some(history) = app.try_state::<HistoryStorage>() {
+            super::history::apply_history_trim_strategy(&app, &history);
+            super::history::apply_history_session_gap(&app, &history);
             let _ = history.add_request_entry(
                 req_id.to_string(),
                 model_info,
                 max_saved_recordings,
             );
-            let _ = app.emit("history-changed", ());
         }
     }
 
@@ -674,7 +701,6 @@ pub async fn pipeline_retry_transcription(
             if let Some(req_id) = new_request_id.as_deref() {
                 if let Some(history) = app.try_state::<HistoryStorage>() {
                     let _ = history.complete_request_error(req_id, e.to_string());
-                    let _ = app.emit("history-changed", ());
                 }
             }
 
@@ -724,8 +750,8 @@ pub async fn pipeline_retry_transcription(
     // Update history on success
     if let Some(req_id) = new_request_id.as_deref() {
         if let Some(history) = app.try_state::<HistoryStorage>() {
+            super::history::apply_history_redaction(&app, &history);
             let _ = history.complete_request_success(req_id, final_text.clone());
-            let _ = app.emit("history-changed", ());
         }
     }
 
@@ -799,6 +825,17 @@ pub fn pipeline_is_recording(
     Ok(pipeline.is_recording())
 }
 
+/// App-wide event carrying the new `RecordingState` ("idle", "recording", "transcribing")
+/// on every transition, emitted by the pipeline's state listener.
+pub const RECORDING_STATE_EVENT: &str = "recording-state-changed";
+
+/// Current recording state: the coarse view of the pipeline state, which is the single
+/// source of truth. `recording-state-changed` reports every later change.
+#[tauri::command]
+pub fn get_recording_state(pipeline: State<'_, SharedPipeline>) -> RecordingState {
+    pipeline.recording_state()
+}
+
 /// Configuration payload for updating the pipeline
 #[derive(Debug, serde::Deserialize)]
 pub struct PipelineConfigPayload {
@@ -1023,6 +1060,19 @@ pub async fn pipeline_test_transcribe_last_audio(
         .map_err(CommandError::from)
 }
 
+/// Measure one STT round trip (send to final transcript) with the current provider.
+///
+/// Uses the last captured audio when there is some, otherwise a short generated sample.
+#[tauri::command]
+pub async fn measure_transcription_latency(
+    pipeline: State<'_, SharedPipeline>,
+) -> Result<TranscriptionLatency, CommandError> {
+    pipeline
+        .measure_transcription_latency()
+        .await
+        .map_err(CommandError::from)
+}
+
 /// Whether there is a previously captured audio buffer available for STT testing.
 #[tauri::command]
 pub fn pipeline_has_last_audio(pipeline: State<'_, SharedPipeline>) -> Result<bool, CommandError> {
@@ -1071,6 +1121,66 @@ pub fn pipeline_get_last_recording_diagnostics(
     Ok(pipeline.last_recording_diagnostics())
 }
 
+/// App-wide event carrying the live input level, for integrations (e.g. a Stream Deck
+/// plugin) that want a meter without the overlay. Emitted only while
+/// `set_level_meter_enabled(true)` is in effect.
+pub const RECORDING_LEVEL_EVENT: &str = "recording-level";
+
+/// Set by `set_level_meter_enabled`. In-memory only; integrations enable it when they
+/// connect.
+static LEVEL_METER_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Payload of `recording-level`.
+///
+/// Sent up to ~60 times a second while recording, independent of overlay visibility. When
+/// recording ends, one final frame with `recording: false` and zero levels is sent so
+/// meters can drop back to silence.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct RecordingLevelEvent {
+    /// Increments with every captured audio chunk; 0 until the first chunk arrives.
+    pub seq: u64,
+    /// Root-mean-square amplitude of the latest chunk, in [0, 1].
+    pub rms: f32,
+    /// Peak absolute amplitude of the latest chunk, in [0, 1].
+    pub peak: f32,
+    pub recording: bool,
+}
+
+impl RecordingLevelEvent {
+    pub fn stopped() -> Self {
+        Self {
+            seq: 0,
+            rms: 0.0,
+            peak: 0.0,
+            recording: false,
+        }
+    }
+}
+
+pub fn level_meter_enabled() -> bool {
+    LEVEL_METER_ENABLED.load(Ordering::SeqCst)
+}
+
+/// Turn the app-wide `recording-level` event on or off. The overlay's own level events
+/// are unaffected.
+#[tauri::command]
+pub fn set_level_meter_enabled(enabled: bool) {
+    LEVEL_METER_ENABLED.store(enabled, Ordering::SeqCst);
+    log::info!("Level meter events {}", if enabled { "enabled" } else { "disabled" });
+}
+
+/// Get peak/average input level of the most recent recording (raw, pre-gate samples).
+///
+/// `too_quiet` lets the UI suggest raising the mic gain when transcriptions come back empty.
+#[tauri::command]
+pub fn get_last_recording_level_stats(
+    pipeline: State<'_, SharedPipeline>,
+) -> Result<Option<RecordingLevelStats>, CommandError> {
+    Ok(pipeline
+        .last_recording_diagnostics()
+        .map(|diag| RecordingLevelStats::from(diag.stats)))
+}
+
 /// Full pipeline helper: Start recording if not recording, or stop and transcribe if recording
 #[tauri::command]
 pub async fn pipeline_toggle(
@@ -1148,4 +1258,33 @@ mod tests {
         let error = CommandError::from("test error".to_string());
         assert_eq!(error.message, "test error");
     }
+
+    #[test]
+    fn test_command_error_shape_matches_shared_error() {
+        let error = CommandError::from(PipelineError::NoProvider);
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["code"], "config");
+        assert_eq!(json["message"], PipelineError::NoProvider.to_string());
+
+        let shared = crate::commands::error::CommandError::internal("x");
+        let shared = serde_json::to_value(shared).unwrap();
+        let keys = |v: &serde_json::Value| {
+            let mut keys: Vec<String> = v.as_object().unwrap().keys().cloned().collect();
+            keys.sort();
+            keys
+        };
+        assert_eq!(keys(&json), keys(&shared));
+    }
+
+    #[test]
+    fn test_command_error_keeps_shared_error_code() {
+        let shared = crate::commands::error::CommandError::PermissionDenied("no".to_string());
+        let error = CommandError::from(shared.clone());
+        assert_eq!(error.error_type, "permission_denied");
+        assert_eq!(error.message, "no");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::to_value(&shared).unwrap()
+        );
+    }
 }
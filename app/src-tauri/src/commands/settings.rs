@@ -80,7 +80,8 @@ pub async fn register_shortcuts(app: AppHandle) -> Result<(), String> {
         .map_err(|e| format!("Failed to unregister shortcuts: {}", e))?;
 
     // Collect shortcuts to register
-    let shortcuts: Vec<Shortcut> = vec![toggle_shortcut, hold_shortcut, paste_last_shortcut];
+    let mut shortcuts: Vec<Shortcut> = vec![toggle_shortcut, hold_shortcut, paste_last_shortcut];
+    shortcuts.extend(crate::mode_hotkey_shortcuts(&app, &shortcuts));
 
     // Register new shortcuts with handler
     shortcut_manager
@@ -107,3 +108,710 @@ pub async fn register_shortcuts(app: AppHandle) -> Result<(), String> {
 pub async fn register_shortcuts(_app: AppHandle) -> Result<(), String> {
     Ok(())
 }
+
+/// Bind `hotkey` to record and output with `mode`, or remove the binding when `mode` is
+/// `None`. Call `register_shortcuts` afterwards to apply.
+///
+/// Rejects unknown modes, and combos already used by the toggle/hold/paste-last hotkeys or
+/// bound to a different mode.
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn set_mode_hotkey(
+    app: AppHandle,
+    hotkey: HotkeyConfig,
+    mode: Option<String>,
+) -> Result<(), String> {
+    use crate::commands::text::OutputMode;
+    use std::collections::HashMap;
+
+    let combo = hotkey.to_shortcut_string();
+    let mut bindings: HashMap<String, String> =
+        get_setting_from_store(&app, "mode_hotkeys", HashMap::new());
+
+    // Drop any existing binding of this combo (however it was spelled) first.
+    bindings.retain(|bound, _| {
+        crate::normalize_shortcut_string(bound) != crate::normalize_shortcut_string(&combo)
+    });
+
+    if let Some(mode) = mode {
+        let mode = OutputMode::parse(&mode).ok_or_else(|| format!("Unknown output mode: {}", mode))?;
+        hotkey.to_shortcut()?;
+
+        let bound = bound_hotkeys(&app, &bindings);
+        if let Some(conflict) = crate::find_hotkey_conflict(&combo, &bound) {
+            return Err(format!("{} is already used by {}", combo, conflict));
+        }
+
+        log::info!("Binding {} to output mode {}", combo, mode.to_str());
+        bindings.insert(combo, mode.to_str().to_string());
+    } else {
+        log::info!("Unbinding mode hotkey {}", combo);
+    }
+
+    let store = app
+        .store("settings.json")
+        .map_err(|e| format!("Failed to open settings: {}", e))?;
+    store.set("mode_hotkeys", serde_json::json!(bindings));
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+// Stub for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn set_mode_hotkey(
+    _app: AppHandle,
+    _hotkey: HotkeyConfig,
+    _mode: Option<String>,
+) -> Result<(), String> {
+    Ok(())
+}
+
+/// Every combo the app currently binds, paired with a description of what uses it: the
+/// toggle/hold/paste-last hotkeys plus the given mode bindings.
+#[cfg(desktop)]
+fn bound_hotkeys(
+    app: &AppHandle,
+    mode_bindings: &std::collections::HashMap<String, String>,
+) -> Vec<(String, String)> {
+    let mut bound: Vec<(String, String)> = [
+        ("toggle", "toggle_hotkey", HotkeyConfig::default_toggle()),
+        ("hold", "hold_hotkey", HotkeyConfig::default_hold()),
+        ("paste-last", "paste_last_hotkey", HotkeyConfig::default_paste_last()),
+    ]
+    .into_iter()
+    .map(|(label, key, default)| {
+        let config: HotkeyConfig = get_setting_from_store(app, key, default);
+        (format!("the {} hotkey", label), config.to_shortcut_string())
+    })
+    .collect();
+    bound.extend(
+        mode_bindings
+            .iter()
+            .map(|(combo, mode)| (format!("the {} hotkey", mode), combo.clone())),
+    );
+    bound
+}
+
+/// Result of `validate_hotkey`.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct HotkeyValidation {
+    /// The combo parsed and its key is one the shortcut parser recognizes.
+    pub valid: bool,
+    /// The parsed combo, when it was well-formed.
+    pub hotkey: Option<HotkeyConfig>,
+    /// Why the combo isn't valid.
+    pub error: Option<String>,
+    /// What in this app already uses the combo, if anything.
+    pub conflict: Option<String>,
+    /// Whether the OS let us register the combo. `None` when it couldn't be checked
+    /// (invalid combo, already bound by this app, or not a desktop platform).
+    pub available: Option<bool>,
+}
+
+/// Check a hotkey string like "Ctrl+Alt+Space" before binding it.
+///
+/// Parses it into modifiers and a key, reports which of this app's hotkeys already use it,
+/// and probes whether another application holds it by briefly registering it. The probe is
+/// best effort: some platforms let several apps register the same combo.
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn validate_hotkey(app: AppHandle, combo: String) -> Result<HotkeyValidation, String> {
+    use std::collections::HashMap;
+
+    let hotkey = match HotkeyConfig::parse(&combo) {
+        Ok(hotkey) => hotkey,
+        Err(e) => {
+            return Ok(HotkeyValidation {
+                error: Some(e.to_string()),
+                ..Default::default()
+            })
+        }
+    };
+
+    let shortcut = match hotkey.to_shortcut() {
+        Ok(shortcut) => shortcut,
+        Err(e) => {
+            return Ok(HotkeyValidation {
+                hotkey: Some(hotkey),
+                error: Some(e),
+                ..Default::default()
+            })
+        }
+    };
+
+    let combo = hotkey.to_shortcut_string();
+    let mode_bindings: HashMap<String, String> =
+        get_setting_from_store(&app, "mode_hotkeys", HashMap::new());
+    let bound = bound_hotkeys(&app, &mode_bindings);
+    let conflict = crate::find_hotkey_conflict(&combo, &bound).map(str::to_string);
+
+    // Registering a combo we already hold would fail for the wrong reason, so only probe
+    // combos that are free as far as this app is concerned.
+    let shortcut_manager = app.global_shortcut();
+    let available = if conflict.is_some() || shortcut_manager.is_registered(shortcut) {
+        None
+    } else {
+        match shortcut_manager.register(shortcut) {
+            Ok(()) => {
+                if let Err(e) = shortcut_manager.unregister(shortcut) {
+                    log::warn!("Failed to release probed shortcut {}: {}", combo, e);
+                }
+                Some(true)
+            }
+            Err(e) => {
+                log::debug!("Shortcut {} is not available: {}", combo, e);
+                Some(false)
+            }
+        }
+    };
+
+    Ok(HotkeyValidation {
+        valid: true,
+        hotkey: Some(hotkey),
+        error: None,
+        conflict,
+        available,
+    })
+}
+
+// Non-desktop platforms have no global shortcuts, so only the parse is checked
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn validate_hotkey(_app: AppHandle, combo: String) -> Result<HotkeyValidation, String> {
+    Ok(match HotkeyConfig::parse(&combo) {
+        Ok(hotkey) => HotkeyValidation {
+            valid: true,
+            hotkey: Some(hotkey),
+            ..Default::default()
+        },
+        Err(e) => HotkeyValidation {
+            error: Some(e.to_string()),
+            ..Default::default()
+        },
+    })
+}
+
+/// Seeded settings that hold the user's own collections (saved presets, per-app overrides,
+/// profiles, patterns) or connection setup. A full reset keeps them; pass them in `keys` to
+/// reset them explicitly.
+#[cfg(desktop)]
+pub(crate) const RESET_PRESERVED_SETTINGS: &[&str] = &[
+    "overlay_presets",
+    "overlay_layouts",
+    "output_mode_overrides",
+    "keystroke_newline_key_overrides",
+    "output_mode_cues",
+    "mode_hotkeys",
+    "audio_profiles",
+    "rewrite_program_prompt_profiles",
+    "redact_patterns",
+    "proxy_url",
+];
+
+/// Reset settings to their defaults (the values seeded at startup) and emit
+/// `settings-reset` with the list of keys that were reset so the frontend can reload.
+///
+/// With `keys`, only those settings are reset; unknown keys are rejected. Without, every
+/// seeded setting except `RESET_PRESERVED_SETTINGS` is reset. API keys, the selected
+/// microphone and history are never touched. Runtime state (shortcuts, proxy, pipeline,
+/// cues) is re-applied afterwards.
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn reset_settings_to_defaults(
+    app: AppHandle,
+    keys: Option<Vec<String>>,
+) -> Result<Vec<String>, String> {
+    use tauri::Emitter;
+
+    let defaults = crate::default_settings()
+        .map_err(|e| format!("Failed to build default settings: {}", e))?;
+
+    if let Some(keys) = &keys {
+        let unknown: Vec<&str> = keys
+            .iter()
+            .map(String::as_str)
+            .filter(|key| !defaults.iter().any(|(known, _)| known == key))
+            .collect();
+        if !unknown.is_empty() {
+            return Err(format!("Unknown settings: {}", unknown.join(", ")));
+        }
+    }
+
+    let store = app
+        .store("settings.json")
+        .map_err(|e| format!("Failed to open settings: {}", e))?;
+
+    let mut reset: Vec<String> = Vec::new();
+    for (key, value) in defaults {
+        let selected = match &keys {
+            Some(keys) => keys.iter().any(|k| k == key),
+            None => !RESET_PRESERVED_SETTINGS.contains(&key),
+        };
+        if !selected {
+            continue;
+        }
+        store.set(key, value);
+        reset.push(key.to_string());
+    }
+
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+    log::info!("Reset {} settings to defaults", reset.len());
+
+    apply_bulk_settings_change(&app, &reset).await?;
+    let _ = app.emit("settings-reset", &reset);
+    Ok(reset)
+}
+
+// Stub for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn reset_settings_to_defaults(
+    _app: AppHandle,
+    _keys: Option<Vec<String>>,
+) -> Result<Vec<String>, String> {
+    Ok(Vec::new())
+}
+
+/// Settings without a seeded default that are still worth carrying to another machine.
+///
+/// API keys are deliberately absent: exports are plain files that get copied around.
+#[cfg(desktop)]
+const PORTABLE_UNSEEDED_SETTINGS: &[&str] = &[
+    "audio_cue",
+    "selected_mic_id",
+    "auto_mute_audio",
+    "noise_gate_strength",
+    "stt_model",
+    "llm_provider",
+    "llm_model",
+    "cleanup_prompt_sections",
+    "openai_reasoning_effort",
+    "anthropic_thinking_budget",
+    "gemini_thinking_budget",
+    "gemini_thinking_level",
+];
+
+/// Version written to (and accepted from) settings export files.
+#[cfg(desktop)]
+const SETTINGS_EXPORT_VERSION: u64 = 1;
+
+/// Outcome of checking one imported setting value.
+#[cfg(desktop)]
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SettingValidation {
+    Valid,
+    /// Out of range or slightly off type; stored as `value` instead.
+    Repaired {
+        value: serde_json::Value,
+        reason: String,
+    },
+    Invalid(String),
+}
+
+/// Bounds for numeric settings, matching the clamps applied where they're read. Imported
+/// values of the right type but outside these are clamped.
+#[cfg(desktop)]
+const NUMERIC_SETTING_RANGES: &[(&str, f64, f64)] = &[
+    ("overlay_min_size", 1.0, 4_096.0),
+    ("stt_timeout_seconds", 1.0, 600.0),
+    ("output_enter_delay_ms", 0.0, 2_000.0),
+    ("keystroke_verified_delay_ms", 5.0, 1_000.0),
+    ("type_then_paste_chars", 0.0, 500.0),
+    ("max_saved_recordings", 1.0, 100_000.0),
+    ("output_retries", 0.0, crate::commands::text::MAX_OUTPUT_RETRIES as f64),
+    ("confirm_output_timeout_secs", 5.0, 3_600.0),
+    ("cue_debounce_ms", 0.0, 2_000.0),
+    ("cue_crossfade_ms", 0.0, 1_000.0),
+    ("double_press_window_ms", 0.0, crate::MAX_DOUBLE_PRESS_WINDOW_MS as f64),
+];
+
+/// Clamp `value` into the range configured for `key`, if any. `None` means it's fine
+/// as is.
+#[cfg(desktop)]
+fn clamp_to_setting_range(
+    key: &str,
+    value: &serde_json::Value,
+) -> Option<(serde_json::Value, String)> {
+    let &(_, min, max) = NUMERIC_SETTING_RANGES.iter().find(|(name, ..)| *name == key)?;
+    let n = value.as_f64()?;
+    let clamped = n.clamp(min, max);
+    if clamped == n {
+        return None;
+    }
+    // Integer settings stay integers.
+    let repaired = if value.is_u64() {
+        serde_json::json!(clamped as u64)
+    } else {
+        serde_json::json!(clamped)
+    };
+    Some((repaired, format!("{} out of range ({}..={}); using {}", n, min, max, clamped)))
+}
+
+/// Check an imported value for `key` against the backend's parsers and the type of its
+/// default (`None` for unseeded settings, which only get the key-specific checks).
+///
+/// A `null` default means the setting is optional, so any value is accepted.
+#[cfg(desktop)]
+pub(crate) fn validate_setting_value(
+    key: &str,
+    value: &serde_json::Value,
+    default: Option<&serde_json::Value>,
+) -> SettingValidation {
+    use crate::audio::AudioCue;
+    use crate::commands::overlay::{OverlayShape, MAX_OVERLAY_CORNER_RADIUS};
+    use crate::commands::text::{NewlineKey, OutputMode};
+    use serde_json::Value;
+    use std::collections::HashMap;
+
+    let as_str = || value.as_str().unwrap_or_default();
+
+    match key {
+        _ if key.ends_with("_hotkey") => {
+            return match serde_json::from_value::<HotkeyConfig>(value.clone()) {
+                Ok(hotkey) if !hotkey.key.is_empty() => SettingValidation::Valid,
+                Ok(_) => SettingValidation::Invalid("hotkey has no key".to_string()),
+                Err(e) => SettingValidation::Invalid(format!("not a hotkey: {}", e)),
+            };
+        }
+        "mode_hotkeys" => {
+            let Ok(bindings) = serde_json::from_value::<HashMap<String, String>>(value.clone())
+            else {
+                return SettingValidation::Invalid(
+                    "expected an object of hotkey -> output mode".to_string(),
+                );
+            };
+            return match bindings.values().find(|mode| OutputMode::parse(mode).is_none()) {
+                Some(mode) => SettingValidation::Invalid(format!("unknown output mode: {}", mode)),
+                None => SettingValidation::Valid,
+            };
+        }
+        "output_mode_cues" => {
+            let Ok(cues) = serde_json::from_value::<HashMap<String, String>>(value.clone()) else {
+                return SettingValidation::Invalid(
+                    "expected an object of output mode -> audio cue".to_string(),
+                );
+            };
+            if let Some(mode) = cues.keys().find(|mode| OutputMode::parse(mode).is_none()) {
+                return SettingValidation::Invalid(format!("unknown output mode: {}", mode));
+            }
+            return match cues.values().find(|cue| AudioCue::parse(cue).is_none()) {
+                Some(cue) => SettingValidation::Invalid(format!("unknown audio cue: {}", cue)),
+                None => SettingValidation::Valid,
+            };
+        }
+        "output_mode" if OutputMode::parse(as_str()).is_none() => {
+            return SettingValidation::Invalid(format!("unknown output mode: {}", value));
+        }
+        "keystroke_newline_key" if NewlineKey::parse(as_str()).is_none() => {
+            return SettingValidation::Invalid(format!("unknown newline key: {}", value));
+        }
+        "keystroke_newline_key_overrides" => {
+            let Ok(keys) = serde_json::from_value::<HashMap<String, String>>(value.clone()) else {
+                return SettingValidation::Invalid(
+                    "expected an object of app path -> newline key".to_string(),
+                );
+            };
+            return match keys.values().find(|key| NewlineKey::parse(key).is_none()) {
+                Some(key) => SettingValidation::Invalid(format!("unknown newline key: {}", key)),
+                None => SettingValidation::Valid,
+            };
+        }
+        "audio_cue" if AudioCue::parse(as_str()).is_none() => {
+            return SettingValidation::Invalid(format!("unknown audio cue: {}", value));
+        }
+        "audio_cue_start" | "audio_cue_stop"
+            if !value.is_null() && AudioCue::parse(as_str()).is_none() =>
+        {
+            return SettingValidation::Invalid(format!("unknown audio cue: {}", value));
+        }
+        "active_cue_theme" if !value.is_null() => {
+            if let Err(e) = crate::cue_themes::validate_theme_name(as_str()) {
+                return SettingValidation::Invalid(e);
+            }
+        }
+        "proxy_url" if !value.is_null() => {
+            let Some(url) = value.as_str() else {
+                return SettingValidation::Invalid("expected a proxy URL or null".to_string());
+            };
+            if let Err(e) = crate::http_client::validate_proxy_url(url) {
+                return SettingValidation::Invalid(e);
+            }
+        }
+        "overlay_shape" if OverlayShape::parse(as_str()).is_none() => {
+            return SettingValidation::Invalid(format!("unknown overlay shape: {}", value));
+        }
+        "overlay_corner_radius" => {
+            let Some(radius) = value.as_f64() else {
+                return SettingValidation::Invalid("expected a number".to_string());
+            };
+            let clamped = radius.clamp(0.0, MAX_OVERLAY_CORNER_RADIUS);
+            if clamped != radius {
+                return SettingValidation::Repaired {
+                    value: serde_json::json!(clamped),
+                    reason: format!("corner radius {} out of range; using {}", radius, clamped),
+                };
+            }
+            return SettingValidation::Valid;
+        }
+        "cue_volume" => {
+            let Some(volume) = value.as_f64() else {
+                return SettingValidation::Invalid("expected a number".to_string());
+            };
+            let clamped = volume.clamp(0.0, 1.0);
+            if clamped != volume {
+                return SettingValidation::Repaired {
+                    value: serde_json::json!(clamped),
+                    reason: format!("cue volume {} out of range; using {}", volume, clamped),
+                };
+            }
+            return SettingValidation::Valid;
+        }
+        // Negative values (pre-roll) are allowed, unlike other integer settings.
+        "recording_start_delay_ms" => {
+            let Some(ms) = value.as_i64() else {
+                return SettingValidation::Invalid("expected whole milliseconds".to_string());
+            };
+            let max = crate::audio_capture::MAX_RECORDING_START_DELAY_MS;
+            let clamped = ms.clamp(-max, max);
+            if clamped != ms {
+                return SettingValidation::Repaired {
+                    value: serde_json::json!(clamped),
+                    reason: format!("start delay {}ms out of range; using {}ms", ms, clamped),
+                };
+            }
+            return SettingValidation::Valid;
+        }
+        _ => {}
+    }
+
+    let Some(default) = default else {
+        return SettingValidation::Valid;
+    };
+    let type_name = |v: &Value| match v {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    };
+
+    let typed = match (default, value) {
+        (Value::Null, _) => SettingValidation::Valid,
+        // Integer settings are read as u64, so a float would silently fall back to the
+        // default at runtime. Whole-valued floats (e.g. 10.0 from another tool) are fixed up.
+        (Value::Number(d), Value::Number(n)) if d.is_u64() && !n.is_u64() => match n.as_f64() {
+            Some(f) if f >= 0.0 && f.fract() == 0.0 && f <= u64::MAX as f64 => {
+                SettingValidation::Repaired {
+                    value: serde_json::json!(f as u64),
+                    reason: format!("{} converted to an integer", n),
+                }
+            }
+            _ => SettingValidation::Invalid(format!("expected a non-negative integer, got {}", n)),
+        },
+        (d, v) if std::mem::discriminant(d) == std::mem::discriminant(v) => {
+            SettingValidation::Valid
+        }
+        (d, v) => SettingValidation::Invalid(format!(
+            "expected {}, got {}",
+            type_name(d),
+            type_name(v)
+        )),
+    };
+
+    match typed {
+        SettingValidation::Valid => match clamp_to_setting_range(key, value) {
+            Some((value, reason)) => SettingValidation::Repaired { value, reason },
+            None => SettingValidation::Valid,
+        },
+        SettingValidation::Repaired { value, reason } => {
+            match clamp_to_setting_range(key, &value) {
+                Some((value, range)) => SettingValidation::Repaired {
+                    value,
+                    reason: format!("{}; {}", reason, range),
+                },
+                None => SettingValidation::Repaired { value, reason },
+            }
+        }
+        invalid => invalid,
+    }
+}
+
+/// One imported setting that was repaired or skipped, and why.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SettingIssue {
+    pub key: String,
+    pub reason: String,
+}
+
+/// What `import_settings` changed.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct SettingsImportReport {
+    /// Keys whose stored value changed (including repaired ones).
+    pub changed: Vec<String>,
+    /// Values that were stored after being fixed up.
+    pub repaired: Vec<SettingIssue>,
+    /// Unknown keys and invalid values; the current value was kept.
+    pub skipped: Vec<SettingIssue>,
+}
+
+/// Re-apply settings the backend caches instead of reading on use, after `keys` changed
+/// in bulk (reset or import).
+#[cfg(desktop)]
+async fn apply_bulk_settings_change(app: &AppHandle, keys: &[String]) -> Result<(), String> {
+    crate::commands::text::apply_output_audit_settings(app);
+    crate::commands::audio::apply_cue_settings(app);
+    // Also applies `proxy_url` before rebuilding the providers.
+    crate::commands::config::sync_pipeline_config(app.clone())?;
+    crate::commands::overlay::apply_overlay_always_on_top(
+        app,
+        get_setting_from_store(app, "overlay_always_on_top", true),
+    );
+    if keys
+        .iter()
+        .any(|key| key.ends_with("_hotkey") || key == "mode_hotkeys")
+    {
+        register_shortcuts(app.clone()).await?;
+    }
+    Ok(())
+}
+
+/// Write the portable settings (timing, modes, overlay layout, devices, hotkeys, ...) to
+/// `path` as JSON. History and API keys are not included.
+///
+/// Returns the number of settings written.
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn export_settings(app: AppHandle, path: String) -> Result<usize, String> {
+    let defaults = crate::default_settings()
+        .map_err(|e| format!("Failed to build default settings: {}", e))?;
+    let store = app
+        .store("settings.json")
+        .map_err(|e| format!("Failed to open settings: {}", e))?;
+
+    let mut settings = serde_json::Map::new();
+    let keys = defaults
+        .iter()
+        .map(|(key, _)| *key)
+        .chain(PORTABLE_UNSEEDED_SETTINGS.iter().copied());
+    for key in keys {
+        if let Some(value) = store.get(key) {
+            settings.insert(key.to_string(), value);
+        }
+    }
+
+    let count = settings.len();
+    let export = serde_json::json!({
+        "version": SETTINGS_EXPORT_VERSION,
+        "settings": settings,
+    });
+    let json = serde_json::to_string_pretty(&export)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+
+    log::info!("Exported {} settings to {}", count, path);
+    Ok(count)
+}
+
+/// Merge settings from a file written by `export_settings` into the current settings.
+///
+/// Each value is checked with `validate_setting_value`: unknown keys and invalid values
+/// are skipped (keeping the current value), fixable ones are repaired. Keys missing from
+/// the file are left alone. Emits `settings-imported` with the report.
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn import_settings(app: AppHandle, path: String) -> Result<SettingsImportReport, String> {
+    use tauri::Emitter;
+
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let parsed: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("{} is not a settings export: {}", path, e))?;
+    if let Some(version) = parsed.get("version").and_then(|v| v.as_u64()) {
+        if version > SETTINGS_EXPORT_VERSION {
+            return Err(format!(
+                "Settings export version {} is newer than supported ({})",
+                version, SETTINGS_EXPORT_VERSION
+            ));
+        }
+    }
+    let Some(incoming) = parsed.get("settings").and_then(|v| v.as_object()) else {
+        return Err(format!("{} has no \"settings\" object", path));
+    };
+
+    let defaults = crate::default_settings()
+        .map_err(|e| format!("Failed to build default settings: {}", e))?;
+    let store = app
+        .store("settings.json")
+        .map_err(|e| format!("Failed to open settings: {}", e))?;
+
+    let mut report = SettingsImportReport::default();
+    for (key, value) in incoming {
+        let default = defaults.iter().find(|(known, _)| known == key).map(|(_, v)| v);
+        if default.is_none() && !PORTABLE_UNSEEDED_SETTINGS.contains(&key.as_str()) {
+            report.skipped.push(SettingIssue {
+                key: key.clone(),
+                reason: "unknown setting".to_string(),
+            });
+            continue;
+        }
+
+        let value = match validate_setting_value(key, value, default) {
+            SettingValidation::Valid => value.clone(),
+            SettingValidation::Repaired { value, reason } => {
+                report.repaired.push(SettingIssue {
+                    key: key.clone(),
+                    reason,
+                });
+                value
+            }
+            SettingValidation::Invalid(reason) => {
+                report.skipped.push(SettingIssue {
+                    key: key.clone(),
+                    reason,
+                });
+                continue;
+            }
+        };
+
+        if store.get(key).as_ref() != Some(&value) {
+            store.set(key.clone(), value);
+            report.changed.push(key.clone());
+        }
+    }
+
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+    log::info!(
+        "Imported settings from {}: {} changed, {} repaired, {} skipped",
+        path,
+        report.changed.len(),
+        report.repaired.len(),
+        report.skipped.len()
+    );
+
+    apply_bulk_settings_change(&app, &report.changed).await?;
+    let _ = app.emit("settings-imported", &report);
+    Ok(report)
+}
+
+// Stubs for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn export_settings(_app: AppHandle, _path: String) -> Result<usize, String> {
+    Err("Settings export is only available on desktop".to_string())
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn import_settings(
+    _app: AppHandle,
+    _path: String,
+) -> Result<SettingsImportReport, String> {
+    Err("Settings import is only available on desktop".to_string())
+}
@@ -1,22 +1,117 @@
+use super::error::CommandError;
+use crate::accessibility;
+use crate::audio::{self, AudioCue, SoundType};
+use crate::foreground_window::{self, ForegroundWindow};
+use crate::linux_paste::{self, LinuxPasteConfig};
+use crate::modifier_state;
+use crate::output_audit;
+use crate::output_backend::{
+    send_command_shortcut, OutputBackend, SystemBackend, KEY_EVENT_DELAY_MS,
+    NEXT_OUTPUT_CLIPBOARD_ONLY, OUTPUT_PAUSED,
+};
+use crate::clipboard_restore::{restore_snapshot_within, ClipboardWrite, TimedClipboard};
+use crate::clipboard_snapshot;
+use crate::direct_insert;
+use crate::tts;
 use arboard::Clipboard;
 use enigo::{Direction, Enigo, Key, Keyboard, Settings};
-use std::sync::{Mutex, OnceLock};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::{Mutex, MutexGuard, OnceLock, TryLockError};
 use std::sync::mpsc;
 use std::thread;
-use std::time::Duration;
-use tauri::AppHandle;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+#[cfg(desktop)]
+use tauri_plugin_store::StoreExt;
 
 /// Delay after clipboard operations to ensure system stability
 const CLIPBOARD_STABILIZATION_DELAY_MS: u64 = 50;
 
-/// Delay between keyboard key press and release events
-const KEY_EVENT_DELAY_MS: u64 = 50;
-
 /// Delay before restoring previous clipboard content
 const CLIPBOARD_RESTORE_DELAY_MS: u64 = 100;
 
+/// Time given to the target app to put its selection on the clipboard after Ctrl+C / Cmd+C.
+const SELECTION_COPY_DELAY_MS: u64 = 150;
+
+/// Default longest wait for the user to physically release hotkey modifiers before we start
+/// typing (`modifier_release_max_wait_ms`).
+///
+/// Output is usually triggered by a global shortcut (e.g. Ctrl+Alt+Space). If any of those
+/// modifiers are still held when we inject keystrokes, the target app sees shortcuts
+/// instead of text. Where key state can't be read, this is a fixed sleep.
+pub const DEFAULT_MODIFIER_RELEASE_WAIT_MS: u64 = 250;
+
+/// How often key state is polled during the modifier release wait.
+const MODIFIER_POLL_INTERVAL_MS: u64 = 10;
+
+/// Default max characters sent per `enigo.text` call in keystroke mode.
+pub const DEFAULT_KEYSTROKE_CHUNK_CHARS: usize = 32;
+
+/// Default delay between keystroke chunks so target apps can keep up.
+pub const DEFAULT_KEYSTROKE_CHUNK_DELAY_MS: u64 = 10;
+
+/// Default ± range for jittered chunk pauses when `keystroke_jitter_enabled` is on.
+pub const DEFAULT_KEYSTROKE_JITTER_MS: u64 = 8;
+
+/// Key events timed by `benchmark_keystroke_speed`.
+const BENCHMARK_KEY_EVENTS: usize = 40;
+
+/// Max characters selected by `OutputMode::KeystrokesAndSelect`.
+///
+/// Each selected character is a separate Shift+Left key event, so very long transcripts
+/// would take noticeably long to select.
+const MAX_SELECT_CHARS: usize = 500;
+
+/// Delay between Shift+Left clicks while selecting typed text.
+const SELECT_KEY_DELAY_MS: u64 = 2;
+
+/// Time for a re-activated window to actually take focus before we send keys.
+const REFOCUS_SETTLE_MS: u64 = 100;
+
+/// Pause before retrying output after a transient failure (e.g. another app holding the
+/// clipboard open).
+const OUTPUT_RETRY_DELAY_MS: u64 = 150;
+
+/// Default number of whole-output retries after a transient failure.
+pub const DEFAULT_OUTPUT_RETRIES: u32 = 1;
+
+/// Upper bound for `output_retries`, so a hand-edited value can't stall output.
+pub const MAX_OUTPUT_RETRIES: u32 = 5;
+
+/// Default delay before the optional Enter press after output.
+pub const DEFAULT_ENTER_DELAY_MS: u64 = 80;
+
+/// Default separator for `OutputMode::ClipboardAppend`.
+pub const DEFAULT_CLIPBOARD_APPEND_SEPARATOR: &str = "\n";
+
+/// Default per-character delay for `OutputMode::KeystrokesVerified`.
+pub const DEFAULT_VERIFIED_KEY_DELAY_MS: u64 = 40;
+
+/// Default max transcript length for keystroke-based modes.
+///
+/// At the default verified delay this is already ~80 seconds of typing.
+pub const DEFAULT_MAX_KEYSTROKE_CHARS: usize = 2000;
+
+/// Default number of characters typed before pasting in `OutputMode::TypeThenPaste`.
+pub const DEFAULT_TYPE_THEN_PASTE_CHARS: usize = 20;
+
+/// Default for `max_clipboard_capture_bytes` (1 MiB).
+pub const DEFAULT_MAX_CLIPBOARD_CAPTURE_BYTES: usize = 1024 * 1024;
+
 const SERVER_URL: &str = "http://127.0.0.1:8765";
 
+#[cfg(desktop)]
+fn get_setting_from_store<T: serde::de::DeserializeOwned>(app: &AppHandle, key: &str, default: T) -> T {
+    app.store("settings.json")
+        .ok()
+        .and_then(|store| store.get(key))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or(default)
+}
+
 /// Global lock to ensure we never run multiple output injections concurrently.
 ///
 /// Without this, two overlapping "type/paste" operations can interleave key events and
@@ -27,21 +122,404 @@ fn output_injection_lock() -> &'static Mutex<()> {
     OUTPUT_INJECTION_LOCK.get_or_init(|| Mutex::new(()))
 }
 
-fn maybe_hit_enter(enigo: &mut Enigo, hit_enter: bool) -> Result<(), String> {
-    if !hit_enter {
-        return Ok(());
+/// Whether output (and cue playback) is currently paused.
+#[tauri::command]
+pub fn is_output_paused() -> bool {
+    OUTPUT_PAUSED.load(Ordering::SeqCst)
+}
+
+/// Flip the one-shot clipboard-only target and return the new value.
+pub fn toggle_next_output_clipboard_only() -> bool {
+    !NEXT_OUTPUT_CLIPBOARD_ONLY.fetch_xor(true, Ordering::SeqCst)
+}
+
+/// Cancel every output still waiting for `confirm_output`; each ends as
+/// `OutputStatus::Cancelled`.
+pub fn cancel_pending_confirmations() {
+    for (_, pending) in pending_outputs().drain() {
+        let _ = pending.reply.send(false);
+    }
+}
+
+/// Outputs held by `confirm_before_output`, keyed by the token sent in `output-pending`.
+static PENDING_OUTPUTS: OnceLock<Mutex<HashMap<String, PendingOutput>>> = OnceLock::new();
+
+/// An output waiting for `confirm_output` / `cancel_output`.
+struct PendingOutput {
+    text: String,
+    reply: mpsc::Sender<bool>,
+}
+
+fn pending_outputs() -> MutexGuard<'static, HashMap<String, PendingOutput>> {
+    PENDING_OUTPUTS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+/// Payload of `output-pending`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PendingOutputEvent {
+    /// Pass to `confirm_output` or `cancel_output`.
+    pub token: String,
+    pub text: String,
+    pub mode: &'static str,
+    /// The output is cancelled (and `output-pending-expired` emitted) after this long.
+    pub timeout_ms: u64,
+}
+
+/// Emit `output-pending` and block until the user confirms or cancels, or `timeout`
+/// passes (which cancels). Returns whether to go ahead.
+fn await_output_confirmation(
+    app: &AppHandle,
+    text: &str,
+    mode: OutputMode,
+    timeout: Duration,
+) -> bool {
+    let token = uuid::Uuid::new_v4().to_string();
+    let (reply, answer) = mpsc::channel();
+    pending_outputs().insert(
+        token.clone(),
+        PendingOutput {
+            text: text.to_string(),
+            reply,
+        },
+    );
+    log::info!("Output {} waiting for confirmation", token);
+    let _ = app.emit(
+        "output-pending",
+        PendingOutputEvent {
+            token: token.clone(),
+            text: text.to_string(),
+            mode: mode.to_str(),
+            timeout_ms: timeout.as_millis() as u64,
+        },
+    );
+
+    match answer.recv_timeout(timeout) {
+        Ok(confirmed) => confirmed,
+        Err(_) => {
+            // An answer may have raced the timeout; take it if it did.
+            if pending_outputs().remove(&token).is_some() {
+                log::info!("Output {} not confirmed within {:?}; cancelling", token, timeout);
+                let _ = app.emit("output-pending-expired", &token);
+            }
+            answer.try_recv().unwrap_or(false)
+        }
     }
+}
 
-    // Small delay to avoid racing the paste keystroke.
-    thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
+fn answer_pending_output(token: &str, confirmed: bool) -> Result<(), CommandError> {
+    let pending = pending_outputs().remove(token).ok_or_else(|| {
+        CommandError::InvalidArgument(format!(
+            "No pending output {} (already answered or timed out)",
+            token
+        ))
+    })?;
+    log::info!(
+        "Output {} {} ({} chars)",
+        token,
+        if confirmed { "confirmed" } else { "cancelled" },
+        pending.text.chars().count()
+    );
+    let _ = pending.reply.send(confirmed);
+    Ok(())
+}
+
+/// Let an output held by `confirm_before_output` (announced in `output-pending`) go ahead.
+///
+/// Async so it never runs on the main thread, which a hotkey-triggered output may be
+/// waiting on.
+#[tauri::command]
+pub async fn confirm_output(token: String) -> Result<(), CommandError> {
+    answer_pending_output(&token, true)
+}
+
+/// Drop an output held by `confirm_before_output`; it ends as `OutputStatus::Cancelled`.
+#[tauri::command]
+pub async fn cancel_output(token: String) -> Result<(), CommandError> {
+    answer_pending_output(&token, false)
+}
+
+/// Pause or resume all output without disabling the hotkeys (e.g. during a meeting).
+///
+/// While paused, `output_text_with_mode` returns `OutputStatus::Paused` without touching
+/// the clipboard or keyboard, and cues are silent. Emits `output-paused-changed`.
+#[tauri::command]
+pub fn set_output_paused(app: AppHandle, paused: bool) {
+    OUTPUT_PAUSED.store(paused, Ordering::SeqCst);
+    audio::set_cues_paused(paused);
+    log::info!("Output {}", if paused { "paused" } else { "resumed" });
+    let _ = app.emit("output-paused-changed", paused);
+}
+
+/// Enable or disable the output audit log and persist the choice.
+///
+/// `path` defaults to `output-audit.log` in the app data directory; `include_text` (off by
+/// default) adds the transcript itself to each record.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn set_output_audit_log(
+    app: AppHandle,
+    enabled: bool,
+    path: Option<String>,
+    include_text: Option<bool>,
+) -> Result<(), CommandError> {
+    let path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
 
-    enigo
-        .key(Key::Return, Direction::Click)
-        .map_err(|e| e.to_string())?;
+    let store = app
+        .store("settings.json")
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+    store.set("output_audit_log_enabled", serde_json::json!(enabled));
+    store.set("output_audit_log_path", serde_json::json!(path));
+    store.set(
+        "output_audit_log_include_text",
+        serde_json::json!(include_text.unwrap_or(false)),
+    );
+    store
+        .save()
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
 
+    apply_output_audit_settings(&app);
     Ok(())
 }
 
+// Stub for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn set_output_audit_log(
+    _app: AppHandle,
+    _enabled: bool,
+    _path: Option<String>,
+    _include_text: Option<bool>,
+) -> Result<(), CommandError> {
+    Err(CommandError::Internal(
+        "Output audit log is not supported on this platform".to_string(),
+    ))
+}
+
+/// Configure the output audit log from settings (startup and after `set_output_audit_log`).
+#[cfg(desktop)]
+pub fn apply_output_audit_settings(app: &AppHandle) {
+    use tauri::Manager;
+
+    if !get_setting_from_store(app, "output_audit_log_enabled", false) {
+        output_audit::configure(None);
+        return;
+    }
+
+    let configured: Option<String> = get_setting_from_store(app, "output_audit_log_path", None);
+    let path = match configured {
+        Some(path) => std::path::PathBuf::from(path),
+        None => match app.path().app_data_dir() {
+            Ok(dir) => dir.join(output_audit::DEFAULT_AUDIT_LOG_FILE),
+            Err(e) => {
+                log::warn!("Output audit log disabled: no app data dir ({})", e);
+                output_audit::configure(None);
+                return;
+            }
+        },
+    };
+
+    log::info!("Output audit log: {}", path.display());
+    output_audit::configure(Some(output_audit::AuditConfig {
+        path,
+        include_text: get_setting_from_store(app, "output_audit_log_include_text", false),
+        redactor: app
+            .try_state::<crate::history::HistoryStorage>()
+            .map(|history| history.redactor())
+            .unwrap_or_default(),
+    }));
+}
+
+/// Linux paste settings (`linux_paste_shortcut`, `linux_shift_paste_apps`).
+#[cfg(desktop)]
+pub fn linux_paste_config(app: &AppHandle) -> LinuxPasteConfig {
+    let defaults = LinuxPasteConfig::default();
+    LinuxPasteConfig {
+        shortcut: linux_paste::PasteShortcutMode::from_str(&get_setting_from_store(
+            app,
+            "linux_paste_shortcut",
+            "auto".to_string(),
+        )),
+        shift_paste_apps: get_setting_from_store(
+            app,
+            "linux_shift_paste_apps",
+            defaults.shift_paste_apps,
+        ),
+    }
+}
+
+/// Record an output attempt in the audit log, if enabled.
+fn audit_output(
+    requested: OutputMode,
+    mode: OutputMode,
+    text: &str,
+    result: &Result<OutputResult, CommandError>,
+) {
+    if !output_audit::is_enabled() {
+        return;
+    }
+
+    let result = match result {
+        Ok(output) => match output.status {
+            OutputStatus::Success | OutputStatus::Fallback => "ok".to_string(),
+            OutputStatus::Empty => "empty".to_string(),
+            OutputStatus::Paused => "paused".to_string(),
+            OutputStatus::Cancelled => "cancelled".to_string(),
+        },
+        Err(e) => format!("error: {}", e),
+    };
+    let target_app = crate::windows_apps::get_foreground_process_path();
+    output_audit::record(
+        requested.to_str(),
+        mode.to_str(),
+        text,
+        target_app.as_deref(),
+        &result,
+    );
+}
+
+/// What to do when output is requested while another output is still running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputConcurrency {
+    /// Wait for the in-flight output (including its clipboard restore) to finish.
+    #[default]
+    Queue,
+    /// Fail fast with `CommandError::OutputBusy` (e.g. a double-triggered hotkey).
+    Reject,
+}
+
+impl OutputConcurrency {
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "reject" => OutputConcurrency::Reject,
+            _ => OutputConcurrency::Queue,
+        }
+    }
+}
+
+/// How keystroke modes handle CJK and complex-script text (`complex_script_output`).
+///
+/// `enigo.text` and even Unicode `SendInput` produce nothing or garbage in many apps that
+/// expect those scripts to arrive through an IME, so by default such runs are pasted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ComplexScriptOutput {
+    /// Paste CJK/complex-script runs and type everything else.
+    #[default]
+    Hybrid,
+    /// Paste the whole text if it contains any CJK/complex-script character.
+    PasteAll,
+    /// Always type, even CJK/complex-script text.
+    Keystrokes,
+}
+
+impl ComplexScriptOutput {
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "paste_all" => ComplexScriptOutput::PasteAll,
+            "keystrokes" => ComplexScriptOutput::Keystrokes,
+            _ => ComplexScriptOutput::Hybrid,
+        }
+    }
+}
+
+/// Key pressed for each newline in keystroke modes (`keystroke_newline_key`).
+///
+/// Editors take Enter as a line break, but chat apps (Slack, Teams, Discord) send the
+/// message on Enter and only break the line on Shift+Enter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NewlineKey {
+    #[default]
+    Enter,
+    ShiftEnter,
+}
+
+impl NewlineKey {
+    /// Strict parse of the settings value; `None` for unknown values.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "enter" => Some(NewlineKey::Enter),
+            "shift_enter" => Some(NewlineKey::ShiftEnter),
+            _ => None,
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        Self::parse(s).unwrap_or_default()
+    }
+}
+
+/// The newline key for the foreground app: its entry in `overrides` (keyed by normalized
+/// process path, `keystroke_newline_key_overrides`), else `default`.
+fn newline_key_for_app(
+    default: NewlineKey,
+    overrides: &HashMap<String, NewlineKey>,
+    foreground_app: Option<&str>,
+) -> NewlineKey {
+    foreground_app
+        .and_then(|app| overrides.get(app))
+        .copied()
+        .unwrap_or(default)
+}
+
+/// Take the output lock according to the concurrency policy.
+///
+/// The guard must be held through clipboard restore, otherwise a queued paste can set its
+/// text inside the previous paste's restore window and then get overwritten.
+fn acquire_output_lock(
+    lock: &Mutex<()>,
+    concurrency: OutputConcurrency,
+) -> Result<MutexGuard<'_, ()>, CommandError> {
+    match concurrency {
+        OutputConcurrency::Queue => lock
+            .lock()
+            .map_err(|_| CommandError::internal("Output lock poisoned")),
+        OutputConcurrency::Reject => match lock.try_lock() {
+            Ok(guard) => Ok(guard),
+            Err(TryLockError::WouldBlock) => {
+                log::warn!("Output requested while another output is running; rejecting");
+                Err(CommandError::OutputBusy(
+                    "Another output is still in progress".to_string(),
+                ))
+            }
+            Err(TryLockError::Poisoned(_)) => Err(CommandError::internal("Output lock poisoned")),
+        },
+    }
+}
+
+fn maybe_hit_enter(
+    backend: &mut impl OutputBackend,
+    hit_enter: bool,
+    delay_ms: u64,
+) -> Result<(), CommandError> {
+    if !hit_enter {
+        return Ok(());
+    }
+
+    // Give the target time to process the pasted/typed text; some chat apps send an empty
+    // message if Enter arrives too early.
+    backend.sleep(Duration::from_millis(delay_ms));
+    backend.key(Key::Return, Direction::Click)
+}
+
+/// Press the key a newline in keystroke output stands for.
+fn press_newline(
+    backend: &mut impl OutputBackend,
+    newline_key: NewlineKey,
+) -> Result<(), CommandError> {
+    match newline_key {
+        NewlineKey::Enter => backend.key(Key::Return, Direction::Click),
+        NewlineKey::ShiftEnter => {
+            backend.key(Key::Shift, Direction::Press)?;
+            let result = backend.key(Key::Return, Direction::Click);
+            // Release Shift even if Enter failed, so it doesn't stay stuck down.
+            backend.key(Key::Shift, Direction::Release)?;
+            result
+        }
+    }
+}
+
 /// Output mode for transcribed text
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum OutputMode {
@@ -50,17 +528,62 @@ pub enum OutputMode {
     Paste,
     /// Paste and keep in clipboard (no restore)
     PasteAndClipboard,
+    /// Type the first `type_then_paste_chars` characters as keystrokes, then paste the rest
+    /// (restoring the clipboard as in `Paste`).
+    ///
+    /// Typing first makes sure the field has focus and has accepted input before the
+    /// paste, for forms that ignore a paste into a field that was never typed into.
+    TypeThenPaste,
     /// Just copy to clipboard (no paste)
     Clipboard,
-    // NOTE: Keystrokes mode was removed/disabled due to reliability issues across targets.
+    /// Append to the existing clipboard text (no paste), joined by a configurable separator.
+    ///
+    /// Lets several dictated fragments accumulate so they can be pasted together.
+    ClipboardAppend,
+    /// Type one character at a time with a per-key delay and no batching.
+    ///
+    /// Intended for fields that drop characters under fast input (e.g. password managers).
+    /// Latency trade-off: typing takes roughly `chars * verified_key_delay_ms`, so a
+    /// 200-character transcript at the default 40ms delay takes ~8 seconds.
+    KeystrokesVerified,
+    /// Type via the keystroke path, emitting `{tab}`, `{enter}`, `{esc}` (and a few other
+    /// navigation tokens) as real key presses. Unknown tokens are typed literally.
+    KeystrokesWithKeys,
+    /// Type via the keystroke path, then select what was typed (Shift+Left per char) so the
+    /// user can immediately retype over it. Selection is capped at 500 chars.
+    KeystrokesAndSelect,
+    // NOTE: Fast keystrokes mode was removed/disabled due to reliability issues across targets.
 }
 
 impl OutputMode {
+    /// All selectable modes, in cycling order.
+    pub const ALL: [OutputMode; 8] = [
+        OutputMode::Paste,
+        OutputMode::PasteAndClipboard,
+        OutputMode::TypeThenPaste,
+        OutputMode::Clipboard,
+        OutputMode::ClipboardAppend,
+        OutputMode::KeystrokesVerified,
+        OutputMode::KeystrokesWithKeys,
+        OutputMode::KeystrokesAndSelect,
+    ];
+
+    /// The next mode in `ALL`, wrapping around after the last one.
+    pub fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|m| *m == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
     pub fn from_str(s: &str) -> Self {
         match s {
             "paste" => OutputMode::Paste,
             "paste_and_clipboard" => OutputMode::PasteAndClipboard,
+            "type_then_paste" => OutputMode::TypeThenPaste,
             "clipboard" => OutputMode::Clipboard,
+            "clipboard_append" => OutputMode::ClipboardAppend,
+            "keystrokes_verified" => OutputMode::KeystrokesVerified,
+            "keystrokes_with_keys" => OutputMode::KeystrokesWithKeys,
+            "keystrokes_and_select" => OutputMode::KeystrokesAndSelect,
             // Legacy/disabled values: map to paste so existing settings.json doesn't break.
             "keystrokes" => OutputMode::Paste,
             "keystrokes_and_clipboard" => OutputMode::Paste,
@@ -69,6 +592,82 @@ impl OutputMode {
             _ => OutputMode::Paste,
         }
     }
+
+    /// Strict counterpart of `from_str`: only canonical values, no legacy/unknown fallback.
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.to_str() == s)
+    }
+
+    /// Whether this mode intentionally leaves the output text in the clipboard.
+    pub fn keeps_clipboard(self) -> bool {
+        matches!(
+            self,
+            OutputMode::PasteAndClipboard | OutputMode::Clipboard | OutputMode::ClipboardAppend
+        )
+    }
+
+    /// Whether this mode sends simulated key events (and so needs input permission).
+    pub fn simulates_input(self) -> bool {
+        !matches!(self, OutputMode::Clipboard | OutputMode::ClipboardAppend)
+    }
+
+    /// Short name for settings UIs.
+    pub fn label(self) -> &'static str {
+        match self {
+            OutputMode::Paste => "Paste",
+            OutputMode::PasteAndClipboard => "Paste and keep in clipboard",
+            OutputMode::TypeThenPaste => "Type, then paste",
+            OutputMode::Clipboard => "Clipboard only",
+            OutputMode::ClipboardAppend => "Append to clipboard",
+            OutputMode::KeystrokesVerified => "Type (verified)",
+            OutputMode::KeystrokesWithKeys => "Type with key tokens",
+            OutputMode::KeystrokesAndSelect => "Type and select",
+        }
+    }
+
+    /// One-line explanation for settings UIs.
+    pub fn description(self) -> &'static str {
+        match self {
+            OutputMode::Paste => {
+                "Paste via the clipboard, then restore what was there. Legacy \"keystrokes\", \
+                 \"keystrokes_and_clipboard\" and \"auto_paste\" settings also use this mode."
+            }
+            OutputMode::PasteAndClipboard => {
+                "Paste via the clipboard and leave the text there for pasting again."
+            }
+            OutputMode::TypeThenPaste => {
+                "Type the first few characters, then paste the rest. For fields that need \
+                 typing before they accept a paste."
+            }
+            OutputMode::Clipboard => "Copy to the clipboard without pasting.",
+            OutputMode::ClipboardAppend => {
+                "Add to the end of the clipboard text without pasting, so fragments accumulate."
+            }
+            OutputMode::KeystrokesVerified => {
+                "Type one character at a time, for fields that drop fast input. Slow for long text."
+            }
+            OutputMode::KeystrokesWithKeys => {
+                "Type the text, pressing keys like {tab} and {enter} where they appear."
+            }
+            OutputMode::KeystrokesAndSelect => {
+                "Type the text, then select it so it can be retyped over."
+            }
+        }
+    }
+
+    /// Stable settings value for this mode (inverse of `from_str`).
+    pub fn to_str(self) -> &'static str {
+        match self {
+            OutputMode::Paste => "paste",
+            OutputMode::PasteAndClipboard => "paste_and_clipboard",
+            OutputMode::TypeThenPaste => "type_then_paste",
+            OutputMode::Clipboard => "clipboard",
+            OutputMode::ClipboardAppend => "clipboard_append",
+            OutputMode::KeystrokesVerified => "keystrokes_verified",
+            OutputMode::KeystrokesWithKeys => "keystrokes_with_keys",
+            OutputMode::KeystrokesAndSelect => "keystrokes_and_select",
+        }
+    }
 }
 
 #[tauri::command]
@@ -76,134 +675,3653 @@ pub async fn get_server_url() -> String {
     SERVER_URL.to_string()
 }
 
+/// Output text from the frontend through the same path as a hotkey transcription: the
+/// configured `output_mode`, per-app overrides, retries, cues and audit log.
+#[cfg(desktop)]
 #[tauri::command]
-pub async fn type_text(app: AppHandle, text: String) -> Result<(), String> {
-    // macOS HIToolbox APIs (used by enigo) must run on the main thread
-    // Use a channel to get the result back from the main thread
-    let (tx, rx) = mpsc::channel::<Result<(), String>>();
+pub async fn type_text(app: AppHandle, text: String) -> Result<OutputResult, CommandError> {
+    let mode_str: String = get_setting_from_store(&app, "output_mode", "paste".to_string());
+    let mode = OutputMode::from_str(&mode_str);
+    let sound_enabled: bool = get_setting_from_store(&app, "sound_enabled", true);
+    let options = crate::get_output_options(&app, sound_enabled, crate::get_cue_selection(&app));
 
-    app.run_on_main_thread(move || {
-        // Serialize output across all modes to avoid interleaving key events.
-        let _guard = match output_injection_lock().lock() {
-            Ok(g) => g,
-            Err(_) => {
-                let _ = tx.send(Err("Output lock poisoned".to_string()));
-                return;
-            }
-        };
+    let result = output_text_off_thread(&app, text, mode, options).await;
+    if let Some(timing) = result.as_ref().ok().and_then(OutputResult::timing) {
+        let _ = app.emit("output-timing", timing);
+    }
+    result
+}
 
-        let result = type_text_blocking(&text, false);
-        let _ = tx.send(result);
-    })
-    .map_err(|e| e.to_string())?;
+// Stub for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn type_text(_app: AppHandle, _text: String) -> Result<OutputResult, CommandError> {
+    Err(CommandError::Internal(
+        "Text output is not supported on this platform".to_string(),
+    ))
+}
 
-    // Wait for result from main thread
-    rx.recv().map_err(|e| e.to_string())?
+/// A selectable output mode as shown in settings.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct OutputModeInfo {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub description: &'static str,
 }
 
-/// Output text based on the specified mode
-pub fn output_text_with_mode(text: &str, mode: OutputMode, hit_enter: bool) -> Result<(), String> {
-    let _guard = output_injection_lock()
-        .lock()
-        .map_err(|_| "Output lock poisoned".to_string())?;
+/// All selectable output modes in cycling order, so the UI doesn't hardcode the list.
+#[tauri::command]
+pub fn list_output_modes() -> Vec<OutputModeInfo> {
+    OutputMode::ALL
+        .into_iter()
+        .map(|mode| OutputModeInfo {
+            id: mode.to_str(),
+            label: mode.label(),
+            description: mode.description(),
+        })
+        .collect()
+}
 
-    match mode {
-        OutputMode::Paste => type_text_blocking(text, hit_enter),
-        OutputMode::PasteAndClipboard => paste_and_keep_clipboard(text, hit_enter),
-        OutputMode::Clipboard => copy_to_clipboard(text),
-    }
+/// Get the configured default output mode as its settings value.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn get_output_mode(app: AppHandle) -> String {
+    let current: String = get_setting_from_store(&app, "output_mode", "paste".to_string());
+    OutputMode::from_str(&current).to_str().to_string()
 }
 
-/// Copy text to clipboard and paste, keeping text in clipboard (no restore)
-pub fn paste_and_keep_clipboard(text: &str, hit_enter: bool) -> Result<(), String> {
-    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
+/// Set the default output mode, persist it, and emit `output-mode-changed`.
+///
+/// Unlike `from_str`, unknown values are rejected instead of falling back to paste.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn set_output_mode(app: AppHandle, mode: String) -> Result<(), CommandError> {
+    let mode = OutputMode::parse(&mode)
+        .ok_or_else(|| CommandError::InvalidArgument(format!("Unknown output mode: {}", mode)))?;
 
-    // Set new text
-    clipboard.set_text(text).map_err(|e| e.to_string())?;
+    let store = app
+        .store("settings.json")
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+    store.set("output_mode", serde_json::json!(mode.to_str()));
+    store
+        .save()
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
 
-    // Small delay for clipboard to stabilize
-    thread::sleep(Duration::from_millis(CLIPBOARD_STABILIZATION_DELAY_MS));
+    log::info!("Output mode set: {}", mode.to_str());
+    let _ = app.emit("output-mode-changed", mode.to_str());
+    Ok(())
+}
 
-    // Simulate Ctrl+V / Cmd+V
-    let mut enigo = Enigo::new(&Settings::default()).map_err(|e| e.to_string())?;
+/// Advance the stored output mode to the next one (wrapping around), persist it, and
+/// announce it with an `output-mode-changed` event and a short cue.
+///
+/// Returns the new mode's settings value.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn cycle_output_mode(app: AppHandle) -> Result<String, CommandError> {
+    let current: String = get_setting_from_store(&app, "output_mode", "paste".to_string());
+    let next = OutputMode::from_str(&current).next();
 
-    #[cfg(target_os = "macos")]
-    let modifier = Key::Meta;
-    #[cfg(not(target_os = "macos"))]
-    let modifier = Key::Control;
-
-    enigo
-        .key(modifier, Direction::Press)
-        .map_err(|e| e.to_string())?;
-    thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
-    enigo
-        .key(Key::Unicode('v'), Direction::Click)
-        .map_err(|e| e.to_string())?;
-    thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
-    enigo
-        .key(modifier, Direction::Release)
-        .map_err(|e| e.to_string())?;
-
-    maybe_hit_enter(&mut enigo, hit_enter)?;
+    let store = app
+        .store("settings.json")
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
+    store.set("output_mode", serde_json::json!(next.to_str()));
+    store
+        .save()
+        .map_err(|e| CommandError::Storage(e.to_string()))?;
 
-    // Don't restore clipboard - keep the text there
-    log::info!("Pasted {} chars (kept in clipboard)", text.len());
-    Ok(())
+    log::info!("Output mode cycled: {} -> {}", current, next.to_str());
+    let _ = app.emit("output-mode-changed", next.to_str());
+
+    let sound_enabled: bool = get_setting_from_store(&app, "sound_enabled", true);
+    if sound_enabled {
+        let position = OutputMode::ALL.iter().position(|m| *m == next).unwrap_or(0);
+        audio::play_sound(
+            SoundType::ModeChange(position as u8 + 1),
+            crate::get_cue_selection(&app),
+        );
+    }
+
+    Ok(next.to_str().to_string())
 }
 
-/// Copy text to clipboard only (no paste)
-pub fn copy_to_clipboard(text: &str) -> Result<(), String> {
-    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
-    clipboard.set_text(text).map_err(|e| e.to_string())?;
-    log::info!("Copied {} chars to clipboard", text.len());
-    Ok(())
+// Stubs for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn get_output_mode(_app: AppHandle) -> String {
+    OutputMode::default().to_str().to_string()
 }
 
-// Keystrokes mode intentionally disabled.
-// (Kept as a stub in case any legacy call sites remain in downstream forks.)
-#[allow(dead_code)]
-pub fn type_as_keystrokes(_text: &str) -> Result<(), String> {
-    Err("Keystrokes output mode is disabled".to_string())
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn set_output_mode(_app: AppHandle, _mode: String) -> Result<(), CommandError> {
+    Err(CommandError::Internal(
+        "Output modes are not supported on this platform".to_string(),
+    ))
 }
 
-/// Type text using clipboard and paste. Used internally by shortcut handlers.
-pub fn type_text_blocking(text: &str, hit_enter: bool) -> Result<(), String> {
-    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn cycle_output_mode(_app: AppHandle) -> Result<String, CommandError> {
+    Err(CommandError::Internal(
+        "Output modes are not supported on this platform".to_string(),
+    ))
+}
 
-    // Save previous clipboard content
-    let previous = clipboard.get_text().unwrap_or_default();
+/// Run the current clipboard text through the same output path as a transcription
+/// (transforms + output mode), then restore the clipboard if the output left the processed
+/// text there.
+///
+/// `mode` overrides the configured `output_mode` for this call. Modes that deliberately keep
+/// text in the clipboard (`paste_and_clipboard`, `clipboard`) leave the processed text there.
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn output_clipboard_text(
+    app: AppHandle,
+    mode: Option<String>,
+) -> Result<OutputResult, CommandError> {
+    let mut clipboard = Clipboard::new().map_err(CommandError::clipboard)?;
+    let previous = clipboard
+        .get_text()
+        .ok()
+        .filter(|t| !t.trim().is_empty())
+        .ok_or_else(|| CommandError::ClipboardUnavailable("Clipboard has no text".to_string()))?;
 
-    // Set new text
-    clipboard.set_text(text).map_err(|e| e.to_string())?;
+    let mode_str = match mode {
+        Some(m) => m,
+        None => get_setting_from_store(&app, "output_mode", "paste".to_string()),
+    };
+    let mode = OutputMode::from_str(&mode_str);
 
-    // Small delay for clipboard to stabilize
-    thread::sleep(Duration::from_millis(CLIPBOARD_STABILIZATION_DELAY_MS));
+    let sound_enabled: bool = get_setting_from_store(&app, "sound_enabled", true);
+    let options = crate::get_output_options(&app, sound_enabled, crate::get_cue_selection(&app));
 
-    // Simulate Ctrl+V / Cmd+V
-    let mut enigo = Enigo::new(&Settings::default()).map_err(|e| e.to_string())?;
+    let text = crate::apply_text_transforms(&app, previous.clone());
+    let result = output_text_off_thread(&app, text.clone(), mode, options).await;
 
-    #[cfg(target_os = "macos")]
-    let modifier = Key::Meta;
-    #[cfg(not(target_os = "macos"))]
-    let modifier = Key::Control;
-
-    enigo
-        .key(modifier, Direction::Press)
-        .map_err(|e| e.to_string())?;
-    thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
-    enigo
-        .key(Key::Unicode('v'), Direction::Click)
-        .map_err(|e| e.to_string())?;
-    thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
-    enigo
-        .key(modifier, Direction::Release)
-        .map_err(|e| e.to_string())?;
-
-    maybe_hit_enter(&mut enigo, hit_enter)?;
-
-    // Restore previous clipboard after a delay
+    // A failed output may still have left the text on the clipboard.
+    let ran = result
+        .as_ref()
+        .map_or(mode, |output| OutputMode::from_str(output.mode));
+    log::info!(
+        "Output {} chars from clipboard ({})",
+        text.chars().count(),
+        ran.to_str()
+    );
+    if !ran.keeps_clipboard() {
+        tauri::async_runtime::spawn_blocking(move || {
+            restore_clipboard_after_output(&previous, &text)
+        })
+        .await
+        .map_err(CommandError::internal)?;
+    }
+
+    let result = result?;
+    if let Some(timing) = result.timing() {
+        let _ = app.emit("output-timing", timing);
+    }
+    Ok(result)
+}
+
+/// Put `previous` back after `output_clipboard_text`, but only if the output left `written`
+/// on the clipboard. Paste modes normally restore by themselves and keystroke modes never
+/// touch it; anything else the user copied in the meantime is left alone.
+#[cfg(desktop)]
+fn restore_clipboard_after_output(previous: &str, written: &str) {
+    let read = || Clipboard::new().ok().and_then(|mut clipboard| clipboard.get_text().ok());
+    if written == previous || !clipboard_still_holds(read().as_deref(), written) {
+        return;
+    }
     thread::sleep(Duration::from_millis(CLIPBOARD_RESTORE_DELAY_MS));
-    let _ = clipboard.set_text(&previous);
+    if clipboard_still_holds(read().as_deref(), written) {
+        restore_previous_clipboard(&mut TimedClipboard::plain(), previous, false);
+    }
+}
 
-    Ok(())
+// Stub for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn output_clipboard_text(
+    _app: AppHandle,
+    _mode: Option<String>,
+) -> Result<OutputResult, CommandError> {
+    Err(CommandError::Internal(
+        "Clipboard output is not supported on this platform".to_string(),
+    ))
+}
+
+/// Line ending used by `output_text_to_file` (`file_output_line_ending`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    /// "lf", "crlf", or anything else for the platform's native ending.
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "lf" => Self::Lf,
+            "crlf" => Self::CrLf,
+            _ if cfg!(windows) => Self::CrLf,
+            _ => Self::Lf,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+        }
+    }
+}
+
+/// What `output_text_to_file` wrote.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FileOutputResult {
+    pub path: String,
+    pub bytes_written: usize,
+    pub appended: bool,
+}
+
+/// The bytes written for one output: `text` with its line endings converted to `ending`,
+/// terminated by one line ending so appended entries land on their own lines.
+pub(crate) fn file_output_contents(text: &str, ending: LineEnding) -> String {
+    let mut out = text
+        .trim_end_matches(['\r', '\n'])
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join(ending.as_str());
+    out.push_str(ending.as_str());
+    out
+}
+
+/// The `file_output_dir` setting: the folder the user picked for file output. Unset means
+/// file output is off, so the webview can't be used to write files anywhere else.
+pub(crate) fn file_output_dir(app: &AppHandle) -> Result<std::path::PathBuf, CommandError> {
+    #[cfg(desktop)]
+    let configured: Option<String> = get_setting_from_store(app, "file_output_dir", None);
+    #[cfg(not(desktop))]
+    let configured: Option<String> = {
+        let _ = app;
+        None
+    };
+
+    configured
+        .map(|dir| dir.trim().to_string())
+        .filter(|dir| !dir.is_empty())
+        .map(std::path::PathBuf::from)
+        .ok_or_else(|| {
+            CommandError::InvalidArgument(
+                "No file output folder chosen (set file_output_dir)".to_string(),
+            )
+        })
+}
+
+/// Check that `path` names a file we can write inside `allowed_dir`: not a directory, in an
+/// existing folder, and not escaping `allowed_dir` through `..` or symlinks. Relative paths
+/// are taken relative to `allowed_dir`.
+pub(crate) fn validate_output_file_path(
+    path: &str,
+    allowed_dir: &std::path::Path,
+) -> Result<std::path::PathBuf, CommandError> {
+    let path = std::path::PathBuf::from(path.trim());
+    if path.as_os_str().is_empty() {
+        return Err(CommandError::InvalidArgument("No output file path given".to_string()));
+    }
+    let allowed = allowed_dir.canonicalize().map_err(|e| {
+        CommandError::InvalidArgument(format!(
+            "File output folder {} is not usable: {}",
+            allowed_dir.display(),
+            e
+        ))
+    })?;
+    let path = allowed.join(path);
+    if path.is_dir() {
+        return Err(CommandError::InvalidArgument(format!(
+            "Output file path is a directory: {}",
+            path.display()
+        )));
+    }
+
+    // Resolve the real location: the file itself if it exists (it may be a symlink),
+    // otherwise its folder.
+    let resolved = match path.canonicalize() {
+        Ok(resolved) => resolved,
+        Err(_) => match (path.parent().map(|dir| dir.canonicalize()), path.file_name()) {
+            (Some(Ok(dir)), Some(name)) if dir.is_dir() => dir.join(name),
+            _ => {
+                return Err(CommandError::InvalidArgument(format!(
+                    "Folder for output file does not exist: {}",
+                    path.display()
+                )))
+            }
+        },
+    };
+    if !resolved.starts_with(&allowed) {
+        return Err(CommandError::InvalidArgument(format!(
+            "Output file must be inside {}: {}",
+            allowed.display(),
+            path.display()
+        )));
+    }
+    Ok(resolved)
+}
+
+/// Write `text` to a file instead of the active window ("dictate into a log").
+///
+/// `path` must be inside the `file_output_dir` folder (relative paths are resolved
+/// against it). Appends by default (`append: false` overwrites). The text goes through the
+/// configured text transforms unless `transform` is false, is written as UTF-8 with the
+/// `file_output_line_ending` line ending, and ends with a line break.
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn output_text_to_file(
+    app: AppHandle,
+    text: String,
+    path: String,
+    append: Option<bool>,
+    transform: Option<bool>,
+) -> Result<FileOutputResult, CommandError> {
+    use std::io::Write;
+
+    let path = validate_output_file_path(&path, &file_output_dir(&app)?)?;
+    let append = append.unwrap_or(true);
+    let text = if transform.unwrap_or(true) {
+        crate::apply_text_transforms(&app, text)
+    } else {
+        text
+    };
+    let ending = LineEnding::from_str(&get_setting_from_store(
+        &app,
+        "file_output_line_ending",
+        "native".to_string(),
+    ));
+    let contents = file_output_contents(&text, ending);
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(&path)
+        .map_err(|e| {
+            CommandError::Storage(format!("Cannot open {} for writing: {}", path.display(), e))
+        })?;
+    file.write_all(contents.as_bytes()).map_err(|e| {
+        CommandError::Storage(format!("Failed to write {}: {}", path.display(), e))
+    })?;
+
+    log::info!(
+        "{} {} bytes to {}",
+        if append { "Appended" } else { "Wrote" },
+        contents.len(),
+        path.display()
+    );
+    Ok(FileOutputResult {
+        path: path.display().to_string(),
+        bytes_written: contents.len(),
+        appended: append,
+    })
+}
+
+// Stub for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn output_text_to_file(
+    _app: AppHandle,
+    _text: String,
+    _path: String,
+    _append: Option<bool>,
+    _transform: Option<bool>,
+) -> Result<FileOutputResult, CommandError> {
+    Err(CommandError::Internal(
+        "File output is not supported on this platform".to_string(),
+    ))
+}
+
+/// What `preview_text_transforms` would output for a sample.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TransformPreview {
+    pub text: String,
+    /// Setting keys of the enabled transforms, in the order they ran.
+    pub transforms: Vec<String>,
+}
+
+/// Run `sample` through the configured text transforms without outputting it, so the
+/// transform settings can be tried out as they're changed.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn preview_text_transforms(app: AppHandle, sample: String) -> TransformPreview {
+    let config = crate::text_transform_config(&app);
+    TransformPreview {
+        text: config.apply(&sample),
+        transforms: config.order().into_iter().map(str::to_string).collect(),
+    }
+}
+
+// Stub for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn preview_text_transforms(_app: AppHandle, sample: String) -> TransformPreview {
+    TransformPreview {
+        text: sample,
+        transforms: Vec::new(),
+    }
+}
+
+/// Marker string typed/pasted by `run_output_self_test`.
+const SELF_TEST_MARKER: &str = "tambourine test 123";
+
+/// One step of the output self-test.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SelfTestStep {
+    pub name: &'static str,
+    pub ok: bool,
+    /// Error text for failed steps, or a note for skipped ones.
+    pub detail: Option<String>,
+    /// What the user can do about a failure.
+    pub hint: Option<String>,
+}
+
+/// Chunking for batched keystroke output (`keystroke_chunk_chars`,
+/// `keystroke_chunk_delay_ms`, `keystroke_jitter_ms`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct KeystrokeTuning {
+    pub chunk_chars: usize,
+    pub chunk_delay_ms: u64,
+    /// Randomize each pause by up to this much either side of `chunk_delay_ms`, so the
+    /// cadence doesn't look machine-generated to sites that check. 0 = fixed pauses.
+    pub jitter_ms: u64,
+}
+
+impl Default for KeystrokeTuning {
+    fn default() -> Self {
+        Self {
+            chunk_chars: DEFAULT_KEYSTROKE_CHUNK_CHARS,
+            chunk_delay_ms: DEFAULT_KEYSTROKE_CHUNK_DELAY_MS,
+            jitter_ms: 0,
+        }
+    }
+}
+
+/// Small seeded PRNG (SplitMix64) for keystroke jitter. Not for anything security-related;
+/// seeding it makes jittered timing reproducible in tests.
+struct JitterRng(u64);
+
+impl JitterRng {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Seeded from the clock, so each output gets a different cadence.
+    fn from_clock() -> Self {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        Self::new(nanos)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+/// Pause after a chunk: `chunk_delay_ms`, shifted by a uniform offset in
+/// `-jitter_ms..=jitter_ms` (never below zero).
+fn chunk_pause_ms(tuning: KeystrokeTuning, rng: &mut JitterRng) -> u64 {
+    if tuning.jitter_ms == 0 {
+        return tuning.chunk_delay_ms;
+    }
+    let span = tuning.jitter_ms.saturating_mul(2).saturating_add(1);
+    let offset = rng.next_u64() % span;
+    (tuning.chunk_delay_ms + offset).saturating_sub(tuning.jitter_ms)
+}
+
+/// How keystroke modes wait for hotkey modifiers to be released before typing
+/// (`modifier_release_max_wait_ms`, `abort_keystrokes_on_user_input`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModifierWait {
+    pub max_wait_ms: u64,
+    /// Give up on the output if the user is pressing other keys during the wait.
+    pub abort_on_user_input: bool,
+}
+
+impl Default for ModifierWait {
+    fn default() -> Self {
+        Self {
+            max_wait_ms: DEFAULT_MODIFIER_RELEASE_WAIT_MS,
+            abort_on_user_input: true,
+        }
+    }
+}
+
+/// How the modifier release wait ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ModifierWaitOutcome {
+    /// No modifiers held; nothing needs releasing.
+    Released,
+    /// Modifiers were still held after the max wait.
+    TimedOut,
+    /// The user is typing; keystroke output should not start.
+    UserTyping,
+    /// Key state can't be read here; waited the full time.
+    Unsupported,
+}
+
+/// Poll `sample` until no modifiers are held, the user is seen typing, or `wait.max_wait_ms`
+/// passes. Without key state (`sample` returns `None`), sleeps out the remaining time.
+fn wait_for_modifier_release(
+    wait: ModifierWait,
+    mut sample: impl FnMut() -> Option<modifier_state::KeyActivity>,
+    mut sleep: impl FnMut(Duration),
+) -> ModifierWaitOutcome {
+    let max_wait = Duration::from_millis(wait.max_wait_ms);
+    let poll = Duration::from_millis(MODIFIER_POLL_INTERVAL_MS);
+    let mut waited = Duration::ZERO;
+    loop {
+        let Some(activity) = sample() else {
+            sleep(max_wait.saturating_sub(waited));
+            return ModifierWaitOutcome::Unsupported;
+        };
+        if wait.abort_on_user_input && activity.other_keys {
+            return ModifierWaitOutcome::UserTyping;
+        }
+        if activity.modifiers.is_empty() {
+            return ModifierWaitOutcome::Released;
+        }
+        if waited >= max_wait {
+            return ModifierWaitOutcome::TimedOut;
+        }
+        sleep(poll);
+        waited += poll;
+    }
+}
+
+/// Suggest chunking from the measured latency of a single injected key event.
+///
+/// Slow injection usually means the target (or a remote desktop in between) is slow to
+/// drain input too, so smaller chunks with longer pauses are safer there.
+pub fn recommend_keystroke_tuning(per_event: Duration) -> KeystrokeTuning {
+    let (chunk_chars, chunk_delay_ms) = match per_event.as_micros() {
+        0..=99 => (64, 5),
+        100..=499 => (DEFAULT_KEYSTROKE_CHUNK_CHARS, DEFAULT_KEYSTROKE_CHUNK_DELAY_MS),
+        500..=1999 => (16, 15),
+        _ => (8, 25),
+    };
+    KeystrokeTuning {
+        chunk_chars,
+        chunk_delay_ms,
+        jitter_ms: 0,
+    }
+}
+
+/// Median time for enigo to inject one key event.
+///
+/// Times Shift releases, which have no visible effect when Shift isn't held (the same
+/// assumption `release_held_modifiers` makes), so nothing is typed anywhere.
+fn measure_key_event_latency(enigo: &mut Enigo) -> Result<Duration, CommandError> {
+    let mut samples = Vec::with_capacity(BENCHMARK_KEY_EVENTS);
+    for _ in 0..BENCHMARK_KEY_EVENTS {
+        let start = Instant::now();
+        enigo
+            .key(Key::Shift, Direction::Release)
+            .map_err(CommandError::input)?;
+        samples.push(start.elapsed());
+    }
+    samples.sort_unstable();
+    Ok(samples[samples.len() / 2])
+}
+
+/// Measure key injection latency on this system and suggest a `KeystrokeTuning`.
+#[tauri::command]
+pub async fn benchmark_keystroke_speed(app: AppHandle) -> Result<KeystrokeTuning, CommandError> {
+    let (tx, rx) = mpsc::channel::<Result<KeystrokeTuning, CommandError>>();
+
+    // Same main-thread and output-lock rules as real output.
+    app.run_on_main_thread(move || {
+        let _guard = match acquire_output_lock(output_injection_lock(), OutputConcurrency::Reject) {
+            Ok(g) => g,
+            Err(e) => {
+                let _ = tx.send(Err(e));
+                return;
+            }
+        };
+
+        let result = Enigo::new(&Settings::default())
+            .map_err(CommandError::input)
+            .and_then(|mut enigo| measure_key_event_latency(&mut enigo))
+            .map(|latency| {
+                let tuning = recommend_keystroke_tuning(latency);
+                log::info!(
+                    "Keystroke benchmark: {}us per key event; suggesting {} chars per chunk, {}ms delay",
+                    latency.as_micros(),
+                    tuning.chunk_chars,
+                    tuning.chunk_delay_ms
+                );
+                tuning
+            });
+        let _ = tx.send(result);
+    })
+    .map_err(CommandError::internal)?;
+
+    rx.recv().map_err(CommandError::internal)?
+}
+
+/// Result of `run_output_self_test`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct OutputSelfTestReport {
+    pub mode: String,
+    pub marker: String,
+    pub steps: Vec<SelfTestStep>,
+    pub success: bool,
+}
+
+impl OutputSelfTestReport {
+    fn record(&mut self, name: &'static str, result: Result<(), CommandError>) -> bool {
+        let ok = result.is_ok();
+        let (detail, hint) = match result {
+            Ok(()) => (None, None),
+            Err(e) => (Some(e.to_string()), self_test_hint(name, &e)),
+        };
+        self.steps.push(SelfTestStep {
+            name,
+            ok,
+            detail,
+            hint,
+        });
+        self.success &= ok;
+        ok
+    }
+
+    fn skip(&mut self, name: &'static str, reason: &str) {
+        self.steps.push(SelfTestStep {
+            name,
+            ok: false,
+            detail: Some(format!("Skipped: {}", reason)),
+            hint: None,
+        });
+        self.success = false;
+    }
+}
+
+/// Actionable advice for a failed self-test step.
+fn self_test_hint(step: &str, err: &CommandError) -> Option<String> {
+    match err {
+        CommandError::ClipboardUnavailable(_) => Some(
+            "The clipboard is locked or unavailable. Close clipboard managers or remote-desktop \
+             clipboard sync and try again."
+                .to_string(),
+        ),
+        CommandError::InputInjectionFailed(_) if step == "enigo" => Some(if cfg!(target_os = "macos") {
+            "enigo could not be created — check accessibility permissions on macOS \
+             (System Settings → Privacy & Security → Accessibility)."
+                .to_string()
+        } else if cfg!(target_os = "linux") {
+            "enigo could not be created — keyboard simulation needs an X11 session (or XWayland) \
+             with libxdo installed."
+                .to_string()
+        } else {
+            "enigo could not be created — another app may be blocking simulated input \
+             (e.g. running as administrator)."
+                .to_string()
+        }),
+        CommandError::InputInjectionFailed(_) => Some(
+            "Key events were rejected. Make sure the target window is focused and not running \
+             with higher privileges than this app."
+                .to_string(),
+        ),
+        CommandError::PermissionDenied(_) => Some(
+            "Grant this app Accessibility access in System Settings → Privacy & Security → \
+             Accessibility, then restart it."
+                .to_string(),
+        ),
+        CommandError::OutputBusy(_) => {
+            Some("Another output is still running. Wait for it to finish and retry.".to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Write the marker to the clipboard and read it back, then restore the previous text.
+///
+/// Returns the round trip's result and the restore's. Non-text content can't be restored
+/// and isn't reported.
+fn self_test_clipboard() -> (Result<(), CommandError>, Result<(), CommandError>) {
+    let mut clipboard = match Clipboard::new() {
+        Ok(clipboard) => clipboard,
+        Err(e) => return (Err(CommandError::clipboard(e)), Ok(())),
+    };
+    let previous = clipboard.get_text().ok();
+
+    if let Err(e) = clipboard.set_text(SELF_TEST_MARKER) {
+        return (Err(CommandError::clipboard(e)), Ok(()));
+    }
+    let read_back = clipboard.get_text().map_err(CommandError::clipboard);
+
+    let restored = match previous {
+        Some(previous) => clipboard.set_text(previous).map_err(CommandError::clipboard),
+        None => Ok(()),
+    };
+
+    let round_trip = read_back.and_then(|read_back| {
+        if read_back == SELF_TEST_MARKER {
+            Ok(())
+        } else {
+            Err(CommandError::clipboard(
+                "clipboard content did not match after writing",
+            ))
+        }
+    });
+    (round_trip, restored)
+}
+
+/// Run the self-test steps. Must be called on the main thread (enigo on macOS).
+fn run_output_self_test_blocking(mode: OutputMode, options: &OutputOptions) -> OutputSelfTestReport {
+    let mut report = OutputSelfTestReport {
+        mode: mode.to_str().to_string(),
+        marker: SELF_TEST_MARKER.to_string(),
+        steps: Vec::new(),
+        success: true,
+    };
+
+    // 1. Clipboard round trip, then put back whatever the user had.
+    let (round_trip, restored) = self_test_clipboard();
+    let clipboard_ok = report.record("clipboard", round_trip);
+    report.record("clipboard_restore", restored);
+
+    // 2. Keyboard simulation available.
+    let needs_input = mode.simulates_input();
+    let enigo_ok = if needs_input {
+        report.record(
+            "enigo",
+            ensure_input_permission(&mut SystemBackend::default()).and_then(|_| {
+                Enigo::new(&Settings::default())
+                    .map(|_| ())
+                    .map_err(CommandError::input)
+            }),
+        )
+    } else {
+        true
+    };
+
+    // 3. Send the marker through the real output path.
+    let needs_clipboard = matches!(
+        mode,
+        OutputMode::Paste
+            | OutputMode::PasteAndClipboard
+            | OutputMode::TypeThenPaste
+            | OutputMode::Clipboard
+            | OutputMode::ClipboardAppend
+    );
+    if !clipboard_ok && needs_clipboard {
+        report.skip("output", "clipboard is unavailable");
+    } else if !enigo_ok {
+        report.skip("output", "keyboard simulation is unavailable");
+    } else {
+        report.record(
+            "output",
+            output_text_with_mode(SELF_TEST_MARKER, mode, options).map(|_| ()),
+        );
+    }
+
+    log::info!(
+        "Output self-test ({}): {}",
+        report.mode,
+        if report.success { "passed" } else { "failed" }
+    );
+    report
+}
+
+/// Output a known marker through `mode` and report which steps worked.
+///
+/// Intended for onboarding/troubleshooting: focus a text field, run this, and check that
+/// "tambourine test 123" appears. Failed steps include a hint the UI can show as-is.
+#[tauri::command]
+pub async fn run_output_self_test(
+    app: AppHandle,
+    mode: String,
+) -> Result<OutputSelfTestReport, CommandError> {
+    let mode = OutputMode::from_str(&mode);
+
+    // The user's real output settings, minus what would get in the way of checking the
+    // marker: holding it for confirmation, or pressing Enter after it.
+    #[cfg(desktop)]
+    let options = {
+        let sound_enabled: bool = get_setting_from_store(&app, "sound_enabled", true);
+        OutputOptions {
+            hit_enter: false,
+            confirm_before_output: None,
+            events: None,
+            ..crate::get_output_options(&app, sound_enabled, crate::get_cue_selection(&app))
+        }
+    };
+    #[cfg(not(desktop))]
+    let options = OutputOptions::default();
+
+    let (tx, rx) = mpsc::channel::<OutputSelfTestReport>();
+    app.run_on_main_thread(move || {
+        let _ = tx.send(run_output_self_test_blocking(mode, &options));
+    })
+    .map_err(CommandError::internal)?;
+
+    rx.recv().map_err(CommandError::internal)
+}
+
+/// Default size of the `test_clipboard_roundtrip` sample, big enough to hit the
+/// truncation limits of typical remote-desktop clipboard bridges.
+const ROUNDTRIP_SAMPLE_LINES: usize = 400;
+
+/// Characters shown on each side of the first mismatch.
+const ROUNDTRIP_EXCERPT_CHARS: usize = 24;
+
+/// Result of `test_clipboard_roundtrip`. Positions and counts are in chars.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ClipboardRoundTripReport {
+    pub matched: bool,
+    pub written_chars: usize,
+    pub read_chars: usize,
+    /// Where the read-back text first differs from the sample.
+    pub first_mismatch: Option<usize>,
+    pub expected_excerpt: Option<String>,
+    pub actual_excerpt: Option<String>,
+    /// The text only differs in line endings (LF vs CRLF), which most targets tolerate.
+    pub newlines_translated: bool,
+    /// Whether the user's previous clipboard content was put back.
+    pub restored: bool,
+}
+
+/// Numbered lines mixing ASCII, accents, CJK, emoji and tabs, so a truncation point is
+/// easy to locate and encoding damage shows up.
+fn default_roundtrip_sample() -> String {
+    (1..=ROUNDTRIP_SAMPLE_LINES)
+        .map(|n| format!("{:04}\tThe quick brown fox — café naïve 日本語 한국어 🎙️ end\n", n))
+        .collect()
+}
+
+/// Compare the sample with what the clipboard gave back.
+fn compare_roundtrip(expected: &str, actual: &str) -> ClipboardRoundTripReport {
+    let first_mismatch = expected
+        .chars()
+        .zip(actual.chars())
+        .position(|(a, b)| a != b)
+        .or_else(|| {
+            let (expected_len, actual_len) = (expected.chars().count(), actual.chars().count());
+            (expected_len != actual_len).then(|| expected_len.min(actual_len))
+        });
+    let excerpt = |text: &str, at: usize| -> String {
+        text.chars()
+            .skip(at.saturating_sub(ROUNDTRIP_EXCERPT_CHARS))
+            .take(ROUNDTRIP_EXCERPT_CHARS * 2)
+            .collect()
+    };
+
+    ClipboardRoundTripReport {
+        matched: first_mismatch.is_none(),
+        written_chars: expected.chars().count(),
+        read_chars: actual.chars().count(),
+        first_mismatch,
+        expected_excerpt: first_mismatch.map(|at| excerpt(expected, at)),
+        actual_excerpt: first_mismatch.map(|at| excerpt(actual, at)),
+        newlines_translated: first_mismatch.is_some()
+            && expected.replace("\r\n", "\n") == actual.replace("\r\n", "\n"),
+        restored: false,
+    }
+}
+
+/// Write `sample`, read it back and compare, then restore the previous clipboard the same
+/// way paste does (full snapshot, falling back to text).
+fn clipboard_roundtrip_blocking(
+    sample: &str,
+    max_capture_bytes: usize,
+) -> Result<ClipboardRoundTripReport, CommandError> {
+    let mut clipboard = Clipboard::new().map_err(CommandError::clipboard)?;
+    let snapshot = clipboard_snapshot::capture(max_capture_bytes);
+    let previous = capture_for_restore(clipboard.get_text().unwrap_or_default(), max_capture_bytes);
+
+    let written = clipboard.set_text(sample).map_err(CommandError::clipboard);
+    // Give clipboard bridges (RDP, VM tools) the same settle time paste gets.
+    thread::sleep(Duration::from_millis(CLIPBOARD_STABILIZATION_DELAY_MS));
+    let read_back = written.and_then(|_| clipboard.get_text().map_err(CommandError::clipboard));
+
+    let restored = snapshot
+        .as_ref()
+        .is_some_and(|snapshot| restore_snapshot_within(snapshot, false).is_ok())
+        || match previous.as_deref() {
+            // Nothing to put back, but the sample mustn't be left behind either.
+            Some("") => clipboard.clear().is_ok(),
+            Some(previous) => {
+                restore_previous_clipboard(&mut TimedClipboard::plain(), previous, true)
+            }
+            None => false,
+        };
+
+    let mut report = compare_roundtrip(sample, &read_back?);
+    report.restored = restored;
+    log::info!(
+        "Clipboard round trip: {} of {} chars read back, {}",
+        report.read_chars,
+        report.written_chars,
+        match report.first_mismatch {
+            None => "intact".to_string(),
+            Some(at) => format!("diverged at char {}", at),
+        }
+    );
+    Ok(report)
+}
+
+/// Check that text survives the clipboard intact: write a sample (a long built-in one by
+/// default), read it back and report where it diverged. The user's clipboard is restored.
+///
+/// For diagnosing remote-desktop/VM clipboard bridges that truncate or mangle long text.
+#[tauri::command]
+pub async fn test_clipboard_roundtrip(
+    app: AppHandle,
+    sample: Option<String>,
+) -> Result<ClipboardRoundTripReport, CommandError> {
+    let sample = sample
+        .filter(|sample| !sample.is_empty())
+        .unwrap_or_else(default_roundtrip_sample);
+
+    #[cfg(desktop)]
+    let max_capture_bytes = get_setting_from_store(
+        &app,
+        "max_clipboard_capture_bytes",
+        DEFAULT_MAX_CLIPBOARD_CAPTURE_BYTES,
+    );
+    #[cfg(not(desktop))]
+    let max_capture_bytes = {
+        let _ = app;
+        DEFAULT_MAX_CLIPBOARD_CAPTURE_BYTES
+    };
+
+    // Waiting for the output lock and the clipboard settle delay mustn't block the main
+    // thread or a tokio worker.
+    tauri::async_runtime::spawn_blocking(move || {
+        // Don't write to the clipboard underneath an output in progress.
+        let _guard = acquire_output_lock(output_injection_lock(), OutputConcurrency::Queue)?;
+        clipboard_roundtrip_blocking(&sample, max_capture_bytes)
+    })
+    .await
+    .map_err(CommandError::internal)?
+}
+
+/// Per-call output knobs, resolved from settings by the caller.
+#[derive(Debug, Clone)]
+pub struct OutputOptions {
+    /// Press Enter after the text has been output.
+    pub hit_enter: bool,
+    /// Delay between output completing (after clipboard restore, for paste) and Enter.
+    pub enter_delay_ms: u64,
+    /// If set, play this cue theme's error sound when output fails, so a silent paste
+    /// failure doesn't go unnoticed.
+    pub error_cue: Option<AudioCue>,
+    /// If set, play this cue theme's no-speech sound when the text to output is empty.
+    pub no_speech_cue: Option<AudioCue>,
+    /// Per-character delay for `OutputMode::KeystrokesVerified`.
+    pub verified_key_delay_ms: u64,
+    /// Transcripts longer than this (in chars) are pasted instead of typed, so a huge
+    /// transcript can't start a multi-minute typing session that's hard to stop.
+    pub max_keystroke_chars: usize,
+    /// Characters typed before pasting the rest in `OutputMode::TypeThenPaste`.
+    pub type_then_paste_chars: usize,
+    /// Paste modes: put the previous clipboard back after pasting. Off, every paste leaves
+    /// the dictated text on the clipboard, as `PasteAndClipboard` does.
+    pub restore_clipboard_after_paste: bool,
+    /// In paste mode, leave the clipboard alone if its previous content was empty instead
+    /// of writing back an empty string.
+    pub skip_empty_restore: bool,
+    /// Previous clipboard text larger than this (in bytes) isn't restored after a paste.
+    pub max_clipboard_capture_bytes: usize,
+    /// In paste mode, skip the restore if the clipboard no longer holds the pasted text
+    /// (the user copied something else during the restore delay).
+    pub safe_clipboard_restore: bool,
+    /// In `Paste` mode, copy the current selection before pasting so dictation replaces it
+    /// ("dictate over selection"). The clipboard is restored after both steps.
+    pub replace_selection: bool,
+    /// Mark the paste's temporary clipboard writes (the text and the restore) so clipboard
+    /// managers don't record them, where the platform has a convention for it.
+    pub clipboard_manager_friendly: bool,
+    /// Linux: paste shortcut (Ctrl+Shift+V for terminals) and injection path. Ignored on
+    /// other platforms.
+    pub linux_paste: LinuxPasteConfig,
+    /// Time each phase of paste output and report an `OutputTiming`.
+    pub profile_output: bool,
+    /// Queue or reject output requested while another output is running.
+    pub concurrency: OutputConcurrency,
+    /// Window to re-activate before sending output (see `refocus_before_paste`).
+    pub refocus_target: Option<ForegroundWindow>,
+    /// Inserted between existing clipboard text and new text in `ClipboardAppend` mode.
+    pub clipboard_append_separator: String,
+    /// Windows: send layout-sensitive symbols in keystroke modes as Unicode `SendInput`
+    /// events instead of simulated layout keys (`unicode_keystrokes`). Ignored elsewhere.
+    pub unicode_input: bool,
+    /// Keystroke modes: paste CJK/complex-script text instead of typing it.
+    pub complex_scripts: ComplexScriptOutput,
+    /// Chunk size and pause for batched keystroke modes.
+    pub keystroke_tuning: KeystrokeTuning,
+    /// Keystroke modes: key pressed for each newline (`keystroke_newline_key`).
+    pub newline_key: NewlineKey,
+    /// Per-app `newline_key`, keyed by normalized process path
+    /// (`keystroke_newline_key_overrides`).
+    pub newline_key_overrides: HashMap<String, NewlineKey>,
+    /// Keystroke modes: how to wait for hotkey modifiers to be released first.
+    pub modifier_wait: ModifierWait,
+    /// Paste modes: wait for and release held hotkey modifiers (same `modifier_wait`) before
+    /// pressing the paste combo, so a held Cmd/Ctrl doesn't turn it into another shortcut.
+    pub release_modifiers_before_paste: bool,
+    /// Speak a short confirmation ("pasted", "copied") after output (`tts_confirmations`).
+    pub tts_confirmations: bool,
+    /// Extra attempts at the whole output after a failure that happened before any input was
+    /// sent (see `retry_is_safe`). Other errors are never retried.
+    pub output_retries: u32,
+    /// macOS: in `Paste` mode, first try inserting through the Accessibility API
+    /// (`direct_insert`), which leaves the clipboard untouched. Falls back to paste.
+    pub direct_insert: bool,
+    /// Per-app mode overrides, keyed by normalized process path (`output_mode_overrides`).
+    pub app_mode_overrides: HashMap<String, OutputMode>,
+    /// Hold the output until `confirm_output` (see `output-pending`), cancelling it after
+    /// this long (`confirm_before_output`). Needs `events`; ignored without them.
+    pub confirm_before_output: Option<Duration>,
+    /// Where to emit `output-mode-resolved`. `None` skips the event (tests, self-test).
+    pub events: Option<AppHandle>,
+}
+
+impl Default for OutputOptions {
+    fn default() -> Self {
+        Self {
+            hit_enter: false,
+            enter_delay_ms: DEFAULT_ENTER_DELAY_MS,
+            error_cue: None,
+            no_speech_cue: None,
+            verified_key_delay_ms: DEFAULT_VERIFIED_KEY_DELAY_MS,
+            max_keystroke_chars: DEFAULT_MAX_KEYSTROKE_CHARS,
+            type_then_paste_chars: DEFAULT_TYPE_THEN_PASTE_CHARS,
+            restore_clipboard_after_paste: true,
+            skip_empty_restore: true,
+            max_clipboard_capture_bytes: DEFAULT_MAX_CLIPBOARD_CAPTURE_BYTES,
+            safe_clipboard_restore: true,
+            replace_selection: false,
+            clipboard_manager_friendly: false,
+            linux_paste: LinuxPasteConfig::default(),
+            profile_output: false,
+            concurrency: OutputConcurrency::Queue,
+            refocus_target: None,
+            clipboard_append_separator: DEFAULT_CLIPBOARD_APPEND_SEPARATOR.to_string(),
+            unicode_input: true,
+            complex_scripts: ComplexScriptOutput::Hybrid,
+            keystroke_tuning: KeystrokeTuning::default(),
+            newline_key: NewlineKey::default(),
+            newline_key_overrides: HashMap::new(),
+            modifier_wait: ModifierWait::default(),
+            release_modifiers_before_paste: true,
+            tts_confirmations: false,
+            direct_insert: false,
+            output_retries: DEFAULT_OUTPUT_RETRIES,
+            app_mode_overrides: HashMap::new(),
+            confirm_before_output: None,
+            events: None,
+        }
+    }
+}
+
+/// Per-phase timing of a paste, reported when `profile_output` is enabled.
+///
+/// Useful for checking whether the stabilization delay is long enough on a given machine.
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct OutputTiming {
+    pub clipboard_set_ms: u64,
+    pub stabilization_ms: u64,
+    pub key_sequence_ms: u64,
+    pub restore_ms: u64,
+    pub total_ms: u64,
+}
+
+/// How an output attempt ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputStatus {
+    /// The text was output in the requested mode.
+    Success,
+    /// The text was output, but in another mode than requested (see `output-mode-resolved`).
+    Fallback,
+    /// The text was empty or whitespace-only, so nothing was output.
+    Empty,
+    /// Output is paused (`set_output_paused`), so nothing was output.
+    Paused,
+    /// Cancelled (`cancel_output`, `cancel_pending_confirmations`, or the timeout under
+    /// `confirm_before_output`) before anything was output.
+    Cancelled,
+}
+
+/// What `output_text_with_mode` and `type_text` did.
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct OutputResult {
+    pub status: OutputStatus,
+    /// Chars output (0 when nothing was output).
+    pub chars: usize,
+    /// Mode that ran (the requested one when nothing was output).
+    pub mode: &'static str,
+    /// Paste timing, when profiling is enabled and the mode pastes with clipboard restore.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<OutputTiming>,
+}
+
+impl OutputResult {
+    /// A result for output that didn't happen.
+    fn skipped(status: OutputStatus, mode: OutputMode) -> Self {
+        Self {
+            status,
+            chars: 0,
+            mode: mode.to_str(),
+            timing: None,
+        }
+    }
+
+    /// Paste timing, if the text was output with profiling enabled.
+    pub fn timing(&self) -> Option<OutputTiming> {
+        self.timing
+    }
+}
+
+/// Lap timer for `OutputTiming`; only constructed when profiling is enabled.
+struct PhaseTimer {
+    start: Instant,
+    last: Instant,
+}
+
+impl PhaseTimer {
+    fn start() -> Self {
+        let now = Instant::now();
+        Self { start: now, last: now }
+    }
+
+    /// Milliseconds since the previous lap.
+    fn lap(&mut self) -> u64 {
+        let now = Instant::now();
+        let ms = now.duration_since(self.last).as_millis() as u64;
+        self.last = now;
+        ms
+    }
+
+    fn total(&self) -> u64 {
+        self.start.elapsed().as_millis() as u64
+    }
+}
+
+/// Output text based on the specified mode.
+///
+/// The result carries paste timing when `options.profile_output` is set and the mode pastes
+/// with clipboard restore. Empty/whitespace-only text short-circuits to
+/// `OutputStatus::Empty` without touching the clipboard or keyboard (a no-op paste could
+/// clear a selection), and emits `output-empty`.
+pub fn output_text_with_mode(
+    text: &str,
+    mode: OutputMode,
+    options: &OutputOptions,
+) -> Result<OutputResult, CommandError> {
+    let result = output_text_with_mode_impl(&mut SystemBackend::default(), text, mode, options);
+    speak_confirmation(&result, options);
+    result
+}
+
+/// `output_text_with_mode` for async callers.
+///
+/// Waiting for `confirm_before_output` can take a while and runs on a blocking worker. The
+/// output itself is sent to the main thread, which macOS needs for enigo (HIToolbox) and
+/// where the shared Enigo lives.
+pub async fn output_text_off_thread(
+    app: &AppHandle,
+    text: String,
+    mode: OutputMode,
+    options: OutputOptions,
+) -> Result<OutputResult, CommandError> {
+    let (text, options, skipped) = tauri::async_runtime::spawn_blocking(move || {
+        let skipped = skip_output(&mut SystemBackend::default(), &text, mode, &options);
+        (text, options, skipped)
+    })
+    .await
+    .map_err(CommandError::internal)?;
+    if let Some(skipped) = skipped {
+        return Ok(skipped);
+    }
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.run_on_main_thread(move || {
+        let result = send_output(&mut SystemBackend::default(), &text, mode, &options);
+        speak_confirmation(&result, &options);
+        let _ = tx.send(result);
+    })
+    .map_err(CommandError::internal)?;
+    rx.await
+        .map_err(|_| CommandError::internal("Output was dropped before it ran"))?
+}
+
+/// Speak the `tts_confirmations` phrase for `result`, if enabled.
+fn speak_confirmation(result: &Result<OutputResult, CommandError>, options: &OutputOptions) {
+    if options.tts_confirmations {
+        if let Some(phrase) = confirmation_phrase(result) {
+            tts::speak(phrase.to_string());
+        }
+    }
+}
+
+/// What `tts_confirmations` says after output, or `None` to stay quiet when nothing was output.
+fn confirmation_phrase(result: &Result<OutputResult, CommandError>) -> Option<&'static str> {
+    let output = match result {
+        Ok(output) => output,
+        Err(_) => return Some("output failed"),
+    };
+    match output.status {
+        OutputStatus::Success | OutputStatus::Fallback => {
+            Some(match OutputMode::from_str(output.mode) {
+                OutputMode::Paste | OutputMode::PasteAndClipboard | OutputMode::TypeThenPaste => {
+                    "pasted"
+                }
+                OutputMode::Clipboard => "copied",
+                OutputMode::ClipboardAppend => "appended",
+                OutputMode::KeystrokesVerified
+                | OutputMode::KeystrokesWithKeys
+                | OutputMode::KeystrokesAndSelect => "typed",
+            })
+        }
+        // Nothing was output, so there's nothing to confirm.
+        OutputStatus::Empty | OutputStatus::Paused | OutputStatus::Cancelled => None,
+    }
+}
+
+pub(crate) fn output_text_with_mode_impl(
+    backend: &mut impl OutputBackend,
+    text: &str,
+    mode: OutputMode,
+    options: &OutputOptions,
+) -> Result<OutputResult, CommandError> {
+    match skip_output(backend, text, mode, options) {
+        Some(skipped) => Ok(skipped),
+        None => send_output(backend, text, mode, options),
+    }
+}
+
+/// The checks that end an output before anything is sent: paused output, empty text, and
+/// `confirm_before_output` (which blocks until the user answers). Returns the result to
+/// report if the output stops here.
+fn skip_output(
+    backend: &mut impl OutputBackend,
+    text: &str,
+    mode: OutputMode,
+    options: &OutputOptions,
+) -> Option<OutputResult> {
+    if backend.output_paused() {
+        log::info!("Output is paused; skipping {} chars", text.chars().count());
+        let result = Ok(OutputResult::skipped(OutputStatus::Paused, mode));
+        audit_output(mode, mode, text, &result);
+        return result.ok();
+    }
+
+    if text.trim().is_empty() {
+        log::info!("Output text is empty; nothing to output");
+        if let Some(cue) = options.no_speech_cue {
+            audio::play_sound(SoundType::NoSpeech, cue);
+        }
+        if let Some(app) = &options.events {
+            let _ = app.emit("output-empty", ());
+        }
+        let result = Ok(OutputResult::skipped(OutputStatus::Empty, mode));
+        audit_output(mode, mode, text, &result);
+        return result.ok();
+    }
+
+    if let (Some(timeout), Some(app)) = (options.confirm_before_output, &options.events) {
+        if !await_output_confirmation(app, text, mode, timeout) {
+            let result = Ok(OutputResult::skipped(OutputStatus::Cancelled, mode));
+            audit_output(mode, mode, text, &result);
+            return result.ok();
+        }
+    }
+    None
+}
+
+/// Output text that passed `skip_output`: take the output lock, resolve the mode and send
+/// it, retrying where that's safe.
+fn send_output(
+    backend: &mut impl OutputBackend,
+    text: &str,
+    mode: OutputMode,
+    options: &OutputOptions,
+) -> Result<OutputResult, CommandError> {
+    // Held across retries so another output can't interleave between attempts.
+    let _guard = match acquire_output_lock(output_injection_lock(), options.concurrency) {
+        Ok(guard) => guard,
+        Err(e) => {
+            if let Some(cue) = options.error_cue {
+                audio::play_sound(SoundType::Error, cue);
+            }
+            let result = Err(e);
+            audit_output(mode, mode, text, &result);
+            return result;
+        }
+    };
+
+    // Refocus first so the foreground app and focused field below are the output target.
+    if let Some(target) = options.refocus_target {
+        if foreground_window::focus_previous_window(target) {
+            backend.sleep(Duration::from_millis(REFOCUS_SETTLE_MS));
+        } else {
+            log::warn!("Could not refocus output target window {:?}", target);
+        }
+    }
+
+    let foreground_app = if options.app_mode_overrides.is_empty()
+        && options.newline_key_overrides.is_empty()
+    {
+        None
+    } else {
+        crate::windows_apps::get_foreground_process_path()
+            .map(|path| crate::pipeline::normalize_program_path(&path))
+    };
+    let mut context = OutputContext {
+        text_chars: text.chars().count(),
+        max_keystroke_chars: options.max_keystroke_chars,
+        foreground_app: foreground_app.as_deref(),
+        app_overrides: &options.app_mode_overrides,
+        clipboard_available: true,
+        secure_field: backend.secure_field_focused(),
+    };
+    let (mut effective, mut reason) = resolve_output_mode(mode, &context);
+    // Opening the clipboard isn't free, so only probe it when the chosen mode pastes.
+    if matches!(
+        effective,
+        OutputMode::Paste | OutputMode::PasteAndClipboard | OutputMode::TypeThenPaste
+    ) && !backend.clipboard_available()
+    {
+        context.clipboard_available = false;
+        (effective, reason) = resolve_output_mode(mode, &context);
+    }
+
+    // The one-shot clipboard-only target is applied to the resolved mode, so it also beats
+    // per-app overrides and fallbacks.
+    if backend.take_clipboard_only() {
+        log::info!("One-shot clipboard-only target: copying instead of {}", effective.to_str());
+        if let Some(app) = &options.events {
+            let _ = app.emit(
+                "output-target-changed",
+                serde_json::json!({ "clipboard_only": false }),
+            );
+        }
+        if effective.simulates_input() {
+            effective = OutputMode::Clipboard;
+            reason = ResolutionReason::ClipboardOnly;
+        }
+    }
+
+    if reason != ResolutionReason::Requested {
+        log::warn!(
+            "Output mode {} -> {}: {}",
+            mode.to_str(),
+            effective.to_str(),
+            reason.describe().unwrap_or_default()
+        );
+    }
+    if let Some(app) = &options.events {
+        let resolution = OutputModeResolution::new(mode, effective, &reason);
+        let _ = app.emit("output-mode-resolved", &resolution);
+    }
+    let requested = mode;
+    let mode = effective;
+
+    let newline_key = newline_key_for_app(
+        options.newline_key,
+        &options.newline_key_overrides,
+        foreground_app.as_deref(),
+    );
+    let app_options;
+    let options = if newline_key != options.newline_key {
+        log::info!("Using {:?} for newlines in the foreground app", newline_key);
+        app_options = OutputOptions {
+            newline_key,
+            ..options.clone()
+        };
+        &app_options
+    } else {
+        options
+    };
+
+    // Clipboard-only output doesn't simulate input, so it works without the permission.
+    let permission = if mode.simulates_input() {
+        ensure_input_permission(backend)
+    } else {
+        Ok(())
+    };
+
+    let mut attempt = || {
+        match mode {
+            OutputMode::Paste => {
+                // Direct insert skips the clipboard, so it can't do the copy-first step.
+                if options.direct_insert
+                    && !options.replace_selection
+                    && try_direct_insert(backend, text, options)?
+                {
+                    Ok(None)
+                } else {
+                    type_text_blocking(backend, text, options)
+                }
+            }
+            OutputMode::PasteAndClipboard => {
+                paste_and_keep_clipboard(
+                    backend,
+                    text,
+                    options.hit_enter,
+                    options.enter_delay_ms,
+                    &options.linux_paste,
+                    options
+                        .release_modifiers_before_paste
+                        .then_some(options.modifier_wait),
+                )
+                .map(|_| None)
+            }
+            OutputMode::TypeThenPaste => type_then_paste(backend, text, options),
+            OutputMode::Clipboard => copy_to_clipboard_with(backend, text).map(|_| None),
+            OutputMode::ClipboardAppend => {
+                append_to_clipboard_with(backend, text, &options.clipboard_append_separator)
+                    .map(|_| None)
+            }
+            OutputMode::KeystrokesVerified => {
+                type_as_keystrokes_verified(backend, text, options).map(|_| None)
+            }
+            OutputMode::KeystrokesWithKeys => {
+                type_with_key_tokens(backend, text, options).map(|_| None)
+            }
+            // Enter would replace the selection, so `hit_enter` is ignored here.
+            OutputMode::KeystrokesAndSelect => {
+                type_and_select(backend, text, options).map(|_| None)
+            }
+        }
+    };
+
+    let result = permission.and_then(|_| {
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            match attempt() {
+                Err(e)
+                    if retry_is_safe(mode, &e) && attempts <= options.output_retries =>
+                {
+                    log::warn!(
+                        "Output attempt {} of {} failed ({}); retrying in {}ms",
+                        attempts,
+                        options.output_retries + 1,
+                        e,
+                        OUTPUT_RETRY_DELAY_MS
+                    );
+                    thread::sleep(Duration::from_millis(OUTPUT_RETRY_DELAY_MS));
+                }
+                result => {
+                    if attempts > 1 {
+                        log::info!(
+                            "Output attempt {} {}",
+                            attempts,
+                            if result.is_ok() { "succeeded" } else { "failed; giving up" }
+                        );
+                    }
+                    break result;
+                }
+            }
+        }
+    });
+
+    if result.is_err() {
+        if let Some(cue) = options.error_cue {
+            audio::play_sound(SoundType::Error, cue);
+        }
+    }
+
+    let result = result.map(|timing| OutputResult {
+        status: if mode == requested {
+            OutputStatus::Success
+        } else {
+            OutputStatus::Fallback
+        },
+        chars: context.text_chars,
+        mode: mode.to_str(),
+        timing,
+    });
+    audit_output(requested, mode, text, &result);
+    result
+}
+
+/// Whether a failed output attempt can run again without typing or pasting twice.
+///
+/// Only clipboard failures in modes that set the clipboard before sending any input qualify.
+/// Keystroke modes may already have typed part of the text (and the hybrid script fallback
+/// pastes mid-way), and an input injection error can come after some keys went through.
+fn retry_is_safe(mode: OutputMode, err: &CommandError) -> bool {
+    err.is_transient()
+        && matches!(err, CommandError::ClipboardUnavailable(_))
+        && matches!(
+            mode,
+            OutputMode::Paste
+                | OutputMode::PasteAndClipboard
+                | OutputMode::Clipboard
+                | OutputMode::ClipboardAppend
+        )
+}
+
+/// Insert via the Accessibility API instead of pasting. `Ok(false)` means the focused
+/// element doesn't support it and the caller should paste as usual.
+fn try_direct_insert(
+    backend: &mut impl OutputBackend,
+    text: &str,
+    options: &OutputOptions,
+) -> Result<bool, CommandError> {
+    if let Err(e) = direct_insert::insert_text(text) {
+        log::info!("Direct insert unavailable ({}), falling back to paste", e);
+        return Ok(false);
+    }
+
+    log::info!("Inserted {} chars directly (clipboard untouched)", text.len());
+    maybe_hit_enter(backend, options.hit_enter, options.enter_delay_ms)?;
+    Ok(true)
+}
+
+/// Fail with a specific error when the OS won't let us simulate input (macOS Accessibility),
+/// instead of letting enigo fail cryptically later.
+fn ensure_input_permission(backend: &mut impl OutputBackend) -> Result<(), CommandError> {
+    if backend.input_permitted() {
+        Ok(())
+    } else {
+        Err(CommandError::PermissionDenied(
+            "Accessibility permission required to type or paste".to_string(),
+        ))
+    }
+}
+
+/// Accessibility permission status, as reported by `check_accessibility_permission`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionStatus {
+    Granted,
+    Denied,
+}
+
+/// Whether the app may simulate keyboard input. Always `granted` outside macOS.
+#[tauri::command]
+pub fn check_accessibility_permission() -> PermissionStatus {
+    if accessibility::is_accessibility_trusted() {
+        PermissionStatus::Granted
+    } else {
+        PermissionStatus::Denied
+    }
+}
+
+/// Payload of the `output-mode-resolved` event: what was asked for vs. what actually ran.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct OutputModeResolution {
+    pub requested: &'static str,
+    pub effective: &'static str,
+    /// Why the effective mode differs from the requested one (`None` when they match).
+    pub reason: Option<String>,
+}
+
+impl OutputModeResolution {
+    pub(crate) fn new(requested: OutputMode, effective: OutputMode, reason: &ResolutionReason) -> Self {
+        Self {
+            requested: requested.to_str(),
+            effective: effective.to_str(),
+            reason: reason.describe(),
+        }
+    }
+}
+
+/// Everything `resolve_output_mode` looks at besides the requested mode.
+#[derive(Debug, Clone)]
+pub struct OutputContext<'a> {
+    /// Length of the text to output, in chars.
+    pub text_chars: usize,
+    /// See `OutputOptions::max_keystroke_chars`.
+    pub max_keystroke_chars: usize,
+    /// Normalized process path of the foreground app, if known.
+    pub foreground_app: Option<&'a str>,
+    /// Per-app mode overrides, keyed by normalized process path.
+    pub app_overrides: &'a HashMap<String, OutputMode>,
+    /// Whether the system clipboard could be opened.
+    pub clipboard_available: bool,
+    /// Whether focus is in a password/secure input field.
+    pub secure_field: bool,
+}
+
+/// Why `resolve_output_mode` picked the mode it did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolutionReason {
+    Requested,
+    AppOverride { app: String },
+    TextTooLong { chars: usize, limit: usize },
+    SecureField,
+    ClipboardUnavailable,
+    /// The one-shot clipboard-only target (`toggle_next_output_clipboard_only`).
+    ClipboardOnly,
+}
+
+impl ResolutionReason {
+    /// Human-readable explanation for the `output-mode-resolved` event.
+    fn describe(&self) -> Option<String> {
+        match self {
+            ResolutionReason::Requested => None,
+            ResolutionReason::AppOverride { app } => {
+                Some(format!("Output mode overridden for {}", app))
+            }
+            ResolutionReason::TextTooLong { chars, limit } => Some(format!(
+                "Text is {} characters, over the {}-character limit for keystroke output",
+                chars, limit
+            )),
+            ResolutionReason::SecureField => {
+                Some("Secure input field; typing instead of using the clipboard".to_string())
+            }
+            ResolutionReason::ClipboardUnavailable => {
+                Some("Clipboard unavailable; typing instead of pasting".to_string())
+            }
+            ResolutionReason::ClipboardOnly => {
+                Some("Next output set to clipboard only; copying instead".to_string())
+            }
+        }
+    }
+}
+
+fn is_keystroke_mode(mode: OutputMode) -> bool {
+    matches!(
+        mode,
+        OutputMode::KeystrokesVerified
+            | OutputMode::KeystrokesWithKeys
+            | OutputMode::KeystrokesAndSelect
+    )
+}
+
+/// Decide which mode actually runs. Pure, so every rule can be tested without a desktop.
+///
+/// Rules apply in order, and the reason reported is the last one that changed the mode:
+/// 1. A per-app override replaces the requested mode.
+/// 2. In a secure field, anything that goes through the clipboard is typed instead, so
+///    secrets never land in clipboard history. Selecting the typed text is skipped too.
+/// 3. Otherwise keystroke modes are downgraded to paste when the text is too long to type.
+/// 4. Paste modes are typed instead when the clipboard can't be opened (and the text is
+///    short enough to type). Clipboard-only modes are left alone and fail loudly.
+pub fn resolve_output_mode(
+    requested: OutputMode,
+    context: &OutputContext,
+) -> (OutputMode, ResolutionReason) {
+    let mut mode = requested;
+    let mut reason = ResolutionReason::Requested;
+
+    if let Some(app) = context.foreground_app {
+        if let Some(&override_mode) = context.app_overrides.get(app) {
+            if override_mode != mode {
+                mode = override_mode;
+                reason = ResolutionReason::AppOverride { app: app.to_string() };
+            }
+        }
+    }
+
+    if context.secure_field {
+        if !matches!(
+            mode,
+            OutputMode::KeystrokesVerified | OutputMode::KeystrokesWithKeys
+        ) {
+            mode = OutputMode::KeystrokesVerified;
+            reason = ResolutionReason::SecureField;
+        }
+        return (mode, reason);
+    }
+
+    let too_long = context.text_chars > context.max_keystroke_chars;
+    if is_keystroke_mode(mode) && too_long {
+        mode = OutputMode::Paste;
+        reason = ResolutionReason::TextTooLong {
+            chars: context.text_chars,
+            limit: context.max_keystroke_chars,
+        };
+    }
+
+    if !context.clipboard_available
+        && matches!(
+            mode,
+            OutputMode::Paste | OutputMode::PasteAndClipboard | OutputMode::TypeThenPaste
+        )
+        && !too_long
+    {
+        mode = OutputMode::KeystrokesVerified;
+        reason = ResolutionReason::ClipboardUnavailable;
+    }
+
+    (mode, reason)
+}
+
+/// Copy text to clipboard and paste, keeping text in clipboard (no restore)
+pub fn paste_and_keep_clipboard(
+    backend: &mut impl OutputBackend,
+    text: &str,
+    hit_enter: bool,
+    enter_delay_ms: u64,
+    linux_paste: &LinuxPasteConfig,
+    modifier_wait: Option<ModifierWait>,
+) -> Result<(), CommandError> {
+    // Set new text
+    backend.set_clipboard_text(text)?;
+
+    // Small delay for clipboard to stabilize
+    backend.sleep(Duration::from_millis(CLIPBOARD_STABILIZATION_DELAY_MS));
+
+    // Simulate Ctrl+V / Cmd+V
+    if let Some(wait) = modifier_wait {
+        release_modifiers_before_paste(backend, wait);
+    }
+    backend.paste(linux_paste)?;
+    maybe_hit_enter(backend, hit_enter, enter_delay_ms)?;
+
+    // Don't restore clipboard - keep the text there
+    log::info!("Pasted {} chars (kept in clipboard)", text.chars().count());
+    Ok(())
+}
+
+/// Copy text to clipboard only (no paste)
+pub fn copy_to_clipboard(text: &str) -> Result<(), CommandError> {
+    copy_to_clipboard_with(&mut SystemBackend::default(), text)
+}
+
+fn copy_to_clipboard_with(
+    backend: &mut impl OutputBackend,
+    text: &str,
+) -> Result<(), CommandError> {
+    backend.set_clipboard_text(text)?;
+    record_clipboard_write(OutputMode::Clipboard, text.to_string());
+    log::info!("Copied {} chars to clipboard", text.len());
+    Ok(())
+}
+
+/// Append text to the current clipboard text as `existing + separator + text`.
+///
+/// Non-text clipboard content (images, files) is treated as empty and replaced.
+fn append_to_clipboard_with(
+    backend: &mut impl OutputBackend,
+    text: &str,
+    separator: &str,
+) -> Result<(), CommandError> {
+    let existing = backend.clipboard_text().unwrap_or_default();
+    let combined = join_clipboard_append(&existing, text, separator);
+    backend.set_clipboard_text(&combined)?;
+    log::info!(
+        "Appended {} chars to clipboard ({} total)",
+        text.len(),
+        combined.len()
+    );
+    record_clipboard_write(OutputMode::ClipboardAppend, combined);
+    Ok(())
+}
+
+/// What the clipboard-only modes last put on the clipboard, from `get_last_clipboard_write`,
+/// for undoing or comparing it later.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct LastClipboardWrite {
+    /// "clipboard" or "clipboard_append".
+    pub mode: &'static str,
+    /// The full clipboard text after the write (for append, including the earlier text).
+    pub text: String,
+    /// Length of `text` in chars.
+    pub chars: usize,
+    pub written_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Set by `copy_to_clipboard_with` and `append_to_clipboard_with`. In-memory only, so it's empty
+/// after a restart, and only the latest write is kept: each one drops the previous text.
+static LAST_CLIPBOARD_WRITE: Mutex<Option<LastClipboardWrite>> = Mutex::new(None);
+
+fn record_clipboard_write(mode: OutputMode, text: String) {
+    let write = LastClipboardWrite {
+        mode: mode.to_str(),
+        chars: text.chars().count(),
+        text,
+        written_at: chrono::Utc::now(),
+    };
+    *LAST_CLIPBOARD_WRITE.lock().unwrap_or_else(|e| e.into_inner()) = Some(write);
+}
+
+/// The last write by the clipboard-only modes (and copying a history entry), or `None` if
+/// nothing has been copied since the app started.
+#[tauri::command]
+pub fn get_last_clipboard_write() -> Option<LastClipboardWrite> {
+    LAST_CLIPBOARD_WRITE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+fn join_clipboard_append(existing: &str, text: &str, separator: &str) -> String {
+    if existing.is_empty() {
+        text.to_string()
+    } else {
+        format!("{}{}{}", existing, separator, text)
+    }
+}
+
+/// Give the user a moment to let go of the hotkey, then release modifiers still held.
+///
+/// Where the OS can report key state, this returns as soon as the modifiers are up, only
+/// modifiers that are actually down are released, and output is aborted with
+/// `OutputInterrupted` if the user starts typing during the wait (our releases and text
+/// would interleave with their keys). Otherwise it waits the full `max_wait_ms` and, with
+/// the `blanket-modifier-release` feature (on by default), releases all common modifiers;
+/// releasing a key that isn't held is harmless on supported platforms.
+fn release_held_modifiers(
+    backend: &mut impl OutputBackend,
+    wait: ModifierWait,
+) -> Result<(), CommandError> {
+    let backend = RefCell::new(backend);
+    let outcome = wait_for_modifier_release(
+        wait,
+        || backend.borrow_mut().key_activity(),
+        |duration| backend.borrow_mut().sleep(duration),
+    );
+    let backend = backend.into_inner();
+
+    let keys = match outcome {
+        ModifierWaitOutcome::Released => return Ok(()),
+        ModifierWaitOutcome::UserTyping => {
+            log::info!("User is typing; not starting keystroke output");
+            return Err(CommandError::OutputInterrupted(
+                "Keys were pressed before typing started; output skipped (the text is in history)"
+                    .to_string(),
+            ));
+        }
+        ModifierWaitOutcome::TimedOut => backend.held_modifiers().unwrap_or_default(),
+        ModifierWaitOutcome::Unsupported => match backend.held_modifiers() {
+            Some(held) => held,
+            None if cfg!(feature = "blanket-modifier-release") => {
+                vec![Key::Control, Key::Alt, Key::Shift, Key::Meta]
+            }
+            None => Vec::new(),
+        },
+    };
+
+    for key in keys {
+        if let Err(e) = backend.key(key, Direction::Release) {
+            log::debug!("Failed to release modifier {:?}: {}", key, e);
+        }
+    }
+    Ok(())
+}
+
+/// Before a paste combo, wait for the hotkey's modifiers to be let go and release any still
+/// down, so e.g. a held Cmd+Shift doesn't turn Cmd+V into Cmd+Shift+V. Unlike keystroke
+/// modes this never skips the output when the user is typing: the paste is one combo and
+/// can't interleave with their keys.
+fn release_modifiers_before_paste(backend: &mut impl OutputBackend, wait: ModifierWait) {
+    let wait = ModifierWait {
+        abort_on_user_input: false,
+        ..wait
+    };
+    // Only fails when aborting on user input, which is off.
+    let _ = release_held_modifiers(backend, wait);
+}
+
+/// After keystroke output, release modifiers the OS still reports as down. Apps that track
+/// held keys can otherwise be left with a "sticky" Shift after a long dictation, and a stuck
+/// modifier would turn a following Enter into Shift+Enter.
+///
+/// A no-op where key state can't be queried (Linux).
+fn release_stuck_modifiers(backend: &mut impl OutputBackend) {
+    let held = backend.held_modifiers();
+    release_stuck(held, |key| {
+        if let Err(e) = backend.key(key, Direction::Release) {
+            log::debug!("Failed to release modifier {:?}: {}", key, e);
+        }
+    });
+}
+
+/// Release each key in `held` (`None` = state unknown, do nothing). Returns the keys released.
+fn release_stuck(held: Option<Vec<Key>>, mut release: impl FnMut(Key)) -> Vec<Key> {
+    let held = held.unwrap_or_default();
+    if !held.is_empty() {
+        log::warn!("Modifiers still down after keystroke output: {:?}; releasing", held);
+    }
+    for &key in &held {
+        release(key);
+    }
+    held
+}
+
+/// Type a run in `tuning.chunk_chars` batches with a short (optionally jittered) pause
+/// between them. Jitter only changes the pauses; chunk boundaries stay the same.
+///
+/// Newlines (`\n` or `\r\n`) aren't typed as text; each one presses `newline_key`.
+fn send_text_chunked(
+    backend: &mut impl OutputBackend,
+    text: &str,
+    unicode_input: bool,
+    tuning: KeystrokeTuning,
+    newline_key: NewlineKey,
+) -> Result<(), CommandError> {
+    let mut rng = JitterRng::from_clock();
+    let mut lines = text.split('\n').peekable();
+    while let Some(line) = lines.next() {
+        let last = lines.peek().is_none();
+        let line = if last { line } else { line.strip_suffix('\r').unwrap_or(line) };
+        let chars: Vec<char> = line.chars().collect();
+        for chunk in chars.chunks(tuning.chunk_chars.max(1)) {
+            let chunk: String = chunk.iter().collect();
+            backend.text(&chunk, unicode_input)?;
+            backend.sleep(Duration::from_millis(chunk_pause_ms(tuning, &mut rng)));
+        }
+        if !last {
+            press_newline(backend, newline_key)?;
+            backend.sleep(Duration::from_millis(chunk_pause_ms(tuning, &mut rng)));
+        }
+    }
+    Ok(())
+}
+
+/// Whether `c` belongs to a script that generally has to be entered through an IME or
+/// needs shaping (CJK, Hangul, kana, Thai, Indic, ...), so typing it as keystrokes is
+/// unreliable.
+pub fn is_complex_script_char(c: char) -> bool {
+    matches!(
+        c as u32,
+        // Indic scripts (Devanagari through Malayalam and Sinhala)
+        0x0900..=0x0DFF
+            // Thai, Lao, Tibetan
+            | 0x0E00..=0x0FFF
+            // Myanmar
+            | 0x1000..=0x109F
+            // Hangul Jamo
+            | 0x1100..=0x11FF
+            // Khmer
+            | 0x1780..=0x17FF
+            // CJK radicals, symbols and punctuation, kana, Bopomofo, Hangul compatibility
+            // Jamo, CJK strokes, enclosed/compatibility forms, Extension A, unified
+            // ideographs, Yi
+            | 0x2E80..=0xA4CF
+            // Hangul Jamo Extended-A
+            | 0xA960..=0xA97F
+            // Hangul syllables and Jamo Extended-B
+            | 0xAC00..=0xD7FF
+            // CJK compatibility ideographs
+            | 0xF900..=0xFAFF
+            // CJK compatibility forms, small form variants
+            | 0xFE30..=0xFE6F
+            // Halfwidth and fullwidth forms
+            | 0xFF00..=0xFFEF
+            // Kana supplements and extensions
+            | 0x1AFF0..=0x1B16F
+            // CJK ideograph extensions B and later
+            | 0x20000..=0x3FFFF
+    )
+}
+
+/// A run of output text and how the keystroke path should send it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptSegment<'a> {
+    /// Type as keystrokes.
+    Keys(&'a str),
+    /// Paste through the clipboard (CJK/complex-script run).
+    Paste(&'a str),
+}
+
+/// Split text into alternating keystroke and paste runs.
+///
+/// Whitespace stays with the run it follows (leading whitespace with the first run), so
+/// "你好 世界" is a single paste instead of two pastes around a typed space.
+pub fn split_script_segments(text: &str) -> Vec<ScriptSegment<'_>> {
+    fn segment(run: &str, paste: bool) -> ScriptSegment<'_> {
+        if paste {
+            ScriptSegment::Paste(run)
+        } else {
+            ScriptSegment::Keys(run)
+        }
+    }
+
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut current: Option<bool> = None;
+    for (i, c) in text.char_indices() {
+        if c.is_whitespace() {
+            continue;
+        }
+        let paste = is_complex_script_char(c);
+        match current {
+            Some(prev) if prev != paste => {
+                segments.push(segment(&text[start..i], prev));
+                start = i;
+                current = Some(paste);
+            }
+            Some(_) => {}
+            None => current = Some(paste),
+        }
+    }
+    if start < text.len() {
+        segments.push(segment(&text[start..], current.unwrap_or(false)));
+    }
+    segments
+}
+
+/// Keystroke-mode output with CJK/complex-script runs pasted per `complex_scripts`.
+///
+/// `type_run` types a keystroke run. Paste runs go through the clipboard, which is
+/// restored once at the end when `restore_clipboard_after_paste` is on.
+fn type_with_script_fallback<B: OutputBackend>(
+    backend: &mut B,
+    text: &str,
+    options: &OutputOptions,
+    mut type_run: impl FnMut(&mut B, &str) -> Result<(), CommandError>,
+) -> Result<(), CommandError> {
+    let segments = match options.complex_scripts {
+        ComplexScriptOutput::Keystrokes => vec![ScriptSegment::Keys(text)],
+        ComplexScriptOutput::Hybrid => split_script_segments(text),
+        ComplexScriptOutput::PasteAll if text.chars().any(is_complex_script_char) => {
+            vec![ScriptSegment::Paste(text)]
+        }
+        ComplexScriptOutput::PasteAll => vec![ScriptSegment::Keys(text)],
+    };
+    if !segments.iter().any(|s| matches!(s, ScriptSegment::Paste(_))) {
+        return type_run(backend, text);
+    }
+
+    let capture = capture_before_paste(backend, options);
+
+    let mut pasted = 0usize;
+    let mut last_pasted = None;
+    let result = segments.into_iter().try_for_each(|segment| match segment {
+        ScriptSegment::Keys(run) => type_run(backend, run),
+        ScriptSegment::Paste(run) => {
+            if capture.plan.conceal {
+                backend.set_concealed_clipboard_text(run)?;
+            } else {
+                backend.set_clipboard_text(run)?;
+            }
+            last_pasted = Some(run);
+            backend.sleep(Duration::from_millis(CLIPBOARD_STABILIZATION_DELAY_MS));
+            backend.paste(&options.linux_paste)?;
+            // Let the paste land before any following keystrokes.
+            backend.sleep(Duration::from_millis(CLIPBOARD_RESTORE_DELAY_MS));
+            pasted += run.chars().count();
+            Ok(())
+        }
+    });
+
+    // The last paste has had `CLIPBOARD_RESTORE_DELAY_MS` to land.
+    if let Some(last_pasted) = last_pasted {
+        restore_after_paste(backend, &capture, last_pasted, options);
+    }
+    log::info!(
+        "Pasted {} of {} chars within keystroke output",
+        pasted,
+        text.chars().count()
+    );
+    result
+}
+
+/// Type text as keystrokes in small batches.
+///
+/// The fast keystrokes output mode is disabled, but other modes build on this path.
+pub fn type_as_keystrokes(
+    backend: &mut impl OutputBackend,
+    text: &str,
+    options: &OutputOptions,
+) -> Result<(), CommandError> {
+    release_held_modifiers(backend, options.modifier_wait)?;
+    type_with_script_fallback(backend, text, options, |backend, run| {
+        send_text_chunked(
+            backend,
+            run,
+            options.unicode_input,
+            options.keystroke_tuning,
+            options.newline_key,
+        )
+    })?;
+    release_stuck_modifiers(backend);
+
+    log::info!("Typed {} chars as keystrokes", text.chars().count());
+    Ok(())
+}
+
+/// Split `text` after its first `typed_chars` characters for `OutputMode::TypeThenPaste`.
+///
+/// A CRLF pair is never split, so the typed part can't end in a lone carriage return.
+fn split_type_then_paste(text: &str, typed_chars: usize) -> (&str, &str) {
+    let mut at = text
+        .char_indices()
+        .nth(typed_chars)
+        .map_or(text.len(), |(index, _)| index);
+    if text[..at].ends_with('\r') && text[at..].starts_with('\n') {
+        at += 1;
+    }
+    text.split_at(at)
+}
+
+/// Type the start of `text` as keystrokes, then paste the remainder with clipboard
+/// restore. Enter (if enabled) follows the paste.
+fn type_then_paste(
+    backend: &mut impl OutputBackend,
+    text: &str,
+    options: &OutputOptions,
+) -> Result<Option<OutputTiming>, CommandError> {
+    let (typed, rest) = split_type_then_paste(text, options.type_then_paste_chars);
+    if !typed.is_empty() {
+        type_as_keystrokes(backend, typed, options)?;
+    }
+
+    if rest.is_empty() {
+        maybe_hit_enter(backend, options.hit_enter, options.enter_delay_ms)?;
+        return Ok(None);
+    }
+    // Typing has already replaced any selection, so there's nothing left to copy first.
+    type_text_blocking(
+        backend,
+        rest,
+        &OutputOptions {
+            replace_selection: false,
+            ..options.clone()
+        },
+    )
+}
+
+/// Type text one character at a time with a fixed delay between keys.
+///
+/// Slow but reliable: no batching means targets that drop characters under bursty input
+/// (password fields, remote desktops) still receive every key.
+pub fn type_as_keystrokes_verified(
+    backend: &mut impl OutputBackend,
+    text: &str,
+    options: &OutputOptions,
+) -> Result<(), CommandError> {
+    release_held_modifiers(backend, options.modifier_wait)?;
+
+    let key_delay_ms = options.verified_key_delay_ms;
+    let mut buf = [0u8; 4];
+    let mut typed = 0usize;
+    type_with_script_fallback(backend, text, options, |backend, run| {
+        let mut chars = run.chars().peekable();
+        while let Some(c) = chars.next() {
+            // "\r\n" is one newline.
+            if c == '\r' && chars.peek() == Some(&'\n') {
+                continue;
+            }
+            let sent = if c == '\n' {
+                press_newline(backend, options.newline_key)
+            } else {
+                backend.text(c.encode_utf8(&mut buf), options.unicode_input)
+            };
+            sent.map_err(|e| {
+                CommandError::InputInjectionFailed(format!(
+                    "Failed after typing {} chars: {}",
+                    typed, e
+                ))
+            })?;
+            typed += 1;
+            backend.sleep(Duration::from_millis(key_delay_ms));
+        }
+        Ok(())
+    })?;
+
+    release_stuck_modifiers(backend);
+    maybe_hit_enter(backend, options.hit_enter, options.enter_delay_ms)?;
+
+    log::info!(
+        "Typed {} chars as verified keystrokes ({}ms/key)",
+        typed,
+        key_delay_ms
+    );
+    Ok(())
+}
+
+/// One step of `OutputMode::KeystrokesWithKeys` output.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Output {
+    Text(String),
+    Key(Key),
+}
+
+/// Map a `{token}` name (case-insensitive) to the key it stands for.
+fn key_for_token(name: &str) -> Option<Key> {
+    match name.to_ascii_lowercase().as_str() {
+        "tab" => Some(Key::Tab),
+        "enter" | "return" => Some(Key::Return),
+        "esc" | "escape" => Some(Key::Escape),
+        "backspace" => Some(Key::Backspace),
+        "delete" => Some(Key::Delete),
+        "up" => Some(Key::UpArrow),
+        "down" => Some(Key::DownArrow),
+        "left" => Some(Key::LeftArrow),
+        "right" => Some(Key::RightArrow),
+        "home" => Some(Key::Home),
+        "end" => Some(Key::End),
+        _ => None,
+    }
+}
+
+/// Split text into literal runs and `{token}` key presses.
+///
+/// Unrecognized or unterminated tokens stay in the text verbatim so dictation is never
+/// silently dropped. Adjacent literal runs are merged.
+pub fn tokenize_key_tokens(text: &str) -> Vec<Output> {
+    fn push_text(out: &mut Vec<Output>, s: &str) {
+        if s.is_empty() {
+            return;
+        }
+        if let Some(Output::Text(prev)) = out.last_mut() {
+            prev.push_str(s);
+        } else {
+            out.push(Output::Text(s.to_string()));
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut rest = text;
+
+    while let Some(open) = rest.find('{') {
+        push_text(&mut out, &rest[..open]);
+        let after = &rest[open + 1..];
+
+        match after.find(['{', '}']) {
+            Some(close) if after.as_bytes()[close] == b'}' => {
+                match key_for_token(&after[..close]) {
+                    Some(key) => out.push(Output::Key(key)),
+                    None => push_text(&mut out, &rest[open..open + close + 2]),
+                }
+                rest = &after[close + 1..];
+            }
+            _ => {
+                // No closing brace before the next token (or at all): keep the '{' literal.
+                push_text(&mut out, "{");
+                rest = after;
+            }
+        }
+    }
+
+    push_text(&mut out, rest);
+    out
+}
+
+/// Type text as keystrokes, pressing keys for recognized `{token}`s.
+pub fn type_with_key_tokens(
+    backend: &mut impl OutputBackend,
+    text: &str,
+    options: &OutputOptions,
+) -> Result<(), CommandError> {
+    release_held_modifiers(backend, options.modifier_wait)?;
+
+    let mut keys = 0usize;
+    for item in tokenize_key_tokens(text) {
+        match item {
+            Output::Text(run) => {
+                type_with_script_fallback(backend, &run, options, |backend, run| {
+                    send_text_chunked(
+                        backend,
+                        run,
+                        options.unicode_input,
+                        options.keystroke_tuning,
+                        options.newline_key,
+                    )
+                })?;
+            }
+            Output::Key(key) => {
+                // Give the target time to process preceding text before navigation keys.
+                backend.sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
+                backend.key(key, Direction::Click)?;
+                keys += 1;
+                backend.sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
+            }
+        }
+    }
+
+    release_stuck_modifiers(backend);
+    maybe_hit_enter(backend, options.hit_enter, options.enter_delay_ms)?;
+
+    log::info!("Typed text with {} key token(s)", keys);
+    Ok(())
+}
+
+/// Type text via the keystroke path, then select it with Shift+Left.
+pub fn type_and_select(
+    backend: &mut impl OutputBackend,
+    text: &str,
+    options: &OutputOptions,
+) -> Result<(), CommandError> {
+    type_as_keystrokes(backend, text, options)?;
+
+    // "\r\n" is typed as one newline, so it's one caret position.
+    let typed = text.chars().count() - text.matches("\r\n").count();
+    let count = typed.min(MAX_SELECT_CHARS);
+    if count == 0 {
+        return Ok(());
+    }
+
+    // Let the target app finish processing the typed text before we start selecting;
+    // otherwise Shift can bleed into the tail of the typing and capitalize it.
+    backend.sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
+
+    backend.key(Key::Shift, Direction::Press)?;
+    backend.sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
+
+    let mut result = Ok(());
+    for _ in 0..count {
+        if let Err(e) = backend.key(Key::LeftArrow, Direction::Click) {
+            result = Err(e);
+            break;
+        }
+        backend.sleep(Duration::from_millis(SELECT_KEY_DELAY_MS));
+    }
+
+    // Always release Shift, even if a click failed, so it doesn't stay stuck down.
+    backend.sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
+    backend.key(Key::Shift, Direction::Release)?;
+
+    result?;
+    log::info!("Selected {} typed chars", count);
+    Ok(())
+}
+
+/// Restores made through an output backend (`OutputBackend::restore_clipboard_text`).
+struct BackendRestore<'b, B> {
+    backend: &'b mut B,
+    concealed: bool,
+}
+
+impl<'b, B: OutputBackend> BackendRestore<'b, B> {
+    fn new(backend: &'b mut B, concealed: bool) -> Self {
+        Self { backend, concealed }
+    }
+}
+
+impl<B: OutputBackend> ClipboardWrite for BackendRestore<'_, B> {
+    fn write_text(&mut self, text: &str) -> Result<(), CommandError> {
+        self.backend.restore_clipboard_text(text, self.concealed)
+    }
+}
+
+/// Keep the previous clipboard text for restore unless it's over `max_bytes`.
+///
+/// Writing megabytes back to the clipboard can stall the output thread, so oversized content
+/// is dropped and the clipboard is left holding the pasted text instead.
+fn capture_for_restore(previous: String, max_bytes: usize) -> Option<String> {
+    if previous.len() > max_bytes {
+        log::info!(
+            "Previous clipboard is {} bytes (cap {}); it will not be restored after paste",
+            previous.len(),
+            max_bytes
+        );
+        return None;
+    }
+    Some(previous)
+}
+
+/// Put the previously captured clipboard text back. Returns whether the clipboard was written.
+///
+/// A failed write is logged and otherwise ignored: the output it follows already succeeded.
+///
+/// arboard's `get_text` can't distinguish an empty clipboard from a failed read, and writing
+/// back an empty string makes some apps think there's nothing to paste, so with
+/// `skip_empty_restore` an empty capture leaves the clipboard untouched.
+fn restore_previous_clipboard(
+    clipboard: &mut impl ClipboardWrite,
+    previous: &str,
+    skip_empty_restore: bool,
+) -> bool {
+    if previous.is_empty() && skip_empty_restore {
+        log::debug!("Previous clipboard was empty; skipping restore");
+        return false;
+    }
+
+    match clipboard.write_text(previous) {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!(
+                "Failed to restore previous clipboard ({} bytes): {}",
+                previous.len(),
+                e
+            );
+            false
+        }
+    }
+}
+
+/// Whether the clipboard still holds the text we pasted, i.e. nobody replaced it during the
+/// restore delay. A failed read (e.g. the user copied an image) counts as replaced.
+fn clipboard_still_holds(current: Option<&str>, written: &str) -> bool {
+    current == Some(written)
+}
+
+/// The selection copied by `copy_selection`, or `None` if nothing was selected (the copy
+/// left the cleared clipboard empty, or put non-text content on it).
+fn copied_selection(copied: Option<String>) -> Option<String> {
+    copied.filter(|text| !text.is_empty())
+}
+
+/// Copy the current selection with Ctrl+C / Cmd+C and return it.
+///
+/// The clipboard is cleared first: copying with nothing selected is a no-op in most apps,
+/// and would otherwise hand back whatever was on the clipboard before.
+fn copy_selection(backend: &mut impl OutputBackend) -> Result<Option<String>, CommandError> {
+    backend.clear_clipboard()?;
+    send_command_shortcut(backend, 'c')?;
+    backend.sleep(Duration::from_millis(SELECTION_COPY_DELAY_MS));
+    Ok(copied_selection(backend.clipboard_text().ok()))
+}
+
+/// How the paste paths treat the clipboard around a paste.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PasteClipboardPlan {
+    /// Capture the previous content and put it back after the paste.
+    restore: bool,
+    /// Hide the pasted text from clipboard managers. Only while it's on the clipboard
+    /// temporarily: text that stays is a normal copy.
+    conceal: bool,
+}
+
+impl PasteClipboardPlan {
+    fn new(options: &OutputOptions) -> Self {
+        let restore = options.restore_clipboard_after_paste;
+        Self {
+            restore,
+            conceal: restore && options.clipboard_manager_friendly,
+        }
+    }
+}
+
+/// The clipboard as it was before a paste, for `restore_after_paste`.
+struct ClipboardCapture {
+    plan: PasteClipboardPlan,
+    snapshot: Option<clipboard_snapshot::ClipboardSnapshot>,
+    previous: Option<String>,
+}
+
+/// Save the clipboard before a paste overwrites it, if the plan restores it. On Windows every
+/// format is snapshotted too, so rich content (Excel cells, RTF, HTML) survives; the text
+/// copy is the fallback.
+fn capture_before_paste(
+    backend: &mut impl OutputBackend,
+    options: &OutputOptions,
+) -> ClipboardCapture {
+    let plan = PasteClipboardPlan::new(options);
+    let (snapshot, previous) = if plan.restore {
+        (
+            backend.snapshot_clipboard(options.max_clipboard_capture_bytes),
+            capture_for_restore(
+                backend.clipboard_text().unwrap_or_default(),
+                options.max_clipboard_capture_bytes,
+            ),
+        )
+    } else {
+        (None, None)
+    };
+    ClipboardCapture {
+        plan,
+        snapshot,
+        previous,
+    }
+}
+
+/// Put back what `capture_before_paste` saved, once the paste of `pasted` has landed. With
+/// `safe_clipboard_restore`, nothing is restored if the user has copied something else in
+/// the meantime.
+fn restore_after_paste(
+    backend: &mut impl OutputBackend,
+    capture: &ClipboardCapture,
+    pasted: &str,
+    options: &OutputOptions,
+) {
+    if !capture.plan.restore {
+        return;
+    }
+    let replaced = options.safe_clipboard_restore
+        && !clipboard_still_holds(backend.clipboard_text().ok().as_deref(), pasted);
+    if replaced {
+        log::info!("Clipboard changed since paste; skipping restore");
+        return;
+    }
+    let restored_snapshot = capture.snapshot.as_ref().is_some_and(|snapshot| {
+        backend
+            .restore_clipboard_snapshot(snapshot, capture.plan.conceal)
+            .map_err(|e| log::warn!("Full clipboard restore failed ({}); restoring text only", e))
+            .is_ok()
+    });
+    if !restored_snapshot {
+        if let Some(previous) = &capture.previous {
+            restore_previous_clipboard(
+                &mut BackendRestore::new(backend, capture.plan.conceal),
+                previous,
+                options.skip_empty_restore,
+            );
+        }
+    }
+}
+
+/// Type text using clipboard and paste. Used internally by shortcut handlers.
+///
+/// With `restore_clipboard_after_paste` off the text stays on the clipboard, as in
+/// `paste_and_keep_clipboard`.
+///
+/// Returns per-phase timing when `options.profile_output` is set. With profiling off no
+/// clocks are read.
+pub fn type_text_blocking(
+    backend: &mut impl OutputBackend,
+    text: &str,
+    options: &OutputOptions,
+) -> Result<Option<OutputTiming>, CommandError> {
+    let mut timer = options.profile_output.then(PhaseTimer::start);
+    let capture = capture_before_paste(backend, options);
+
+    // Before any combo (the selection copy too), so held hotkey modifiers can't join it.
+    if options.release_modifiers_before_paste {
+        release_modifiers_before_paste(backend, options.modifier_wait);
+    }
+
+    // Dictate over selection: copy it first. The paste below replaces a live selection,
+    // and with nothing selected it's a normal paste at the cursor. Both steps are covered
+    // by the snapshot taken above.
+    if options.replace_selection {
+        match copy_selection(backend)? {
+            Some(selection) => log::info!(
+                "Replacing selection ({} chars) with dictated text",
+                selection.chars().count()
+            ),
+            None => log::debug!("No selection to replace; pasting at the cursor"),
+        }
+    }
+
+    // Set new text
+    if capture.plan.conceal {
+        backend.set_concealed_clipboard_text(text)?;
+    } else {
+        backend.set_clipboard_text(text)?;
+    }
+    let clipboard_set_ms = timer.as_mut().map_or(0, PhaseTimer::lap);
+
+    // Small delay for clipboard to stabilize
+    backend.sleep(Duration::from_millis(CLIPBOARD_STABILIZATION_DELAY_MS));
+    let stabilization_ms = timer.as_mut().map_or(0, PhaseTimer::lap);
+
+    // Simulate Ctrl+V / Cmd+V
+    backend.paste(&options.linux_paste)?;
+
+    let key_sequence_ms = timer.as_mut().map_or(0, PhaseTimer::lap);
+
+    // Restore previous clipboard after a delay, unless the user has copied something
+    // else in the meantime.
+    if capture.plan.restore {
+        backend.sleep(Duration::from_millis(CLIPBOARD_RESTORE_DELAY_MS));
+        restore_after_paste(backend, &capture, text, options);
+    } else {
+        log::info!("Pasted {} chars (kept in clipboard)", text.chars().count());
+    }
+
+    // Enter goes last, after the restore, so the target has had the full restore window
+    // to process the paste before we submit.
+    maybe_hit_enter(backend, options.hit_enter, options.enter_delay_ms)?;
+
+    let Some(mut timer) = timer else {
+        return Ok(None);
+    };
+
+    let timing = OutputTiming {
+        clipboard_set_ms,
+        stabilization_ms,
+        key_sequence_ms,
+        restore_ms: timer.lap(),
+        total_ms: timer.total(),
+    };
+    log::info!(
+        "Paste timing: clipboard_set={}ms stabilization={}ms keys={}ms restore={}ms total={}ms",
+        timing.clipboard_set_ms,
+        timing.stabilization_ms,
+        timing.key_sequence_ms,
+        timing.restore_ms,
+        timing.total_ms
+    );
+    Ok(Some(timing))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output_backend::{send_key_combo, BackendCall, MockOutputBackend};
+
+    /// Serializes tests that write `LAST_CLIPBOARD_WRITE`.
+    static CLIPBOARD_WRITE_TEST_LOCK: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn test_output_mode_from_str() {
+        assert_eq!(OutputMode::from_str("paste"), OutputMode::Paste);
+        assert_eq!(
+            OutputMode::from_str("paste_and_clipboard"),
+            OutputMode::PasteAndClipboard
+        );
+        assert_eq!(OutputMode::from_str("clipboard"), OutputMode::Clipboard);
+        assert_eq!(
+            OutputMode::from_str("clipboard_append"),
+            OutputMode::ClipboardAppend
+        );
+        assert_eq!(
+            OutputMode::from_str("keystrokes_verified"),
+            OutputMode::KeystrokesVerified
+        );
+        assert_eq!(
+            OutputMode::from_str("keystrokes_and_select"),
+            OutputMode::KeystrokesAndSelect
+        );
+    }
+
+    #[test]
+    fn test_type_then_paste_split() {
+        assert_eq!(split_type_then_paste("hello world", 5), ("hello", " world"));
+        assert_eq!(split_type_then_paste("hello", 5), ("hello", ""));
+        assert_eq!(split_type_then_paste("hello", 50), ("hello", ""));
+        assert_eq!(split_type_then_paste("hello", 0), ("", "hello"));
+        assert_eq!(split_type_then_paste("", 3), ("", ""));
+        // Counts characters, not bytes.
+        assert_eq!(split_type_then_paste("café au lait", 4), ("café", " au lait"));
+        assert_eq!(split_type_then_paste("日本語です", 2), ("日本", "語です"));
+        // CRLF stays together.
+        assert_eq!(split_type_then_paste("ab\r\ncd", 3), ("ab\r\n", "cd"));
+        assert_eq!(split_type_then_paste("ab\r\ncd", 2), ("ab", "\r\ncd"));
+        assert_eq!(OutputMode::from_str("type_then_paste"), OutputMode::TypeThenPaste);
+    }
+
+    #[test]
+    fn test_output_mode_round_trip() {
+        for mode in OutputMode::ALL {
+            assert_eq!(OutputMode::from_str(mode.to_str()), mode);
+        }
+    }
+
+    #[test]
+    fn test_output_mode_next_wraps_around() {
+        assert_eq!(OutputMode::Paste.next(), OutputMode::PasteAndClipboard);
+        assert_eq!(OutputMode::KeystrokesAndSelect.next(), OutputMode::Paste);
+
+        // Cycling through every mode returns to the start.
+        let mut mode = OutputMode::Paste;
+        for _ in 0..OutputMode::ALL.len() {
+            mode = mode.next();
+        }
+        assert_eq!(mode, OutputMode::Paste);
+    }
+
+    fn context(text_chars: usize, max_keystroke_chars: usize) -> OutputContext<'static> {
+        static NO_OVERRIDES: OnceLock<HashMap<String, OutputMode>> = OnceLock::new();
+        OutputContext {
+            text_chars,
+            max_keystroke_chars,
+            foreground_app: None,
+            app_overrides: NO_OVERRIDES.get_or_init(HashMap::new),
+            clipboard_available: true,
+            secure_field: false,
+        }
+    }
+
+    fn resolved(mode: OutputMode, context: &OutputContext) -> OutputMode {
+        resolve_output_mode(mode, context).0
+    }
+
+    #[test]
+    fn test_long_text_falls_back_to_paste_in_keystroke_modes() {
+        assert_eq!(
+            resolved(OutputMode::KeystrokesVerified, &context(11, 10)),
+            OutputMode::Paste
+        );
+        assert_eq!(
+            resolved(OutputMode::KeystrokesAndSelect, &context(11, 10)),
+            OutputMode::Paste
+        );
+        assert_eq!(
+            resolved(OutputMode::KeystrokesVerified, &context(11, 11)),
+            OutputMode::KeystrokesVerified
+        );
+        // Non-keystroke modes are never downgraded.
+        assert_eq!(
+            resolved(OutputMode::Clipboard, &context(11, 1)),
+            OutputMode::Clipboard
+        );
+    }
+
+    #[test]
+    fn test_output_mode_resolution_reports_reason_only_on_change() {
+        let (effective, reason) =
+            resolve_output_mode(OutputMode::KeystrokesVerified, &context(11, 10));
+        let fallback = OutputModeResolution::new(OutputMode::KeystrokesVerified, effective, &reason);
+        assert_eq!(fallback.requested, "keystrokes_verified");
+        assert_eq!(fallback.effective, "paste");
+        assert!(fallback.reason.unwrap().contains("11 characters"));
+
+        let (effective, reason) = resolve_output_mode(OutputMode::Paste, &context(11, 1));
+        assert_eq!(reason, ResolutionReason::Requested);
+        assert_eq!(
+            OutputModeResolution::new(OutputMode::Paste, effective, &reason),
+            OutputModeResolution {
+                requested: "paste",
+                effective: "paste",
+                reason: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_resolve_output_mode_matrix() {
+        use OutputMode::*;
+
+        // (requested, clipboard_available, secure_field, too_long) -> (effective, reason)
+        let cases = [
+            (Paste, true, false, false, Paste, ResolutionReason::Requested),
+            (Paste, false, false, false, KeystrokesVerified, ResolutionReason::ClipboardUnavailable),
+            (Paste, false, false, true, Paste, ResolutionReason::Requested),
+            (Paste, true, true, false, KeystrokesVerified, ResolutionReason::SecureField),
+            (PasteAndClipboard, false, false, false, KeystrokesVerified, ResolutionReason::ClipboardUnavailable),
+            (PasteAndClipboard, true, true, true, KeystrokesVerified, ResolutionReason::SecureField),
+            (Clipboard, false, false, false, Clipboard, ResolutionReason::Requested),
+            (Clipboard, true, true, false, KeystrokesVerified, ResolutionReason::SecureField),
+            (ClipboardAppend, false, false, false, ClipboardAppend, ResolutionReason::Requested),
+            (ClipboardAppend, true, true, false, KeystrokesVerified, ResolutionReason::SecureField),
+            (KeystrokesVerified, true, false, false, KeystrokesVerified, ResolutionReason::Requested),
+            (KeystrokesVerified, false, false, false, KeystrokesVerified, ResolutionReason::Requested),
+            (KeystrokesVerified, true, true, true, KeystrokesVerified, ResolutionReason::Requested),
+            (KeystrokesWithKeys, true, false, true, Paste, ResolutionReason::TextTooLong { chars: 20, limit: 10 }),
+            (KeystrokesWithKeys, true, true, true, KeystrokesWithKeys, ResolutionReason::Requested),
+            (KeystrokesAndSelect, true, true, false, KeystrokesVerified, ResolutionReason::SecureField),
+            (KeystrokesAndSelect, false, false, true, Paste, ResolutionReason::TextTooLong { chars: 20, limit: 10 }),
+        ];
+
+        for (requested, clipboard, secure, too_long, expected_mode, expected_reason) in cases {
+            let mut ctx = context(if too_long { 20 } else { 5 }, 10);
+            ctx.clipboard_available = clipboard;
+            ctx.secure_field = secure;
+            assert_eq!(
+                resolve_output_mode(requested, &ctx),
+                (expected_mode, expected_reason),
+                "requested={:?} clipboard={} secure={} too_long={}",
+                requested,
+                clipboard,
+                secure,
+                too_long
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_output_mode_app_override() {
+        let overrides = HashMap::from([(
+            "c:\\apps\\terminal.exe".to_string(),
+            OutputMode::KeystrokesVerified,
+        )]);
+        let mut ctx = context(5, 10);
+        ctx.app_overrides = &overrides;
+
+        // No foreground app: the override can't apply.
+        assert_eq!(
+            resolve_output_mode(OutputMode::Paste, &ctx),
+            (OutputMode::Paste, ResolutionReason::Requested)
+        );
+
+        ctx.foreground_app = Some("c:\\apps\\terminal.exe");
+        assert_eq!(
+            resolve_output_mode(OutputMode::Paste, &ctx),
+            (
+                OutputMode::KeystrokesVerified,
+                ResolutionReason::AppOverride {
+                    app: "c:\\apps\\terminal.exe".to_string()
+                }
+            )
+        );
+        // Overriding to the mode that was requested anyway isn't a change.
+        assert_eq!(
+            resolve_output_mode(OutputMode::KeystrokesVerified, &ctx),
+            (OutputMode::KeystrokesVerified, ResolutionReason::Requested)
+        );
+
+        // Later rules still apply on top of the override.
+        ctx.text_chars = 50;
+        assert_eq!(
+            resolve_output_mode(OutputMode::Paste, &ctx),
+            (
+                OutputMode::Paste,
+                ResolutionReason::TextTooLong {
+                    chars: 50,
+                    limit: 10
+                }
+            )
+        );
+
+        ctx.foreground_app = Some("c:\\apps\\editor.exe");
+        assert_eq!(
+            resolve_output_mode(OutputMode::Clipboard, &ctx),
+            (OutputMode::Clipboard, ResolutionReason::Requested)
+        );
+    }
+
+    #[derive(Default)]
+    struct RecordingClipboard {
+        writes: Vec<String>,
+    }
+
+    impl ClipboardWrite for RecordingClipboard {
+        fn write_text(&mut self, text: &str) -> Result<(), CommandError> {
+            self.writes.push(text.to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_skip_empty_restore_leaves_clipboard_untouched() {
+        let mut clipboard = RecordingClipboard::default();
+        assert!(!restore_previous_clipboard(&mut clipboard, "", true));
+        assert!(clipboard.writes.is_empty());
+    }
+
+    #[test]
+    fn test_restore_writes_previous_content() {
+        let mut clipboard = RecordingClipboard::default();
+        assert!(restore_previous_clipboard(&mut clipboard, "previous", true));
+        assert!(restore_previous_clipboard(&mut clipboard, "", false));
+        assert_eq!(clipboard.writes, vec!["previous".to_string(), String::new()]);
+    }
+
+    struct FailingClipboard;
+
+    impl ClipboardWrite for FailingClipboard {
+        fn write_text(&mut self, _text: &str) -> Result<(), CommandError> {
+            Err(CommandError::clipboard(arboard::Error::ClipboardOccupied))
+        }
+    }
+
+    #[test]
+    fn test_failed_restore_is_not_an_error() {
+        assert!(!restore_previous_clipboard(&mut FailingClipboard, "previous", true));
+    }
+
+    #[test]
+    fn test_paste_clipboard_plan_follows_restore_setting() {
+        let options = |restore, friendly| OutputOptions {
+            restore_clipboard_after_paste: restore,
+            clipboard_manager_friendly: friendly,
+            ..Default::default()
+        };
+        let plan = |restore, conceal| PasteClipboardPlan { restore, conceal };
+
+        assert_eq!(PasteClipboardPlan::new(&OutputOptions::default()), plan(true, false));
+        assert_eq!(PasteClipboardPlan::new(&options(true, true)), plan(true, true));
+        // Kept text is a normal copy, so clipboard managers see it.
+        assert_eq!(PasteClipboardPlan::new(&options(false, true)), plan(false, false));
+        assert_eq!(PasteClipboardPlan::new(&options(false, false)), plan(false, false));
+    }
+
+    #[test]
+    fn test_paste_sequences_follow_restore_setting() {
+        let output = |text: &str, mode, restore| {
+            let options = OutputOptions {
+                restore_clipboard_after_paste: restore,
+                ..Default::default()
+            };
+            let mut backend = MockOutputBackend::with_clipboard("old");
+            output_text_with_mode_impl(&mut backend, text, mode, &options).unwrap();
+            backend
+        };
+        let set = |s: &str| BackendCall::SetClipboard(s.to_string());
+        let text = |s: &str| BackendCall::Text(s.to_string());
+
+        // With restore off the previous clipboard is never read or written back.
+        let backend = output("hello", OutputMode::Paste, false);
+        assert_eq!(backend.actions(), [set("hello"), BackendCall::Paste]);
+        assert_eq!(backend.clipboard, "hello");
+
+        // The same goes for CJK runs pasted inside keystroke output.
+        let typed_then_pasted = [text("h"), text("i"), text(" "), set("日本"), BackendCall::Paste];
+        let backend = output("hi 日本", OutputMode::KeystrokesVerified, false);
+        assert_eq!(backend.actions(), typed_then_pasted);
+        assert_eq!(backend.clipboard, "日本");
+
+        let backend = output("hi 日本", OutputMode::KeystrokesVerified, true);
+        assert_eq!(backend.actions()[0], BackendCall::GetClipboard);
+        assert_eq!(
+            backend.actions().last(),
+            Some(&BackendCall::RestoreClipboard("old".to_string()))
+        );
+        assert_eq!(backend.clipboard, "old");
+    }
+
+    #[test]
+    fn test_restore_after_paste_is_shared_and_safe() {
+        let restore = |safe| {
+            let options = OutputOptions {
+                safe_clipboard_restore: safe,
+                ..Default::default()
+            };
+            let mut backend = MockOutputBackend::with_clipboard("old");
+            let capture = capture_before_paste(&mut backend, &options);
+            // The user copies something else before the restore.
+            backend.clipboard = "copied since".to_string();
+            restore_after_paste(&mut backend, &capture, "日本", &options);
+            backend.clipboard
+        };
+        assert_eq!(restore(true), "copied since");
+        assert_eq!(restore(false), "old");
+
+        // Nothing is captured when the pasted text stays on the clipboard.
+        let options = OutputOptions {
+            restore_clipboard_after_paste: false,
+            ..Default::default()
+        };
+        let mut backend = MockOutputBackend::with_clipboard("old");
+        let capture = capture_before_paste(&mut backend, &options);
+        restore_after_paste(&mut backend, &capture, "日本", &options);
+        assert!(backend.calls.is_empty());
+    }
+
+    #[test]
+    fn test_confirmation_phrases() {
+        let done = |status, mode: OutputMode| {
+            confirmation_phrase(&Ok(OutputResult {
+                status,
+                chars: 5,
+                mode: mode.to_str(),
+                timing: None,
+            }))
+        };
+        assert_eq!(done(OutputStatus::Success, OutputMode::Paste), Some("pasted"));
+        assert_eq!(done(OutputStatus::Fallback, OutputMode::Clipboard), Some("copied"));
+        assert_eq!(done(OutputStatus::Success, OutputMode::KeystrokesVerified), Some("typed"));
+        assert_eq!(done(OutputStatus::Cancelled, OutputMode::Paste), None);
+        assert_eq!(done(OutputStatus::Paused, OutputMode::Paste), None);
+        assert_eq!(done(OutputStatus::Empty, OutputMode::Paste), None);
+        assert_eq!(
+            confirmation_phrase(&Err(CommandError::internal("boom"))),
+            Some("output failed")
+        );
+    }
+
+    #[test]
+    fn test_modifier_wait_polls_until_released() {
+        use crate::modifier_state::KeyActivity;
+
+        let activity = |modifiers: Vec<Key>, other_keys| Some(KeyActivity { modifiers, other_keys });
+        let wait = ModifierWait::default();
+
+        // Ctrl released after three polls: returns early, having slept only 30ms.
+        let mut samples = vec![
+            activity(vec![Key::Control], false),
+            activity(vec![Key::Control], false),
+            activity(vec![Key::Control], false),
+            activity(vec![], false),
+        ]
+        .into_iter();
+        let mut slept = Duration::ZERO;
+        let outcome = wait_for_modifier_release(wait, || samples.next().unwrap(), |d| slept += d);
+        assert_eq!(outcome, ModifierWaitOutcome::Released);
+        assert_eq!(slept, Duration::from_millis(30));
+
+        // Held the whole time: gives up after the max wait.
+        let mut slept = Duration::ZERO;
+        let outcome = wait_for_modifier_release(wait, || activity(vec![Key::Alt], false), |d| slept += d);
+        assert_eq!(outcome, ModifierWaitOutcome::TimedOut);
+        assert_eq!(slept, Duration::from_millis(DEFAULT_MODIFIER_RELEASE_WAIT_MS));
+
+        // Typing aborts, unless abort is off.
+        let typing = || activity(vec![Key::Shift], true);
+        assert_eq!(
+            wait_for_modifier_release(wait, typing, |_| {}),
+            ModifierWaitOutcome::UserTyping
+        );
+        let no_abort = ModifierWait {
+            abort_on_user_input: false,
+            ..wait
+        };
+        assert_eq!(
+            wait_for_modifier_release(no_abort, typing, |_| {}),
+            ModifierWaitOutcome::TimedOut
+        );
+
+        // No key state: a single fixed sleep.
+        let mut sleeps = Vec::new();
+        let outcome = wait_for_modifier_release(wait, || None, |d| sleeps.push(d));
+        assert_eq!(outcome, ModifierWaitOutcome::Unsupported);
+        assert_eq!(sleeps, vec![Duration::from_millis(DEFAULT_MODIFIER_RELEASE_WAIT_MS)]);
+    }
+
+    #[test]
+    fn test_release_stuck_modifiers() {
+        let mut released = Vec::new();
+        assert!(release_stuck(None, |key| released.push(key)).is_empty());
+        assert!(release_stuck(Some(Vec::new()), |key| released.push(key)).is_empty());
+        assert!(released.is_empty());
+
+        let stuck = release_stuck(Some(vec![Key::Shift]), |key| released.push(key));
+        assert_eq!(stuck, vec![Key::Shift]);
+        assert_eq!(released, vec![Key::Shift]);
+    }
+
+    #[test]
+    fn test_chunk_pause_jitter_is_bounded_and_seeded() {
+        let fixed = KeystrokeTuning::default();
+        let mut rng = JitterRng::new(7);
+        assert_eq!(chunk_pause_ms(fixed, &mut rng), fixed.chunk_delay_ms);
+
+        let jittered = KeystrokeTuning {
+            chunk_delay_ms: 10,
+            jitter_ms: 4,
+            ..fixed
+        };
+        let pauses = |seed| {
+            let mut rng = JitterRng::new(seed);
+            (0..200).map(|_| chunk_pause_ms(jittered, &mut rng)).collect::<Vec<_>>()
+        };
+        let a = pauses(42);
+        assert_eq!(a, pauses(42));
+        assert_ne!(a, pauses(43));
+        assert!(a.iter().all(|&ms| (6..=14).contains(&ms)));
+        assert!(a.iter().any(|&ms| ms < 10) && a.iter().any(|&ms| ms > 10));
+
+        // Jitter larger than the base delay clamps at zero instead of underflowing.
+        let wide = KeystrokeTuning {
+            chunk_delay_ms: 2,
+            jitter_ms: 5,
+            ..fixed
+        };
+        let mut rng = JitterRng::new(1);
+        assert!((0..200).all(|_| chunk_pause_ms(wide, &mut rng) <= 7));
+    }
+
+    #[test]
+    fn test_keystroke_tuning_recommendation() {
+        let fast = recommend_keystroke_tuning(Duration::from_micros(20));
+        let typical = recommend_keystroke_tuning(Duration::from_micros(300));
+        let slow = recommend_keystroke_tuning(Duration::from_millis(5));
+        assert_eq!(typical, KeystrokeTuning::default());
+        assert!(fast.chunk_chars > typical.chunk_chars);
+        assert!(fast.chunk_delay_ms < typical.chunk_delay_ms);
+        assert!(slow.chunk_chars < typical.chunk_chars);
+        assert!(slow.chunk_delay_ms > typical.chunk_delay_ms);
+    }
+
+    #[test]
+    fn test_copied_selection_treats_empty_as_none() {
+        assert_eq!(copied_selection(Some("old draft".to_string())).as_deref(), Some("old draft"));
+        assert_eq!(copied_selection(Some(String::new())), None);
+        assert_eq!(copied_selection(None), None);
+    }
+
+    #[test]
+    fn test_restore_skipped_when_clipboard_replaced() {
+        assert!(clipboard_still_holds(Some("dictated"), "dictated"));
+        assert!(!clipboard_still_holds(Some("copied by user"), "dictated"));
+        assert!(!clipboard_still_holds(None, "dictated"));
+    }
+
+    #[test]
+    fn test_oversized_clipboard_is_not_captured_for_restore() {
+        assert_eq!(capture_for_restore("abcd".to_string(), 4), Some("abcd".to_string()));
+        assert_eq!(capture_for_restore("abcde".to_string(), 4), None);
+        // The cap is in bytes, not chars.
+        assert_eq!(capture_for_restore("éé".to_string(), 3), None);
+    }
+
+    #[test]
+    fn test_output_result_serialization() {
+        let cases = [
+            (OutputStatus::Success, "success"),
+            (OutputStatus::Fallback, "fallback"),
+            (OutputStatus::Empty, "empty"),
+            (OutputStatus::Paused, "paused"),
+            (OutputStatus::Cancelled, "cancelled"),
+        ];
+        for (status, name) in cases {
+            let result = OutputResult {
+                status,
+                chars: 12,
+                mode: OutputMode::KeystrokesVerified.to_str(),
+                timing: None,
+            };
+            assert_eq!(
+                serde_json::to_value(result).unwrap(),
+                serde_json::json!({
+                    "status": name,
+                    "chars": 12,
+                    "mode": "keystrokes_verified",
+                })
+            );
+        }
+
+        let timed = OutputResult {
+            status: OutputStatus::Success,
+            chars: 3,
+            mode: OutputMode::Paste.to_str(),
+            timing: Some(OutputTiming {
+                clipboard_set_ms: 1,
+                stabilization_ms: 2,
+                key_sequence_ms: 3,
+                restore_ms: 4,
+                total_ms: 10,
+            }),
+        };
+        assert_eq!(
+            serde_json::to_value(timed).unwrap()["timing"]["total_ms"],
+            serde_json::json!(10)
+        );
+    }
+
+    #[test]
+    fn test_empty_text_short_circuits_without_output() {
+        let options = OutputOptions::default();
+        for text in ["", "   ", "\n\t "] {
+            for mode in OutputMode::ALL {
+                let result = output_text_with_mode(text, mode, &options).unwrap();
+                assert_eq!(result.status, OutputStatus::Empty);
+                assert_eq!(result.chars, 0);
+                assert_eq!(result.mode, mode.to_str());
+            }
+        }
+    }
+
+    #[test]
+    fn test_list_output_modes_covers_every_mode() {
+        let modes = list_output_modes();
+        assert_eq!(modes.len(), OutputMode::ALL.len());
+        for (info, mode) in modes.iter().zip(OutputMode::ALL) {
+            assert_eq!(OutputMode::parse(info.id), Some(mode));
+            assert!(!info.label.is_empty());
+            assert!(!info.description.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_output_mode_parse_rejects_unknown_values() {
+        for mode in OutputMode::ALL {
+            assert_eq!(OutputMode::parse(mode.to_str()), Some(mode));
+        }
+        assert_eq!(OutputMode::parse("bogus"), None);
+        assert_eq!(OutputMode::parse("auto_paste"), None);
+        assert_eq!(OutputMode::parse("Paste"), None);
+    }
+
+    #[test]
+    fn test_output_mode_from_str_legacy_values_fall_back_to_paste() {
+        assert_eq!(OutputMode::from_str("keystrokes"), OutputMode::Paste);
+        assert_eq!(OutputMode::from_str("auto_paste"), OutputMode::Paste);
+        assert_eq!(OutputMode::from_str("bogus"), OutputMode::Paste);
+    }
+
+    #[test]
+    fn test_tokenize_key_tokens() {
+        assert_eq!(
+            tokenize_key_tokens("name{tab}value{ENTER}"),
+            vec![
+                Output::Text("name".to_string()),
+                Output::Key(Key::Tab),
+                Output::Text("value".to_string()),
+                Output::Key(Key::Return),
+            ]
+        );
+        assert_eq!(
+            tokenize_key_tokens("{esc}{esc}"),
+            vec![Output::Key(Key::Escape), Output::Key(Key::Escape)]
+        );
+        assert_eq!(tokenize_key_tokens(""), vec![]);
+    }
+
+    #[test]
+    fn test_tokenize_unknown_tokens_are_literal() {
+        assert_eq!(
+            tokenize_key_tokens("a {smile} b"),
+            vec![Output::Text("a {smile} b".to_string())]
+        );
+        assert_eq!(
+            tokenize_key_tokens("open { brace {tab}"),
+            vec![
+                Output::Text("open { brace ".to_string()),
+                Output::Key(Key::Tab),
+            ]
+        );
+        assert_eq!(
+            tokenize_key_tokens("dangling {tab"),
+            vec![Output::Text("dangling {tab".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_output_concurrency_reject_fails_while_locked() {
+        let lock = Mutex::new(());
+        let held = acquire_output_lock(&lock, OutputConcurrency::Queue).unwrap();
+
+        assert!(matches!(
+            acquire_output_lock(&lock, OutputConcurrency::Reject),
+            Err(CommandError::OutputBusy(_))
+        ));
+
+        drop(held);
+        assert!(acquire_output_lock(&lock, OutputConcurrency::Reject).is_ok());
+    }
+
+    #[test]
+    fn test_only_pre_input_failures_are_retried() {
+        let locked = CommandError::clipboard("locked");
+        assert!(retry_is_safe(OutputMode::Paste, &locked));
+        assert!(retry_is_safe(OutputMode::ClipboardAppend, &locked));
+        // Typing may have started before the clipboard was needed.
+        assert!(!retry_is_safe(OutputMode::TypeThenPaste, &locked));
+        assert!(!retry_is_safe(OutputMode::KeystrokesVerified, &locked));
+        // Some keys may already have gone through.
+        assert!(!retry_is_safe(OutputMode::Paste, &CommandError::input("enigo")));
+        assert!(!retry_is_safe(OutputMode::Paste, &CommandError::internal("x")));
+    }
+
+    #[test]
+    fn test_output_concurrency_from_str() {
+        assert_eq!(OutputConcurrency::from_str("reject"), OutputConcurrency::Reject);
+        assert_eq!(OutputConcurrency::from_str("queue"), OutputConcurrency::Queue);
+        assert_eq!(OutputConcurrency::from_str("bogus"), OutputConcurrency::Queue);
+    }
+
+    #[test]
+    fn test_self_test_hints() {
+        let hint = self_test_hint("clipboard", &CommandError::clipboard("locked")).unwrap();
+        assert!(hint.contains("clipboard"));
+
+        let hint = self_test_hint("enigo", &CommandError::input("no display")).unwrap();
+        assert!(hint.starts_with("enigo could not be created"));
+
+        let hint = self_test_hint("output", &CommandError::input("rejected")).unwrap();
+        assert!(hint.contains("Key events were rejected"));
+
+        assert!(self_test_hint("output", &CommandError::internal("x")).is_none());
+    }
+
+    #[test]
+    fn test_self_test_report_tracks_failures() {
+        let mut report = OutputSelfTestReport {
+            mode: "paste".to_string(),
+            marker: SELF_TEST_MARKER.to_string(),
+            steps: Vec::new(),
+            success: true,
+        };
+
+        assert!(report.record("clipboard", Ok(())));
+        assert!(report.success);
+
+        assert!(!report.record("enigo", Err(CommandError::input("denied"))));
+        report.skip("output", "keyboard simulation is unavailable");
+
+        assert!(!report.success);
+        assert_eq!(report.steps.len(), 3);
+        assert!(report.steps[1].hint.is_some());
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn test_input_permission_always_granted_off_macos() {
+        assert!(ensure_input_permission(&mut SystemBackend::default()).is_ok());
+        assert_eq!(check_accessibility_permission(), PermissionStatus::Granted);
+    }
+
+    #[test]
+    fn test_permission_denied_has_hint() {
+        let err = CommandError::PermissionDenied("x".to_string());
+        assert!(self_test_hint("enigo", &err).unwrap().contains("Accessibility"));
+    }
+
+    #[test]
+    fn test_complex_script_detection() {
+        for c in ['你', 'の', 'カ', '한', 'ㄱ', '。', '，', 'ก', 'क', '𠀀'] {
+            assert!(is_complex_script_char(c), "{:?}", c);
+        }
+        for c in ['a', 'Z', '9', ' ', '.', 'é', 'ß', 'Ж', 'ا', '😀'] {
+            assert!(!is_complex_script_char(c), "{:?}", c);
+        }
+    }
+
+    #[test]
+    fn test_split_script_segments() {
+        use ScriptSegment::{Keys, Paste};
+
+        assert_eq!(split_script_segments("hello world"), vec![Keys("hello world")]);
+        assert_eq!(split_script_segments("你好 世界"), vec![Paste("你好 世界")]);
+        assert_eq!(
+            split_script_segments("Open 東京駅 map, then 검색."),
+            vec![
+                Keys("Open "),
+                Paste("東京駅 "),
+                Keys("map, then "),
+                Paste("검색"),
+                Keys("."),
+            ]
+        );
+        assert_eq!(split_script_segments("  日本"), vec![Paste("  日本")]);
+        assert!(split_script_segments("").is_empty());
+    }
+
+    #[test]
+    fn test_complex_script_output_from_str() {
+        assert_eq!(ComplexScriptOutput::from_str("paste_all"), ComplexScriptOutput::PasteAll);
+        assert_eq!(ComplexScriptOutput::from_str("keystrokes"), ComplexScriptOutput::Keystrokes);
+        assert_eq!(ComplexScriptOutput::from_str("hybrid"), ComplexScriptOutput::Hybrid);
+        assert_eq!(ComplexScriptOutput::from_str("bogus"), ComplexScriptOutput::Hybrid);
+    }
+
+    #[test]
+    fn test_clipboard_append_join() {
+        assert_eq!(join_clipboard_append("", "new", "\n"), "new");
+        assert_eq!(join_clipboard_append("one", "two", "\n"), "one\ntwo");
+        assert_eq!(join_clipboard_append("one", "two", " | "), "one | two");
+    }
+
+    #[test]
+    fn test_last_clipboard_write_is_recorded() {
+        let _lock = CLIPBOARD_WRITE_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        record_clipboard_write(OutputMode::Clipboard, "one".to_string());
+        record_clipboard_write(OutputMode::ClipboardAppend, "one\ntwo".to_string());
+
+        // Only the latest write is kept.
+        let last = get_last_clipboard_write().unwrap();
+        assert_eq!(last.mode, "clipboard_append");
+        assert_eq!(last.text, "one\ntwo");
+        assert_eq!(last.chars, 7);
+    }
+
+    #[test]
+    fn test_clipboard_modes_through_mock_backend() {
+        let _lock = CLIPBOARD_WRITE_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut backend = MockOutputBackend::with_clipboard("one");
+        append_to_clipboard_with(&mut backend, "two", " | ").unwrap();
+        copy_to_clipboard_with(&mut backend, "three").unwrap();
+        assert_eq!(
+            backend.calls,
+            [
+                BackendCall::GetClipboard,
+                BackendCall::SetClipboard("one | two".to_string()),
+                BackendCall::SetClipboard("three".to_string()),
+            ]
+        );
+        assert_eq!(backend.clipboard, "three");
+        let last = get_last_clipboard_write().unwrap();
+        assert_eq!((last.mode, last.text.as_str()), ("clipboard", "three"));
+    }
+
+    #[test]
+    fn test_keystroke_sequences_through_mock_backend() {
+        let mut backend = MockOutputBackend::default();
+        let tuning = KeystrokeTuning {
+            chunk_chars: 3,
+            chunk_delay_ms: 10,
+            jitter_ms: 0,
+        };
+        send_text_chunked(&mut backend, "abcdéfg", true, tuning, NewlineKey::Enter).unwrap();
+        assert_eq!(
+            backend.calls,
+            [
+                BackendCall::Text("abc".to_string()),
+                BackendCall::Sleep(Duration::from_millis(10)),
+                BackendCall::Text("déf".to_string()),
+                BackendCall::Sleep(Duration::from_millis(10)),
+                BackendCall::Text("g".to_string()),
+                BackendCall::Sleep(Duration::from_millis(10)),
+            ]
+        );
+
+        let mut backend = MockOutputBackend::default();
+        send_key_combo(&mut backend, &[Key::Control, Key::Shift], 'v').unwrap();
+        maybe_hit_enter(&mut backend, false, 80).unwrap();
+        maybe_hit_enter(&mut backend, true, 80).unwrap();
+        assert_eq!(
+            backend.actions(),
+            [
+                BackendCall::Key(Key::Control, Direction::Press),
+                BackendCall::Key(Key::Shift, Direction::Press),
+                BackendCall::Key(Key::Unicode('v'), Direction::Click),
+                BackendCall::Key(Key::Shift, Direction::Release),
+                BackendCall::Key(Key::Control, Direction::Release),
+                BackendCall::Key(Key::Return, Direction::Click),
+            ]
+        );
+        // Enter waits for the configured delay first.
+        assert_eq!(
+            backend.calls[backend.calls.len() - 2],
+            BackendCall::Sleep(Duration::from_millis(80))
+        );
+    }
+
+    #[test]
+    fn test_output_modes_through_mock_backend() {
+        let output = |text: &str, mode, options: &OutputOptions| {
+            let mut backend = MockOutputBackend::with_clipboard("old");
+            let result = output_text_with_mode_impl(&mut backend, text, mode, options).unwrap();
+            (result, backend)
+        };
+        let paste_sequence = [
+            BackendCall::GetClipboard,
+            BackendCall::SetClipboard("hello".to_string()),
+            BackendCall::Paste,
+            BackendCall::GetClipboard,
+            BackendCall::RestoreClipboard("old".to_string()),
+        ];
+
+        let (result, backend) = output("hello", OutputMode::Paste, &OutputOptions::default());
+        assert_eq!(result.status, OutputStatus::Success);
+        assert_eq!(backend.actions(), paste_sequence);
+        assert_eq!(backend.clipboard, "old");
+
+        // Too long to type: resolved to paste, reported as a fallback.
+        let options = OutputOptions {
+            max_keystroke_chars: 3,
+            ..Default::default()
+        };
+        let (result, backend) = output("hello", OutputMode::KeystrokesVerified, &options);
+        assert_eq!(result.status, OutputStatus::Fallback);
+        assert_eq!(result.mode, "paste");
+        assert_eq!(backend.actions(), paste_sequence);
+
+        let options = OutputOptions {
+            newline_key: NewlineKey::ShiftEnter,
+            hit_enter: true,
+            ..Default::default()
+        };
+        let (result, backend) = output("hi\nyo", OutputMode::KeystrokesVerified, &options);
+        assert_eq!(result.status, OutputStatus::Success);
+        let text = |s: &str| BackendCall::Text(s.to_string());
+        assert_eq!(
+            backend.actions(),
+            [
+                text("h"),
+                text("i"),
+                BackendCall::Key(Key::Shift, Direction::Press),
+                BackendCall::Key(Key::Return, Direction::Click),
+                BackendCall::Key(Key::Shift, Direction::Release),
+                text("y"),
+                text("o"),
+                BackendCall::Key(Key::Return, Direction::Click),
+            ]
+        );
+        assert_eq!(backend.clipboard, "old");
+    }
+
+    #[test]
+    fn test_system_checks_come_from_the_backend() {
+        let _lock = CLIPBOARD_WRITE_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let options = OutputOptions::default();
+        let output = |backend: &mut MockOutputBackend, mode| {
+            output_text_with_mode_impl(backend, "hello", mode, &options)
+        };
+
+        let mut paused = MockOutputBackend {
+            paused: true,
+            ..Default::default()
+        };
+        let result = output(&mut paused, OutputMode::Paste).unwrap();
+        assert_eq!(result.status, OutputStatus::Paused);
+        assert!(paused.calls.is_empty());
+
+        // The one-shot clipboard-only target copies instead, and is consumed.
+        let mut clipboard_only = MockOutputBackend {
+            clipboard_only: true,
+            ..Default::default()
+        };
+        let result = output(&mut clipboard_only, OutputMode::KeystrokesVerified).unwrap();
+        assert_eq!(result.status, OutputStatus::Fallback);
+        assert_eq!(result.mode, "clipboard");
+        assert_eq!(
+            clipboard_only.actions(),
+            [BackendCall::SetClipboard("hello".to_string())]
+        );
+        assert!(!clipboard_only.clipboard_only);
+
+        // Password fields are typed into, never pasted.
+        let mut secure = MockOutputBackend {
+            secure_field: true,
+            ..Default::default()
+        };
+        let result = output(&mut secure, OutputMode::Paste).unwrap();
+        assert_eq!(result.mode, "keystrokes_verified");
+        assert!(!secure.actions().contains(&BackendCall::Paste));
+
+        let mut denied = MockOutputBackend {
+            input_denied: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            output(&mut denied, OutputMode::Paste),
+            Err(CommandError::PermissionDenied(_))
+        ));
+        assert!(denied.actions().is_empty());
+        let result = output(&mut denied, OutputMode::Clipboard).unwrap();
+        assert_eq!(result.status, OutputStatus::Success);
+    }
+
+    #[test]
+    fn test_newlines_press_the_configured_key() {
+        let typed = |newline_key| {
+            let mut backend = MockOutputBackend::default();
+            let tuning = KeystrokeTuning::default();
+            send_text_chunked(&mut backend, "hi\nthere\r\n", true, tuning, newline_key).unwrap();
+            backend.actions()
+        };
+        let text = |s: &str| BackendCall::Text(s.to_string());
+        let enter = BackendCall::Key(Key::Return, Direction::Click);
+
+        assert_eq!(
+            typed(NewlineKey::Enter),
+            [text("hi"), enter.clone(), text("there"), enter.clone()]
+        );
+        let shift_enter = [
+            BackendCall::Key(Key::Shift, Direction::Press),
+            enter.clone(),
+            BackendCall::Key(Key::Shift, Direction::Release),
+        ];
+        assert_eq!(
+            typed(NewlineKey::ShiftEnter),
+            [&[text("hi")][..], &shift_enter, &[text("there")], &shift_enter].concat()
+        );
+
+        // Per-app overrides win over the global mapping.
+        let slack = "c:\\apps\\slack.exe";
+        let overrides = HashMap::from([(slack.to_string(), NewlineKey::ShiftEnter)]);
+        let for_app = |app| newline_key_for_app(NewlineKey::Enter, &overrides, app);
+        assert_eq!(for_app(Some(slack)), NewlineKey::ShiftEnter);
+        assert_eq!(for_app(Some("c:\\apps\\editor.exe")), NewlineKey::Enter);
+        assert_eq!(for_app(None), NewlineKey::Enter);
+        assert_eq!(NewlineKey::from_str("shift_enter"), NewlineKey::ShiftEnter);
+        assert_eq!(NewlineKey::parse("ctrl_enter"), None);
+    }
+
+    #[test]
+    fn test_type_and_select_counts_crlf_as_one_char() {
+        let mut backend = MockOutputBackend::default();
+        type_and_select(&mut backend, "ab\r\ncd\n", &OutputOptions::default()).unwrap();
+        let left_clicks = backend
+            .actions()
+            .iter()
+            .filter(|call| **call == BackendCall::Key(Key::LeftArrow, Direction::Click))
+            .count();
+        assert_eq!(left_clicks, 6);
+    }
+
+    #[test]
+    fn test_clipboard_roundtrip_comparison() {
+        let sample = default_roundtrip_sample();
+        let intact = compare_roundtrip(&sample, &sample);
+        assert!(intact.matched);
+        assert_eq!(intact.first_mismatch, None);
+
+        let truncated: String = sample.chars().take(1000).collect();
+        let report = compare_roundtrip(&sample, &truncated);
+        assert!(!report.matched);
+        assert_eq!(report.first_mismatch, Some(1000));
+        assert_eq!(report.read_chars, 1000);
+        assert_eq!(report.actual_excerpt.as_deref().map(|e| e.chars().count()), Some(24));
+
+        let report = compare_roundtrip("a\nb\n", "a\r\nb\r\n");
+        assert_eq!(report.first_mismatch, Some(1));
+        assert!(report.newlines_translated);
+        assert!(!compare_roundtrip("café", "caf?").newlines_translated);
+    }
+
+    #[test]
+    fn test_file_output_contents_and_path_validation() {
+        assert_eq!(file_output_contents("one\ntwo", LineEnding::CrLf), "one\r\ntwo\r\n");
+        assert_eq!(file_output_contents("one\r\ntwo\n\n", LineEnding::Lf), "one\ntwo\n");
+        assert_eq!(file_output_contents("café 日本", LineEnding::Lf), "café 日本\n");
+        assert_eq!(LineEnding::from_str("crlf"), LineEnding::CrLf);
+        assert_eq!(LineEnding::from_str("lf"), LineEnding::Lf);
+
+        let root = std::env::temp_dir()
+            .join(format!("tangerine-file-output-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("allowed").join("sub")).unwrap();
+        std::fs::create_dir_all(root.join("other")).unwrap();
+        // Compare against resolved paths (the temp dir is itself a symlink on macOS).
+        let root = root.canonicalize().unwrap();
+        let allowed = root.join("allowed");
+        let validate = |path: &str| validate_output_file_path(path, &allowed);
+
+        assert!(validate("").is_err());
+        assert!(validate("sub").is_err());
+        assert!(validate("no-such-dir/notes.txt").is_err());
+        assert_eq!(validate("notes.txt").unwrap(), allowed.join("notes.txt"));
+        assert_eq!(validate("sub/notes.txt").unwrap(), allowed.join("sub/notes.txt"));
+        let absolute = allowed.join("notes.txt").display().to_string();
+        assert!(validate(&absolute).is_ok());
+
+        // Nothing outside the chosen folder, however it's spelled.
+        assert!(validate(&root.join("other/notes.txt").display().to_string()).is_err());
+        assert!(validate("../other/notes.txt").is_err());
+        assert!(validate_output_file_path("notes.txt", &root.join("missing")).is_err());
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(root.join("other"), allowed.join("link")).unwrap();
+            assert!(validate("link/notes.txt").is_err());
+        }
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_pending_output_answers() {
+        let (reply, answer) = mpsc::channel();
+        pending_outputs().insert(
+            "test-confirm".to_string(),
+            PendingOutput {
+                text: "hello".to_string(),
+                reply,
+            },
+        );
+        let answer_with = |token: &str, confirmed| {
+            tauri::async_runtime::block_on(async {
+                if confirmed {
+                    confirm_output(token.to_string()).await
+                } else {
+                    cancel_output(token.to_string()).await
+                }
+            })
+        };
+        answer_with("test-confirm", true).unwrap();
+        assert_eq!(answer.try_recv(), Ok(true));
+        // Each output is answered once.
+        assert!(answer_with("test-confirm", false).is_err());
+        assert!(answer_with("no-such-token", true).is_err());
+    }
 }
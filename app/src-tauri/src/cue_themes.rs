@@ -0,0 +1,378 @@
+//! User cue themes: folders of WAV files that replace the built-in cue sounds.
+//!
+//! `install_cue_theme` copies a folder's `start.wav`, `stop.wav` and (optionally)
+//! `error.wav` into `cue-themes/<name>/` in the app data directory, after checking that
+//! each file decodes. The active theme (`active_cue_theme`) is decoded into memory once and
+//! played instead of the built-in sounds it has files for.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Directory (in the app data dir) installed themes are copied into.
+pub const THEMES_DIR: &str = "cue-themes";
+
+/// Sounds a theme can provide, as `(name, required)`. Files are `<name>.wav`.
+pub const THEME_SOUNDS: [(&str, bool); 3] = [("start", true), ("stop", true), ("error", false)];
+
+/// Longest accepted cue, so a misplaced song can't play on every recording.
+const MAX_CUE_DURATION: Duration = Duration::from_secs(5);
+
+/// Longest theme name.
+const MAX_THEME_NAME_CHARS: usize = 64;
+
+/// A decoded cue sound, ready to hand to rodio.
+#[derive(Debug, Clone)]
+pub struct CueClip {
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub samples: Arc<[f32]>,
+}
+
+impl CueClip {
+    pub fn duration(&self) -> Duration {
+        let frames = self.samples.len() / usize::from(self.channels.max(1));
+        Duration::from_secs_f64(frames as f64 / f64::from(self.sample_rate.max(1)))
+    }
+}
+
+/// An installed theme, from `list_cue_themes` / `install_cue_theme`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CueThemeInfo {
+    pub name: String,
+    /// Sound names the theme provides ("start", "stop" and maybe "error").
+    pub sounds: Vec<String>,
+    pub active: bool,
+}
+
+/// Whether an installed theme folder still loads, from `check_installed`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ThemeCheck {
+    pub name: String,
+    /// Why the theme can't be played (a missing or undecodable file, or a bad folder
+    /// name); `None` when it's healthy.
+    pub error: Option<String>,
+    /// A required sound file doesn't exist at all, as opposed to one that's unreadable or
+    /// doesn't decode.
+    pub missing_required: bool,
+}
+
+/// Decode a WAV file (integer or float PCM) into normalized samples.
+pub fn decode_wav(bytes: &[u8]) -> Result<CueClip, String> {
+    let mut reader =
+        hound::WavReader::new(Cursor::new(bytes)).map_err(|e| format!("not a WAV file ({})", e))?;
+    let spec = reader.spec();
+    if spec.channels == 0 || spec.sample_rate == 0 {
+        return Err("WAV header has no channels or sample rate".to_string());
+    }
+
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>(),
+        hound::SampleFormat::Int => {
+            let scale = 1.0 / (1_i64 << (spec.bits_per_sample.clamp(1, 32) - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|sample| sample.map(|sample| sample as f32 * scale))
+                .collect::<Result<_, _>>()
+        }
+    }
+    .map_err(|e| format!("WAV data is corrupt ({})", e))?;
+
+    let clip = CueClip {
+        channels: spec.channels,
+        sample_rate: spec.sample_rate,
+        samples: samples.into(),
+    };
+    if clip.samples.is_empty() {
+        return Err("WAV file has no audio".to_string());
+    }
+    if clip.duration() > MAX_CUE_DURATION {
+        return Err(format!(
+            "cue is {:.1}s long; the limit is {}s",
+            clip.duration().as_secs_f32(),
+            MAX_CUE_DURATION.as_secs()
+        ));
+    }
+    Ok(clip)
+}
+
+/// Trimmed theme name, or an error if it's empty, too long, or could escape the themes
+/// directory. Letters, digits, spaces, `-` and `_` only.
+pub fn validate_theme_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Theme name is empty".to_string());
+    }
+    if name.chars().count() > MAX_THEME_NAME_CHARS {
+        return Err(format!(
+            "Theme name is longer than {} characters",
+            MAX_THEME_NAME_CHARS
+        ));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+    {
+        return Err(format!(
+            "Theme name '{}' may only contain letters, digits, spaces, '-' and '_'",
+            name
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// Read and decode every theme sound present in `dir`. Missing optional sounds are skipped;
+/// a missing required sound or a file that doesn't decode is an error naming the file.
+fn read_theme_files(dir: &Path) -> Result<Vec<(&'static str, Vec<u8>, CueClip)>, String> {
+    let mut files = Vec::new();
+    for (sound, required) in THEME_SOUNDS {
+        let path = dir.join(format!("{}.wav", sound));
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && !required => continue,
+            Err(e) => return Err(format!("{}.wav: {}", sound, e)),
+        };
+        let clip = decode_wav(&bytes).map_err(|e| format!("{}.wav: {}", sound, e))?;
+        files.push((sound, bytes, clip));
+    }
+    Ok(files)
+}
+
+/// Validate the theme folder `source` and copy its sounds to `themes_dir/<name>`, replacing
+/// an installed theme of the same name. `name` defaults to the folder's name.
+pub fn install(
+    themes_dir: &Path,
+    source: &Path,
+    name: Option<&str>,
+) -> Result<CueThemeInfo, String> {
+    if !source.is_dir() {
+        return Err(format!("{} is not a folder", source.display()));
+    }
+    let name = match name {
+        Some(name) => name.to_string(),
+        None => source
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+    };
+    let name = validate_theme_name(&name)?;
+
+    // Decode everything before touching the installed copy.
+    let files = read_theme_files(source)?;
+
+    let dest = themes_dir.join(&name);
+    if dest.exists() {
+        fs::remove_dir_all(&dest).map_err(|e| format!("Failed to replace theme: {}", e))?;
+    }
+    fs::create_dir_all(&dest).map_err(|e| format!("Failed to create theme folder: {}", e))?;
+    for (sound, bytes, _) in &files {
+        fs::write(dest.join(format!("{}.wav", sound)), bytes)
+            .map_err(|e| format!("Failed to copy {}.wav: {}", sound, e))?;
+    }
+
+    Ok(CueThemeInfo {
+        name,
+        sounds: files.iter().map(|(sound, _, _)| sound.to_string()).collect(),
+        active: false,
+    })
+}
+
+/// Installed themes, sorted by name. Folders missing a required sound are skipped.
+pub fn list(themes_dir: &Path, active: Option<&str>) -> Vec<CueThemeInfo> {
+    let Ok(entries) = fs::read_dir(themes_dir) else {
+        return Vec::new();
+    };
+    let mut themes: Vec<CueThemeInfo> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let dir = entry.path();
+            let sounds: Vec<String> = THEME_SOUNDS
+                .iter()
+                .filter(|(sound, _)| dir.join(format!("{}.wav", sound)).is_file())
+                .map(|(sound, _)| sound.to_string())
+                .collect();
+            let complete = THEME_SOUNDS
+                .iter()
+                .all(|(sound, required)| !required || sounds.iter().any(|s| s == sound));
+            complete.then(|| CueThemeInfo {
+                active: active == Some(name.as_str()),
+                name,
+                sounds,
+            })
+        })
+        .collect();
+    themes.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    themes
+}
+
+/// Every folder in `themes_dir`, sorted by name, with whether its sounds still decode.
+/// Unlike `list`, broken folders are included.
+pub fn check_installed(themes_dir: &Path) -> Vec<ThemeCheck> {
+    let Ok(entries) = fs::read_dir(themes_dir) else {
+        return Vec::new();
+    };
+    let mut checks: Vec<ThemeCheck> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let error = validate_theme_name(&name)
+                .and_then(|_| read_theme_files(&entry.path()))
+                .err();
+            let missing_required = THEME_SOUNDS.iter().any(|(sound, required)| {
+                *required
+                    && fs::metadata(entry.path().join(format!("{}.wav", sound)))
+                        .is_err_and(|e| e.kind() == std::io::ErrorKind::NotFound)
+            });
+            ThemeCheck {
+                name,
+                error,
+                missing_required,
+            }
+        })
+        .collect();
+    checks.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    checks
+}
+
+/// Delete the theme folders missing a required sound file. Folders whose files exist but
+/// don't decode (or have a bad name) are left alone: they may hold the user's only copy.
+/// Returns the names removed.
+pub fn remove_incomplete(themes_dir: &Path) -> Result<Vec<String>, String> {
+    let mut removed = Vec::new();
+    for check in check_installed(themes_dir) {
+        // Only folders with a name we could have installed; anything else isn't ours.
+        let valid_name = validate_theme_name(&check.name).is_ok_and(|name| name == check.name);
+        if !check.missing_required || !valid_name {
+            continue;
+        }
+        fs::remove_dir_all(themes_dir.join(&check.name))
+            .map_err(|e| format!("Failed to remove theme '{}': {}", check.name, e))?;
+        removed.push(check.name);
+    }
+    Ok(removed)
+}
+
+/// Decode an installed theme's sounds, keyed by sound name.
+pub fn load(themes_dir: &Path, name: &str) -> Result<HashMap<&'static str, CueClip>, String> {
+    let name = validate_theme_name(name)?;
+    let dir = themes_dir.join(&name);
+    if !dir.is_dir() {
+        return Err(format!("Cue theme '{}' is not installed", name));
+    }
+    Ok(read_theme_files(&dir)?
+        .into_iter()
+        .map(|(sound, _, clip)| (sound, clip))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn temp_dir() -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("tangerine-cue-theme-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn wav(frames: usize) -> Vec<u8> {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 8_000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut cursor = Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut cursor, spec).unwrap();
+        for i in 0..frames {
+            let sample: i16 = if i % 2 == 0 { 16_384 } else { -16_384 };
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+        cursor.into_inner()
+    }
+
+    #[test]
+    fn test_decode_wav_validates_content() {
+        let clip = decode_wav(&wav(4_000)).unwrap();
+        assert_eq!(clip.duration(), Duration::from_millis(500));
+        assert_eq!(clip.samples[0], 0.5);
+
+        assert!(decode_wav(b"ID3 not a wav").is_err());
+        assert!(decode_wav(&wav(0)).is_err());
+        assert!(decode_wav(&wav(8_000 * 6)).unwrap_err().contains("limit"));
+    }
+
+    #[test]
+    fn test_install_list_and_load_theme() {
+        let root = temp_dir();
+        let themes_dir = root.join(THEMES_DIR);
+        let source = root.join("My Pack");
+        fs::create_dir_all(&source).unwrap();
+        fs::write(source.join("start.wav"), wav(800)).unwrap();
+
+        // stop.wav is required.
+        assert!(install(&themes_dir, &source, None).unwrap_err().contains("stop.wav"));
+
+        fs::write(source.join("stop.wav"), wav(800)).unwrap();
+        fs::write(source.join("error.wav"), b"garbage").unwrap();
+        assert!(install(&themes_dir, &source, None).unwrap_err().contains("error.wav"));
+
+        fs::remove_file(source.join("error.wav")).unwrap();
+        let info = install(&themes_dir, &source, None).unwrap();
+        assert_eq!(info.name, "My Pack");
+        assert_eq!(info.sounds, ["start", "stop"]);
+
+        assert!(install(&themes_dir, &source, Some("../escape")).is_err());
+
+        let themes = list(&themes_dir, Some("My Pack"));
+        assert_eq!(themes.len(), 1);
+        assert!(themes[0].active);
+
+        let clips = load(&themes_dir, "My Pack").unwrap();
+        assert!(clips.contains_key("start") && clips.contains_key("stop"));
+        assert!(load(&themes_dir, "Missing").is_err());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_check_and_remove_broken_themes() {
+        let root = temp_dir();
+        let themes_dir = root.join(THEMES_DIR);
+        for name in ["Good", "Gone", "Garbled"] {
+            let dir = themes_dir.join(name);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("start.wav"), wav(800)).unwrap();
+            fs::write(dir.join("stop.wav"), wav(800)).unwrap();
+        }
+        fs::remove_file(themes_dir.join("Gone").join("stop.wav")).unwrap();
+        fs::write(themes_dir.join("Garbled").join("start.wav"), b"garbage").unwrap();
+
+        let checks = check_installed(&themes_dir);
+        let names: Vec<&str> = checks.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Garbled", "Gone", "Good"]);
+        assert!(checks[0].error.as_deref().unwrap().contains("start.wav"));
+        assert!(!checks[0].missing_required);
+        assert!(checks[1].error.as_deref().unwrap().contains("stop.wav"));
+        assert!(checks[1].missing_required);
+        assert_eq!(checks[2].error, None);
+
+        // Only the folder missing a file goes; the undecodable one is kept for the user.
+        assert_eq!(remove_incomplete(&themes_dir).unwrap(), ["Gone"]);
+        assert_eq!(list(&themes_dir, None).len(), 1);
+        let names: Vec<String> = check_installed(&themes_dir).into_iter().map(|c| c.name).collect();
+        assert_eq!(names, ["Garbled", "Good"]);
+        assert!(themes_dir.join("Garbled").join("start.wav").is_file());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,130 @@
+//! Insert text into the focused field without the clipboard (`direct_insert`).
+//!
+//! On macOS the Accessibility API can replace the focused element's selected text
+//! (`AXSelectedText`), which inserts at the caret and leaves the clipboard untouched. Many
+//! native text fields support this; web views and custom editors often don't, so callers
+//! treat any error as "unsupported here" and fall back to paste. Other platforms have no
+//! equivalent and always return an error.
+
+/// Insert `text` at the caret of the focused text element, replacing any selection.
+pub fn insert_text(text: &str) -> Result<(), String> {
+    platform::insert_text(text)
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::ffi::c_void;
+    use std::ptr;
+
+    type CFTypeRef = *const c_void;
+    type CFStringRef = *const c_void;
+    type AXUIElementRef = *const c_void;
+    type AXError = i32;
+
+    const AX_ERROR_SUCCESS: AXError = 0;
+    const CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXUIElementCreateSystemWide() -> AXUIElementRef;
+        fn AXUIElementCopyAttributeValue(
+            element: AXUIElementRef,
+            attribute: CFStringRef,
+            value: *mut CFTypeRef,
+        ) -> AXError;
+        fn AXUIElementIsAttributeSettable(
+            element: AXUIElementRef,
+            attribute: CFStringRef,
+            settable: *mut u8,
+        ) -> AXError;
+        fn AXUIElementSetAttributeValue(
+            element: AXUIElementRef,
+            attribute: CFStringRef,
+            value: CFTypeRef,
+        ) -> AXError;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFStringCreateWithBytes(
+            allocator: *const c_void,
+            bytes: *const u8,
+            num_bytes: isize,
+            encoding: u32,
+            is_external_representation: u8,
+        ) -> CFStringRef;
+        fn CFRelease(cf: CFTypeRef);
+    }
+
+    /// An owned CoreFoundation reference, released on drop.
+    struct Owned(CFTypeRef);
+
+    impl Owned {
+        fn new(value: CFTypeRef) -> Option<Self> {
+            (!value.is_null()).then_some(Self(value))
+        }
+
+        fn string(s: &str) -> Option<Self> {
+            // SAFETY: `s` is valid UTF-8 of the given length; a null allocator means default.
+            Self::new(unsafe {
+                CFStringCreateWithBytes(
+                    ptr::null(),
+                    s.as_ptr(),
+                    s.len() as isize,
+                    CF_STRING_ENCODING_UTF8,
+                    0,
+                )
+            })
+        }
+    }
+
+    impl Drop for Owned {
+        fn drop(&mut self) {
+            // SAFETY: we hold the only reference obtained from a Create/Copy call.
+            unsafe { CFRelease(self.0) }
+        }
+    }
+
+    pub fn insert_text(text: &str) -> Result<(), String> {
+        let focused_attr = Owned::string("AXFocusedUIElement").ok_or("CFString failed")?;
+        let selected_attr = Owned::string("AXSelectedText").ok_or("CFString failed")?;
+        let value = Owned::string(text).ok_or("CFString failed")?;
+
+        // SAFETY: all references are valid for the duration of the calls, and every
+        // Create/Copy result is wrapped in `Owned` so it's released exactly once.
+        unsafe {
+            let system = Owned::new(AXUIElementCreateSystemWide())
+                .ok_or("AXUIElementCreateSystemWide returned null")?;
+
+            let mut focused: CFTypeRef = ptr::null();
+            let err = AXUIElementCopyAttributeValue(system.0, focused_attr.0, &mut focused);
+            let focused = match Owned::new(focused) {
+                Some(focused) if err == AX_ERROR_SUCCESS => focused,
+                _ => return Err(format!("no focused element (AXError {})", err)),
+            };
+
+            let mut settable: u8 = 0;
+            let err = AXUIElementIsAttributeSettable(focused.0, selected_attr.0, &mut settable);
+            if err != AX_ERROR_SUCCESS || settable == 0 {
+                return Err(format!(
+                    "focused element doesn't support setting selected text (AXError {})",
+                    err
+                ));
+            }
+
+            let err = AXUIElementSetAttributeValue(focused.0, selected_attr.0, value.0);
+            if err != AX_ERROR_SUCCESS {
+                return Err(format!("setting selected text failed (AXError {})", err));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    pub fn insert_text(_text: &str) -> Result<(), String> {
+        Err("direct insert is only available on macOS".to_string())
+    }
+}
@@ -0,0 +1,111 @@
+//! Best-effort capture and restore of the OS foreground window.
+//!
+//! Used to put focus back on the window the user was dictating into before output is sent,
+//! for setups where the hotkey briefly pulls focus elsewhere. Every platform does this
+//! differently and window managers are free to refuse, so all calls are best-effort.
+
+/// Opaque handle to a previously focused window (HWND on Windows, process id on macOS,
+/// X11 window id on Linux).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ForegroundWindow(i64);
+
+/// Remember the window that currently has keyboard focus, if the platform can tell us.
+pub fn capture_foreground_window() -> Option<ForegroundWindow> {
+    platform::capture().map(ForegroundWindow)
+}
+
+/// Bring a previously captured window back to the foreground.
+///
+/// Returns false if the window no longer exists or the OS refused the request.
+pub fn focus_previous_window(target: ForegroundWindow) -> bool {
+    platform::focus(target.0)
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetForegroundWindow, IsWindow, SetForegroundWindow,
+    };
+
+    pub fn capture() -> Option<i64> {
+        let hwnd = unsafe { GetForegroundWindow() };
+        (!hwnd.is_invalid()).then_some(hwnd.0 as isize as i64)
+    }
+
+    pub fn focus(raw: i64) -> bool {
+        let hwnd = HWND(raw as isize as *mut core::ffi::c_void);
+        unsafe { IsWindow(Some(hwnd)).as_bool() && SetForegroundWindow(hwnd).as_bool() }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::process::Command;
+
+    /// macOS activates applications, not windows, so we track the frontmost process.
+    pub fn capture() -> Option<i64> {
+        let output = Command::new("osascript")
+            .args([
+                "-e",
+                "tell application \"System Events\" to get unix id of first process whose frontmost is true",
+            ])
+            .output()
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+
+        let pid: i64 = String::from_utf8_lossy(&output.stdout).trim().parse().ok()?;
+        // Don't "restore" focus to ourselves.
+        (pid != std::process::id() as i64).then_some(pid)
+    }
+
+    pub fn focus(pid: i64) -> bool {
+        let script = format!(
+            "tell application \"System Events\" to set frontmost of first process whose unix id is {} to true",
+            pid
+        );
+        Command::new("osascript")
+            .args(["-e", &script])
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::process::Command;
+
+    /// X11 only (via xdotool); Wayland compositors don't let clients change focus.
+    pub fn capture() -> Option<i64> {
+        let output = Command::new("xdotool").arg("getactivewindow").output().ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+
+        String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+    }
+
+    pub fn focus(window_id: i64) -> bool {
+        Command::new("xdotool")
+            .args(["windowactivate", "--sync", &window_id.to_string()])
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+mod platform {
+    pub fn capture() -> Option<i64> {
+        None
+    }
+
+    pub fn focus(_raw: i64) -> bool {
+        false
+    }
+}
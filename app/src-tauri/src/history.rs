@@ -1,10 +1,39 @@
+use crate::redaction::Redactor;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
-use std::sync::RwLock;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, RwLock, Weak};
+use std::thread;
+use std::time::Duration;
 use uuid::Uuid;
 
+/// Which entries go first when history exceeds `max_saved_recordings` (`trim_strategy`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrimStrategy {
+    /// Drop the oldest entries, pinned or not.
+    Oldest,
+    /// Drop the oldest unpinned entries; pinned entries are always kept.
+    #[default]
+    OldestUnpinned,
+    /// Drop the longest unpinned entries (oldest first among equal lengths), keeping short
+    /// notes around longer than one-off long dictations.
+    LongestUnpinned,
+}
+
+impl TrimStrategy {
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "oldest" => TrimStrategy::Oldest,
+            "longest_unpinned" => TrimStrategy::LongestUnpinned,
+            _ => TrimStrategy::OldestUnpinned,
+        }
+    }
+}
+
 /// Status of a transcription attempt in history.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -26,6 +55,11 @@ impl Default for HistoryStatus {
 pub struct HistoryEntry {
     pub id: String,
     pub timestamp: DateTime<Utc>,
+    /// Insertion sequence number, increasing with every entry added. Breaks ties between
+    /// equal timestamps (see `newest_first`). Entries saved before this field existed are
+    /// numbered on load in their stored order.
+    #[serde(default)]
+    pub seq: u64,
     pub text: String,
     #[serde(default)]
     pub status: HistoryStatus,
@@ -43,15 +77,61 @@ pub struct HistoryEntry {
     /// LLM model used for rewriting (if enabled).
     #[serde(default)]
     pub llm_model: Option<String>,
+    /// Pinned entries are exempt from age-based pruning.
+    #[serde(default)]
+    pub pinned: bool,
+    /// Server-reported details about the transcription, when available.
+    ///
+    /// Entries written before this field existed deserialize as `None`.
+    #[serde(default)]
+    pub metadata: Option<TranscriptionMetadata>,
+    /// Caller-supplied key used to collapse retried `add_history_entry` calls.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+    /// Dictation session this entry belongs to: entries added within the session gap of the
+    /// previous one share it (see `get_sessions`). `None` for entries written before
+    /// sessions existed.
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Executable of the app that was in the foreground when the dictation finished
+    /// (Windows only for now).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_app: Option<String>,
+}
+
+/// Consecutive entries grouped into one dictation session (`get_history_sessions`).
+#[derive(Debug, Clone, Serialize)]
+pub struct HistorySession {
+    pub id: String,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    /// Newest first, like `get_all`.
+    pub entries: Vec<HistoryEntry>,
+}
+
+/// Per-transcription metadata reported by the transcription server.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptionMetadata {
+    /// Length of the transcribed audio in seconds.
+    #[serde(default)]
+    pub duration_secs: Option<f64>,
+    /// Provider confidence in [0, 1], if reported.
+    #[serde(default)]
+    pub confidence: Option<f64>,
+    /// Model that produced the transcript, as reported by the server.
+    #[serde(default)]
+    pub model: Option<String>,
 }
 
-/// Metadata about which models were used for a transcription request.
+/// Metadata about which models were used for a transcription request, and where it was
+/// dictated.
 #[derive(Debug, Clone, Default)]
 pub struct RequestModelInfo {
     pub stt_provider: Option<String>,
     pub stt_model: Option<String>,
     pub llm_provider: Option<String>,
     pub llm_model: Option<String>,
+    pub source_app: Option<String>,
 }
 
 impl HistoryEntry {
@@ -59,6 +139,7 @@ impl HistoryEntry {
         Self {
             id: Uuid::new_v4().to_string(),
             timestamp: Utc::now(),
+            seq: 0,
             text,
             status: HistoryStatus::Success,
             error_message: None,
@@ -66,6 +147,11 @@ impl HistoryEntry {
             stt_model: None,
             llm_provider: None,
             llm_model: None,
+            pinned: false,
+            metadata: None,
+            idempotency_key: None,
+            session_id: None,
+            source_app: None,
         }
     }
 
@@ -73,6 +159,7 @@ impl HistoryEntry {
         Self {
             id,
             timestamp: Utc::now(),
+            seq: 0,
             text: String::new(),
             status: HistoryStatus::InProgress,
             error_message: None,
@@ -80,25 +167,360 @@ impl HistoryEntry {
             stt_model: model_info.stt_model,
             llm_provider: model_info.llm_provider,
             llm_model: model_info.llm_model,
+            pinned: false,
+            metadata: None,
+            idempotency_key: None,
+            session_id: None,
+            source_app: model_info.source_app,
+        }
+    }
+}
+
+/// Default and maximum page size for `query`.
+pub const DEFAULT_QUERY_PAGE_SIZE: usize = 50;
+pub const MAX_QUERY_PAGE_SIZE: usize = 500;
+
+/// Filter for `query_history`. Every field is optional; set fields must all match.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct HistoryFilter {
+    /// Typo-tolerant text match, as in `fuzzy_search_history`.
+    pub query: Option<String>,
+    /// Inclusive bounds on the entry timestamp.
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Executable name ("chrome", "chrome.exe") or full path, case-insensitive.
+    pub source_app: Option<String>,
+    /// Page start within the matching entries (newest first).
+    pub offset: usize,
+    /// Page size; defaults to 50, capped at 500.
+    pub limit: Option<usize>,
+}
+
+impl HistoryFilter {
+    fn matches(&self, entry: &HistoryEntry) -> bool {
+        let query = self.query.as_deref().map(str::trim).filter(|q| !q.is_empty());
+        self.from.is_none_or(|from| entry.timestamp >= from)
+            && self.to.is_none_or(|to| entry.timestamp <= to)
+            && self.source_app.as_deref().is_none_or(|wanted| {
+                entry.source_app.as_deref().is_some_and(|app| {
+                    app.eq_ignore_ascii_case(wanted)
+                        || crate::app_window::matches_app_name(app, wanted)
+                })
+            })
+            && query.is_none_or(|query| fuzzy_score(query, &entry.text).is_some())
+    }
+}
+
+/// One page of `query_history` results.
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryPage {
+    /// Newest first.
+    pub entries: Vec<HistoryEntry>,
+    /// Number of entries matching the filter, across all pages.
+    pub total: usize,
+    pub offset: usize,
+}
+
+/// Default gap after which a new entry starts a new session (`history_session_gap_minutes`).
+pub const DEFAULT_SESSION_GAP_MINUTES: u64 = 10;
+
+/// Session ID for an entry added at `at`: the newest entry's session when it was added
+/// within `gap`, otherwise a fresh one. Legacy entries without a session lend their own ID.
+fn session_for_new_entry(
+    newest: Option<&HistoryEntry>,
+    at: DateTime<Utc>,
+    gap: chrono::Duration,
+) -> String {
+    match newest {
+        Some(newest) if at - newest.timestamp <= gap => newest
+            .session_id
+            .clone()
+            .unwrap_or_else(|| newest.id.clone()),
+        _ => Uuid::new_v4().to_string(),
+    }
+}
+
+/// Whether `older` belongs to the same session as the `newer` entry right after it. Entries
+/// without a session ID fall back to the time-gap rule.
+fn same_session(newer: &HistoryEntry, older: &HistoryEntry, gap: chrono::Duration) -> bool {
+    match (&newer.session_id, &older.session_id) {
+        (Some(a), Some(b)) => a == b,
+        _ => newer.timestamp - older.timestamp <= gap,
+    }
+}
+
+/// Group newest-first entries into newest-first sessions.
+fn group_into_sessions(entries: Vec<HistoryEntry>, gap: chrono::Duration) -> Vec<HistorySession> {
+    let mut sessions: Vec<HistorySession> = Vec::new();
+    for entry in entries {
+        if let Some(session) = sessions.last_mut() {
+            let newer = session.entries.last().expect("sessions are never empty");
+            if same_session(newer, &entry, gap) {
+                session.started_at = session.started_at.min(entry.timestamp);
+                session.ended_at = session.ended_at.max(entry.timestamp);
+                session.entries.push(entry);
+                continue;
+            }
         }
+        sessions.push(HistorySession {
+            id: entry.session_id.clone().unwrap_or_else(|| entry.id.clone()),
+            started_at: entry.timestamp,
+            ended_at: entry.timestamp,
+            entries: vec![entry],
+        });
     }
+    sessions
+}
+
+/// The order history is kept and returned in: newest `timestamp` first, equal timestamps
+/// by descending `seq` (most recently added first). Total for distinct `seq`s, so pages of
+/// `get_history`/`query_history` never skip or repeat entries.
+pub fn newest_first(a: &HistoryEntry, b: &HistoryEntry) -> std::cmp::Ordering {
+    b.timestamp.cmp(&a.timestamp).then(b.seq.cmp(&a.seq))
 }
 
 /// Storage for dictation history entries
 #[derive(Debug, Serialize, Deserialize, Default)]
 struct HistoryData {
+    /// Always sorted by `newest_first`.
     entries: Vec<HistoryEntry>,
+    /// Search index over `entries`; rebuilt on load, kept in sync by every mutation.
+    #[serde(skip)]
+    index: SearchIndex,
+    /// `seq` for the next added entry; one past the highest stored `seq`.
+    #[serde(skip)]
+    next_seq: u64,
 }
 
-/// Manages loading and saving of dictation history
-pub struct HistoryStorage {
+impl HistoryData {
+    /// Number any entries without a `seq` (saved before it existed) oldest-first in their
+    /// stored order after the existing numbers, sort, and set `next_seq`.
+    fn normalize_order(&mut self) {
+        let mut next = self.entries.iter().map(|e| e.seq).max().map_or(1, |max| max + 1);
+        for entry in self.entries.iter_mut().rev().filter(|e| e.seq == 0) {
+            entry.seq = next;
+            next += 1;
+        }
+        self.entries.sort_by(newest_first);
+        self.next_seq = next;
+    }
+
+    /// Give `entry` the next `seq` and insert it (and index it) in `newest_first` order.
+    /// Normally that's the front; a clock that went backwards puts it further down.
+    fn insert(&mut self, entry: &mut HistoryEntry) {
+        entry.seq = self.next_seq.max(1);
+        self.next_seq = entry.seq + 1;
+        let at = self
+            .entries
+            .partition_point(|e| newest_first(e, entry) == std::cmp::Ordering::Less);
+        self.entries.insert(at, entry.clone());
+        self.index.insert(entry);
+    }
+}
+
+/// A change to stored history, delivered to the listener set with
+/// `HistoryStorage::set_change_listener` (emitted to the frontend as `history-changed`).
+///
+/// Added/updated changes carry the entry itself so the UI can patch its list without
+/// refetching the whole history.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HistoryChange {
+    Added { entry: HistoryEntry },
+    Updated { entry: HistoryEntry },
+    Deleted { ids: Vec<String> },
+    Cleared,
+}
+
+type ChangeListener = Box<dyn Fn(&HistoryChange) + Send + Sync>;
+
+/// How long an idempotency key passed to `add_entry_with_metadata` stays valid.
+const IDEMPOTENCY_WINDOW: chrono::Duration = chrono::Duration::seconds(60);
+
+/// How long to coalesce history changes before writing them to disk.
+///
+/// Rapid-fire dictation can produce several updates per second (in-progress entry,
+/// completion, retention pruning). Batching them keeps disk churn bounded.
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// State shared between `HistoryStorage` and its background flush thread.
+struct HistoryShared {
     data: RwLock<HistoryData>,
     file_path: PathBuf,
+    /// Serializes writers so concurrent saves can't clobber the shared temp file.
+    save_lock: Mutex<()>,
+    /// Whether the file currently at `file_path` is known to be valid.
+    ///
+    /// We only rotate the primary file into the backup slot when it's known-good, so a
+    /// corrupt primary can never overwrite the last good backup.
+    primary_is_good: AtomicBool,
+    /// Set when in-memory state has changes that haven't been written yet.
+    dirty: Mutex<bool>,
+    /// Wakes the flush thread when `dirty` becomes true.
+    dirty_signal: Condvar,
+    /// Write single-line JSON instead of pretty-printed. Set by `compact()`, and on load
+    /// when the file on disk is already compact, so compaction sticks across saves.
+    compact_json: AtomicBool,
+    /// How long the flush thread waits for more changes before writing (`SAVE_DEBOUNCE`).
+    save_debounce: Duration,
+}
+
+impl HistoryShared {
+    /// Write pending changes to disk, if any.
+    fn flush(&self) -> Result<(), String> {
+        let _save_guard = self
+            .save_lock
+            .lock()
+            .map_err(|e| format!("Failed to lock history file: {}", e))?;
+
+        {
+            let mut dirty = self
+                .dirty
+                .lock()
+                .map_err(|e| format!("Failed to lock history state: {}", e))?;
+            if !*dirty {
+                return Ok(());
+            }
+            *dirty = false;
+        }
+
+        let result = self.write_to_disk();
+        if result.is_err() {
+            // Keep the changes pending so the next flush retries.
+            if let Ok(mut dirty) = self.dirty.lock() {
+                *dirty = true;
+            }
+        }
+        result
+    }
+
+    /// Write current history to disk. Caller must hold `save_lock`.
+    fn write_to_disk(&self) -> Result<(), String> {
+        let content = {
+            let data = self
+                .data
+                .read()
+                .map_err(|e| format!("Failed to read history: {}", e))?;
+
+            if self.compact_json.load(Ordering::SeqCst) {
+                serde_json::to_string(&*data)
+            } else {
+                serde_json::to_string_pretty(&*data)
+            }
+            .map_err(|e| format!("Failed to serialize history: {}", e))?
+        };
+
+        // Write the new content to a temp file and flush it to disk first, so a crash
+        // mid-write can only ever leave a partial *temp* file behind.
+        let temp_path = HistoryStorage::temp_path_for(&self.file_path);
+        {
+            let mut file = fs::File::create(&temp_path)
+                .map_err(|e| format!("Failed to create temp history file: {}", e))?;
+            file.write_all(content.as_bytes())
+                .map_err(|e| format!("Failed to write temp history file: {}", e))?;
+            file.sync_all()
+                .map_err(|e| format!("Failed to sync temp history file: {}", e))?;
+        }
+
+        // Keep one backup of the last good state.
+        if self.primary_is_good.load(Ordering::SeqCst) && self.file_path.exists() {
+            let backup_path = HistoryStorage::backup_path_for(&self.file_path);
+            if let Err(e) = fs::copy(&self.file_path, &backup_path) {
+                log::warn!("Failed to back up history file: {}", e);
+            }
+        }
+
+        fs::rename(&temp_path, &self.file_path)
+            .map_err(|e| format!("Failed to replace history file: {}", e))?;
+        self.primary_is_good.store(true, Ordering::SeqCst);
+
+        Ok(())
+    }
+}
+
+/// Background loop that writes pending history changes at most once per `save_debounce`.
+///
+/// Holds only a weak reference so the thread exits once the storage is dropped.
+fn run_flush_loop(shared: Weak<HistoryShared>) {
+    loop {
+        let Some(strong) = shared.upgrade() else {
+            return;
+        };
+
+        let is_dirty = match strong.dirty.lock() {
+            Ok(guard) => {
+                if *guard {
+                    true
+                } else {
+                    // Wake periodically so we notice when the storage has been dropped.
+                    match strong.dirty_signal.wait_timeout(guard, Duration::from_secs(1)) {
+                        Ok((guard, _)) => *guard,
+                        Err(_) => return,
+                    }
+                }
+            }
+            Err(_) => return,
+        };
+        let debounce = strong.save_debounce;
+        drop(strong);
+
+        if !is_dirty {
+            continue;
+        }
+
+        // Let further changes accumulate before writing.
+        thread::sleep(debounce);
+
+        let Some(strong) = shared.upgrade() else {
+            return;
+        };
+        if let Err(e) = strong.flush() {
+            log::warn!("Failed to persist history: {}", e);
+        }
+    }
+}
+
+/// Size of the history file on disk, from `HistoryStorage::storage_info`.
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryStorageInfo {
+    pub entry_count: usize,
+    /// Size of `history.json` (0 if it hasn't been written yet).
+    pub file_bytes: u64,
+    /// Size of the backup and any leftover temp file.
+    pub backup_bytes: u64,
+    /// `file_bytes / entry_count`, rounded down (0 when empty).
+    pub average_entry_bytes: u64,
+}
+
+/// Manages loading and saving of dictation history
+///
+/// Writes are crash-safe: the JSON is written to a temp file and atomically renamed over
+/// `history.json`. The previous good file is kept as `history.json.bak` and is used as a
+/// fallback if the primary file fails to parse on startup.
+///
+/// Writes are debounced: mutations update the in-memory state immediately (so reads are
+/// always current) and a background thread persists them at most every `SAVE_DEBOUNCE`.
+/// Call `flush()` to force pending changes to disk (e.g. on exit or before export).
+pub struct HistoryStorage {
+    shared: Arc<HistoryShared>,
+    /// Entries older than this many days are dropped on insert. 0 disables age pruning.
+    max_age_days: AtomicU64,
+    /// Minutes between entries after which a new session starts.
+    session_gap_minutes: AtomicU64,
+    trim_strategy: RwLock<TrimStrategy>,
+    change_listener: RwLock<Option<ChangeListener>>,
+    redaction: Redactor,
 }
 
 impl HistoryStorage {
     /// Create a new history storage with the given app data directory
     pub fn new(app_data_dir: PathBuf) -> Self {
+        Self::with_save_debounce(app_data_dir, SAVE_DEBOUNCE)
+    }
+
+    /// `new` with a custom write debounce, so tests can rule out a background write.
+    fn with_save_debounce(app_data_dir: PathBuf, save_debounce: Duration) -> Self {
         let file_path = app_data_dir.join("history.json");
 
         // Ensure the directory exists
@@ -106,58 +528,341 @@ impl HistoryStorage {
             let _ = fs::create_dir_all(parent);
         }
 
-        // Load existing history or use empty
-        let data = Self::load_from_file(&file_path).unwrap_or_default();
+        // Load existing history, falling back to the backup if the primary is missing/corrupt.
+        let (mut data, primary_is_good, compact_json) = match Self::load_with_format(&file_path) {
+            Some((data, compact)) => (data, true, compact),
+            None => {
+                let backup_path = Self::backup_path_for(&file_path);
+                match Self::load_from_file(&backup_path) {
+                    Some(data) => {
+                        if file_path.exists() {
+                            log::warn!(
+                                "History file {} failed to parse; restored from backup",
+                                file_path.display()
+                            );
+                        }
+                        (data, false, false)
+                    }
+                    None => (HistoryData::default(), false, false),
+                }
+            }
+        };
+
+        data.normalize_order();
+        data.index = SearchIndex::build(&data.entries);
 
-        Self {
+        let shared = Arc::new(HistoryShared {
             data: RwLock::new(data),
             file_path,
+            save_lock: Mutex::new(()),
+            primary_is_good: AtomicBool::new(primary_is_good),
+            dirty: Mutex::new(false),
+            dirty_signal: Condvar::new(),
+            compact_json: AtomicBool::new(compact_json),
+            save_debounce,
+        });
+
+        let weak = Arc::downgrade(&shared);
+        if let Err(e) = thread::Builder::new()
+            .name("history-flush".to_string())
+            .spawn(move || run_flush_loop(weak))
+        {
+            log::warn!("Failed to start history flush thread: {}", e);
         }
+
+        Self {
+            shared,
+            max_age_days: AtomicU64::new(0),
+            session_gap_minutes: AtomicU64::new(DEFAULT_SESSION_GAP_MINUTES),
+            trim_strategy: RwLock::new(TrimStrategy::default()),
+            change_listener: RwLock::new(None),
+            redaction: Redactor::default(),
+        }
+    }
+
+    /// Register a callback invoked after every mutation with a description of the change.
+    ///
+    /// Replaces any previous listener. Called outside the data lock, so the listener may
+    /// read history itself.
+    pub fn set_change_listener(&self, listener: impl Fn(&HistoryChange) + Send + Sync + 'static) {
+        if let Ok(mut slot) = self.change_listener.write() {
+            *slot = Some(Box::new(listener));
+        }
+    }
+
+    fn notify(&self, change: HistoryChange) {
+        if let Ok(listener) = self.change_listener.read() {
+            if let Some(listener) = listener.as_ref() {
+                listener(&change);
+            }
+        }
+    }
+
+    /// Notify about removed entries, skipping the event when nothing was removed.
+    fn notify_deleted(&self, ids: Vec<String>) {
+        if !ids.is_empty() {
+            self.notify(HistoryChange::Deleted { ids });
+        }
+    }
+
+    fn backup_path_for(file_path: &Path) -> PathBuf {
+        file_path.with_extension("json.bak")
+    }
+
+    fn temp_path_for(file_path: &Path) -> PathBuf {
+        file_path.with_extension("json.tmp")
     }
 
     /// Load history from the JSON file
-    fn load_from_file(file_path: &PathBuf) -> Option<HistoryData> {
+    fn load_from_file(file_path: &Path) -> Option<HistoryData> {
+        Self::load_with_format(file_path).map(|(data, _)| data)
+    }
+
+    /// Load history, also reporting whether the file is single-line (compacted) JSON.
+    fn load_with_format(file_path: &Path) -> Option<(HistoryData, bool)> {
         let content = fs::read_to_string(file_path).ok()?;
-        serde_json::from_str(&content).ok()
+        let data = serde_json::from_str(&content).ok()?;
+        Some((data, !content.trim_end().contains('\n')))
     }
 
-    /// Save current history to disk
-    fn save(&self) -> Result<(), String> {
-        let data = self
+    fn file_len(path: &Path) -> u64 {
+        fs::metadata(path).map(|meta| meta.len()).unwrap_or(0)
+    }
+
+    /// Mark history as changed so the background thread persists it shortly.
+    fn schedule_save(&self) -> Result<(), String> {
+        let mut dirty = self
+            .shared
+            .dirty
+            .lock()
+            .map_err(|e| format!("Failed to lock history state: {}", e))?;
+        *dirty = true;
+        self.shared.dirty_signal.notify_one();
+        Ok(())
+    }
+
+    /// Immediately write any pending changes to disk.
+    pub fn flush(&self) -> Result<(), String> {
+        self.shared.flush()
+    }
+
+    /// Entry count and on-disk size. Pending changes are flushed first so the sizes match
+    /// what's in memory.
+    pub fn storage_info(&self) -> Result<HistoryStorageInfo, String> {
+        self.flush()?;
+        let entry_count = self
+            .shared
             .data
             .read()
-            .map_err(|e| format!("Failed to read history: {}", e))?;
+            .map_err(|e| format!("Failed to read history: {}", e))?
+            .entries
+            .len();
+        let file_path = &self.shared.file_path;
+        let file_bytes = Self::file_len(file_path);
+        Ok(HistoryStorageInfo {
+            entry_count,
+            file_bytes,
+            backup_bytes: Self::file_len(&Self::backup_path_for(file_path))
+                + Self::file_len(&Self::temp_path_for(file_path)),
+            average_entry_bytes: file_bytes.checked_div(entry_count as u64).unwrap_or(0),
+        })
+    }
+
+    /// Rewrite `history.json` as single-line JSON and delete the backup and any leftover
+    /// temp file. Later saves stay compact; the next save after this one recreates the
+    /// backup from the compacted file.
+    pub fn compact(&self) -> Result<HistoryStorageInfo, String> {
+        {
+            let _save_guard = self
+                .shared
+                .save_lock
+                .lock()
+                .map_err(|e| format!("Failed to lock history file: {}", e))?;
+            self.shared.compact_json.store(true, Ordering::SeqCst);
+            let was_dirty = self
+                .shared
+                .dirty
+                .lock()
+                .map(|mut dirty| std::mem::replace(&mut *dirty, false))
+                .unwrap_or(false);
+            if let Err(e) = self.shared.write_to_disk() {
+                if was_dirty {
+                    if let Ok(mut dirty) = self.shared.dirty.lock() {
+                        *dirty = true;
+                    }
+                }
+                return Err(e);
+            }
 
-        let content = serde_json::to_string_pretty(&*data)
-            .map_err(|e| format!("Failed to serialize history: {}", e))?;
+            let file_path = &self.shared.file_path;
+            for path in [Self::backup_path_for(file_path), Self::temp_path_for(file_path)] {
+                if let Err(e) = fs::remove_file(&path) {
+                    if e.kind() != std::io::ErrorKind::NotFound {
+                        log::warn!("Failed to remove {}: {}", path.display(), e);
+                    }
+                }
+            }
+        }
+        self.storage_info()
+    }
 
-        fs::write(&self.file_path, content)
-            .map_err(|e| format!("Failed to write history file: {}", e))?;
+    /// Set the patterns redacted from entry text before it's stored (see `Redactor::set`).
+    /// Already-stored entries are left as they are.
+    pub fn set_redaction(&self, custom: &[String], builtins: bool) {
+        self.redaction.set(custom, builtins);
+    }
 
-        Ok(())
+    /// The redactor applied to stored text, for other stores that keep transcripts.
+    pub fn redactor(&self) -> Redactor {
+        self.redaction.clone()
+    }
+
+    fn redact(&self, text: String) -> String {
+        self.redaction.apply(text)
     }
 
     /// Add a new entry to the history
     pub fn add_entry(&self, text: String, max_entries: usize) -> Result<HistoryEntry, String> {
-        let entry = HistoryEntry::new(text);
-        {
+        self.add_entry_with_metadata(text, None, None, max_entries)
+    }
+
+    /// Add a new entry to the history, attaching transcription metadata if provided.
+    ///
+    /// If `idempotency_key` matches an entry added within `IDEMPOTENCY_WINDOW`, that entry
+    /// is returned unchanged instead of inserting a near-duplicate (e.g. a retried output).
+    pub fn add_entry_with_metadata(
+        &self,
+        text: String,
+        metadata: Option<TranscriptionMetadata>,
+        idempotency_key: Option<String>,
+        max_entries: usize,
+    ) -> Result<HistoryEntry, String> {
+        let mut entry = HistoryEntry::new(self.redact(text));
+        entry.metadata = metadata;
+        entry.idempotency_key = idempotency_key;
+        let removed = {
             let mut data = self
+                .shared
                 .data
                 .write()
                 .map_err(|e| format!("Failed to write history: {}", e))?;
 
-            // Add to the beginning (newest first)
-            data.entries.insert(0, entry.clone());
+            if let Some(key) = entry.idempotency_key.as_deref() {
+                let window_start = entry.timestamp - IDEMPOTENCY_WINDOW;
+                if let Some(existing) = data.entries.iter().find(|e| {
+                    e.idempotency_key.as_deref() == Some(key) && e.timestamp >= window_start
+                }) {
+                    log::debug!("Idempotent history add for key {}; returning {}", key, existing.id);
+                    return Ok(existing.clone());
+                }
+            }
+
+            entry.session_id = Some(session_for_new_entry(
+                data.entries.first(),
+                entry.timestamp,
+                self.session_gap(),
+            ));
+
+            data.insert(&mut entry);
 
             let max = max_entries.max(1);
-            if data.entries.len() > max {
-                data.entries.truncate(max);
-            }
-        }
-        self.save()?;
+            let mut removed = trim_entries(&mut data.entries, max, self.trim_strategy());
+            removed.extend(expire_old_entries(
+                &mut data.entries,
+                self.max_age_days(),
+                Utc::now(),
+            ));
+            data.index.remove_all(&removed);
+            removed
+        };
+        self.schedule_save()?;
+        self.notify(HistoryChange::Added {
+            entry: entry.clone(),
+        });
+        self.notify_deleted(removed);
         Ok(entry)
     }
 
+    /// Set how entries are chosen for removal when history exceeds its size limit.
+    pub fn set_trim_strategy(&self, strategy: TrimStrategy) {
+        if let Ok(mut current) = self.trim_strategy.write() {
+            *current = strategy;
+        }
+    }
+
+    fn trim_strategy(&self) -> TrimStrategy {
+        self.trim_strategy
+            .read()
+            .map(|strategy| *strategy)
+            .unwrap_or_default()
+    }
+
+    /// Set the maximum entry age (in days) enforced by `add_entry` and `prune_expired`.
+    ///
+    /// 0 disables age-based pruning.
+    pub fn set_max_age_days(&self, days: u64) {
+        self.max_age_days.store(days, Ordering::Relaxed);
+    }
+
+    fn max_age_days(&self) -> u64 {
+        self.max_age_days.load(Ordering::Relaxed)
+    }
+
+    /// Set the gap (in minutes) after which a new entry starts a new session.
+    pub fn set_session_gap_minutes(&self, minutes: u64) {
+        self.session_gap_minutes.store(minutes, Ordering::Relaxed);
+    }
+
+    fn session_gap(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.session_gap_minutes.load(Ordering::Relaxed) as i64)
+    }
+
+    /// Remove unpinned entries older than the configured maximum age.
+    ///
+    /// Returns the removed entry IDs (empty when age pruning is disabled).
+    pub fn prune_expired(&self) -> Result<Vec<String>, String> {
+        let removed = {
+            let mut data = self
+                .shared
+                .data
+                .write()
+                .map_err(|e| format!("Failed to write history: {}", e))?;
+            let removed = expire_old_entries(&mut data.entries, self.max_age_days(), Utc::now());
+            data.index.remove_all(&removed);
+            removed
+        };
+
+        if !removed.is_empty() {
+            self.schedule_save()?;
+            self.notify_deleted(removed.clone());
+        }
+
+        Ok(removed)
+    }
+
+    /// Pin or unpin an entry. Returns false if no entry has that ID.
+    pub fn set_pinned(&self, id: &str, pinned: bool) -> Result<bool, String> {
+        let updated = {
+            let mut data = self
+                .shared
+                .data
+                .write()
+                .map_err(|e| format!("Failed to write history: {}", e))?;
+
+            data.entries.iter_mut().find(|e| e.id == id).map(|entry| {
+                entry.pinned = pinned;
+                entry.clone()
+            })
+        };
+
+        let Some(entry) = updated else {
+            return Ok(false);
+        };
+        self.schedule_save()?;
+        self.notify(HistoryChange::Updated { entry });
+        Ok(true)
+    }
+
     /// Add a new in-progress request entry with a predetermined id.
     ///
     /// This is used to show a placeholder in the History view while a transcription
@@ -168,66 +873,83 @@ impl HistoryStorage {
         model_info: RequestModelInfo,
         max_entries: usize,
     ) -> Result<HistoryEntry, String> {
-        let entry = HistoryEntry::new_request_in_progress(request_id, model_info);
-        {
+        let mut entry = HistoryEntry::new_request_in_progress(request_id, model_info);
+        let removed = {
             let mut data = self
+                .shared
                 .data
                 .write()
                 .map_err(|e| format!("Failed to write history: {}", e))?;
 
-            // Add to the beginning (newest first)
-            data.entries.insert(0, entry.clone());
+            entry.session_id = Some(session_for_new_entry(
+                data.entries.first(),
+                entry.timestamp,
+                self.session_gap(),
+            ));
 
-            let max = max_entries.max(1);
-            if data.entries.len() > max {
-                data.entries.truncate(max);
-            }
-        }
-        self.save()?;
+            data.insert(&mut entry);
+
+            let removed =
+                trim_entries(&mut data.entries, max_entries.max(1), self.trim_strategy());
+            data.index.remove_all(&removed);
+            removed
+        };
+        self.schedule_save()?;
+        self.notify(HistoryChange::Added {
+            entry: entry.clone(),
+        });
+        self.notify_deleted(removed);
         Ok(entry)
     }
 
     /// Truncate history to at most `max_entries` entries.
     pub fn trim_to(&self, max_entries: usize) -> Result<(), String> {
-        let max = max_entries.max(1);
-        {
+        let removed = {
             let mut data = self
+                .shared
                 .data
                 .write()
                 .map_err(|e| format!("Failed to write history: {}", e))?;
-            if data.entries.len() > max {
-                data.entries.truncate(max);
-            }
-        }
-        self.save()
+            let removed =
+                trim_entries(&mut data.entries, max_entries.max(1), self.trim_strategy());
+            data.index.remove_all(&removed);
+            removed
+        };
+        self.schedule_save()?;
+        self.notify_deleted(removed);
+        Ok(())
     }
 
-    /// Delete entries older than `cutoff` (strictly earlier than cutoff).
+    /// Delete entries older than `cutoff` (strictly earlier than cutoff). Pinned entries
+    /// are kept regardless of age.
     ///
     /// Returns the list of removed entry IDs (useful for cleaning up recordings).
     pub fn prune_older_than(&self, cutoff: DateTime<Utc>) -> Result<Vec<String>, String> {
         let mut removed: Vec<String> = Vec::new();
         let changed = {
             let mut data = self
+                .shared
                 .data
                 .write()
                 .map_err(|e| format!("Failed to write history: {}", e))?;
 
             let before = data.entries.len();
             data.entries.retain(|entry| {
-                if entry.timestamp < cutoff {
+                if entry.timestamp < cutoff && !entry.pinned {
                     removed.push(entry.id.clone());
                     false
                 } else {
                     true
                 }
             });
+            data.index.remove_all(&removed);
 
             data.entries.len() != before
         };
 
         if changed {
-            self.save()?;
+            self.schedule_save()?;
+            self.notify_deleted(removed.clone());
         }
 
         Ok(removed)
@@ -235,33 +957,53 @@ impl HistoryStorage {
 
     /// Mark an existing request entry as successful and set the final text.
     pub fn complete_request_success(&self, request_id: &str, text: String) -> Result<(), String> {
-        {
+        let text = self.redact(text);
+        let (entry, inserted) = {
             let mut data = self
+                .shared
                 .data
                 .write()
                 .map_err(|e| format!("Failed to write history: {}", e))?;
 
+            let inserted = !data.entries.iter().any(|e| e.id == request_id);
             if let Some(entry) = data.entries.iter_mut().find(|e| e.id == request_id) {
                 entry.text = text;
                 entry.status = HistoryStatus::Success;
                 entry.error_message = None;
             } else {
                 // If we somehow missed creating an in-progress entry, fall back to inserting.
-                data.entries.insert(0, HistoryEntry::new_request_in_progress(request_id.to_string(), RequestModelInfo::default()));
-                if let Some(entry) = data.entries.iter_mut().find(|e| e.id == request_id) {
-                    entry.text = text;
-                    entry.status = HistoryStatus::Success;
-                    entry.error_message = None;
-                }
+                let mut entry = HistoryEntry::new_request_in_progress(
+                    request_id.to_string(),
+                    RequestModelInfo::default(),
+                );
+                entry.text = text;
+                entry.status = HistoryStatus::Success;
+                data.insert(&mut entry);
             }
+
+            let HistoryData { entries, index, .. } = &mut *data;
+            let entry = entries.iter().find(|e| e.id == request_id).cloned();
+            if let Some(entry) = entry.as_ref() {
+                index.insert(entry);
+            }
+            (entry, inserted)
+        };
+        self.schedule_save()?;
+        if let Some(entry) = entry {
+            self.notify(if inserted {
+                HistoryChange::Added { entry }
+            } else {
+                HistoryChange::Updated { entry }
+            });
         }
-        self.save()
+        Ok(())
     }
 
     /// Mark an existing request entry as failed with an error message.
     pub fn complete_request_error(&self, request_id: &str, error_message: String) -> Result<(), String> {
-        {
+        let change = {
             let mut data = self
+                .shared
                 .data
                 .write()
                 .map_err(|e| format!("Failed to write history: {}", e))?;
@@ -270,19 +1012,26 @@ impl HistoryStorage {
                 entry.status = HistoryStatus::Error;
                 entry.error_message = Some(error_message);
                 // Keep text as-is (likely empty). We intentionally do not delete the entry.
+                HistoryChange::Updated {
+                    entry: entry.clone(),
+                }
             } else {
                 let mut entry = HistoryEntry::new_request_in_progress(request_id.to_string(), RequestModelInfo::default());
                 entry.status = HistoryStatus::Error;
                 entry.error_message = Some(error_message);
-                data.entries.insert(0, entry);
+                data.insert(&mut entry);
+                HistoryChange::Added { entry }
             }
-        }
-        self.save()
+        };
+        self.schedule_save()?;
+        self.notify(change);
+        Ok(())
     }
 
-    /// Get all history entries (newest first), optionally limited
+    /// Get all history entries in `newest_first` order, optionally limited
     pub fn get_all(&self, limit: Option<usize>) -> Result<Vec<HistoryEntry>, String> {
         let data = self
+            .shared
             .data
             .read()
             .map_err(|e| format!("Failed to read history: {}", e))?;
@@ -295,35 +1044,1133 @@ impl HistoryStorage {
         Ok(entries)
     }
 
-    /// Delete an entry by ID
-    pub fn delete(&self, id: &str) -> Result<bool, String> {
-        let deleted = {
-            let mut data = self
-                .data
-                .write()
-                .map_err(|e| format!("Failed to write history: {}", e))?;
-
-            let initial_len = data.entries.len();
-            data.entries.retain(|e| e.id != id);
-            data.entries.len() < initial_len
-        };
+    /// Entries matching every set field of `filter` in `newest_first` order, one page at a
+    /// time.
+    pub fn query(&self, filter: &HistoryFilter) -> Result<HistoryPage, String> {
+        let data = self
+            .shared
+            .data
+            .read()
+            .map_err(|e| format!("Failed to read history: {}", e))?;
 
-        if deleted {
-            self.save()?;
+        let limit = filter
+            .limit
+            .unwrap_or(DEFAULT_QUERY_PAGE_SIZE)
+            .clamp(1, MAX_QUERY_PAGE_SIZE);
+        let mut total = 0;
+        let mut entries = Vec::new();
+        for entry in data.entries.iter().filter(|entry| filter.matches(entry)) {
+            if total >= filter.offset && entries.len() < limit {
+                entries.push(entry.clone());
+            }
+            total += 1;
         }
 
-        Ok(deleted)
+        Ok(HistoryPage {
+            entries,
+            total,
+            offset: filter.offset,
+        })
     }
 
-    /// Clear all history
-    pub fn clear(&self) -> Result<(), String> {
-        {
-            let mut data = self
-                .data
+    /// Get entries grouped into sessions (newest first), optionally limited to the newest
+    /// `limit` sessions.
+    pub fn get_sessions(&self, limit: Option<usize>) -> Result<Vec<HistorySession>, String> {
+        let mut sessions = group_into_sessions(self.get_all(None)?, self.session_gap());
+        if let Some(limit) = limit {
+            sessions.truncate(limit);
+        }
+        Ok(sessions)
+    }
+
+    /// Look up a single entry by ID.
+    pub fn get(&self, id: &str) -> Result<Option<HistoryEntry>, String> {
+        let data = self
+            .shared
+            .data
+            .read()
+            .map_err(|e| format!("Failed to read history: {}", e))?;
+
+        Ok(data.entries.iter().find(|e| e.id == id).cloned())
+    }
+
+    /// Rank entries by how well their text fuzzily matches `query` (best first).
+    ///
+    /// Returns at most `limit` results (capped at `MAX_FUZZY_RESULTS`). Equal scores keep
+    /// history order, so newer entries win ties. Uses the in-memory index, so entry text
+    /// isn't re-lowercased/re-tokenized per query and each distinct word is only compared
+    /// against each query word once.
+    pub fn fuzzy_search(&self, query: &str, limit: usize) -> Result<Vec<HistorySearchResult>, String> {
+        let data = self
+            .shared
+            .data
+            .read()
+            .map_err(|e| format!("Failed to read history: {}", e))?;
+
+        let Some(query) = PreparedQuery::new(query) else {
+            return Ok(Vec::new());
+        };
+        let mut word_scores = WordScores::new(&query);
+
+        let mut results: Vec<HistorySearchResult> = data
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let score = match data.index.by_id.get(&entry.id) {
+                    Some(indexed) => query.score(indexed, &mut word_scores),
+                    // Not expected (every mutation updates the index), but never drop a match.
+                    None => fuzzy_score(&query.lower, &entry.text),
+                };
+                score.map(|score| HistorySearchResult {
+                    entry: entry.clone(),
+                    score,
+                })
+            })
+            .collect();
+
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+        results.truncate(limit.clamp(1, MAX_FUZZY_RESULTS));
+        Ok(results)
+    }
+
+    /// Delete an entry by ID
+    pub fn delete(&self, id: &str) -> Result<bool, String> {
+        let deleted = {
+            let mut data = self
+                .shared
+                .data
+                .write()
+                .map_err(|e| format!("Failed to write history: {}", e))?;
+
+            let initial_len = data.entries.len();
+            data.entries.retain(|e| e.id != id);
+            data.index.by_id.remove(id);
+            data.entries.len() < initial_len
+        };
+
+        if deleted {
+            self.schedule_save()?;
+            self.notify_deleted(vec![id.to_string()]);
+        }
+
+        Ok(deleted)
+    }
+
+    /// Delete all entries whose ID is in `ids`.
+    ///
+    /// Unknown IDs are ignored. Persists at most once and returns the number of entries
+    /// actually removed.
+    pub fn delete_many(&self, ids: &[String]) -> Result<usize, String> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let wanted: HashSet<&str> = ids.iter().map(|id| id.as_str()).collect();
+
+        let deleted = {
+            let mut data = self
+                .shared
+                .data
+                .write()
+                .map_err(|e| format!("Failed to write history: {}", e))?;
+
+            let mut removed = Vec::new();
+            data.entries.retain(|e| {
+                if wanted.contains(e.id.as_str()) {
+                    removed.push(e.id.clone());
+                    false
+                } else {
+                    true
+                }
+            });
+            data.index.remove_all(&removed);
+            removed
+        };
+
+        let count = deleted.len();
+        if count > 0 {
+            self.schedule_save()?;
+            self.notify_deleted(deleted);
+        }
+
+        Ok(count)
+    }
+
+    /// Clear all history
+    pub fn clear(&self) -> Result<(), String> {
+        {
+            let mut data = self
+                .shared
+                .data
                 .write()
                 .map_err(|e| format!("Failed to write history: {}", e))?;
             data.entries.clear();
+            data.index.by_id.clear();
+        }
+        self.schedule_save()?;
+        self.notify(HistoryChange::Cleared);
+        Ok(())
+    }
+}
+
+/// Indices (into `entries`, newest first) of the entries to drop so that at most `max`
+/// remain, per `strategy`.
+///
+/// Unpinned strategies never pick pinned entries, so more than `max` can remain if enough
+/// are pinned. `LongestUnpinned` also spares the newest entry, so a long dictation isn't
+/// dropped the moment it's added.
+fn select_entries_to_trim(
+    entries: &[HistoryEntry],
+    max: usize,
+    strategy: TrimStrategy,
+) -> Vec<usize> {
+    let excess = entries.len().saturating_sub(max);
+    if excess == 0 {
+        return Vec::new();
+    }
+
+    match strategy {
+        TrimStrategy::Oldest => (max..entries.len()).collect(),
+        TrimStrategy::OldestUnpinned => (0..entries.len())
+            .rev()
+            .filter(|&i| !entries[i].pinned)
+            .take(excess)
+            .collect(),
+        TrimStrategy::LongestUnpinned => {
+            let mut candidates: Vec<usize> =
+                (1..entries.len()).filter(|&i| !entries[i].pinned).collect();
+            // Stable sort over oldest-first order keeps ties oldest first.
+            candidates.reverse();
+            candidates.sort_by_key(|&i| std::cmp::Reverse(entries[i].text.chars().count()));
+            candidates.truncate(excess);
+            candidates
+        }
+    }
+}
+
+/// Drop entries so that at most `max` remain (see `select_entries_to_trim`), returning the
+/// IDs of the ones dropped.
+fn trim_entries(entries: &mut Vec<HistoryEntry>, max: usize, strategy: TrimStrategy) -> Vec<String> {
+    let drop: HashSet<usize> = select_entries_to_trim(entries, max, strategy)
+        .into_iter()
+        .collect();
+    if drop.is_empty() {
+        return Vec::new();
+    }
+
+    let mut removed = Vec::with_capacity(drop.len());
+    let mut index = 0;
+    entries.retain(|entry| {
+        let keep = !drop.contains(&index);
+        if !keep {
+            removed.push(entry.id.clone());
+        }
+        index += 1;
+        keep
+    });
+    removed
+}
+
+/// Drop unpinned entries older than `max_age_days` relative to `now`.
+///
+/// Returns the removed IDs. `max_age_days == 0` disables pruning.
+fn expire_old_entries(
+    entries: &mut Vec<HistoryEntry>,
+    max_age_days: u64,
+    now: DateTime<Utc>,
+) -> Vec<String> {
+    if max_age_days == 0 {
+        return Vec::new();
+    }
+
+    let cutoff = now - chrono::Duration::days(max_age_days.min(i64::MAX as u64 / 86_400) as i64);
+    let mut removed = Vec::new();
+    entries.retain(|entry| {
+        if entry.pinned || entry.timestamp >= cutoff {
+            true
+        } else {
+            removed.push(entry.id.clone());
+            false
+        }
+    });
+    removed
+}
+
+/// Upper bound on results returned by a fuzzy history search.
+pub const MAX_FUZZY_RESULTS: usize = 50;
+
+/// Minimum score for an entry to be considered a fuzzy match.
+const FUZZY_MATCH_THRESHOLD: f64 = 0.5;
+
+/// A history entry paired with its fuzzy match score (0.0..=1.0, higher is better).
+#[derive(Debug, Clone, Serialize)]
+pub struct HistorySearchResult {
+    #[serde(flatten)]
+    pub entry: HistoryEntry,
+    pub score: f64,
+}
+
+/// An entry's text prepared for searching: lowercased once, split into distinct words.
+#[derive(Debug, Clone)]
+struct IndexedText {
+    lower: String,
+    words: Vec<String>,
+}
+
+impl IndexedText {
+    fn new(text: &str) -> Self {
+        let lower = text.to_lowercase();
+        let mut seen = HashSet::new();
+        let words = split_words(&lower)
+            .filter(|w| seen.insert(*w))
+            .map(str::to_string)
+            .collect();
+        Self { lower, words }
+    }
+}
+
+/// `IndexedText` per entry ID.
+#[derive(Debug, Default)]
+struct SearchIndex {
+    by_id: HashMap<String, IndexedText>,
+}
+
+impl SearchIndex {
+    fn build(entries: &[HistoryEntry]) -> Self {
+        let mut index = Self::default();
+        for entry in entries {
+            index.insert(entry);
+        }
+        index
+    }
+
+    /// Index (or re-index, after its text changed) one entry.
+    fn insert(&mut self, entry: &HistoryEntry) {
+        self.by_id
+            .insert(entry.id.clone(), IndexedText::new(&entry.text));
+    }
+
+    fn remove_all(&mut self, ids: &[String]) {
+        for id in ids {
+            self.by_id.remove(id);
+        }
+    }
+}
+
+fn split_words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+}
+
+/// A search query, lowercased and split once per search.
+struct PreparedQuery {
+    lower: String,
+    words: Vec<String>,
+}
+
+impl PreparedQuery {
+    fn new(query: &str) -> Option<Self> {
+        let lower = query.trim().to_lowercase();
+        if lower.is_empty() {
+            return None;
+        }
+        let words = split_words(&lower).map(str::to_string).collect();
+        Some(Self { lower, words })
+    }
+
+    /// See `fuzzy_score`. `word_scores` memoizes word similarity across entries.
+    fn score<'a>(&self, text: &'a IndexedText, word_scores: &mut WordScores<'a>) -> Option<f64> {
+        if text.lower.is_empty() {
+            return None;
+        }
+
+        if text.lower.contains(&self.lower) {
+            return Some(1.0);
+        }
+
+        if text.words.is_empty() || self.words.is_empty() {
+            return None;
+        }
+
+        let total: f64 = self
+            .words
+            .iter()
+            .zip(word_scores.per_query_word.iter_mut())
+            .map(|(qw, memo)| {
+                text.words
+                    .iter()
+                    .map(|tw| {
+                        *memo
+                            .entry(tw.as_str())
+                            .or_insert_with(|| word_similarity(qw, tw))
+                    })
+                    .fold(0.0, f64::max)
+            })
+            .sum();
+
+        // Substring hits already returned 1.0; keep fuzzy matches strictly below that.
+        let score = (total / self.words.len() as f64).min(0.99);
+        (score >= FUZZY_MATCH_THRESHOLD).then_some(score)
+    }
+}
+
+/// Similarity of each query word to each text word seen so far in one search.
+///
+/// History vocabulary repeats heavily, so this turns most Levenshtein computations into
+/// lookups.
+struct WordScores<'a> {
+    per_query_word: Vec<HashMap<&'a str, f64>>,
+}
+
+impl<'a> WordScores<'a> {
+    fn new(query: &PreparedQuery) -> Self {
+        Self {
+            per_query_word: vec![HashMap::new(); query.words.len()],
+        }
+    }
+}
+
+/// Score how well `text` matches `query`, or `None` if it doesn't match at all.
+///
+/// An exact (case-insensitive) substring scores 1.0. Otherwise each query word is matched
+/// against its most similar word in the text (Levenshtein similarity, or a prefix/
+/// subsequence match for partial recall) and the per-word scores are averaged.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<f64> {
+    let query = PreparedQuery::new(query)?;
+    let text = IndexedText::new(text);
+    query.score(&text, &mut WordScores::new(&query))
+}
+
+fn word_similarity(query: &str, word: &str) -> f64 {
+    if query == word {
+        return 1.0;
+    }
+
+    let q_len = query.chars().count();
+    let w_len = word.chars().count();
+
+    // Partial recall: "transcr" for "transcription", "trnscrpt" for "transcript".
+    let partial = if word.starts_with(query) {
+        0.9
+    } else if q_len >= 3 && is_subsequence(query, word) {
+        0.6 + 0.3 * (q_len as f64 / w_len as f64)
+    } else {
+        0.0
+    };
+
+    let distance = levenshtein(query, word);
+    let edit = 1.0 - distance as f64 / q_len.max(w_len) as f64;
+
+    partial.max(edit)
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut hay = haystack.chars();
+    needle.chars().all(|c| hay.any(|h| h == c))
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::text::{
+        output_text_with_mode_impl, OutputMode, OutputOptions, OutputStatus,
+    };
+    use crate::output_backend::{BackendCall, MockOutputBackend};
+
+    fn temp_app_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tangerine-history-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_save_and_reload_roundtrip() {
+        let dir = temp_app_dir();
+
+        let storage = HistoryStorage::new(dir.clone());
+        storage.add_entry("hello".to_string(), 10).unwrap();
+        storage.add_entry("world".to_string(), 10).unwrap();
+        storage.flush().unwrap();
+
+        let reloaded = HistoryStorage::new(dir.clone());
+        let entries = reloaded.get_all(None).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].text, "world");
+        assert!(!HistoryStorage::temp_path_for(&dir.join("history.json")).exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_corrupt_primary_falls_back_to_backup() {
+        let dir = temp_app_dir();
+
+        {
+            let storage = HistoryStorage::new(dir.clone());
+            storage.add_entry("first".to_string(), 10).unwrap();
+            storage.flush().unwrap();
+            // Second save rotates the (good) first state into the backup slot.
+            storage.add_entry("second".to_string(), 10).unwrap();
+            storage.flush().unwrap();
+        }
+
+        // Simulate a crash that left a truncated primary file.
+        let primary = dir.join("history.json");
+        fs::write(&primary, "{\"entries\": [{\"id\": \"trunc").unwrap();
+
+        let storage = HistoryStorage::new(dir.clone());
+        let entries = storage.get_all(None).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].text, "first");
+
+        // Saving over a corrupt primary must not clobber the good backup.
+        storage.add_entry("third".to_string(), 10).unwrap();
+        storage.flush().unwrap();
+        let backup = HistoryStorage::load_from_file(&dir.join("history.json.bak")).unwrap();
+        assert_eq!(backup.entries.len(), 1);
+        assert_eq!(backup.entries[0].text, "first");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_writes_are_debounced_and_flush_persists() {
+        let dir = temp_app_dir();
+        let primary = dir.join("history.json");
+
+        // Long enough that the flush thread can't write before the assertions below.
+        let storage = HistoryStorage::with_save_debounce(dir.clone(), Duration::from_secs(3600));
+        storage.add_entry("pending".to_string(), 10).unwrap();
+
+        // In-memory state is authoritative immediately, but nothing has hit disk yet.
+        assert_eq!(storage.get_all(None).unwrap().len(), 1);
+        assert!(!primary.exists());
+
+        storage.flush().unwrap();
+        let on_disk = HistoryStorage::load_from_file(&primary).unwrap();
+        assert_eq!(on_disk.entries.len(), 1);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_compact_shrinks_file_and_drops_backup() {
+        let dir = temp_app_dir();
+        let primary = dir.join("history.json");
+        let backup = dir.join("history.json.bak");
+
+        let storage = HistoryStorage::new(dir.clone());
+        for text in ["one", "two", "three"] {
+            storage.add_entry(text.to_string(), 10).unwrap();
+            storage.flush().unwrap();
+        }
+        let before = storage.storage_info().unwrap();
+        assert_eq!(before.entry_count, 3);
+        assert!(before.backup_bytes > 0);
+        assert_eq!(before.average_entry_bytes, before.file_bytes / 3);
+
+        let after = storage.compact().unwrap();
+        assert_eq!(after.entry_count, 3);
+        assert!(after.file_bytes < before.file_bytes);
+        assert_eq!(after.backup_bytes, 0);
+        assert!(!backup.exists());
+
+        // Compaction survives later saves and a reload.
+        storage.add_entry("four".to_string(), 10).unwrap();
+        storage.flush().unwrap();
+        drop(storage);
+        let reloaded = HistoryStorage::new(dir.clone());
+        assert_eq!(reloaded.get_all(None).unwrap().len(), 4);
+        assert!(!fs::read_to_string(&primary).unwrap().contains('\n'));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("same", "same"), 0);
+    }
+
+    #[test]
+    fn test_fuzzy_score_exact_and_misses() {
+        assert_eq!(fuzzy_score("Hello", "well hello there"), Some(1.0));
+        assert_eq!(fuzzy_score("", "anything"), None);
+        assert_eq!(fuzzy_score("zebra", "quarterly budget review"), None);
+    }
+
+    #[test]
+    fn test_fuzzy_search_ranks_typo_above_unrelated() {
+        let dir = temp_app_dir();
+        let storage = HistoryStorage::new(dir.clone());
+
+        storage.add_entry("Remember to buy groceries".to_string(), 10).unwrap();
+        storage.add_entry("Schedule the quarterly budget meeting".to_string(), 10).unwrap();
+        storage.add_entry("Call the dentist tomorrow".to_string(), 10).unwrap();
+
+        // Typo plus partial word.
+        let results = storage.fuzzy_search("quartely budg", 10).unwrap();
+        assert!(!results.is_empty());
+        assert_eq!(results[0].entry.text, "Schedule the quarterly budget meeting");
+        assert!(results[0].score < 1.0);
+        assert!(results
+            .iter()
+            .skip(1)
+            .all(|r| r.score < results[0].score));
+
+        // Subsequence-style recall.
+        let results = storage.fuzzy_search("dntst", 10).unwrap();
+        assert_eq!(results[0].entry.text, "Call the dentist tomorrow");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_search_index_tracks_mutations() {
+        let dir = temp_app_dir();
+        let storage = HistoryStorage::new(dir.clone());
+
+        let kept = storage.add_entry("Quarterly budget review".to_string(), 10).unwrap();
+        let gone = storage.add_entry("Budget for the offsite".to_string(), 10).unwrap();
+        storage.delete(&gone.id).unwrap();
+
+        storage
+            .add_request_entry("req-1".to_string(), RequestModelInfo::default(), 10)
+            .unwrap();
+        storage
+            .complete_request_success("req-1", "Renew the budgie license".to_string())
+            .unwrap();
+
+        let ids: Vec<String> = storage
+            .fuzzy_search("budg", 10)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.entry.id)
+            .collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&kept.id));
+        assert!(ids.contains(&"req-1".to_string()));
+
+        // Reloading rebuilds the index from disk.
+        storage.flush().unwrap();
+        drop(storage);
+        let reloaded = HistoryStorage::new(dir.clone());
+        assert_eq!(reloaded.fuzzy_search("budg", 10).unwrap().len(), 2);
+
+        reloaded.clear().unwrap();
+        assert!(reloaded.fuzzy_search("budg", 10).unwrap().is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_mutations_notify_change_listener() {
+        let dir = temp_app_dir();
+        let storage = HistoryStorage::new(dir.clone());
+        let changes: Arc<Mutex<Vec<HistoryChange>>> = Arc::new(Mutex::new(Vec::new()));
+        let sink = changes.clone();
+        storage.set_change_listener(move |change| sink.lock().unwrap().push(change.clone()));
+
+        let first = storage.add_entry("First".to_string(), 2).unwrap();
+        storage
+            .add_request_entry("req-1".to_string(), RequestModelInfo::default(), 2)
+            .unwrap();
+        storage.complete_request_success("req-1", "Second".to_string()).unwrap();
+        // Exceeds the limit, so the oldest entry is dropped.
+        let third = storage.add_entry("Third".to_string(), 2).unwrap();
+        assert!(storage.set_pinned(&third.id, true).unwrap());
+        assert!(!storage.set_pinned("missing", true).unwrap());
+        storage.delete("req-1").unwrap();
+        storage.delete("missing").unwrap();
+        storage.clear().unwrap();
+
+        let changes = changes.lock().unwrap();
+        let summary: Vec<String> = changes
+            .iter()
+            .map(|change| match change {
+                HistoryChange::Added { entry } => format!("added {}", entry.text),
+                HistoryChange::Updated { entry } => format!("updated {} {}", entry.text, entry.pinned),
+                HistoryChange::Deleted { ids } => format!("deleted {}", ids.join(",")),
+                HistoryChange::Cleared => "cleared".to_string(),
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                "added First".to_string(),
+                "added ".to_string(),
+                "updated Second false".to_string(),
+                "added Third".to_string(),
+                format!("deleted {}", first.id),
+                "updated Third true".to_string(),
+                "deleted req-1".to_string(),
+                "cleared".to_string(),
+            ]
+        );
+
+        let payload = serde_json::to_value(&changes[6]).unwrap();
+        assert_eq!(payload, serde_json::json!({ "kind": "deleted", "ids": ["req-1"] }));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_trim_selection_per_strategy() {
+        // Newest first: index 0 is the most recent.
+        let entries: Vec<HistoryEntry> = [
+            ("newest", false),
+            ("a long one-off dictation", false),
+            ("pinned", true),
+            ("short", false),
+            ("oldest entry here", false),
+        ]
+        .into_iter()
+        .map(|(text, pinned)| HistoryEntry {
+            pinned,
+            ..HistoryEntry::new(text.to_string())
+        })
+        .collect();
+
+        let select = |max, strategy| {
+            let mut picked = select_entries_to_trim(&entries, max, strategy);
+            picked.sort_unstable();
+            picked
+        };
+        assert_eq!(select(5, TrimStrategy::Oldest), Vec::<usize>::new());
+        assert_eq!(select(3, TrimStrategy::Oldest), vec![3, 4]);
+        assert_eq!(select(3, TrimStrategy::OldestUnpinned), vec![3, 4]);
+        assert_eq!(select(2, TrimStrategy::OldestUnpinned), vec![1, 3, 4]);
+        assert_eq!(select(3, TrimStrategy::LongestUnpinned), vec![1, 4]);
+        // Pinned and newest entries are never picked, even if that leaves more than `max`.
+        assert_eq!(select(1, TrimStrategy::LongestUnpinned), vec![1, 3, 4]);
+        assert_eq!(select(1, TrimStrategy::OldestUnpinned), vec![0, 1, 3, 4]);
+
+        assert_eq!(TrimStrategy::from_str("longest_unpinned"), TrimStrategy::LongestUnpinned);
+        assert_eq!(TrimStrategy::from_str("oldest"), TrimStrategy::Oldest);
+        assert_eq!(TrimStrategy::from_str("bogus"), TrimStrategy::OldestUnpinned);
+
+        let mut trimmed = entries.clone();
+        let removed = trim_entries(&mut trimmed, 3, TrimStrategy::LongestUnpinned);
+        assert_eq!(removed, vec![entries[1].id.clone(), entries[4].id.clone()]);
+        let texts: Vec<&str> = trimmed.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(texts, vec!["newest", "pinned", "short"]);
+    }
+
+    #[test]
+    fn test_redaction_applies_to_stored_text_only() {
+        let dir = temp_app_dir();
+        let storage = HistoryStorage::new(dir.clone());
+        storage.set_redaction(&[r"hunter\d+".to_string(), "(".to_string()], true);
+
+        let entry = storage
+            .add_entry(
+                "password hunter22, card 4111 1111 1111 1111, key sk-proj-abcdEFGH12345678, on \
+                 2024-01-15"
+                    .to_string(),
+                10,
+            )
+            .unwrap();
+        assert_eq!(
+            entry.text,
+            "password [redacted], card [redacted], key [redacted], on 2024-01-15"
+        );
+
+        storage
+            .add_request_entry("req-1".to_string(), RequestModelInfo::default(), 10)
+            .unwrap();
+        storage
+            .complete_request_success("req-1", "new password hunter7".to_string())
+            .unwrap();
+        storage.flush().unwrap();
+
+        // What actually landed on disk, not just what the API hands back.
+        let on_disk = fs::read_to_string(dir.join("history.json")).unwrap();
+        assert!(on_disk.contains("new password [redacted]"));
+        for secret in ["hunter", "4111", "sk-proj"] {
+            assert!(!on_disk.contains(secret), "{:?} was stored", secret);
+        }
+
+        let reloaded = HistoryStorage::new(dir.clone());
+        assert!(reloaded.fuzzy_search("hunter", 10).unwrap().is_empty());
+
+        // The output is sent as transcribed; only the stored copy is redacted.
+        let mut backend = MockOutputBackend::default();
+        let output = output_text_with_mode_impl(
+            &mut backend,
+            "new password hunter7",
+            OutputMode::Paste,
+            &OutputOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(output.status, OutputStatus::Success);
+        assert!(backend
+            .actions()
+            .contains(&BackendCall::SetClipboard("new password hunter7".to_string())));
+
+        // Off by default and when cleared.
+        storage.set_redaction(&[], false);
+        let plain = storage.add_entry("hunter22".to_string(), 10).unwrap();
+        assert_eq!(plain.text, "hunter22");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_indexed_search_matches_unindexed_scoring_at_scale() {
+        const ENTRIES: usize = 3000;
+        let vocabulary = [
+            "meeting", "budget", "quarterly", "dentist", "groceries", "tomorrow", "review",
+            "schedule", "project", "deadline", "invoice", "customer", "release", "notes",
+        ];
+
+        let dir = temp_app_dir();
+        let storage = HistoryStorage::new(dir.clone());
+        for i in 0..ENTRIES {
+            let text = format!(
+                "{} the {} {} item {}",
+                vocabulary[i % vocabulary.len()],
+                vocabulary[(i / 3) % vocabulary.len()],
+                vocabulary[(i / 7) % vocabulary.len()],
+                i
+            );
+            storage.add_entry(text, ENTRIES).unwrap();
+        }
+
+        let all = storage.get_all(None).unwrap();
+        for query in ["quartely budg", "dntst", "proj", "item 2999", "zzzz"] {
+            let results = storage.fuzzy_search(query, MAX_FUZZY_RESULTS).unwrap();
+
+            let mut expected: Vec<(String, f64)> = all
+                .iter()
+                .filter_map(|e| fuzzy_score(query, &e.text).map(|s| (e.id.clone(), s)))
+                .collect();
+            expected.sort_by(|a, b| b.1.total_cmp(&a.1));
+            expected.truncate(MAX_FUZZY_RESULTS);
+
+            let actual: Vec<(String, f64)> =
+                results.into_iter().map(|r| (r.entry.id, r.score)).collect();
+            assert_eq!(actual, expected, "query {:?}", query);
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_fuzzy_search_caps_results() {
+        let dir = temp_app_dir();
+        let storage = HistoryStorage::new(dir.clone());
+
+        for i in 0..5 {
+            storage.add_entry(format!("note number {}", i), 10).unwrap();
+        }
+
+        assert_eq!(storage.fuzzy_search("note", 2).unwrap().len(), 2);
+        // Ties keep newest-first order.
+        assert_eq!(storage.fuzzy_search("note", 2).unwrap()[0].entry.text, "note number 4");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_max_age_prunes_old_unpinned_entries() {
+        let dir = temp_app_dir();
+        let storage = HistoryStorage::new(dir.clone());
+
+        let old = storage.add_entry("old".to_string(), 10).unwrap();
+        let old_pinned = storage.add_entry("old pinned".to_string(), 10).unwrap();
+        let recent = storage.add_entry("recent".to_string(), 10).unwrap();
+        storage.set_pinned(&old_pinned.id, true).unwrap();
+
+        {
+            let mut data = storage.shared.data.write().unwrap();
+            for entry in data.entries.iter_mut() {
+                if entry.id == old.id || entry.id == old_pinned.id {
+                    entry.timestamp = Utc::now() - chrono::Duration::days(40);
+                } else if entry.id == recent.id {
+                    entry.timestamp = Utc::now() - chrono::Duration::days(2);
+                }
+            }
+        }
+
+        // Disabled by default.
+        assert!(storage.prune_expired().unwrap().is_empty());
+
+        storage.set_max_age_days(30);
+        storage.add_entry("new".to_string(), 10).unwrap();
+
+        let texts: Vec<String> = storage
+            .get_all(None)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.text)
+            .collect();
+        assert_eq!(texts, vec!["new", "recent", "old pinned"]);
+
+        // Time-based retention keeps pinned entries too.
+        let removed = storage.prune_older_than(Utc::now() + chrono::Duration::days(1)).unwrap();
+        assert_eq!(removed.len(), 2);
+        assert_eq!(storage.get_all(None).unwrap()[0].id, old_pinned.id);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_metadata_roundtrip_and_legacy_entries() {
+        // Entries written before metadata existed must still load.
+        let legacy = r#"{"id":"a","timestamp":"2024-01-01T00:00:00Z","text":"old"}"#;
+        let entry: HistoryEntry = serde_json::from_str(legacy).unwrap();
+        assert!(entry.metadata.is_none());
+        assert!(!entry.pinned);
+
+        let dir = temp_app_dir();
+        let storage = HistoryStorage::new(dir.clone());
+        let metadata = TranscriptionMetadata {
+            duration_secs: Some(3.5),
+            confidence: Some(0.42),
+            model: Some("whisper-large-v3".to_string()),
+        };
+        storage
+            .add_entry_with_metadata("new".to_string(), Some(metadata.clone()), None, 10)
+            .unwrap();
+        storage.flush().unwrap();
+
+        let reloaded = HistoryStorage::new(dir.clone());
+        let entries = reloaded.get_all(None).unwrap();
+        assert_eq!(entries[0].metadata.as_ref(), Some(&metadata));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_idempotency_key_returns_existing_entry() {
+        let dir = temp_app_dir();
+        let storage = HistoryStorage::new(dir.clone());
+
+        let first = storage
+            .add_entry_with_metadata("hello".to_string(), None, Some("utt-1".to_string()), 10)
+            .unwrap();
+        let second = storage
+            .add_entry_with_metadata("hello".to_string(), None, Some("utt-1".to_string()), 10)
+            .unwrap();
+        assert_eq!(first.id, second.id);
+        assert_eq!(storage.get_all(None).unwrap().len(), 1);
+
+        let other = storage
+            .add_entry_with_metadata("hello".to_string(), None, Some("utt-2".to_string()), 10)
+            .unwrap();
+        assert_ne!(other.id, first.id);
+        assert_eq!(storage.get_all(None).unwrap().len(), 2);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_idempotency_key_expires_after_window() {
+        let dir = temp_app_dir();
+        let storage = HistoryStorage::new(dir.clone());
+
+        let first = storage
+            .add_entry_with_metadata("hello".to_string(), None, Some("utt-1".to_string()), 10)
+            .unwrap();
+        {
+            let mut data = storage.shared.data.write().unwrap();
+            data.entries[0].timestamp = Utc::now() - IDEMPOTENCY_WINDOW - chrono::Duration::seconds(1);
         }
-        self.save()
+
+        let second = storage
+            .add_entry_with_metadata("hello".to_string(), None, Some("utt-1".to_string()), 10)
+            .unwrap();
+        assert_ne!(first.id, second.id);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_sessions_split_on_time_gap() {
+        let dir = temp_app_dir();
+        let storage = HistoryStorage::new(dir.clone());
+        storage.set_session_gap_minutes(10);
+        let gap = storage.session_gap();
+
+        // Fabricated timestamps, oldest first: 09:00 and 09:10 (exactly the gap) share a
+        // session; 09:20:01 is just past the gap and starts a new one.
+        let base = Utc::now() - chrono::Duration::days(1);
+        let offsets = [
+            chrono::Duration::zero(),
+            chrono::Duration::minutes(10),
+            chrono::Duration::minutes(20) + chrono::Duration::seconds(1),
+            chrono::Duration::minutes(25),
+        ];
+        {
+            let mut data = storage.shared.data.write().unwrap();
+            for (i, offset) in offsets.iter().enumerate() {
+                let mut entry = HistoryEntry::new(format!("entry {}", i));
+                entry.timestamp = base + *offset;
+                entry.session_id =
+                    Some(session_for_new_entry(data.entries.first(), entry.timestamp, gap));
+                data.entries.insert(0, entry);
+            }
+        }
+
+        let sessions = storage.get_sessions(None).unwrap();
+        assert_eq!(sessions.len(), 2);
+        let texts = |s: &HistorySession| s.entries.iter().map(|e| e.text.clone()).collect::<Vec<_>>();
+        assert_eq!(texts(&sessions[0]), ["entry 3", "entry 2"]);
+        assert_eq!(texts(&sessions[1]), ["entry 1", "entry 0"]);
+        assert_eq!(sessions[1].started_at, base);
+        assert_eq!(sessions[1].ended_at, base + chrono::Duration::minutes(10));
+        assert_eq!(sessions[0].started_at, base + offsets[2]);
+        assert_eq!(sessions[0].ended_at, base + offsets[3]);
+        assert_eq!(storage.get_sessions(Some(1)).unwrap().len(), 1);
+
+        // Legacy entries without a session ID are grouped by the same gap rule.
+        let mut legacy = sessions[0].entries.clone();
+        legacy.extend(sessions[1].entries.clone());
+        legacy.iter_mut().for_each(|e| e.session_id = None);
+        assert_eq!(group_into_sessions(legacy, gap).len(), 2);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_query_combines_filters_and_paginates() {
+        let dir = temp_app_dir();
+        let storage = HistoryStorage::new(dir.clone());
+        let base = Utc::now() - chrono::Duration::days(10);
+        let fixtures = [
+            ("meeting notes for monday", 0, Some(r"C:\Apps\Slack\slack.exe")),
+            ("meeting notes for tuesday", 1, Some(r"C:\Program Files\Google\Chrome\chrome.exe")),
+            ("grocery list", 2, Some(r"C:\Apps\Slack\slack.exe")),
+            ("meeting recap", 3, Some(r"C:\Apps\Slack\slack.exe")),
+            ("meeting agenda", 4, None),
+        ];
+        {
+            let mut data = storage.shared.data.write().unwrap();
+            for (text, day, app) in fixtures {
+                let mut entry = HistoryEntry::new(text.to_string());
+                entry.timestamp = base + chrono::Duration::days(day);
+                entry.source_app = app.map(str::to_string);
+                data.entries.insert(0, entry);
+            }
+        }
+        let texts = |page: &HistoryPage| page.entries.iter().map(|e| e.text.clone()).collect::<Vec<_>>();
+
+        // Empty filter: the most recent page of everything.
+        let page = storage.query(&HistoryFilter { limit: Some(2), ..Default::default() }).unwrap();
+        assert_eq!(page.total, 5);
+        assert_eq!(texts(&page), ["meeting agenda", "meeting recap"]);
+
+        // Text AND app AND date range.
+        let filter = HistoryFilter {
+            query: Some("meeting".to_string()),
+            source_app: Some("Slack".to_string()),
+            from: Some(base),
+            to: Some(base + chrono::Duration::days(2)),
+            ..Default::default()
+        };
+        let page = storage.query(&filter).unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(texts(&page), ["meeting notes for monday"]);
+
+        // Widening the date range brings in the later Slack meeting; pages split the total.
+        let filter = HistoryFilter {
+            to: None,
+            limit: Some(1),
+            offset: 1,
+            ..filter
+        };
+        let page = storage.query(&filter).unwrap();
+        assert_eq!(page.total, 2);
+        assert_eq!(texts(&page), ["meeting notes for monday"]);
+
+        // Full paths match too; entries without a source app never match an app filter.
+        let by_path = HistoryFilter {
+            source_app: Some(r"c:\program files\google\chrome\chrome.exe".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(texts(&storage.query(&by_path).unwrap()), ["meeting notes for tuesday"]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_identical_timestamps_order_by_insertion() {
+        let dir = temp_app_dir();
+        let storage = HistoryStorage::new(dir.clone());
+        let now = Utc::now();
+        {
+            let mut data = storage.shared.data.write().unwrap();
+            for text in ["first", "second", "third", "fourth"] {
+                let mut entry = HistoryEntry::new(text.to_string());
+                entry.timestamp = now;
+                data.insert(&mut entry);
+            }
+            // A clock that stepped back files the entry below the newer ones.
+            let mut late = HistoryEntry::new("stepped back".to_string());
+            late.timestamp = now - chrono::Duration::seconds(1);
+            data.insert(&mut late);
+        }
+        let texts = |entries: Vec<HistoryEntry>| {
+            entries.into_iter().map(|e| e.text).collect::<Vec<_>>()
+        };
+        let expected = ["fourth", "third", "second", "first", "stepped back"];
+        assert_eq!(texts(storage.get_all(None).unwrap()), expected);
+
+        // Pages tile the list with no repeats or gaps.
+        let mut paged = Vec::new();
+        for offset in (0..5).step_by(2) {
+            let filter = HistoryFilter { offset, limit: Some(2), ..Default::default() };
+            paged.extend(texts(storage.query(&filter).unwrap().entries));
+        }
+        assert_eq!(paged, expected);
+
+        // Same order after a reload.
+        storage.flush().unwrap();
+        let reloaded = HistoryStorage::new(dir.clone());
+        assert_eq!(texts(reloaded.get_all(None).unwrap()), expected);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_legacy_entries_get_seq_in_stored_order() {
+        let dir = temp_app_dir();
+        let timestamp = "2024-05-01T12:00:00Z";
+        let legacy = serde_json::json!({
+            "entries": [
+                { "id": "c", "timestamp": timestamp, "text": "newest" },
+                { "id": "b", "timestamp": timestamp, "text": "middle" },
+                { "id": "a", "timestamp": timestamp, "text": "oldest" },
+            ]
+        });
+        fs::write(dir.join("history.json"), legacy.to_string()).unwrap();
+
+        let storage = HistoryStorage::new(dir.clone());
+        let entries = storage.get_all(None).unwrap();
+        let order: Vec<_> = entries.iter().map(|e| (e.text.as_str(), e.seq)).collect();
+        assert_eq!(order, [("newest", 3), ("middle", 2), ("oldest", 1)]);
+
+        let added = storage.add_entry("added".to_string(), 10).unwrap();
+        assert_eq!(added.seq, 4);
+
+        let _ = fs::remove_dir_all(&dir);
     }
 }
@@ -7,18 +7,37 @@ use tauri::{
 };
 use tauri_utils::config::BackgroundThrottlingPolicy;
 
+mod accessibility;
+mod app_window;
 mod audio;
 mod audio_capture;
 mod audio_mute;
+mod clipboard_restore;
+mod clipboard_snapshot;
 mod commands;
+mod cue_themes;
+mod direct_insert;
+mod foreground_window;
 mod history;
+mod http_client;
+mod linux_paste;
+mod log_buffer;
 mod llm;
+mod modifier_state;
+mod output_audit;
+mod output_backend;
 mod pipeline;
 mod recordings;
+mod redaction;
 mod request_log;
+mod secure_input;
 mod settings;
 mod state;
 mod stt;
+mod system_dnd;
+mod text_transform;
+mod tts;
+mod unicode_input;
 mod vad;
 mod windows_apps;
 
@@ -65,6 +84,43 @@ pub(crate) fn normalize_shortcut_string(s: &str) -> String {
         .replace("win", "super")
 }
 
+/// Find which existing binding already uses `candidate`'s key combo (compared normalized).
+///
+/// `bound` is `(label, shortcut string)` pairs; returns the conflicting label.
+#[cfg(desktop)]
+pub(crate) fn find_hotkey_conflict<'a>(
+    candidate: &str,
+    bound: &'a [(String, String)],
+) -> Option<&'a str> {
+    let candidate = normalize_shortcut_string(candidate);
+    bound
+        .iter()
+        .find(|(_, combo)| normalize_shortcut_string(combo) == candidate)
+        .map(|(label, _)| label.as_str())
+}
+
+/// Shortcuts bound to a specific output mode (`mode_hotkeys`: shortcut string -> mode),
+/// excluding any that collide with `exclude`. Unparseable entries are skipped.
+#[cfg(desktop)]
+pub(crate) fn mode_hotkey_shortcuts(app: &AppHandle, exclude: &[Shortcut]) -> Vec<Shortcut> {
+    use std::str::FromStr;
+
+    let bindings: std::collections::HashMap<String, String> =
+        get_setting_from_store(app, "mode_hotkeys", Default::default());
+
+    let mut shortcuts: Vec<Shortcut> = Vec::new();
+    for combo in bindings.keys() {
+        match Shortcut::from_str(combo) {
+            Ok(shortcut) if !exclude.contains(&shortcut) && !shortcuts.contains(&shortcut) => {
+                shortcuts.push(shortcut)
+            }
+            Ok(_) => log::warn!("Mode hotkey {} collides with another hotkey; skipped", combo),
+            Err(e) => log::warn!("Invalid mode hotkey {}: {:?}", combo, e),
+        }
+    }
+    shortcuts
+}
+
 /// Helper to read a setting from the store with a default fallback
 #[cfg(desktop)]
 fn get_setting_from_store<T: serde::de::DeserializeOwned>(
@@ -88,135 +144,345 @@ fn get_setting_from_store<T: serde::de::DeserializeOwned>(
 /// (without overwriting any existing values).
 #[cfg(desktop)]
 fn ensure_default_settings(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    use serde_json::{json, Value};
+    use serde_json::Value;
     use tauri_plugin_store::StoreExt;
 
     let store = app.store("settings.json")?;
 
-    // Keep these defaults aligned with pipeline defaults / expected backend behavior.
-    // We intentionally seed these so a brand new install has the same effective
-    // settings that the pipeline will use at runtime (and what the UI shows).
-    let default_pipeline_config = crate::pipeline::PipelineConfig::default();
-
     let is_missing = |v: Option<Value>| -> bool {
         matches!(v, None | Some(Value::Null))
     };
 
     let mut dirty = false;
-    let mut set_if_missing = |key: &str, value: Value| {
+    for (key, value) in default_settings()? {
         if is_missing(store.get(key)) {
             store.set(key.to_string(), value);
             dirty = true;
         }
-    };
+    }
+
+    if dirty {
+        // Persist seeded defaults.
+        // If saving fails, we don't want to crash the app; the runtime fallbacks will still work.
+        if let Err(e) = store.save() {
+            log::warn!("Failed to save seeded default settings: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Every setting the backend seeds, with its default value (in seeding order).
+///
+/// Used to seed missing keys at startup and by `reset_settings_to_defaults`.
+#[cfg(desktop)]
+fn default_settings() -> Result<Vec<(&'static str, serde_json::Value)>, serde_json::Error> {
+    use serde_json::{json, Value};
+
+    // Keep these defaults aligned with pipeline defaults / expected backend behavior.
+    // We intentionally seed these so a brand new install has the same effective
+    // settings that the pipeline will use at runtime (and what the UI shows).
+    let default_pipeline_config = crate::pipeline::PipelineConfig::default();
+
+    let mut defaults: Vec<(&'static str, Value)> = Vec::new();
+    let mut set_default = |key: &'static str, value: Value| defaults.push((key, value));
 
-    set_if_missing("stt_provider", json!("groq"));
-    set_if_missing("stt_transcription_prompt", json!(null));
-    set_if_missing("stt_timeout_seconds", json!(10.0));
+    set_default("stt_provider", json!("groq"));
+    set_default("stt_transcription_prompt", json!(null));
+    set_default("stt_timeout_seconds", json!(10.0));
+    // HTTP(S) proxy for STT/LLM requests. null = use HTTPS_PROXY/HTTP_PROXY env vars.
+    // Loopback hosts always bypass the proxy.
+    set_default("proxy_url", json!(null));
     // How many recordings/history items to retain (impacts disk usage).
     // Keep this aligned with the UI default.
-    set_if_missing("max_saved_recordings", json!(1000));
+    set_default("max_saved_recordings", json!(1000));
 
     // Request logs retention (in-memory request log history).
     // Keep this aligned with the UI default.
-    set_if_missing("request_logs_retention_mode", json!("amount"));
-    set_if_missing("request_logs_retention_amount", json!(10));
+    set_default("request_logs_retention_mode", json!("amount"));
+    set_default("request_logs_retention_amount", json!(10));
     // Only used when mode == "time" (days; 0 = forever)
-    set_if_missing("request_logs_retention_days", json!(7));
-    // Time-based retention for history/transcriptions. 0 = keep forever.
-    set_if_missing("transcription_retention_days", json!(0));
+    set_default("request_logs_retention_days", json!(7));
+    // Time-based retention for history/transcriptions; pinned entries are kept. 0 = keep forever.
+    set_default("transcription_retention_days", json!(0));
+    // Age-based history expiry in days, applied on insert. Pinned entries are kept. 0 = off.
+    set_default("max_history_age_days", json!(0));
+    // Which entries go first when history exceeds max_saved_recordings: "oldest",
+    // "oldest_unpinned" or "longest_unpinned".
+    set_default("trim_strategy", json!("oldest_unpinned"));
+    // Minutes of silence after which the next history entry starts a new session.
+    set_default("history_session_gap_minutes", json!(10));
+    // Regexes whose matches are replaced with "[redacted]" in stored history (output is
+    // unaffected), plus optional built-ins for long digit runs and key-like tokens.
+    set_default("redact_patterns", json!([]));
+    set_default("redact_builtin_patterns", json!(false));
     // New retention keys (unit+value) used by newer UI.
     // Keep legacy days key as well for backward compatibility.
-    set_if_missing("transcription_retention_unit", json!("days"));
-    set_if_missing("transcription_retention_value", json!(0.0));
+    set_default("transcription_retention_unit", json!("days"));
+    set_default("transcription_retention_value", json!(0.0));
     // When deleting old transcriptions, optionally also delete their .wav recordings.
-    set_if_missing("transcription_retention_delete_recordings", json!(false));
-    set_if_missing("overlay_mode", json!("recording_only"));
-    set_if_missing("widget_position", json!("bottom-center"));
-    set_if_missing("output_mode", json!("paste"));
-    set_if_missing("output_hit_enter", json!(false));
-    set_if_missing("playing_audio_handling", json!("mute"));
-    set_if_missing("sound_enabled", json!(true));
-    set_if_missing("rewrite_llm_enabled", json!(false));
-    set_if_missing("rewrite_program_prompt_profiles", json!([]));
+    set_default("transcription_retention_delete_recordings", json!(false));
+    set_default("overlay_mode", json!("recording_only"));
+    set_default("overlay_min_size", json!(48.0));
+    set_default("overlay_draggable", json!(true));
+    set_default("overlay_always_on_top", json!(true));
+    // Overlay outline: "pill", "rounded" or "square"; radius in logical px (see set_overlay_shape).
+    set_default("overlay_shape", json!("rounded"));
+    set_default(
+        "overlay_corner_radius",
+        json!(commands::overlay::DEFAULT_OVERLAY_CORNER_RADIUS),
+    );
+    set_default("overlay_presets", json!({}));
+    // Named overlay placements saved by the user: { "name": { x, y, width, height, monitor } }.
+    set_default("overlay_layouts", json!({}));
+    // Briefly show the end of the last transcript on the overlay after output.
+    set_default("overlay_preview_enabled", json!(false));
+    set_default("overlay_preview_max_chars", json!(40));
+    set_default("widget_position", json!("bottom-center"));
+    // App whose window the overlay follows (null = static screen position).
+    set_default("overlay_follow_app", json!(null));
+    // Ping the overlay webview and reload it when it stops answering (see spawn_overlay_heartbeat).
+    set_default("overlay_heartbeat_enabled", json!(false));
+    set_default(
+        "overlay_heartbeat_interval_ms",
+        json!(commands::overlay::DEFAULT_OVERLAY_HEARTBEAT_INTERVAL_MS),
+    );
+    set_default(
+        "overlay_heartbeat_timeout_ms",
+        json!(commands::overlay::DEFAULT_OVERLAY_HEARTBEAT_TIMEOUT_MS),
+    );
+    set_default("output_mode", json!("paste"));
+    set_default("output_hit_enter", json!(false));
+    // Off: paste modes leave the dictated text on the clipboard instead of restoring it.
+    set_default("restore_clipboard_after_paste", json!(true));
+    set_default("skip_empty_restore", json!(true));
+    // Hold each transcript (`output-pending`) until it's confirmed in the UI; unanswered
+    // outputs are cancelled after `confirm_output_timeout_secs`.
+    set_default("confirm_before_output", json!(false));
+    set_default("confirm_output_timeout_secs", json!(60));
+    // Previous clipboard content larger than this isn't restored after paste (restoring
+    // megabytes of text can stall output).
+    set_default(
+        "max_clipboard_capture_bytes",
+        json!(commands::text::DEFAULT_MAX_CLIPBOARD_CAPTURE_BYTES),
+    );
+    // Skip the post-paste clipboard restore if the clipboard changed during the restore delay.
+    set_default("safe_clipboard_restore", json!(true));
+    // Paste mode: copy the selection first so dictation replaces it (dictate over selection).
+    set_default("replace_selection", json!(false));
+    // Hide the paste's temporary clipboard writes from clipboard managers where supported.
+    set_default("clipboard_manager_friendly", json!(false));
+    // Linux paste shortcut: "auto" (Ctrl+Shift+V in the terminals listed below, by X11
+    // window class), "ctrl_v" or "ctrl_shift_v".
+    set_default("linux_paste_shortcut", json!("auto"));
+    set_default(
+        "linux_shift_paste_apps",
+        json!(linux_paste::DEFAULT_SHIFT_PASTE_APPS),
+    );
+    set_default("profile_output", json!(false));
+    // What to do when output is triggered while a previous output is still running:
+    // "queue" (wait) or "reject" (fail fast).
+    set_default("output_concurrency", json!("queue"));
+    // `output_text_to_file` line ending: "native", "lf" or "crlf".
+    set_default("file_output_line_ending", json!("native"));
+    // Folder file output and history export may write into; null = file output off.
+    set_default("file_output_dir", json!(null));
+    // Pause between output completing and the optional Enter press (output_hit_enter).
+    set_default("output_enter_delay_ms", json!(80));
+    // Joins fragments in the "clipboard_append" output mode.
+    set_default("clipboard_append_separator", json!("\n"));
+    // Re-activate the window that was focused at recording start before sending output.
+    // Off by default: focus APIs are platform-sensitive (xdotool/X11 only on Linux).
+    set_default("refocus_before_paste", json!(false));
+    // Per-app output mode, keyed by process path: {"C:\\...\\app.exe": "keystrokes_verified"}.
+    set_default("output_mode_overrides", json!({}));
+    // Key a newline presses in keystroke modes: "enter" or "shift_enter" (chat apps that
+    // send on Enter). Per-app overrides are keyed by process path, like the mode overrides.
+    set_default("keystroke_newline_key", json!("enter"));
+    set_default("keystroke_newline_key_overrides", json!({}));
+    // Cue theme per output mode (e.g. {"keystrokes_verified": "clave"}); modes without an
+    // entry use `audio_cue`.
+    set_default("output_mode_cues", json!({}));
+    // Extra hotkeys that record and output with a specific mode: {"ctrl+alt+KeyT": "keystrokes_verified"}.
+    set_default("mode_hotkeys", json!({}));
+    // Double-pressing the toggle hotkey within this many ms switches the next output to
+    // clipboard-only (again to switch back). 0 disables it; when enabled, single presses
+    // act after the window has passed.
+    set_default("double_press_window_ms", json!(0));
+    // Windows: type keystroke modes as Unicode events so non-US layouts get the right
+    // characters. Turn off to fall back to enigo's layout-based typing for a problem app.
+    set_default("unicode_keystrokes", json!(true));
+    // Keystroke modes: "hybrid" pastes CJK/complex-script runs and types the rest,
+    // "paste_all" pastes the whole text if it has any, "keystrokes" always types.
+    set_default("complex_script_output", json!("hybrid"));
+    // macOS: insert paste-mode output via Accessibility (no clipboard) where the field allows it.
+    set_default("direct_insert", json!(false));
+    // Retry the whole output this many times after a transient failure (clipboard locked, ...).
+    set_default("output_retries", json!(commands::text::DEFAULT_OUTPUT_RETRIES));
+    set_default("capitalize_first_letter", json!(false));
+    set_default("transform_locale", json!(""));
+    set_default("punctuation_spacing", json!(false));
+    set_default("number_grouping", json!(false));
+    set_default(
+        "number_grouping_min",
+        json!(text_transform::DEFAULT_NUMBER_GROUPING_MIN),
+    );
+    set_default(
+        "keystroke_verified_delay_ms",
+        json!(commands::text::DEFAULT_VERIFIED_KEY_DELAY_MS),
+    );
+    set_default(
+        "max_keystroke_chars",
+        json!(commands::text::DEFAULT_MAX_KEYSTROKE_CHARS),
+    );
+    set_default(
+        "type_then_paste_chars",
+        json!(commands::text::DEFAULT_TYPE_THEN_PASTE_CHARS),
+    );
+    // Batched keystroke output; `benchmark_keystroke_speed` suggests values for this machine.
+    set_default(
+        "keystroke_chunk_chars",
+        json!(commands::text::DEFAULT_KEYSTROKE_CHUNK_CHARS),
+    );
+    set_default(
+        "keystroke_chunk_delay_ms",
+        json!(commands::text::DEFAULT_KEYSTROKE_CHUNK_DELAY_MS),
+    );
+    // Human-like keystroke cadence: vary each chunk pause by up to ± this many ms.
+    set_default("keystroke_jitter_enabled", json!(false));
+    set_default(
+        "keystroke_jitter_ms",
+        json!(commands::text::DEFAULT_KEYSTROKE_JITTER_MS),
+    );
+    // Keystroke modes: longest wait for hotkey modifiers to be let go before typing, and
+    // whether to skip the output if the user starts typing during that wait.
+    set_default(
+        "modifier_release_max_wait_ms",
+        json!(commands::text::DEFAULT_MODIFIER_RELEASE_WAIT_MS),
+    );
+    set_default("abort_keystrokes_on_user_input", json!(true));
+    // Paste modes: also wait for/release held hotkey modifiers before pressing the paste combo.
+    set_default("release_modifiers_before_paste", json!(true));
+    set_default("playing_audio_handling", json!("mute"));
+    set_default("sound_enabled", json!(true));
+    set_default("respect_system_dnd", json!(false));
+    set_default("cue_debounce_ms", json!(audio::DEFAULT_CUE_DEBOUNCE_MS));
+    // Crossfade between overlapping cues of an installed cue theme; 0 = none.
+    set_default("cue_crossfade_ms", json!(0));
+    // Skip the stop cue when the recording was shorter than this (accidental taps); 0 = never.
+    set_default("min_recording_ms_for_stop_cue", json!(0));
+    // Auto-stop and transcribe a recording after this many seconds; 0 = no limit.
+    set_default("max_recording_seconds", json!(0));
+    // Emit `recording-countdown` each second over the last this-many seconds; 0 = off.
+    set_default("recording_countdown_seconds", json!(5));
+    set_default("error_sounds", json!(true));
+    // Speak "pasted"/"copied"/... after output (OS text-to-speech), for eyes-free use.
+    set_default("tts_confirmations", json!(false));
+    // Play a distinct cue when a transcript comes back empty (silence/noise).
+    set_default("no_speech_cue", json!(false));
+    // Opt-in per-output audit log (separate file, rotated); path null = app data dir.
+    set_default("output_audit_log_enabled", json!(false));
+    set_default("output_audit_log_path", json!(null));
+    set_default("output_audit_log_include_text", json!(false));
+    // Per-sound overrides of the audio_cue preset (null = follow audio_cue).
+    set_default("audio_cue_start", json!(null));
+    set_default("audio_cue_stop", json!(null));
+    // Installed cue theme (cue-themes/<name>) played instead of the built-in sounds.
+    set_default("active_cue_theme", json!(null));
+    // Cue loudness (0.0-1.0) and the output device cues play on (null = system default).
+    set_default("cue_volume", json!(1.0));
+    set_default("cue_output_device", json!(null));
+    // Named snapshots of the cue settings: {"Studio": {"audio_cue": ..., "cue_volume": ...}}.
+    set_default("audio_profiles", json!({}));
+    set_default("rewrite_llm_enabled", json!(false));
+    set_default("rewrite_program_prompt_profiles", json!([]));
 
     // Hotkeys: seed explicit defaults so both Rust and UI see the same persisted values.
-    set_if_missing(
+    set_default(
         "toggle_hotkey",
         serde_json::to_value(HotkeyConfig::default_toggle())?,
     );
-    set_if_missing(
+    set_default(
         "hold_hotkey",
         serde_json::to_value(HotkeyConfig::default_hold())?,
     );
-    set_if_missing(
+    set_default(
         "paste_last_hotkey",
         serde_json::to_value(HotkeyConfig::default_paste_last())?,
     );
 
     // VAD settings are used by the pipeline.
-    set_if_missing(
+    set_default(
         "vad_settings",
         serde_json::to_value(settings::VadSettings::default())?,
     );
 
     // Audio + quiet-recording gating.
-    set_if_missing(
+    set_default(
         "quiet_audio_gate_enabled",
         json!(default_pipeline_config.quiet_audio_gate_enabled),
     );
-    set_if_missing(
+    set_default(
         "quiet_audio_min_duration_secs",
         json!(default_pipeline_config.quiet_audio_min_duration_secs),
     );
-    set_if_missing(
+    set_default(
         "quiet_audio_rms_dbfs_threshold",
         json!(default_pipeline_config.quiet_audio_rms_dbfs_threshold),
     );
-    set_if_missing(
+    set_default(
         "quiet_audio_peak_dbfs_threshold",
         json!(default_pipeline_config.quiet_audio_peak_dbfs_threshold),
     );
-    set_if_missing(
+    set_default(
         "quiet_audio_require_speech",
         json!(default_pipeline_config.quiet_audio_require_speech),
     );
 
     // Stop-time preprocessing defaults.
-    set_if_missing(
+    set_default(
         "noise_gate_threshold_dbfs",
         json!(default_pipeline_config.noise_gate_threshold_dbfs),
     );
-    set_if_missing(
+    set_default(
         "audio_downmix_to_mono",
         json!(default_pipeline_config.audio_downmix_to_mono),
     );
-    set_if_missing(
+    set_default(
         "audio_resample_to_16khz",
         json!(default_pipeline_config.audio_resample_to_16khz),
     );
-    set_if_missing(
+    set_default(
         "audio_highpass_enabled",
         json!(default_pipeline_config.audio_highpass_enabled),
     );
-    set_if_missing(
+    set_default(
         "audio_agc_enabled",
         json!(default_pipeline_config.audio_agc_enabled),
     );
-    set_if_missing(
+    set_default(
         "audio_noise_suppression_enabled",
         json!(default_pipeline_config.audio_noise_suppression_enabled),
     );
+    // Preferred capture format (e.g. 16000 Hz, 1 channel). null keeps the device default,
+    // as does a format the device doesn't support.
+    set_default("audio_capture_sample_rate", json!(null));
+    set_default("audio_capture_channels", json!(null));
+    // Cut leading/trailing audio below the threshold (at most `silence_trim_max_ms` per end).
+    set_default("silence_trim_enabled", json!(false));
+    set_default(
+        "silence_trim_threshold_dbfs",
+        json!(audio_capture::DEFAULT_SILENCE_TRIM_THRESHOLD_DBFS),
+    );
+    set_default(
+        "silence_trim_max_ms",
+        json!(audio_capture::DEFAULT_SILENCE_TRIM_MAX_MS),
+    );
+    // Positive: drop this many ms at the start of each recording. Negative: keep the mic
+    // open between recordings and prepend this many ms from before the hotkey (pre-roll).
+    set_default("recording_start_delay_ms", json!(0));
 
-    if dirty {
-        // Persist seeded defaults.
-        // If saving fails, we don't want to crash the app; the runtime fallbacks will still work.
-        if let Err(e) = store.save() {
-            log::warn!("Failed to save seeded default settings: {}", e);
-        }
-    }
-
-    Ok(())
+    Ok(defaults)
 }
 
 /// Emit a system event to the frontend for debugging
@@ -253,6 +519,30 @@ fn sanitize_transcript(transcript: &str) -> Option<String> {
     }
 }
 
+/// The user's configured deterministic text transforms.
+#[cfg(desktop)]
+fn text_transform_config(app: &AppHandle) -> text_transform::TransformConfig {
+    let number_grouping: bool = get_setting_from_store(app, "number_grouping", false);
+    text_transform::TransformConfig {
+        locale: get_setting_from_store(app, "transform_locale", String::new()),
+        number_grouping_min: number_grouping.then(|| {
+            get_setting_from_store(
+                app,
+                "number_grouping_min",
+                text_transform::DEFAULT_NUMBER_GROUPING_MIN,
+            )
+        }),
+        punctuation_spacing: get_setting_from_store(app, "punctuation_spacing", false),
+        capitalize_first_letter: get_setting_from_store(app, "capitalize_first_letter", false),
+    }
+}
+
+/// Apply the user's configured deterministic text transforms to final output text.
+#[cfg(desktop)]
+fn apply_text_transforms(app: &AppHandle, text: String) -> String {
+    text_transform_config(app).apply(&text)
+}
+
 // ============================================================================
 // Playing audio handling during recording
 // ============================================================================
@@ -406,22 +696,276 @@ fn is_non_system_audio_session_active() -> Result<bool, String> {
     Ok(true)
 }
 
+/// Whether `what` (cues, overlay) should be suppressed because the OS reports DND/Focus.
+///
+/// Only queries the OS when `respect_system_dnd` is enabled. If the platform can't tell
+/// us, we proceed normally.
+#[cfg(desktop)]
+fn is_suppressed_by_system_dnd(app: &AppHandle, what: &str) -> bool {
+    let respect_system_dnd: bool = get_setting_from_store(app, "respect_system_dnd", false);
+    if !respect_system_dnd {
+        return false;
+    }
+
+    match system_dnd::is_dnd_active() {
+        Some(true) => {
+            log::info!("Suppressing {}: system Do Not Disturb is active", what);
+            true
+        }
+        Some(false) => false,
+        None => {
+            log::debug!("System Do Not Disturb state unknown; not suppressing {}", what);
+            false
+        }
+    }
+}
+
+/// `sound_enabled`, unless cues are suppressed by system Do Not Disturb.
+#[cfg(desktop)]
+fn cue_sound_enabled(app: &AppHandle) -> bool {
+    get_setting_from_store(app, "sound_enabled", true)
+        && !is_suppressed_by_system_dnd(app, "audio cues")
+}
+
+/// Resolve the start/stop cue themes from `audio_cue` and its per-sound overrides.
+#[cfg(desktop)]
+fn get_cue_selection(app: &AppHandle) -> audio::CueSelection {
+    let preset: String = get_setting_from_store(app, "audio_cue", "tangerine".to_string());
+    let start: Option<String> = get_setting_from_store(app, "audio_cue_start", None);
+    let stop: Option<String> = get_setting_from_store(app, "audio_cue_stop", None);
+    audio::CueSelection::from_settings(&preset, start.as_deref(), stop.as_deref())
+}
+
+/// Apply the per-output-mode cue override (`output_mode_cues`) for `mode`, or for the
+/// default output mode when the recording didn't pick one. A per-app mode override for the
+/// foreground app (`output_mode_overrides`) wins, as it does when the text is output.
+#[cfg(desktop)]
+fn cue_selection_for_mode(
+    app: &AppHandle,
+    mode: Option<commands::text::OutputMode>,
+    audio_cue: audio::CueSelection,
+) -> audio::CueSelection {
+    let mode = mode.unwrap_or_else(|| {
+        commands::text::OutputMode::from_str(&get_setting_from_store(
+            app,
+            "output_mode",
+            "paste".to_string(),
+        ))
+    });
+    let app_overrides = app_mode_overrides(app);
+    let mode = if app_overrides.is_empty() {
+        mode
+    } else {
+        crate::windows_apps::get_foreground_process_path()
+            .and_then(|path| app_overrides.get(&pipeline::normalize_program_path(&path)).copied())
+            .unwrap_or(mode)
+    };
+    let overrides: std::collections::HashMap<String, String> =
+        get_setting_from_store(app, "output_mode_cues", Default::default());
+    audio_cue.for_output_mode(&overrides, mode.to_str())
+}
+
+/// Per-app output mode overrides (`output_mode_overrides`), keyed by normalized process path.
+#[cfg(desktop)]
+fn app_mode_overrides(
+    app: &AppHandle,
+) -> std::collections::HashMap<String, commands::text::OutputMode> {
+    get_setting_from_store::<std::collections::HashMap<String, String>>(
+        app,
+        "output_mode_overrides",
+        Default::default(),
+    )
+    .into_iter()
+    .map(|(path, mode)| {
+        (
+            pipeline::normalize_program_path(&path),
+            commands::text::OutputMode::from_str(&mode),
+        )
+    })
+    .collect()
+}
+
+/// Resolve per-call output options from settings.
+#[cfg(desktop)]
+fn get_output_options(
+    app: &AppHandle,
+    sound_enabled: bool,
+    audio_cue: audio::CueSelection,
+) -> commands::text::OutputOptions {
+    // Optional: after pasting, press Enter.
+    let hit_enter: bool = get_setting_from_store(app, "output_hit_enter", false);
+
+    // Error cue when output fails (only if sounds are enabled at all).
+    let error_sounds: bool = get_setting_from_store(app, "error_sounds", true);
+    let error_cue = if sound_enabled && error_sounds {
+        Some(audio_cue.cue_for(audio::SoundType::Error))
+    } else {
+        None
+    };
+    let no_speech_sound: bool = get_setting_from_store(app, "no_speech_cue", false);
+    let no_speech_cue = if sound_enabled && no_speech_sound {
+        Some(audio_cue.cue_for(audio::SoundType::NoSpeech))
+    } else {
+        None
+    };
+
+    let verified_key_delay_ms: u64 = get_setting_from_store(
+        app,
+        "keystroke_verified_delay_ms",
+        commands::text::DEFAULT_VERIFIED_KEY_DELAY_MS,
+    );
+
+    let max_keystroke_chars: usize = get_setting_from_store(
+        app,
+        "max_keystroke_chars",
+        commands::text::DEFAULT_MAX_KEYSTROKE_CHARS,
+    );
+
+    commands::text::OutputOptions {
+        hit_enter,
+        enter_delay_ms: get_setting_from_store::<u64>(
+            app,
+            "output_enter_delay_ms",
+            commands::text::DEFAULT_ENTER_DELAY_MS,
+        )
+        .min(2000),
+        error_cue,
+        no_speech_cue,
+        // Defensive clamp in case settings.json was edited by hand.
+        verified_key_delay_ms: verified_key_delay_ms.clamp(5, 1000),
+        max_keystroke_chars,
+        type_then_paste_chars: get_setting_from_store::<usize>(
+            app,
+            "type_then_paste_chars",
+            commands::text::DEFAULT_TYPE_THEN_PASTE_CHARS,
+        )
+        .min(500),
+        restore_clipboard_after_paste: get_setting_from_store(
+            app,
+            "restore_clipboard_after_paste",
+            true,
+        ),
+        skip_empty_restore: get_setting_from_store(app, "skip_empty_restore", true),
+        max_clipboard_capture_bytes: get_setting_from_store(
+            app,
+            "max_clipboard_capture_bytes",
+            commands::text::DEFAULT_MAX_CLIPBOARD_CAPTURE_BYTES,
+        ),
+        safe_clipboard_restore: get_setting_from_store(app, "safe_clipboard_restore", true),
+        replace_selection: get_setting_from_store(app, "replace_selection", false),
+        clipboard_manager_friendly: get_setting_from_store(app, "clipboard_manager_friendly", false),
+        linux_paste: commands::text::linux_paste_config(app),
+        profile_output: get_setting_from_store(app, "profile_output", false),
+        concurrency: commands::text::OutputConcurrency::from_str(&get_setting_from_store(
+            app,
+            "output_concurrency",
+            "queue".to_string(),
+        )),
+        refocus_target: None,
+        clipboard_append_separator: get_setting_from_store(
+            app,
+            "clipboard_append_separator",
+            commands::text::DEFAULT_CLIPBOARD_APPEND_SEPARATOR.to_string(),
+        ),
+        unicode_input: get_setting_from_store(app, "unicode_keystrokes", true),
+        complex_scripts: commands::text::ComplexScriptOutput::from_str(&get_setting_from_store(
+            app,
+            "complex_script_output",
+            "hybrid".to_string(),
+        )),
+        // Defensive clamps in case settings.json was edited by hand.
+        tts_confirmations: get_setting_from_store(app, "tts_confirmations", false)
+            && !is_suppressed_by_system_dnd(app, "spoken confirmations"),
+        keystroke_tuning: commands::text::KeystrokeTuning {
+            chunk_chars: get_setting_from_store::<usize>(
+                app,
+                "keystroke_chunk_chars",
+                commands::text::DEFAULT_KEYSTROKE_CHUNK_CHARS,
+            )
+            .clamp(1, 256),
+            chunk_delay_ms: get_setting_from_store::<u64>(
+                app,
+                "keystroke_chunk_delay_ms",
+                commands::text::DEFAULT_KEYSTROKE_CHUNK_DELAY_MS,
+            )
+            .min(500),
+            jitter_ms: if get_setting_from_store(app, "keystroke_jitter_enabled", false) {
+                get_setting_from_store::<u64>(
+                    app,
+                    "keystroke_jitter_ms",
+                    commands::text::DEFAULT_KEYSTROKE_JITTER_MS,
+                )
+                .min(500)
+            } else {
+                0
+            },
+        },
+        modifier_wait: commands::text::ModifierWait {
+            max_wait_ms: get_setting_from_store::<u64>(
+                app,
+                "modifier_release_max_wait_ms",
+                commands::text::DEFAULT_MODIFIER_RELEASE_WAIT_MS,
+            )
+            .min(2_000),
+            abort_on_user_input: get_setting_from_store(app, "abort_keystrokes_on_user_input", true),
+        },
+        release_modifiers_before_paste: get_setting_from_store(
+            app,
+            "release_modifiers_before_paste",
+            true,
+        ),
+        direct_insert: get_setting_from_store(app, "direct_insert", false),
+        output_retries: get_setting_from_store(
+            app,
+            "output_retries",
+            commands::text::DEFAULT_OUTPUT_RETRIES,
+        )
+        .min(commands::text::MAX_OUTPUT_RETRIES),
+        app_mode_overrides: app_mode_overrides(app),
+        newline_key: commands::text::NewlineKey::from_str(&get_setting_from_store(
+            app,
+            "keystroke_newline_key",
+            "enter".to_string(),
+        )),
+        newline_key_overrides: get_setting_from_store::<std::collections::HashMap<String, String>>(
+            app,
+            "keystroke_newline_key_overrides",
+            Default::default(),
+        )
+        .into_iter()
+        .map(|(path, key)| {
+            (
+                pipeline::normalize_program_path(&path),
+                commands::text::NewlineKey::from_str(&key),
+            )
+        })
+        .collect(),
+        confirm_before_output: get_setting_from_store(app, "confirm_before_output", false).then(|| {
+            let secs: u64 = get_setting_from_store(app, "confirm_output_timeout_secs", 60);
+            Duration::from_secs(secs.clamp(5, 3600))
+        }),
+        events: Some(app.clone()),
+    }
+}
+
 /// Start recording with sound and audio mute handling
 #[cfg(desktop)]
 fn start_recording(
     app: &AppHandle,
     state: &AppState,
     sound_enabled: bool,
-    audio_cue: audio::AudioCue,
+    audio_cue: audio::CueSelection,
     audio_mute_manager: &Option<tauri::State<'_, AudioMuteManager>>,
     playing_audio_handling: PlayingAudioHandling,
     source: &str,
+    output_mode: Option<commands::text::OutputMode>,
 ) {
     // Log current pipeline state before attempting to start
     let current_state = app
         .try_state::<pipeline::SharedPipeline>()
         .map(|p| p.state());
     log::info!("{}: starting recording (current pipeline state: {:?})", source, current_state);
+    let audio_cue = cue_selection_for_mode(app, output_mode, audio_cue);
     emit_system_event(app, "shortcut", &format!("{}: starting recording", source), Some(&format!("Pipeline state: {:?}", current_state)));
 
     // Start pipeline recording FIRST - if it fails, don't do anything else
@@ -438,6 +982,17 @@ fn start_recording(
             return;
         }
 
+        // Let the UI know if the effective input device changed (e.g. preferred mic was
+        // unplugged and we fell back to the default, or it came back).
+        let active_device = pipeline.active_input_device_name();
+        if let Ok(mut last) = state.last_input_device.lock() {
+            if *last != active_device {
+                log::info!("Effective input device changed: {:?} -> {:?}", *last, active_device);
+                let _ = app.emit("input-device-changed", active_device.clone());
+                *last = active_device;
+            }
+        }
+
         // Pipeline started successfully - now start request logging.
         if let Some(log_store) = app.try_state::<RequestLogStore>() {
             let config = pipeline.config();
@@ -454,11 +1009,28 @@ fn start_recording(
         }
     }
 
+    // Remember where the user was typing so output can be sent back there.
+    if get_setting_from_store(app, "refocus_before_paste", false) {
+        let target = foreground_window::capture_foreground_window();
+        log::debug!("Captured output target window: {:?}", target);
+        if let Ok(mut slot) = state.output_target_window.lock() {
+            *slot = target;
+        }
+    }
+
+    // Mode-bound hotkeys choose the output mode for this recording; others use the default.
+    if let Ok(mut slot) = state.recording_output_mode.lock() {
+        *slot = output_mode;
+    }
+
     // While recording/transcribing, allow Escape to cancel without triggering transcription.
     set_escape_cancel_shortcut_enabled(app, true);
 
     // Pipeline started successfully - now update state and do side effects
     state.is_recording.store(true, Ordering::SeqCst);
+    if let Ok(mut slot) = state.recording_started_at.lock() {
+        *slot = Some(Instant::now());
+    }
 
     // Start the recording chime ASAP.
     // Showing/snapping the overlay window can be a bit slow on some systems (monitor queries,
@@ -470,8 +1042,9 @@ fn start_recording(
         if playing_audio_handling.wants_mute() {
             let app_for_audio = app.clone();
             tauri::async_runtime::spawn(async move {
-                if let Err(e) = audio::play_sound_blocking(audio::SoundType::RecordingStart, audio_cue)
-                {
+                let outcome =
+                    audio::play_sound_blocking(audio::SoundType::RecordingStart, audio_cue);
+                if let Some(e) = outcome.error() {
                     log::warn!("Failed to play start sound: {}", e);
                 }
 
@@ -490,7 +1063,7 @@ fn start_recording(
     // Show overlay if in "recording_only" mode
     let overlay_mode: String =
         get_setting_from_store(app, "overlay_mode", "recording_only".to_string());
-    if overlay_mode == "recording_only" {
+    if overlay_mode == "recording_only" && !is_suppressed_by_system_dnd(app, "overlay") {
         let _ = commands::overlay::show_overlay_with_reset_if_not_always(app);
     }
 
@@ -519,6 +1092,7 @@ fn start_recording(
     // audio side-effects (which may block, e.g. when we ensure the cue finishes
     // before muting system audio).
     let _ = app.emit("recording-start", ());
+    commands::overlay::emit_overlay_state(app, commands::overlay::OverlayState::Recording);
 
     // Mute system audio if enabled.
     // If sound is enabled, mute is deferred until after the cue finishes (see above).
@@ -559,13 +1133,92 @@ fn start_recording(
 
 }
 
+/// Seconds-remaining values for the `recording-countdown` events before an auto-stop after
+/// `max_secs`, one per second, counting down to 1. The countdown never starts before the
+/// recording does.
+#[cfg_attr(not(any(desktop, test)), allow(dead_code))]
+pub(crate) fn recording_countdown_ticks(max_secs: u64, countdown_secs: u64) -> Vec<u64> {
+    (1..=countdown_secs.min(max_secs)).rev().collect()
+}
+
+/// Bumped whenever the pipeline starts recording, so a max-duration watcher can tell
+/// whether the recording it was armed for is still the current one.
+#[cfg(desktop)]
+static RECORDING_GENERATION: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Stop the recording that just started once it has run for `max_recording_seconds`
+/// (0 = no limit), then transcribe what was captured as if the user had stopped it.
+///
+/// Armed from the pipeline's recording state listener, so recordings started by hotkeys,
+/// the UI and commands all get it. Over the last `recording_countdown_seconds` it emits
+/// `recording-countdown` (`{ seconds_remaining }`) once a second so the overlay can warn.
+/// Emits `recording-auto-stopped` so the UI can say why recording ended.
+#[cfg(desktop)]
+fn spawn_max_duration_watcher(app: &AppHandle) {
+    let started = Instant::now();
+    let generation = RECORDING_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let max_secs: u64 = get_setting_from_store(app, "max_recording_seconds", 0u64);
+    if max_secs == 0 {
+        return;
+    }
+    let countdown_secs: u64 = get_setting_from_store(app, "recording_countdown_seconds", 5u64);
+    let ticks = recording_countdown_ticks(max_secs, countdown_secs);
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        // Only act for the recording this watcher was armed for, and only while it's going.
+        let still_recording = || {
+            RECORDING_GENERATION.load(Ordering::SeqCst) == generation
+                && app
+                    .try_state::<pipeline::SharedPipeline>()
+                    .is_some_and(|p| p.state() == pipeline::PipelineState::Recording)
+        };
+
+        let limit = started + Duration::from_secs(max_secs);
+        for seconds_remaining in ticks {
+            let at = limit - Duration::from_secs(seconds_remaining);
+            std::thread::sleep(at.saturating_duration_since(Instant::now()));
+            if !still_recording() {
+                return;
+            }
+            let _ = app.emit(
+                "recording-countdown",
+                serde_json::json!({ "seconds_remaining": seconds_remaining }),
+            );
+        }
+        std::thread::sleep(limit.saturating_duration_since(Instant::now()));
+
+        if !still_recording() {
+            return;
+        }
+        let state = app.state::<AppState>();
+
+        log::info!("Recording reached max_recording_seconds ({}s); stopping", max_secs);
+        let _ = app.emit(
+            "recording-auto-stopped",
+            serde_json::json!({ "reason": "max_duration", "max_recording_seconds": max_secs }),
+        );
+
+        let sound_enabled = cue_sound_enabled(&app);
+        stop_recording(
+            &app,
+            &state,
+            sound_enabled,
+            get_cue_selection(&app),
+            &app.try_state::<AudioMuteManager>(),
+            get_playing_audio_handling(&app),
+            "MaxDuration",
+        );
+    });
+}
+
 /// Stop recording with sound and audio unmute handling
 #[cfg(desktop)]
 fn stop_recording(
     app: &AppHandle,
     state: &AppState,
     sound_enabled: bool,
-    audio_cue: audio::AudioCue,
+    audio_cue: audio::CueSelection,
     audio_mute_manager: &Option<tauri::State<'_, AudioMuteManager>>,
     playing_audio_handling: PlayingAudioHandling,
     source: &str,
@@ -574,13 +1227,41 @@ fn stop_recording(
     log::info!("{}: stopping recording", source);
     emit_system_event(app, "shortcut", &format!("{}: stopping recording", source), None);
 
+    // Get output mode for how to output text: the one picked at recording start by a
+    // mode-bound hotkey, else the default. Its cue override applies to the stop cue too.
+    let recording_output_mode = state
+        .recording_output_mode
+        .lock()
+        .ok()
+        .and_then(|mut slot| slot.take());
+    let audio_cue = cue_selection_for_mode(app, recording_output_mode, audio_cue);
+
     // If hallucination protection (quiet-audio gate) is enabled and the recording is considered
     // effectively quiet, the pipeline will skip STT and immediately return to Idle.
     // In that case, playing the stop sound is misleading, so we only play it if we actually
     // enter Transcribing/Rewriting.
     let quiet_audio_gate_enabled: bool =
         get_setting_from_store(app, "quiet_audio_gate_enabled", true);
-    let play_stop_sound_when_transcribing = sound_enabled && quiet_audio_gate_enabled;
+
+    // An accidental tap of the hotkey shouldn't beep twice in a row.
+    let recorded = state
+        .recording_started_at
+        .lock()
+        .ok()
+        .and_then(|mut slot| slot.take())
+        .map(|started| started.elapsed());
+    let min_recording_ms: u64 =
+        get_setting_from_store(app, "min_recording_ms_for_stop_cue", 0u64);
+    let stop_cue_enabled = sound_enabled && audio::stop_cue_wanted(recorded, min_recording_ms);
+    if sound_enabled && !stop_cue_enabled {
+        log::info!(
+            "{}: recording lasted {:?} (< {}ms); skipping stop cue",
+            source,
+            recorded.unwrap_or_default(),
+            min_recording_ms
+        );
+    }
+    let play_stop_sound_when_transcribing = stop_cue_enabled && quiet_audio_gate_enabled;
 
     // Keep Escape-to-cancel enabled during the transcription phase too.
     set_escape_cancel_shortcut_enabled(app, true);
@@ -593,7 +1274,7 @@ fn stop_recording(
         }
     }
     // If the quiet-audio gate is disabled, play the stop sound immediately as before.
-    if sound_enabled && !quiet_audio_gate_enabled {
+    if stop_cue_enabled && !quiet_audio_gate_enabled {
         audio::play_sound(audio::SoundType::RecordingStop, audio_cue);
     }
 
@@ -610,12 +1291,22 @@ fn stop_recording(
     let overlay_mode: String =
         get_setting_from_store(app, "overlay_mode", "recording_only".to_string());
 
-    // Get output mode for how to output text
-    let output_mode_str: String = get_setting_from_store(app, "output_mode", "paste".to_string());
-    let output_mode = commands::text::OutputMode::from_str(&output_mode_str);
+    let output_mode = recording_output_mode.unwrap_or_else(|| {
+        let output_mode_str: String =
+            get_setting_from_store(app, "output_mode", "paste".to_string());
+        commands::text::OutputMode::from_str(&output_mode_str)
+    });
 
-    // Optional: after pasting, press Enter.
-    let output_hit_enter: bool = get_setting_from_store(app, "output_hit_enter", false);
+    let mut output_options = get_output_options(app, sound_enabled, audio_cue);
+    // Consume the target captured at recording start so a stale handle is never reused.
+    let output_target = state
+        .output_target_window
+        .lock()
+        .ok()
+        .and_then(|mut slot| slot.take());
+    if get_setting_from_store(app, "refocus_before_paste", false) {
+        output_options.refocus_target = output_target;
+    }
 
     // Stop pipeline and trigger transcription in background
     if let Some(pipeline) = app.try_state::<pipeline::SharedPipeline>() {
@@ -640,6 +1331,7 @@ fn stop_recording(
                     None
                 },
                 llm_model: config.llm_config.model.clone(),
+                source_app: crate::windows_apps::get_foreground_process_path(),
             }
         };
 
@@ -659,6 +1351,10 @@ fn stop_recording(
                             pipeline::PipelineState::Transcribing
                             | pipeline::PipelineState::Rewriting => {
                                 let _ = app_for_evt.emit("pipeline-transcription-started", ());
+                                commands::overlay::emit_overlay_state(
+                                    &app_for_evt,
+                                    commands::overlay::OverlayState::Transcribing,
+                                );
 
                                 if should_play_stop_sound {
                                     crate::audio::play_sound(
@@ -694,12 +1390,13 @@ fn stop_recording(
                     ))
                     .clamp(1, 100_000) as usize;
 
+                    commands::history::apply_history_trim_strategy(&app_clone, &history);
+                    commands::history::apply_history_session_gap(&app_clone, &history);
                     let _ = history.add_request_entry(
                         req_id.clone(),
                         model_info,
                         max_saved_recordings,
                     );
-                    let _ = app_clone.emit("history-changed", ());
                 }
             }
 
@@ -716,7 +1413,8 @@ fn stop_recording(
 
                     // Final output after pipeline (STT + optional LLM) normalization.
                     // Quiet recordings should already have been skipped in the pipeline.
-                    let filtered_transcript = sanitize_transcript(&result.final_text);
+                    let filtered_transcript = sanitize_transcript(&result.final_text)
+                        .map(|t| apply_text_transforms(&app_clone, t));
 
                     // Update request log store
                     if let Some(log_store) = app_clone.try_state::<RequestLogStore>() {
@@ -803,24 +1501,48 @@ fn stop_recording(
                     if let Some(ref text) = filtered_transcript {
                         let _ = app_clone.emit("pipeline-transcript-ready", text);
 
-                        // Output the transcript based on mode
-                        if let Err(e) = commands::text::output_text_with_mode(text, output_mode, output_hit_enter) {
-                            log::error!("Failed to output transcript: {}", e);
+                        // Output the transcript based on mode. The confirmation wait runs on a
+                        // blocking worker and the output itself on the main thread.
+                        let output_result = commands::text::output_text_off_thread(
+                            &app_clone,
+                            text.clone(),
+                            output_mode,
+                            output_options.clone(),
+                        )
+                        .await;
+                        match output_result {
+                            Err(e) => {
+                                log::error!("Failed to output transcript: {}", e);
+                                commands::overlay::emit_overlay_state(
+                                    &app_clone,
+                                    commands::overlay::OverlayState::Error,
+                                );
 
-                            if let Some(log_store) = app_clone.try_state::<RequestLogStore>() {
-                                log_store.with_current(|log| {
-                                    log.warn(format!("Output failed: {}", e));
-                                });
+                                if let Some(log_store) = app_clone.try_state::<RequestLogStore>() {
+                                    log_store.with_current(|log| {
+                                        log.warn(format!("Output failed: {}", e));
+                                    });
+                                }
+                            }
+                            Ok(outcome) => {
+                                if let Some(timing) = outcome.timing() {
+                                    let _ = app_clone.emit("output-timing", timing);
+                                }
+                                commands::overlay::emit_overlay_preview_text(&app_clone, text);
+                                commands::overlay::emit_overlay_state(
+                                    &app_clone,
+                                    commands::overlay::OverlayState::Idle,
+                                );
                             }
                         }
 
                         // Save to history
                         if let Some(ref req_id) = request_id {
                             if let Some(history) = app_clone.try_state::<HistoryStorage>() {
+                                commands::history::apply_history_redaction(&app_clone, &history);
                                 if let Err(e) = history.complete_request_success(req_id, text.clone()) {
                                     log::warn!("Failed to update history: {}", e);
                                 }
-                                let _ = app_clone.emit("history-changed", ());
                             }
                         }
 
@@ -830,12 +1552,18 @@ fn stop_recording(
                         // Emit empty transcript event so UI can update appropriately
                         let _ = app_clone.emit("pipeline-transcript-ready", "");
                         log::info!("No transcript output (empty/whitespace), not outputting");
+                        if let Some(cue) = output_options.no_speech_cue {
+                            audio::play_sound(audio::SoundType::NoSpeech, cue);
+                        }
+                        commands::overlay::emit_overlay_state(
+                            &app_clone,
+                            commands::overlay::OverlayState::Idle,
+                        );
 
                         // Mark history entry as success with empty text (keeps timeline consistent)
                         if let Some(ref req_id) = request_id {
                             if let Some(history) = app_clone.try_state::<HistoryStorage>() {
                                 let _ = history.complete_request_success(req_id, String::new());
-                                let _ = app_clone.emit("history-changed", ());
                             }
                         }
 
@@ -846,25 +1574,7 @@ fn stop_recording(
                     // Hide overlay after transcription completes if in "recording_only" mode.
                     // We request a hide so the frontend can animate (zoom-out) before the webview hides.
                     if overlay_mode_clone == "recording_only" {
-                        let _ = app_clone.emit("overlay-hide-requested", ());
-
-                        // Fallback: if the overlay frontend isn't running/listening, hide anyway.
-                        // Re-check the current overlay_mode before hiding to avoid races with settings changes.
-                        if let Some(window) = app_clone.get_webview_window("overlay") {
-                            let window_clone = window.clone();
-                            let app_check = app_clone.clone();
-                            tauri::async_runtime::spawn(async move {
-                                tokio::time::sleep(std::time::Duration::from_millis(220)).await;
-                                let current_mode: String = get_setting_from_store(
-                                    &app_check,
-                                    "overlay_mode",
-                                    "recording_only".to_string(),
-                                );
-                                if current_mode == "recording_only" {
-                                    let _ = window_clone.hide();
-                                }
-                            });
-                        }
+                        commands::overlay::request_overlay_hide(&app_clone, "recording_only");
                     }
                 }
                 Err(e) => {
@@ -882,12 +1592,14 @@ fn stop_recording(
 
                         // Notify frontend and hide overlay if needed.
                         let _ = app_clone.emit("pipeline-cancelled", ());
+                        commands::overlay::emit_overlay_state(
+                            &app_clone,
+                            commands::overlay::OverlayState::Idle,
+                        );
 
                         if overlay_mode_clone == "recording_only" {
                             let _ = app_clone.emit("overlay-hide-requested", ());
-                            if let Some(window) = app_clone.get_webview_window("overlay") {
-                                let _ = window.hide();
-                            }
+                            let _ = commands::overlay::set_overlay_visible(&app_clone, false);
                         }
 
                         // Done - stop stealing Escape.
@@ -901,6 +1613,10 @@ fn stop_recording(
                         "request_id": request_id.clone(),
                     });
                     let _ = app_clone.emit("pipeline-error", payload);
+                    commands::overlay::emit_overlay_state(
+                        &app_clone,
+                        commands::overlay::OverlayState::Error,
+                    );
 
                     if let Some(log_store) = app_clone.try_state::<RequestLogStore>() {
                         log_store.with_current(|log| {
@@ -933,7 +1649,6 @@ fn stop_recording(
                     if let Some(ref req_id) = request_id {
                         if let Some(history) = app_clone.try_state::<HistoryStorage>() {
                             let _ = history.complete_request_error(req_id, e.to_string());
-                            let _ = app_clone.emit("history-changed", ());
                         }
                     }
 
@@ -1022,6 +1737,9 @@ fn set_escape_cancel_shortcut_enabled_inner(app: &AppHandle, enabled: bool) {
 pub(crate) fn cancel_pipeline_session(app: &AppHandle, source: &str) {
     let state = app.state::<AppState>();
 
+    // Output still waiting for confirmation shouldn't be typed after a cancel.
+    commands::text::cancel_pending_confirmations();
+
     // Best-effort: capture the active request id so we can clean up history.
     let active_request_id: Option<String> = app
         .try_state::<RequestLogStore>()
@@ -1048,7 +1766,7 @@ pub(crate) fn cancel_pipeline_session(app: &AppHandle, source: &str) {
     state.ptt_key_held.store(false, Ordering::SeqCst);
 
     // Restore audio side effects (unmute + resume playback if we paused).
-    let sound_enabled: bool = get_setting_from_store(app, "sound_enabled", true);
+    let sound_enabled = cue_sound_enabled(app);
     let playing_audio_handling: PlayingAudioHandling = get_playing_audio_handling(app);
     let audio_mute_manager = app.try_state::<AudioMuteManager>();
 
@@ -1069,10 +1787,15 @@ pub(crate) fn cancel_pipeline_session(app: &AppHandle, source: &str) {
     }
 
     if sound_enabled {
-        let audio_cue_raw: String =
-            get_setting_from_store(app, "audio_cue", "tangerine".to_string());
-        let audio_cue = audio::AudioCue::from_str(&audio_cue_raw);
-        audio::play_sound(audio::SoundType::RecordingStop, audio_cue);
+        let recording_output_mode = state
+            .recording_output_mode
+            .lock()
+            .ok()
+            .and_then(|mut slot| slot.take());
+        audio::play_sound(
+            audio::SoundType::RecordingStop,
+            cue_selection_for_mode(app, recording_output_mode, get_cue_selection(app)),
+        );
     }
 
     // Cancel request log
@@ -1088,7 +1811,6 @@ pub(crate) fn cancel_pipeline_session(app: &AppHandle, source: &str) {
     if let Some(req_id) = active_request_id.as_deref() {
         if let Some(history) = app.try_state::<HistoryStorage>() {
             let _ = history.delete(req_id);
-            let _ = app.emit("history-changed", ());
         }
     }
 
@@ -1101,41 +1823,152 @@ pub(crate) fn cancel_pipeline_session(app: &AppHandle, source: &str) {
     let overlay_mode: String =
         get_setting_from_store(app, "overlay_mode", "recording_only".to_string());
     if overlay_mode == "recording_only" {
-        let _ = app.emit("overlay-hide-requested", ());
-
-        if let Some(window) = app.get_webview_window("overlay") {
-            let window_clone = window.clone();
-            let app_check = app.clone();
-            tauri::async_runtime::spawn(async move {
-                tokio::time::sleep(std::time::Duration::from_millis(220)).await;
-                let current_mode: String = get_setting_from_store(
-                    &app_check,
-                    "overlay_mode",
-                    "recording_only".to_string(),
-                );
-                if current_mode == "recording_only" {
-                    let _ = window_clone.hide();
-                }
-            });
-        }
+        commands::overlay::request_overlay_hide(app, "recording_only");
     }
 
     // Notify frontend
     let _ = app.emit("pipeline-cancelled", ());
+    commands::overlay::emit_overlay_state(app, commands::overlay::OverlayState::Idle);
 
     // Disable Escape shortcut now that we're idle.
     set_escape_cancel_shortcut_enabled(app, false);
 }
 
+/// Longest accepted `double_press_window_ms`.
+const MAX_DOUBLE_PRESS_WINDOW_MS: u64 = 1_000;
+
+/// Whether a toggle release at `now` completes a double press started at `previous`.
+#[cfg_attr(not(any(desktop, test)), allow(dead_code))]
+pub(crate) fn is_double_press(previous: Option<Instant>, now: Instant, window: Duration) -> bool {
+    !window.is_zero() && previous.is_some_and(|previous| now.duration_since(previous) <= window)
+}
+
+/// Start or stop recording for a toggle hotkey release.
+#[cfg(desktop)]
+fn toggle_recording(
+    app: &AppHandle,
+    state: &AppState,
+    sound_enabled: bool,
+    audio_cue: audio::CueSelection,
+    audio_mute_manager: &Option<tauri::State<'_, AudioMuteManager>>,
+    playing_audio_handling: PlayingAudioHandling,
+) {
+    // Check pipeline state directly instead of AppState
+    let pipeline_state = app
+        .try_state::<pipeline::SharedPipeline>()
+        .map(|p| p.state());
+
+    log::info!("Toggle released: pipeline state = {:?}", pipeline_state);
+    emit_system_event(app, "shortcut", "Toggle key released", Some(&format!("Pipeline state: {:?}", pipeline_state)));
+
+    let is_recording = pipeline_state == Some(pipeline::PipelineState::Recording);
+
+    if is_recording {
+        stop_recording(
+            app,
+            state,
+            sound_enabled,
+            audio_cue,
+            audio_mute_manager,
+            playing_audio_handling,
+            "Toggle",
+        );
+    } else {
+        start_recording(
+            app,
+            state,
+            sound_enabled,
+            audio_cue,
+            audio_mute_manager,
+            playing_audio_handling,
+            "Toggle",
+            None,
+        );
+    }
+}
+
+/// Flip the one-shot clipboard-only output target after a double press, with an
+/// `output-target-changed` event and a cue (two pips for clipboard-only, one for back).
+#[cfg(desktop)]
+fn switch_next_output_target(app: &AppHandle, sound_enabled: bool, audio_cue: audio::CueSelection) {
+    let clipboard_only = commands::text::toggle_next_output_clipboard_only();
+    log::info!(
+        "Toggle double-pressed: next output goes to {}",
+        if clipboard_only { "the clipboard only" } else { "the active window" }
+    );
+    let _ = app.emit(
+        "output-target-changed",
+        serde_json::json!({ "clipboard_only": clipboard_only }),
+    );
+    if sound_enabled {
+        audio::play_sound(
+            audio::SoundType::ModeChange(if clipboard_only { 2 } else { 1 }),
+            audio_cue,
+        );
+    }
+}
+
+/// Handle a toggle release when double presses are enabled: wait out the window, and
+/// either run the toggle or, if a second release arrives in time, switch the output target.
+#[cfg(desktop)]
+fn handle_toggle_release_with_double_press(
+    app: &AppHandle,
+    state: &AppState,
+    window: Duration,
+    sound_enabled: bool,
+    audio_cue: audio::CueSelection,
+) {
+    let now = Instant::now();
+    let mut pending = state
+        .pending_toggle_release
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if is_double_press(*pending, now, window) {
+        *pending = None;
+        drop(pending);
+        switch_next_output_target(app, sound_enabled, audio_cue);
+        return;
+    }
+    *pending = Some(now);
+    drop(pending);
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(window);
+        let state = app.state::<AppState>();
+        {
+            let mut pending = state
+                .pending_toggle_release
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            // Consumed by a second press, or superseded by a later one.
+            if *pending != Some(now) {
+                return;
+            }
+            *pending = None;
+        }
+
+        let sound_enabled = cue_sound_enabled(&app);
+        let audio_mute_manager = app.try_state::<AudioMuteManager>();
+        toggle_recording(
+            &app,
+            &state,
+            sound_enabled,
+            get_cue_selection(&app),
+            &audio_mute_manager,
+            get_playing_audio_handling(&app),
+        );
+    });
+}
+
 /// Handle a shortcut event - public so it can be called from commands/settings.rs
 #[cfg(desktop)]
 pub fn handle_shortcut_event(app: &AppHandle, shortcut: &Shortcut, event: &ShortcutEvent) {
     let state = app.state::<AppState>();
 
-    // Get current settings from store
-    let sound_enabled: bool = get_setting_from_store(app, "sound_enabled", true);
-    let audio_cue_raw: String = get_setting_from_store(app, "audio_cue", "tangerine".to_string());
-    let audio_cue = audio::AudioCue::from_str(&audio_cue_raw);
+    // Get current settings from store. Whether cues play is checked only when one is about
+    // to, since it may query the OS Do Not Disturb state.
+    let audio_cue = get_cue_selection(app);
     let playing_audio_handling: PlayingAudioHandling = get_playing_audio_handling(app);
 
     // Get shortcut string for comparison (normalized to handle "ctrl" vs "control" differences)
@@ -1177,6 +2010,14 @@ pub fn handle_shortcut_event(app: &AppHandle, shortcut: &Shortcut, event: &Short
     let is_hold = shortcut_str == hold_shortcut_str;
     let is_paste_last = shortcut_str == paste_last_shortcut_str;
 
+    // Hotkeys bound to a specific output mode (toggle-style recording in that mode)
+    let mode_hotkeys: std::collections::HashMap<String, String> =
+        get_setting_from_store(app, "mode_hotkeys", Default::default());
+    let bound_mode = mode_hotkeys
+        .iter()
+        .find(|(combo, _)| normalize_shortcut_string(combo) == shortcut_str)
+        .map(|(_, mode)| commands::text::OutputMode::from_str(mode));
+
     if is_toggle {
         // Toggle mode: action happens on key release (debounced)
         match event.state {
@@ -1185,35 +2026,26 @@ pub fn handle_shortcut_event(app: &AppHandle, shortcut: &Shortcut, event: &Short
             }
             ShortcutState::Released => {
                 if state.toggle_key_held.swap(false, Ordering::SeqCst) {
-                    // Check pipeline state directly instead of AppState
-                    let pipeline_state = app
-                        .try_state::<pipeline::SharedPipeline>()
-                        .map(|p| p.state());
-
-                    log::info!("Toggle released: pipeline state = {:?}", pipeline_state);
-                    emit_system_event(app, "shortcut", "Toggle key released", Some(&format!("Pipeline state: {:?}", pipeline_state)));
-
-                    let is_recording = pipeline_state == Some(pipeline::PipelineState::Recording);
-
-                    if is_recording {
-                        stop_recording(
+                    let double_press_window = Duration::from_millis(
+                        get_setting_from_store::<u64>(app, "double_press_window_ms", 0)
+                            .min(MAX_DOUBLE_PRESS_WINDOW_MS),
+                    );
+                    if double_press_window.is_zero() {
+                        toggle_recording(
                             app,
                             &state,
-                            sound_enabled,
+                            cue_sound_enabled(app),
                             audio_cue,
                             &audio_mute_manager,
                             playing_audio_handling,
-                            "Toggle",
                         );
                     } else {
-                        start_recording(
+                        handle_toggle_release_with_double_press(
                             app,
                             &state,
-                            sound_enabled,
+                            double_press_window,
+                            cue_sound_enabled(app),
                             audio_cue,
-                            &audio_mute_manager,
-                            playing_audio_handling,
-                            "Toggle",
                         );
                     }
                 }
@@ -1240,11 +2072,12 @@ pub fn handle_shortcut_event(app: &AppHandle, shortcut: &Shortcut, event: &Short
                         start_recording(
                             app,
                             &state,
-                            sound_enabled,
+                            cue_sound_enabled(app),
                             audio_cue,
                             &audio_mute_manager,
                             playing_audio_handling,
                             "Hold",
+                            None,
                         );
                     }
                 }
@@ -1261,7 +2094,7 @@ pub fn handle_shortcut_event(app: &AppHandle, shortcut: &Shortcut, event: &Short
                         stop_recording(
                             app,
                             &state,
-                            sound_enabled,
+                            cue_sound_enabled(app),
                             audio_cue,
                             &audio_mute_manager,
                             playing_audio_handling,
@@ -1287,15 +2120,35 @@ pub fn handle_shortcut_event(app: &AppHandle, shortcut: &Shortcut, event: &Short
                     let output_mode_str: String = get_setting_from_store(app, "output_mode", "paste".to_string());
                     let output_mode = commands::text::OutputMode::from_str(&output_mode_str);
 
-                    let output_hit_enter: bool = get_setting_from_store(app, "output_hit_enter", false);
+                    let output_options = get_output_options(app, cue_sound_enabled(app), audio_cue);
 
                     let history_storage = app.state::<HistoryStorage>();
 
                     if let Ok(entries) = history_storage.get_all(Some(1)) {
                         if let Some(entry) = entries.first() {
-                            if let Err(e) = commands::text::output_text_with_mode(&entry.text, output_mode, output_hit_enter) {
-                                log::error!("Failed to output last transcription: {}", e);
-                            }
+                            // Shortcut events arrive on the main thread; output (which may
+                            // wait for `confirm_output`) must not block it.
+                            let app = app.clone();
+                            let text = entry.text.clone();
+                            tauri::async_runtime::spawn(async move {
+                                let result = commands::text::output_text_off_thread(
+                                    &app,
+                                    text,
+                                    output_mode,
+                                    output_options,
+                                )
+                                .await;
+                                match result {
+                                    Err(e) => {
+                                        log::error!("Failed to output last transcription: {}", e)
+                                    }
+                                    Ok(outcome) => {
+                                        if let Some(timing) = outcome.timing() {
+                                            let _ = app.emit("output-timing", timing);
+                                        }
+                                    }
+                                }
+                            });
                         } else {
                             log::info!("OutputLast: no history entries available");
                         }
@@ -1303,6 +2156,46 @@ pub fn handle_shortcut_event(app: &AppHandle, shortcut: &Shortcut, event: &Short
                 }
             }
         }
+    } else if let Some(mode) = bound_mode {
+        // Mode hotkey: same as toggle (action on release), but output uses `mode`.
+        match event.state {
+            ShortcutState::Pressed => {
+                state.mode_key_held.swap(true, Ordering::SeqCst);
+            }
+            ShortcutState::Released => {
+                if state.mode_key_held.swap(false, Ordering::SeqCst) {
+                    let is_recording = app
+                        .try_state::<pipeline::SharedPipeline>()
+                        .map(|p| p.state() == pipeline::PipelineState::Recording)
+                        .unwrap_or(false);
+
+                    log::info!("Mode hotkey released ({}): recording = {}", mode.to_str(), is_recording);
+
+                    if is_recording {
+                        stop_recording(
+                            app,
+                            &state,
+                            cue_sound_enabled(app),
+                            audio_cue,
+                            &audio_mute_manager,
+                            playing_audio_handling,
+                            "ModeHotkey",
+                        );
+                    } else {
+                        start_recording(
+                            app,
+                            &state,
+                            cue_sound_enabled(app),
+                            audio_cue,
+                            &audio_mute_manager,
+                            playing_audio_handling,
+                            "ModeHotkey",
+                            Some(mode),
+                        );
+                    }
+                }
+            }
+        }
     } else {
         log::warn!("Unknown shortcut: {}", shortcut_str);
     }
@@ -1316,8 +2209,8 @@ fn is_audio_mute_supported() -> bool {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Initialize logger
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    // Initialize logger (also keeps recent lines in memory for `get_recent_logs`)
+    log_buffer::init();
 
     let mut builder = tauri::Builder::default();
 
@@ -1337,36 +2230,113 @@ pub fn run() {
         .manage(AppState::default())
         .invoke_handler(tauri::generate_handler![
             commands::audio::play_audio_cue_preview,
+            commands::audio::set_start_cue,
+            commands::audio::set_stop_cue,
+            commands::audio::install_cue_theme,
+            commands::audio::list_cue_themes,
+            commands::audio::set_active_cue_theme,
+            commands::audio::validate_cue_config,
+            commands::audio::prune_missing_cue_files,
+            commands::audio::save_audio_profile,
+            commands::audio::apply_audio_profile,
+            commands::audio::list_audio_profiles,
+            commands::audio::set_mode_cue_override,
+            commands::audio::list_audio_cues,
+            commands::audio::is_audio_output_available,
+            commands::audio::list_audio_output_devices,
+            commands::audio::preview_all_cues,
+            commands::audio::stop_cue_preview,
+            commands::audio::speak_confirmation,
             commands::audio::list_audio_input_devices,
+            commands::audio::list_audio_input_devices_with_ids,
+            commands::audio::cycle_input_device,
+            commands::audio::set_silence_trim,
+            commands::audio::set_input_muted,
+            commands::audio::diagnose_audio,
+            commands::audio::is_input_muted,
             commands::audio::get_default_audio_input_device_name,
+            commands::audio::capture_test_audio,
+            commands::audio::get_default_audio_input_device_info,
+            commands::audio::get_capture_config,
             commands::text::type_text,
             commands::text::get_server_url,
+            commands::text::list_output_modes,
+            commands::text::benchmark_keystroke_speed,
+            commands::text::is_output_paused,
+            commands::text::confirm_output,
+            commands::text::cancel_output,
+            commands::text::set_output_paused,
+            commands::text::set_output_audit_log,
+            commands::text::get_output_mode,
+            commands::text::set_output_mode,
+            commands::text::cycle_output_mode,
+            commands::text::output_clipboard_text,
+            commands::text::run_output_self_test,
+            commands::text::test_clipboard_roundtrip,
+            commands::text::get_last_clipboard_write,
+            commands::text::output_text_to_file,
+            commands::text::preview_text_transforms,
+            commands::text::check_accessibility_permission,
             commands::settings::register_shortcuts,
+            commands::settings::set_mode_hotkey,
+            commands::settings::validate_hotkey,
+            commands::settings::reset_settings_to_defaults,
+            commands::settings::export_settings,
+            commands::settings::import_settings,
             commands::settings::unregister_shortcuts,
             is_audio_mute_supported,
             commands::history::add_history_entry,
             commands::history::get_history,
+            commands::history::get_history_sessions,
+            commands::history::query_history,
+            commands::history::fuzzy_search_history,
+            commands::history::copy_history_entry,
+            commands::history::export_history_entry,
             commands::history::delete_history_entry,
+            commands::history::delete_history_entries,
+            commands::history::prune_history,
+            commands::history::set_history_entry_pinned,
             commands::history::clear_history,
+            commands::history::flush_history,
+            commands::history::get_history_storage_info,
+            commands::history::compact_history,
             commands::overlay::resize_overlay,
+            commands::overlay::overlay_drag_started,
+            commands::overlay::recreate_overlay,
+            commands::overlay::set_overlay_preset,
             commands::overlay::show_overlay,
             commands::overlay::hide_overlay,
             commands::overlay::set_overlay_mode,
             commands::overlay::set_widget_position,
+            commands::overlay::set_overlay_follow_app,
+            commands::overlay::overlay_pong,
+            commands::overlay::set_overlay_state,
+            commands::overlay::set_overlay_draggable,
+            commands::overlay::is_overlay_draggable,
+            commands::overlay::set_overlay_always_on_top,
+            commands::overlay::set_overlay_shape,
+            commands::overlay::get_overlay_shape,
+            commands::overlay::save_overlay_layout,
+            commands::overlay::apply_overlay_layout,
+            commands::overlay::list_overlay_layouts,
             // Pipeline commands for all-in-app STT
             commands::recording::pipeline_start_recording,
             commands::recording::pipeline_stop_and_transcribe,
             commands::recording::pipeline_cancel,
             commands::recording::pipeline_get_state,
             commands::recording::pipeline_is_recording,
+            commands::recording::get_recording_state,
             commands::recording::pipeline_is_error,
             commands::recording::pipeline_update_config,
             commands::recording::pipeline_dictate,
             commands::recording::pipeline_toggle,
             commands::recording::pipeline_force_reset,
             commands::recording::pipeline_test_transcribe_last_audio,
+            commands::recording::measure_transcription_latency,
             commands::recording::pipeline_has_last_audio,
             commands::recording::pipeline_get_last_recording_diagnostics,
+            commands::recording::get_last_recording_level_stats,
+            commands::recording::set_level_meter_enabled,
             commands::recording::pipeline_test_audio_settings_start_recording,
             commands::recording::pipeline_test_audio_settings_stop_recording,
             commands::recording::pipeline_retry_transcription,
@@ -1380,6 +2350,8 @@ pub fn run() {
             // Config commands (replacing Python server)
             commands::config::get_default_sections,
             commands::config::get_available_providers,
+            commands::config::get_backend_info,
+            commands::config::dump_diagnostics,
             commands::config::sync_pipeline_config,
             // VAD settings commands
             commands::config::get_vad_settings,
@@ -1403,6 +2375,7 @@ pub fn run() {
             // Request logging commands
             commands::logs::get_request_logs,
             commands::logs::clear_request_logs,
+            commands::logs::get_recent_logs,
             // Window/process commands (used for per-program prompts)
             commands::windows::list_open_windows,
             commands::windows::get_foreground_process_path,
@@ -1426,6 +2399,14 @@ pub fn run() {
             app.manage(recording_store);
 
             let history_storage = HistoryStorage::new(app_data_dir);
+            // Request logs and the output audit log redact transcripts with the same patterns.
+            let redactor = history_storage.redactor();
+            // Every mutation is broadcast with what changed, so windows can patch their
+            // history view instead of refetching it.
+            let history_app = app.handle().clone();
+            history_storage.set_change_listener(move |change| {
+                let _ = history_app.emit("history-changed", change);
+            });
             app.manage(history_storage);
 
             // Apply the configured history retention limit immediately so existing installs
@@ -1435,7 +2416,11 @@ pub fn run() {
                 let max_saved_recordings: u64 =
                     get_setting_from_store(app.handle(), "max_saved_recordings", 1000u64);
                 if let Some(history) = app.try_state::<HistoryStorage>() {
+                    commands::history::apply_history_trim_strategy(app.handle(), &history);
                     let _ = history.trim_to(max_saved_recordings as usize);
+                    commands::history::apply_max_history_age(app.handle(), &history);
+                    commands::history::apply_history_redaction(app.handle(), &history);
+                    commands::history::apply_history_session_gap(app.handle(), &history);
                 }
             }
 
@@ -1470,13 +2455,14 @@ pub fn run() {
                     },
                 };
 
-                let request_log_store = request_log::RequestLogStore::new_with_retention(retention);
+                let request_log_store = request_log::RequestLogStore::new_with_retention(retention)
+                    .with_redactor(redactor);
                 app.manage(request_log_store);
             }
 
             #[cfg(not(desktop))]
             {
-                let request_log_store = request_log::RequestLogStore::new();
+                let request_log_store = request_log::RequestLogStore::new().with_redactor(redactor);
                 app.manage(request_log_store);
             }
 
@@ -1489,6 +2475,28 @@ pub fn run() {
             #[cfg(desktop)]
             {
                 let pipeline = initialize_pipeline_from_settings(app.handle());
+
+                // Forward streamed partial transcripts so the overlay can show live text.
+                let partial_app = app.handle().clone();
+                let listener: stt::PartialTranscriptListener = std::sync::Arc::new(move |text: &str| {
+                    let _ = partial_app.emit("partial-transcription", serde_json::json!({ "text": text }));
+                });
+                if let Err(e) = pipeline.set_partial_listener(listener) {
+                    log::warn!("Failed to set partial transcript listener: {}", e);
+                }
+
+                let state_app = app.handle().clone();
+                let state_listener: pipeline::RecordingStateListener =
+                    std::sync::Arc::new(move |state| {
+                        if state == pipeline::RecordingState::Recording {
+                            spawn_max_duration_watcher(&state_app);
+                        }
+                        let _ = state_app.emit(commands::recording::RECORDING_STATE_EVENT, state);
+                    });
+                if let Err(e) = pipeline.set_recording_state_listener(state_listener) {
+                    log::warn!("Failed to set recording state listener: {}", e);
+                }
+
                 app.manage(pipeline);
             }
 
@@ -1511,6 +2519,16 @@ pub fn run() {
                             continue;
                         };
 
+                        if let Some(error) = pipeline.take_capture_failure() {
+                            log::error!("Audio capture failed mid-recording: {}", error);
+                            let device = pipeline.active_input_device_name();
+                            pipeline.fail_capture(&error);
+                            let _ = app_handle.emit(
+                                "capture-failed",
+                                serde_json::json!({ "error": error, "device": device }),
+                            );
+                        }
+
                         // Prefer a non-blocking state check: during pipeline start-up the
                         // mutex may be held while CPAL capture already begins.
                         //
@@ -1518,6 +2536,13 @@ pub fn run() {
                         // allow publishing once the meter seq starts moving.
                         if let Some(state) = pipeline.try_state() {
                             if state != pipeline::PipelineState::Recording {
+                                // Let level meter consumers drop back to silence.
+                                if last_seq != 0 && commands::recording::level_meter_enabled() {
+                                    let _ = app_handle.emit(
+                                        commands::recording::RECORDING_LEVEL_EVENT,
+                                        commands::recording::RecordingLevelEvent::stopped(),
+                                    );
+                                }
                                 last_seq = 0;
                                 last_priming_emit = None;
                                 continue;
@@ -1564,6 +2589,19 @@ pub fn run() {
                         }
                         last_seq = levels.seq;
 
+                        // App-wide level for integrations, whether or not the overlay is shown.
+                        if commands::recording::level_meter_enabled() {
+                            let _ = app_handle.emit(
+                                commands::recording::RECORDING_LEVEL_EVENT,
+                                commands::recording::RecordingLevelEvent {
+                                    seq: levels.seq,
+                                    rms: levels.rms,
+                                    peak: levels.peak,
+                                    recording: true,
+                                },
+                            );
+                        }
+
                         // Waveform buckets (may be all-zeros early or on some devices).
                         let wave = pipeline.audio_waveform_snapshot_fast();
 
@@ -1599,95 +2637,33 @@ pub fn run() {
             }
 
             // Create overlay window
-            let overlay = tauri::WebviewWindowBuilder::new(
-                app,
-                "overlay",
-                tauri::WebviewUrl::App("overlay.html".into()),
-            )
-            .title("Tangerine Overlay")
-            .inner_size(48.0, 48.0)
-            .decorations(false)
-            .transparent(true)
-            .shadow(false)
-            .always_on_top(true)
-            .skip_taskbar(true)
-            .resizable(false)
-            .focused(false)
-            .focusable(false)
-            .accept_first_mouse(true)
-            .visible(true)
-            .visible_on_all_workspaces(true)
-            .background_throttling(BackgroundThrottlingPolicy::Disabled)
-            .build()?;
-
-            // On macOS, convert to NSPanel for better fullscreen app behavior
-            #[cfg(target_os = "macos")]
-            {
-                use tauri_nspanel::{CollectionBehavior, PanelLevel, WebviewWindowExt};
-                match overlay.to_panel::<OverlayPanel>() {
-                    Ok(panel) => {
-                        // Configure panel to float above fullscreen apps
-                        panel.set_level(PanelLevel::ScreenSaver.value());
-                        panel.set_floating_panel(true);
-
-                        // Set collection behavior to appear on all spaces including fullscreen
-                        let behavior = CollectionBehavior::new()
-                            .can_join_all_spaces()
-                            .full_screen_auxiliary();
-                        panel.set_collection_behavior(behavior.value());
-
-                        // Set style mask to non-activating panel
-                        let style = tauri_nspanel::StyleMask::empty().nonactivating_panel();
-                        panel.set_style_mask(style.value());
-
-                        log::info!("[NSPanel] Successfully converted overlay to NSPanel");
-                    }
-                    Err(e) => {
-                        log::error!("[NSPanel] Failed to convert overlay to NSPanel: {:?}", e);
-                    }
-                }
-            }
+            #[cfg(desktop)]
+            create_overlay_window(app.handle())?;
 
-            // Position overlay based on saved setting
-            if let Ok(Some(monitor)) = overlay.current_monitor() {
-                let size = monitor.size();
-                let scale = monitor.scale_factor();
-                let screen_width = size.width as f64 / scale;
-                let screen_height = size.height as f64 / scale;
+            // Opt-in per-output audit log
+            #[cfg(desktop)]
+            commands::text::apply_output_audit_settings(app.handle());
 
-                // Estimate initial widget size (before content loads). The frontend will
-                // auto-resize after mount, but using a closer estimate prevents off-screen drift.
-                let window_width = 264.0;
-                let window_height = 56.0;
-                let margin = 50.0;
+            // User cue theme, if one is active
+            #[cfg(desktop)]
+            commands::audio::apply_active_cue_theme(app.handle());
+            #[cfg(desktop)]
+            commands::audio::apply_cue_settings(app.handle());
 
-                let widget_position: String = get_setting_from_store(
-                    app.handle(),
-                    "widget_position",
-                    "bottom-center".to_string(),
-                );
+            // Detect the audio output device so cues go quiet on headless/remote sessions
+            commands::audio::spawn_output_device_watcher(app.handle().clone());
 
-                let (x, y) = match widget_position.as_str() {
-                    "top-left" => (margin, margin),
-                    "top-center" => ((screen_width - window_width) / 2.0, margin),
-                    "top-right" => (screen_width - window_width - margin, margin),
-                    "center" => (
-                        (screen_width - window_width) / 2.0,
-                        (screen_height - window_height) / 2.0,
-                    ),
-                    "bottom-left" => (margin, screen_height - window_height - margin),
-                    "bottom-center" => (
-                        (screen_width - window_width) / 2.0,
-                        screen_height - window_height - margin,
-                    ),
-                    _ => ( // "bottom-right" or unknown
-                        screen_width - window_width - margin,
-                        screen_height - window_height - margin,
-                    ),
-                };
+            // Keep the overlay on a connected monitor across dock/undock
+            #[cfg(desktop)]
+            commands::overlay::spawn_monitor_watcher(app.handle().clone());
 
-                let _ = overlay.set_position(tauri::Position::Logical(tauri::LogicalPosition { x, y }));
-            }
+            // Optionally keep the overlay on a specific app's window
+            #[cfg(desktop)]
+            commands::overlay::spawn_follow_app_watcher(app.handle().clone());
+
+            // Reload the overlay webview if it hangs
+            #[cfg(desktop)]
+            commands::overlay::spawn_overlay_heartbeat(app.handle().clone());
 
             // Set initial overlay visibility based on saved settings
             #[cfg(desktop)]
@@ -1699,7 +2675,7 @@ pub fn run() {
                 );
                 match overlay_mode.as_str() {
                     "never" | "recording_only" => {
-                        let _ = overlay.hide();
+                        let _ = commands::overlay::set_overlay_visible(app.handle(), false);
                     }
                     _ => {} // "always" - keep visible (default)
                 }
@@ -1710,8 +2686,139 @@ pub fn run() {
 
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                // History writes are debounced; make sure nothing pending is lost on quit.
+                if let Some(history) = app_handle.try_state::<HistoryStorage>() {
+                    if let Err(e) = history.flush() {
+                        log::warn!("Failed to flush history on exit: {}", e);
+                    }
+                }
+            }
+        });
+}
+
+/// Create the overlay window: a small transparent always-on-top widget (an NSPanel on
+/// macOS) placed at the saved `widget_position`. Used at startup and by `recreate_overlay`.
+#[cfg(desktop)]
+pub(crate) fn create_overlay_window(app: &AppHandle) -> tauri::Result<tauri::WebviewWindow> {
+    let overlay_always_on_top: bool = get_setting_from_store(app, "overlay_always_on_top", true);
+    let overlay = tauri::WebviewWindowBuilder::new(
+        app,
+        "overlay",
+        tauri::WebviewUrl::App("overlay.html".into()),
+    )
+    .title("Tangerine Overlay")
+    .inner_size(48.0, 48.0)
+    .decorations(false)
+    .transparent(true)
+    .shadow(false)
+    .always_on_top(overlay_always_on_top)
+    .skip_taskbar(true)
+    .resizable(false)
+    .focused(false)
+    .focusable(false)
+    .accept_first_mouse(true)
+    .visible(true)
+    .visible_on_all_workspaces(true)
+    .background_throttling(BackgroundThrottlingPolicy::Disabled)
+    .build()?;
+
+    // Keep the overlay's logical size when it's dragged to a display with another DPI,
+    // and track drags so resizes wait for the window to settle.
+    let overlay_app = app.clone();
+    overlay.on_window_event(move |event| match event {
+        tauri::WindowEvent::ScaleFactorChanged {
+            scale_factor,
+            new_inner_size,
+            ..
+        } => {
+            commands::overlay::handle_overlay_scale_change(
+                &overlay_app,
+                *scale_factor,
+                (new_inner_size.width, new_inner_size.height),
+            );
+        }
+        tauri::WindowEvent::Moved(_) => commands::overlay::note_overlay_moved(),
+        _ => {}
+    });
+
+    // On macOS, convert to NSPanel for better fullscreen app behavior
+    #[cfg(target_os = "macos")]
+    {
+        use tauri_nspanel::{CollectionBehavior, PanelLevel, WebviewWindowExt};
+        match overlay.to_panel::<OverlayPanel>() {
+            Ok(panel) => {
+                // Configure panel to float above fullscreen apps
+                panel.set_level(if overlay_always_on_top {
+                    PanelLevel::ScreenSaver.value()
+                } else {
+                    PanelLevel::Normal.value()
+                });
+                panel.set_floating_panel(true);
+
+                // Set collection behavior to appear on all spaces including fullscreen
+                let behavior = CollectionBehavior::new()
+                    .can_join_all_spaces()
+                    .full_screen_auxiliary();
+                panel.set_collection_behavior(behavior.value());
+
+                // Set style mask to non-activating panel
+                let style = tauri_nspanel::StyleMask::empty().nonactivating_panel();
+                panel.set_style_mask(style.value());
+
+                log::info!("[NSPanel] Successfully converted overlay to NSPanel");
+            }
+            Err(e) => {
+                log::error!("[NSPanel] Failed to convert overlay to NSPanel: {:?}", e);
+            }
+        }
+    }
+
+    // Position overlay based on saved setting
+    if let Ok(Some(monitor)) = overlay.current_monitor() {
+        let size = monitor.size();
+        let scale = monitor.scale_factor();
+        let screen_width = size.width as f64 / scale;
+        let screen_height = size.height as f64 / scale;
+
+        // Estimate initial widget size (before content loads). The frontend will
+        // auto-resize after mount, but using a closer estimate prevents off-screen drift.
+        let window_width = 264.0;
+        let window_height = 56.0;
+        let margin = 50.0;
+
+        let widget_position: String = get_setting_from_store(
+            app,
+            "widget_position",
+            "bottom-center".to_string(),
+        );
+
+        let (x, y) = match widget_position.as_str() {
+            "top-left" => (margin, margin),
+            "top-center" => ((screen_width - window_width) / 2.0, margin),
+            "top-right" => (screen_width - window_width - margin, margin),
+            "center" => (
+                (screen_width - window_width) / 2.0,
+                (screen_height - window_height) / 2.0,
+            ),
+            "bottom-left" => (margin, screen_height - window_height - margin),
+            "bottom-center" => (
+                (screen_width - window_width) / 2.0,
+                screen_height - window_height - margin,
+            ),
+            _ => ( // "bottom-right" or unknown
+                screen_width - window_width - margin,
+                screen_height - window_height - margin,
+            ),
+        };
+
+        let _ = overlay.set_position(tauri::Position::Logical(tauri::LogicalPosition { x, y }));
+    }
+
+    Ok(overlay)
 }
 
 fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
@@ -1783,6 +2890,9 @@ fn initialize_pipeline_from_settings(app: &AppHandle) -> pipeline::SharedPipelin
     use std::time::Duration;
     use std::collections::HashMap;
 
+    // Apply before any provider (and its HTTP client) is created.
+    http_client::set_proxy_url(get_setting_from_store(app, "proxy_url", None));
+
     // Read STT settings from store
     let stt_provider: String = get_setting_from_store(app, "stt_provider", "groq".to_string());
 
@@ -1937,6 +3047,12 @@ fn initialize_pipeline_from_settings(app: &AppHandle) -> pipeline::SharedPipelin
         "audio_noise_suppression_enabled",
         default_pipeline_config.audio_noise_suppression_enabled,
     );
+    let capture_preference = audio_capture::CapturePreference {
+        sample_rate: get_setting_from_store::<Option<u32>>(app, "audio_capture_sample_rate", None)
+            .filter(|&rate| rate > 0),
+        channels: get_setting_from_store::<Option<u16>>(app, "audio_capture_channels", None)
+            .filter(|&channels| channels > 0),
+    };
 
     let quiet_audio_require_speech: bool = get_setting_from_store(
         app,
@@ -2063,6 +3179,7 @@ fn initialize_pipeline_from_settings(app: &AppHandle) -> pipeline::SharedPipelin
         quiet_audio_rms_dbfs_threshold,
         quiet_audio_peak_dbfs_threshold,
 
+        silence_trim: silence_trim_config(app),
         noise_gate_threshold_dbfs,
 
         audio_downmix_to_mono,
@@ -2070,6 +3187,8 @@ fn initialize_pipeline_from_settings(app: &AppHandle) -> pipeline::SharedPipelin
         audio_highpass_enabled,
         audio_agc_enabled,
         audio_noise_suppression_enabled,
+        capture_preference,
+        recording_start: recording_start_config(app),
 
         quiet_audio_require_speech,
 
@@ -2101,6 +3220,31 @@ fn initialize_pipeline_from_settings(app: &AppHandle) -> pipeline::SharedPipelin
     pipeline::SharedPipeline::new(config)
 }
 
+/// Stop-time silence trim from the `silence_trim_*` settings; `None` when it's off.
+#[cfg(desktop)]
+fn silence_trim_config(app: &AppHandle) -> Option<audio_capture::SilenceTrimConfig> {
+    let enabled: bool = get_setting_from_store(app, "silence_trim_enabled", false);
+    enabled.then(|| audio_capture::SilenceTrimConfig {
+        threshold_dbfs: get_setting_from_store(
+            app,
+            "silence_trim_threshold_dbfs",
+            audio_capture::DEFAULT_SILENCE_TRIM_THRESHOLD_DBFS,
+        ),
+        max_trim_ms: get_setting_from_store(
+            app,
+            "silence_trim_max_ms",
+            audio_capture::DEFAULT_SILENCE_TRIM_MAX_MS,
+        ),
+    })
+}
+
+/// Lead-in or pre-roll from `recording_start_delay_ms`.
+#[cfg(desktop)]
+fn recording_start_config(app: &AppHandle) -> audio_capture::RecordingStart {
+    let delay_ms: i64 = get_setting_from_store(app, "recording_start_delay_ms", 0);
+    audio_capture::RecordingStart::from_delay_ms(delay_ms)
+}
+
 /// Register shortcuts from store settings (called from setup() after store plugin is available)
 #[cfg(desktop)]
 fn register_initial_shortcuts(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
@@ -2127,7 +3271,8 @@ fn register_initial_shortcuts(app: &AppHandle) -> Result<(), Box<dyn std::error:
         paste_last_hotkey.to_shortcut_string()
     );
 
-    let shortcuts: Vec<Shortcut> = vec![toggle_shortcut, hold_shortcut, paste_last_shortcut];
+    let mut shortcuts: Vec<Shortcut> = vec![toggle_shortcut, hold_shortcut, paste_last_shortcut];
+    shortcuts.extend(mode_hotkey_shortcuts(app, &shortcuts));
 
     app.global_shortcut()
         .on_shortcuts(shortcuts, |app, shortcut, event| {
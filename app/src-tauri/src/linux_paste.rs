@@ -0,0 +1,321 @@
+//! How to send the paste shortcut on Linux.
+//!
+//! Simulated input (enigo, via XTest) works on X11, and on Wayland only reaches XWayland
+//! windows. Native Wayland clients ignore it, so on Wayland we shell out to `wtype`
+//! (virtual-keyboard protocol) when it's installed, and otherwise fail with an error that
+//! says what to do instead of pasting into the void. The session type comes from the
+//! environment (`XDG_SESSION_TYPE`, then `WAYLAND_DISPLAY` / `DISPLAY`) and is detected
+//! once per process, along with which helper tools are on `PATH`.
+//!
+//! Terminals paste with Ctrl+Shift+V. `linux_paste_shortcut` picks the shortcut; in
+//! `auto` it's Ctrl+Shift+V when the focused window's class is in `linux_shift_paste_apps`
+//! (X11 only, via xdotool; Wayland doesn't expose other clients' windows).
+
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+/// Window classes (`xdotool getwindowclassname`) of terminals that paste with Ctrl+Shift+V.
+pub const DEFAULT_SHIFT_PASTE_APPS: &[&str] = &[
+    "Gnome-terminal",
+    "org.gnome.Ptyxis",
+    "konsole",
+    "Alacritty",
+    "kitty",
+    "Tilix",
+    "Terminator",
+    "Xfce4-terminal",
+    "org.wezfurlong.wezterm",
+    "foot",
+    "st-256color",
+];
+
+/// Shown when nothing can inject the paste shortcut.
+pub const WAYLAND_BLOCKED_HINT: &str = "This Wayland session doesn't accept simulated key \
+     presses, so the paste shortcut can't be sent. Install `wtype`, log in to an X11 \
+     session, or switch the output mode to Clipboard and paste manually.";
+
+/// `linux_paste_shortcut` setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PasteShortcutMode {
+    /// Ctrl+Shift+V for apps in `linux_shift_paste_apps`, Ctrl+V otherwise.
+    #[default]
+    Auto,
+    CtrlV,
+    CtrlShiftV,
+}
+
+impl PasteShortcutMode {
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "ctrl_v" => Self::CtrlV,
+            "ctrl_shift_v" => Self::CtrlShiftV,
+            _ => Self::Auto,
+        }
+    }
+}
+
+/// The shortcut actually sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasteShortcut {
+    CtrlV,
+    CtrlShiftV,
+}
+
+/// Linux paste settings, resolved by the caller.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinuxPasteConfig {
+    pub shortcut: PasteShortcutMode,
+    pub shift_paste_apps: Vec<String>,
+}
+
+impl Default for LinuxPasteConfig {
+    fn default() -> Self {
+        Self {
+            shortcut: PasteShortcutMode::Auto,
+            shift_paste_apps: DEFAULT_SHIFT_PASTE_APPS
+                .iter()
+                .map(|app| app.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Display server of the current session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionType {
+    X11,
+    Wayland,
+    Unknown,
+}
+
+/// How to deliver the shortcut.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InjectionPath {
+    Enigo,
+    Wtype,
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub fn detect_session() -> SessionType {
+    session_from_env(|name| std::env::var(name).ok())
+}
+
+/// Session type from environment variables, looked up through `var`.
+#[cfg_attr(not(any(target_os = "linux", test)), allow(dead_code))]
+fn session_from_env(var: impl Fn(&str) -> Option<String>) -> SessionType {
+    let is_set = |name: &str| var(name).is_some_and(|value| !value.trim().is_empty());
+
+    match var("XDG_SESSION_TYPE")
+        .map(|value| value.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("wayland") => SessionType::Wayland,
+        Some("x11") => SessionType::X11,
+        // "tty" or unset (e.g. launched from a script): fall back to the display variables.
+        _ if is_set("WAYLAND_DISPLAY") => SessionType::Wayland,
+        _ if is_set("DISPLAY") => SessionType::X11,
+        _ => SessionType::Unknown,
+    }
+}
+
+/// Pick the injection path. On Wayland `wtype` is preferred since it reaches native
+/// clients; XTest through XWayland only reaches X11 windows but is better than nothing.
+#[cfg_attr(not(any(target_os = "linux", test)), allow(dead_code))]
+fn choose_path(
+    session: SessionType,
+    xwayland: bool,
+    wtype_available: bool,
+) -> Result<InjectionPath, String> {
+    match session {
+        SessionType::X11 | SessionType::Unknown => Ok(InjectionPath::Enigo),
+        SessionType::Wayland if wtype_available => Ok(InjectionPath::Wtype),
+        SessionType::Wayland if xwayland => Ok(InjectionPath::Enigo),
+        SessionType::Wayland => Err(WAYLAND_BLOCKED_HINT.to_string()),
+    }
+}
+
+/// Pick the shortcut for the focused window class (`None` when it can't be determined).
+#[cfg_attr(not(any(target_os = "linux", test)), allow(dead_code))]
+fn choose_shortcut(config: &LinuxPasteConfig, focused_class: Option<&str>) -> PasteShortcut {
+    match config.shortcut {
+        PasteShortcutMode::CtrlV => PasteShortcut::CtrlV,
+        PasteShortcutMode::CtrlShiftV => PasteShortcut::CtrlShiftV,
+        PasteShortcutMode::Auto => {
+            let is_terminal = focused_class.is_some_and(|class| {
+                let class = class.trim();
+                config
+                    .shift_paste_apps
+                    .iter()
+                    .any(|app| app.trim().eq_ignore_ascii_case(class))
+            });
+            if is_terminal {
+                PasteShortcut::CtrlShiftV
+            } else {
+                PasteShortcut::CtrlV
+            }
+        }
+    }
+}
+
+/// `wtype` arguments for `shortcut`.
+#[cfg_attr(not(any(target_os = "linux", test)), allow(dead_code))]
+fn wtype_args(shortcut: PasteShortcut) -> Vec<&'static str> {
+    match shortcut {
+        PasteShortcut::CtrlV => vec!["-M", "ctrl", "-k", "v", "-m", "ctrl"],
+        PasteShortcut::CtrlShiftV => {
+            vec!["-M", "ctrl", "-M", "shift", "-k", "v", "-m", "shift", "-m", "ctrl"]
+        }
+    }
+}
+
+/// Whether `name` is an executable on `PATH`.
+#[cfg(target_os = "linux")]
+fn on_path(name: &str) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    std::env::var_os("PATH").is_some_and(|path| {
+        std::env::split_paths(&path).any(|dir| {
+            std::fs::metadata(dir.join(name))
+                .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+        })
+    })
+}
+
+/// Class of the focused X11 window, via xdotool.
+#[cfg(target_os = "linux")]
+fn focused_window_class() -> Option<String> {
+    let output = Command::new("xdotool")
+        .args(["getactivewindow", "getwindowclassname"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let class = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!class.is_empty()).then_some(class)
+}
+
+/// What `plan` needs to know about the session. None of it changes while we run.
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy)]
+struct SessionInfo {
+    session: SessionType,
+    xwayland: bool,
+    wtype: bool,
+    xdotool: bool,
+}
+
+/// Detected once, on the first paste, instead of re-reading the environment and scanning
+/// `PATH` every time.
+#[cfg(target_os = "linux")]
+fn session_info() -> SessionInfo {
+    static INFO: std::sync::OnceLock<SessionInfo> = std::sync::OnceLock::new();
+    *INFO.get_or_init(|| {
+        let info = SessionInfo {
+            session: detect_session(),
+            xwayland: std::env::var("DISPLAY").is_ok_and(|display| !display.trim().is_empty()),
+            wtype: on_path("wtype"),
+            xdotool: on_path("xdotool"),
+        };
+        log::info!("Linux paste session: {:?}", info);
+        info
+    })
+}
+
+/// Decide how to paste in the current session. Errors carry a message for the user.
+#[cfg(target_os = "linux")]
+pub fn plan(config: &LinuxPasteConfig) -> Result<(InjectionPath, PasteShortcut), String> {
+    let SessionInfo {
+        session,
+        xwayland,
+        wtype,
+        xdotool,
+    } = session_info();
+    let path = choose_path(session, xwayland, session == SessionType::Wayland && wtype)?;
+    if session == SessionType::Wayland && path == InjectionPath::Enigo {
+        log::warn!("Wayland session without wtype: paste only reaches XWayland windows");
+    }
+
+    // Only spawn xdotool for the focused window when the answer matters and X11 can tell us.
+    let focused_class = (config.shortcut == PasteShortcutMode::Auto
+        && !config.shift_paste_apps.is_empty()
+        && session != SessionType::Wayland
+        && xdotool)
+        .then(focused_window_class)
+        .flatten();
+    Ok((path, choose_shortcut(config, focused_class.as_deref())))
+}
+
+/// Send `shortcut` through `wtype`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub fn send_with_wtype(shortcut: PasteShortcut) -> Result<(), String> {
+    let output = Command::new("wtype")
+        .args(wtype_args(shortcut))
+        .output()
+        .map_err(|e| format!("Failed to run wtype: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "wtype failed ({}): {}. {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim(),
+            WAYLAND_BLOCKED_HINT
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        }
+    }
+
+    #[test]
+    fn test_session_detection_and_path() {
+        assert_eq!(session_from_env(env(&[("XDG_SESSION_TYPE", "wayland")])), SessionType::Wayland);
+        assert_eq!(
+            session_from_env(env(&[("XDG_SESSION_TYPE", "x11"), ("WAYLAND_DISPLAY", "wayland-0")])),
+            SessionType::X11
+        );
+        assert_eq!(
+            session_from_env(env(&[("XDG_SESSION_TYPE", "tty"), ("WAYLAND_DISPLAY", "wayland-0")])),
+            SessionType::Wayland
+        );
+        assert_eq!(session_from_env(env(&[("DISPLAY", ":0")])), SessionType::X11);
+        assert_eq!(session_from_env(env(&[("DISPLAY", " ")])), SessionType::Unknown);
+
+        assert_eq!(choose_path(SessionType::X11, false, false), Ok(InjectionPath::Enigo));
+        assert_eq!(choose_path(SessionType::Wayland, true, true), Ok(InjectionPath::Wtype));
+        assert_eq!(choose_path(SessionType::Wayland, true, false), Ok(InjectionPath::Enigo));
+        assert_eq!(
+            choose_path(SessionType::Wayland, false, false),
+            Err(WAYLAND_BLOCKED_HINT.to_string())
+        );
+    }
+
+    #[test]
+    fn test_shortcut_choice() {
+        let auto = LinuxPasteConfig::default();
+        assert_eq!(choose_shortcut(&auto, Some("kitty")), PasteShortcut::CtrlShiftV);
+        assert_eq!(choose_shortcut(&auto, Some("gnome-terminal")), PasteShortcut::CtrlShiftV);
+        assert_eq!(choose_shortcut(&auto, Some("firefox")), PasteShortcut::CtrlV);
+        assert_eq!(choose_shortcut(&auto, None), PasteShortcut::CtrlV);
+
+        let forced = LinuxPasteConfig {
+            shortcut: PasteShortcutMode::CtrlShiftV,
+            ..LinuxPasteConfig::default()
+        };
+        assert_eq!(choose_shortcut(&forced, None), PasteShortcut::CtrlShiftV);
+        assert_eq!(PasteShortcutMode::from_str("ctrl_v"), PasteShortcutMode::CtrlV);
+        assert_eq!(PasteShortcutMode::from_str("bogus"), PasteShortcutMode::Auto);
+        assert_eq!(wtype_args(PasteShortcut::CtrlV), ["-M", "ctrl", "-k", "v", "-m", "ctrl"]);
+    }
+}
@@ -26,7 +26,7 @@ impl AnthropicLlmProvider {
     /// Create a new Anthropic provider with the given API key
     pub fn new(api_key: String) -> Self {
         Self {
-            client: Client::new(),
+            client: crate::http_client::client(),
             api_key,
             model: DEFAULT_MODEL.to_string(),
             timeout: Some(DEFAULT_LLM_TIMEOUT),
@@ -38,7 +38,7 @@ impl AnthropicLlmProvider {
     /// Create with a specific model
     pub fn with_model(api_key: String, model: String) -> Self {
         Self {
-            client: Client::new(),
+            client: crate::http_client::client(),
             api_key,
             model,
             timeout: Some(DEFAULT_LLM_TIMEOUT),
@@ -26,7 +26,7 @@ impl OllamaLlmProvider {
     /// Create a new Ollama provider with default settings
     pub fn new() -> Self {
         Self {
-            client: Client::new(),
+            client: crate::http_client::client(),
             base_url: DEFAULT_OLLAMA_URL.to_string(),
             model: DEFAULT_MODEL.to_string(),
             timeout: Some(DEFAULT_OLLAMA_TIMEOUT),
@@ -38,7 +38,7 @@ impl OllamaLlmProvider {
     #[cfg_attr(not(test), allow(dead_code))]
     pub fn with_model(model: String) -> Self {
         Self {
-            client: Client::new(),
+            client: crate::http_client::client(),
             base_url: DEFAULT_OLLAMA_URL.to_string(),
             model,
             timeout: Some(DEFAULT_OLLAMA_TIMEOUT),
@@ -49,7 +49,7 @@ impl OllamaLlmProvider {
     /// Create with custom URL and model
     pub fn with_url(base_url: String, model: Option<String>) -> Self {
         Self {
-            client: Client::new(),
+            client: crate::http_client::client(),
             base_url,
             model: model.unwrap_or_else(|| DEFAULT_MODEL.to_string()),
             timeout: Some(DEFAULT_OLLAMA_TIMEOUT),
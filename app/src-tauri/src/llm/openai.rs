@@ -26,7 +26,7 @@ impl OpenAiLlmProvider {
     /// Create a new OpenAI provider with the given API key
     pub fn new(api_key: String) -> Self {
         Self {
-            client: Client::new(),
+            client: crate::http_client::client(),
             api_key,
             model: DEFAULT_MODEL.to_string(),
             timeout: Some(DEFAULT_LLM_TIMEOUT),
@@ -39,7 +39,7 @@ impl OpenAiLlmProvider {
     /// Create with a specific model
     pub fn with_model(api_key: String, model: String) -> Self {
         Self {
-            client: Client::new(),
+            client: crate::http_client::client(),
             api_key,
             model,
             timeout: Some(DEFAULT_LLM_TIMEOUT),
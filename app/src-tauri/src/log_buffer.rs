@@ -0,0 +1,123 @@
+//! In-memory ring buffer of recent log lines, for `get_recent_logs`.
+//!
+//! Wraps the env_logger backend: every record that passes the env filter is written as
+//! before and also kept in memory (the last `LOG_BUFFER_CAPACITY` lines), so the UI can
+//! show and copy logs for a bug report without the user hunting for a file.
+
+use chrono::{DateTime, Utc};
+use log::{Level, Log, Metadata, Record};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Number of lines kept; older lines are dropped first.
+pub const LOG_BUFFER_CAPACITY: usize = 500;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogLine {
+    pub timestamp: DateTime<Utc>,
+    /// "error", "warn", "info", "debug" or "trace".
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+static BUFFER: Mutex<VecDeque<LogLine>> = Mutex::new(VecDeque::new());
+
+/// env_logger plus the ring buffer.
+struct BufferedLogger {
+    inner: env_logger::Logger,
+}
+
+impl Log for BufferedLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.inner.matches(record) {
+            return;
+        }
+        push(
+            &mut BUFFER.lock().unwrap_or_else(|e| e.into_inner()),
+            LogLine {
+                timestamp: Utc::now(),
+                level: record.level().as_str().to_ascii_lowercase(),
+                target: record.target().to_string(),
+                message: record.args().to_string(),
+            },
+            LOG_BUFFER_CAPACITY,
+        );
+        self.inner.log(record);
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// Install the logger (env_logger, `RUST_LOG`, default `info`) with the ring buffer.
+pub fn init() {
+    let inner = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
+        .build();
+    let max_level = inner.filter();
+    if log::set_boxed_logger(Box::new(BufferedLogger { inner })).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+fn push(buffer: &mut VecDeque<LogLine>, line: LogLine, capacity: usize) {
+    while buffer.len() >= capacity {
+        buffer.pop_front();
+    }
+    buffer.push_back(line);
+}
+
+/// Buffered lines, oldest first, at `min_level` or more severe (all lines for `None`).
+pub fn recent(min_level: Option<Level>) -> Vec<LogLine> {
+    filter_lines(&BUFFER.lock().unwrap_or_else(|e| e.into_inner()), min_level)
+}
+
+fn filter_lines(buffer: &VecDeque<LogLine>, min_level: Option<Level>) -> Vec<LogLine> {
+    buffer
+        .iter()
+        .filter(|line| {
+            min_level.is_none_or(|min| {
+                line.level
+                    .parse::<Level>()
+                    .is_ok_and(|level| level <= min)
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(level: Level, message: &str) -> LogLine {
+        LogLine {
+            timestamp: Utc::now(),
+            level: level.as_str().to_ascii_lowercase(),
+            target: "test".to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_ring_buffer_drops_oldest_and_filters_by_level() {
+        let mut buffer = VecDeque::new();
+        push(&mut buffer, line(Level::Info, "one"), 3);
+        push(&mut buffer, line(Level::Warn, "two"), 3);
+        push(&mut buffer, line(Level::Debug, "three"), 3);
+        push(&mut buffer, line(Level::Error, "four"), 3);
+
+        let messages = |lines: Vec<LogLine>| -> Vec<String> {
+            lines.into_iter().map(|line| line.message).collect()
+        };
+        assert_eq!(messages(filter_lines(&buffer, None)), ["two", "three", "four"]);
+        assert_eq!(messages(filter_lines(&buffer, Some(Level::Warn))), ["two", "four"]);
+        assert_eq!(messages(filter_lines(&buffer, Some(Level::Error))), ["four"]);
+    }
+}
@@ -0,0 +1,198 @@
+//! Query which modifier keys are physically held down right now.
+//!
+//! Used before synthesizing input so we only release modifiers that are actually stuck
+//! from the hotkey, instead of blindly sending releases that can clobber a modifier the
+//! user is holding on purpose.
+
+use enigo::Key;
+
+/// Physical keyboard state at one instant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyActivity {
+    /// Modifiers currently held.
+    pub modifiers: Vec<Key>,
+    /// Whether any non-modifier key is held, i.e. the user is typing.
+    pub other_keys: bool,
+}
+
+/// Modifiers currently held, or `None` if this platform can't report it.
+pub fn held_modifiers() -> Option<Vec<Key>> {
+    platform::held_modifiers()
+}
+
+/// Modifiers and other keys currently held, or `None` if this platform can't report it.
+pub fn key_activity() -> Option<KeyActivity> {
+    Some(KeyActivity {
+        modifiers: platform::held_modifiers()?,
+        other_keys: platform::other_keys_held()?,
+    })
+}
+
+/// macOS virtual keycodes from Command (0x36) through Fn (0x3F): all modifiers.
+#[cfg_attr(not(any(target_os = "macos", test)), allow(dead_code))]
+const MAC_MODIFIER_KEYCODES: std::ops::RangeInclusive<u16> = 0x36..=0x3F;
+
+/// Whether a macOS virtual keycode is a regular (non-modifier) key.
+#[cfg_attr(not(any(target_os = "macos", test)), allow(dead_code))]
+fn is_mac_regular_keycode(keycode: u16) -> bool {
+    keycode <= 0x7E && !MAC_MODIFIER_KEYCODES.contains(&keycode)
+}
+
+// CGEventFlags masks (CoreGraphics/CGEventTypes.h).
+const CG_FLAG_SHIFT: u64 = 0x0002_0000;
+const CG_FLAG_CONTROL: u64 = 0x0004_0000;
+const CG_FLAG_ALTERNATE: u64 = 0x0008_0000;
+const CG_FLAG_COMMAND: u64 = 0x0010_0000;
+
+/// Decode macOS `CGEventFlags` into the modifier keys they represent.
+#[cfg_attr(not(any(target_os = "macos", test)), allow(dead_code))]
+fn keys_from_cg_flags(flags: u64) -> Vec<Key> {
+    [
+        (CG_FLAG_CONTROL, Key::Control),
+        (CG_FLAG_ALTERNATE, Key::Alt),
+        (CG_FLAG_SHIFT, Key::Shift),
+        (CG_FLAG_COMMAND, Key::Meta),
+    ]
+    .into_iter()
+    .filter(|(mask, _)| flags & mask != 0)
+    .map(|(_, key)| key)
+    .collect()
+}
+
+/// Whether a Windows virtual-key code is a regular key: not a mouse button, modifier or
+/// IME/lock toggle.
+#[cfg_attr(not(any(target_os = "windows", test)), allow(dead_code))]
+fn is_windows_regular_vk(vk: u16) -> bool {
+    !matches!(
+        vk,
+        // Mouse buttons
+        0x01..=0x06
+            // Shift, Ctrl, Alt, Pause, Caps Lock
+            | 0x10..=0x14
+            // IME mode keys
+            | 0x15..=0x1A | 0x1C..=0x1F
+            // Windows keys, Apps
+            | 0x5B..=0x5D
+            // Num Lock, Scroll Lock
+            | 0x90 | 0x91
+            // Left/right Shift, Ctrl, Alt
+            | 0xA0..=0xA5
+    )
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use enigo::Key;
+
+    /// kCGEventSourceStateHIDSystemState: the hardware state, independent of our own events.
+    const HID_SYSTEM_STATE: i32 = 1;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceFlagsState(state_id: i32) -> u64;
+        fn CGEventSourceKeyState(state_id: i32, key: u16) -> bool;
+    }
+
+    pub fn held_modifiers() -> Option<Vec<Key>> {
+        // SAFETY: pure query with a valid state id constant.
+        let flags = unsafe { CGEventSourceFlagsState(HID_SYSTEM_STATE) };
+        Some(super::keys_from_cg_flags(flags))
+    }
+
+    pub fn other_keys_held() -> Option<bool> {
+        Some(
+            (0u16..=0x7E)
+                .filter(|&keycode| super::is_mac_regular_keycode(keycode))
+                // SAFETY: pure query with a valid state id and keycode.
+                .any(|keycode| unsafe { CGEventSourceKeyState(HID_SYSTEM_STATE, keycode) }),
+        )
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use enigo::Key;
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        GetAsyncKeyState, VIRTUAL_KEY, VK_CONTROL, VK_LWIN, VK_MENU, VK_RWIN, VK_SHIFT,
+    };
+
+    fn is_down(vk: VIRTUAL_KEY) -> bool {
+        // High bit set => key is currently down.
+        (unsafe { GetAsyncKeyState(vk.0 as i32) } as u16) & 0x8000 != 0
+    }
+
+    pub fn held_modifiers() -> Option<Vec<Key>> {
+        let mut held = Vec::new();
+        if is_down(VK_CONTROL) {
+            held.push(Key::Control);
+        }
+        if is_down(VK_MENU) {
+            held.push(Key::Alt);
+        }
+        if is_down(VK_SHIFT) {
+            held.push(Key::Shift);
+        }
+        if is_down(VK_LWIN) || is_down(VK_RWIN) {
+            held.push(Key::Meta);
+        }
+        Some(held)
+    }
+
+    pub fn other_keys_held() -> Option<bool> {
+        Some(
+            (0x08u16..=0xFE)
+                .filter(|&vk| super::is_windows_regular_vk(vk))
+                .any(|vk| is_down(VIRTUAL_KEY(vk))),
+        )
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    use enigo::Key;
+
+    /// No portable way to read key state on Linux (X11 vs Wayland); callers fall back.
+    pub fn held_modifiers() -> Option<Vec<Key>> {
+        None
+    }
+
+    pub fn other_keys_held() -> Option<bool> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_from_cg_flags() {
+        assert!(keys_from_cg_flags(0).is_empty());
+        assert_eq!(
+            keys_from_cg_flags(CG_FLAG_COMMAND | CG_FLAG_SHIFT),
+            vec![Key::Shift, Key::Meta]
+        );
+        // Unrelated bits (e.g. caps lock 0x10000) are ignored.
+        assert_eq!(keys_from_cg_flags(0x0001_0000 | CG_FLAG_CONTROL), vec![Key::Control]);
+    }
+
+    #[test]
+    fn test_regular_key_codes_exclude_modifiers() {
+        // 'A', Space, Return vs. Shift, Ctrl, Left Win.
+        assert!(is_windows_regular_vk(0x41));
+        assert!(is_windows_regular_vk(0x20));
+        assert!(is_windows_regular_vk(0x0D));
+        assert!(!is_windows_regular_vk(0x10));
+        assert!(!is_windows_regular_vk(0xA2));
+        assert!(!is_windows_regular_vk(0x5B));
+        assert!(!is_windows_regular_vk(0x01));
+
+        // 'a' (0x00), Space (0x31) vs. Command (0x37), Option (0x3A), Fn (0x3F).
+        assert!(is_mac_regular_keycode(0x00));
+        assert!(is_mac_regular_keycode(0x31));
+        assert!(!is_mac_regular_keycode(0x37));
+        assert!(!is_mac_regular_keycode(0x3A));
+        assert!(!is_mac_regular_keycode(0x3F));
+        assert!(!is_mac_regular_keycode(0x80));
+    }
+}
@@ -0,0 +1,181 @@
+//! Opt-in audit log of every output attempt (`output_audit_log_enabled`).
+//!
+//! Writes one JSON line per output (timestamp, mode, char count, target app, result) to a
+//! file separate from the main app log, so intermittent paste failures can be attached to
+//! a bug report. The transcript text itself is only included when explicitly enabled.
+//! The file rotates to `<name>.1` once it reaches `MAX_AUDIT_LOG_BYTES`.
+
+use crate::redaction::Redactor;
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Size at which the audit log is rotated (one previous file is kept).
+pub const MAX_AUDIT_LOG_BYTES: u64 = 1024 * 1024;
+
+/// File name used in the app data directory when no path is configured.
+pub const DEFAULT_AUDIT_LOG_FILE: &str = "output-audit.log";
+
+/// Where and what to log. `None` in `CONFIG` means auditing is off.
+#[derive(Debug, Clone)]
+pub struct AuditConfig {
+    pub path: PathBuf,
+    pub include_text: bool,
+    /// Applied to the text when `include_text` is on.
+    pub redactor: Redactor,
+}
+
+/// Held while writing so concurrent outputs can't interleave or race a rotation.
+static CONFIG: Mutex<Option<AuditConfig>> = Mutex::new(None);
+
+/// Enable (`Some`) or disable (`None`) the audit log.
+pub fn configure(config: Option<AuditConfig>) {
+    if let Ok(mut slot) = CONFIG.lock() {
+        *slot = config;
+    }
+}
+
+pub fn is_enabled() -> bool {
+    CONFIG.lock().map(|c| c.is_some()).unwrap_or(false)
+}
+
+#[derive(Debug, Serialize)]
+struct OutputAuditRecord<'a> {
+    timestamp: String,
+    requested_mode: &'a str,
+    mode: &'a str,
+    chars: usize,
+    target_app: Option<&'a str>,
+    result: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+}
+
+/// Append one output attempt to the audit log. No-op when auditing is off; write errors
+/// are logged and otherwise ignored (auditing must never break output).
+pub fn record(
+    requested_mode: &str,
+    mode: &str,
+    text: &str,
+    target_app: Option<&str>,
+    result: &str,
+) {
+    let Ok(config) = CONFIG.lock() else {
+        return;
+    };
+    let Some(config) = config.as_ref() else {
+        return;
+    };
+
+    let line = match record_line(config, requested_mode, mode, text, target_app, result) {
+        Ok(line) => line,
+        Err(e) => {
+            log::warn!("Failed to serialize output audit record: {}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = append_line(&config.path, &line, MAX_AUDIT_LOG_BYTES) {
+        log::warn!(
+            "Failed to write output audit log {}: {}",
+            config.path.display(),
+            e
+        );
+    }
+}
+
+/// Serialize one audit record. Included text is redacted like every other stored transcript.
+fn record_line(
+    config: &AuditConfig,
+    requested_mode: &str,
+    mode: &str,
+    text: &str,
+    target_app: Option<&str>,
+    result: &str,
+) -> serde_json::Result<String> {
+    let entry = OutputAuditRecord {
+        timestamp: chrono::Local::now().to_rfc3339(),
+        requested_mode,
+        mode,
+        chars: text.chars().count(),
+        target_app,
+        result,
+        text: config
+            .include_text
+            .then(|| config.redactor.apply(text.to_string())),
+    };
+    serde_json::to_string(&entry)
+}
+
+/// `<path>.1`, the single rotated-out file.
+fn rotated_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".1");
+    path.with_file_name(name)
+}
+
+/// Append `line`, first rotating the file if the line would push it past `max_bytes`.
+fn append_line(path: &Path, line: &str, max_bytes: u64) -> std::io::Result<()> {
+    if let Ok(meta) = fs::metadata(path) {
+        if meta.len() > 0 && meta.len() + line.len() as u64 + 1 > max_bytes {
+            fs::rename(path, rotated_path(path))?;
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_append_line_rotates_at_size_cap() {
+        let dir = std::env::temp_dir().join(format!("tangerine-audit-test-{}", Uuid::new_v4()));
+        let path = dir.join("audit.log");
+
+        append_line(&path, "first", 12).unwrap();
+        append_line(&path, "second", 12).unwrap();
+        append_line(&path, "third", 12).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "third\n");
+        assert_eq!(fs::read_to_string(rotated_path(&path)).unwrap(), "second\n");
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_rotated_path_appends_suffix() {
+        assert_eq!(
+            rotated_path(Path::new("/tmp/output-audit.log")),
+            PathBuf::from("/tmp/output-audit.log.1")
+        );
+    }
+
+    #[test]
+    fn test_record_line_redacts_included_text() {
+        let mut config = AuditConfig {
+            path: PathBuf::from("audit.log"),
+            include_text: false,
+            redactor: Redactor::default(),
+        };
+        config.redactor.set(&[r"hunter\d+".to_string()], false);
+
+        let line = record_line(&config, "paste", "paste", "pw hunter22", None, "ok").unwrap();
+        assert!(!line.contains("\"text\""));
+        assert!(line.contains("\"chars\":11"));
+
+        config.include_text = true;
+        let line = record_line(&config, "paste", "paste", "pw hunter22", None, "ok").unwrap();
+        assert!(line.contains("\"text\":\"pw [redacted]\""));
+        assert!(!line.contains("hunter"));
+    }
+}
@@ -0,0 +1,449 @@
+//! The system interactions behind text output, as a trait.
+//!
+//! Every output mode in `commands::text` (pasting with clipboard capture and restore,
+//! copying, appending, typing, key combos, Enter) goes through `OutputBackend` instead of
+//! calling arboard and enigo directly. `SystemBackend` is the real thing; in tests
+//! `MockOutputBackend` records every call (and skips the sleeps), so the sequence an output
+//! produces can be asserted without a desktop.
+
+use crate::accessibility;
+use crate::clipboard_snapshot::{self, ClipboardSnapshot};
+use crate::commands::error::CommandError;
+use crate::clipboard_restore::{
+    restore_snapshot_within, set_concealed_text, supersede_clipboard_restores, ClipboardWrite,
+    TimedClipboard,
+};
+use crate::linux_paste::LinuxPasteConfig;
+use crate::modifier_state::{self, KeyActivity};
+use crate::secure_input;
+use crate::unicode_input;
+use arboard::Clipboard;
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+/// Delay between keyboard key press and release events
+pub(crate) const KEY_EVENT_DELAY_MS: u64 = 50;
+
+/// Set by `set_output_paused`. In-memory only, so a restart always unpauses.
+pub(crate) static OUTPUT_PAUSED: AtomicBool = AtomicBool::new(false);
+
+/// Set by double-pressing the toggle hotkey: the next output only copies to the clipboard.
+/// One-shot; the output that consumes it clears it. In-memory only.
+pub(crate) static NEXT_OUTPUT_CLIPBOARD_ONLY: AtomicBool = AtomicBool::new(false);
+
+pub trait OutputBackend {
+    fn clipboard_text(&mut self) -> Result<String, CommandError>;
+    fn set_clipboard_text(&mut self, text: &str) -> Result<(), CommandError>;
+    /// Set text that's only on the clipboard for a paste, hidden from clipboard managers
+    /// (`clipboard_manager_friendly`).
+    fn set_concealed_clipboard_text(&mut self, text: &str) -> Result<(), CommandError>;
+    fn clear_clipboard(&mut self) -> Result<(), CommandError>;
+    /// Whether the clipboard can be opened at all.
+    fn clipboard_available(&mut self) -> bool;
+    /// Every clipboard format, for restoring rich content after a paste (see
+    /// `clipboard_snapshot`). `None` where only text can be restored.
+    fn snapshot_clipboard(&mut self, _max_bytes: usize) -> Option<ClipboardSnapshot> {
+        None
+    }
+    fn restore_clipboard_snapshot(
+        &mut self,
+        _snapshot: &ClipboardSnapshot,
+        _concealed: bool,
+    ) -> Result<(), String> {
+        Err("full clipboard restore is not supported".to_string())
+    }
+    /// Put earlier clipboard text back after an output. Unlike `set_clipboard_text` this
+    /// doesn't supersede pending restores, and gives up if the clipboard hangs.
+    fn restore_clipboard_text(&mut self, text: &str, concealed: bool) -> Result<(), CommandError>;
+    fn key(&mut self, key: Key, direction: Direction) -> Result<(), CommandError>;
+    /// Type `text`. With `unicode`, layout-sensitive symbols go as Unicode input events
+    /// where the platform supports them (see `unicode_input`); everything else goes
+    /// through the keyboard layout.
+    fn text(&mut self, text: &str, unicode: bool) -> Result<(), CommandError>;
+    /// Send the paste shortcut: Ctrl+V / Cmd+V, or per `linux_paste` on Linux.
+    fn paste(&mut self, linux_paste: &LinuxPasteConfig) -> Result<(), CommandError>;
+    /// Physical keyboard state, or `None` where the platform can't report it.
+    fn key_activity(&mut self) -> Option<KeyActivity>;
+    fn held_modifiers(&mut self) -> Option<Vec<Key>>;
+    /// Whether output is paused (`set_output_paused`).
+    fn output_paused(&mut self) -> bool;
+    /// Consume the one-shot clipboard-only target (`toggle_next_output_clipboard_only`).
+    fn take_clipboard_only(&mut self) -> bool;
+    /// Whether the focused field is a password field (see `secure_input`).
+    fn secure_field_focused(&mut self) -> bool;
+    /// Whether the OS lets us simulate input (macOS Accessibility).
+    fn input_permitted(&mut self) -> bool;
+    fn sleep(&mut self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+thread_local! {
+    /// Enigo reused by every `SystemBackend` on this thread. Creating one per output opens a
+    /// new event source / display connection each time, which is slow on some platforms and
+    /// can fail transiently under rapid triggering.
+    ///
+    /// Thread affinity: Enigo isn't `Send` and its macOS backend (HIToolbox) must stay on the
+    /// main thread, which is where output runs (`output_text_off_thread`). A thread-local
+    /// instance is only ever used and dropped on the thread that created it.
+    static SHARED_ENIGO: RefCell<Option<Enigo>> = const { RefCell::new(None) };
+}
+
+/// Run `f` with the value cached in `slot`, creating it first if needed. The value is
+/// dropped after an input injection error so the next use starts from a fresh one. A nested
+/// call (the slot is already in use) gets a throwaway value instead.
+fn with_cached<T, R>(
+    slot: &RefCell<Option<T>>,
+    create: impl FnOnce() -> Result<T, CommandError>,
+    f: impl FnOnce(&mut T) -> Result<R, CommandError>,
+) -> Result<R, CommandError> {
+    let Ok(mut slot) = slot.try_borrow_mut() else {
+        return f(&mut create()?);
+    };
+    if slot.is_none() {
+        *slot = Some(create()?);
+    }
+    let result = f(slot.as_mut().expect("slot was just filled"));
+    if matches!(result, Err(CommandError::InputInjectionFailed(_))) {
+        log::debug!("Input injection failed; recreating the shared Enigo on next use");
+        *slot = None;
+    }
+    result
+}
+
+/// arboard for the clipboard and enigo for the keyboard.
+///
+/// The clipboard is opened on first use and kept for the backend's lifetime. Enigo comes
+/// from `SHARED_ENIGO` and is only created once input is actually sent, so clipboard-only
+/// output never needs input permission.
+#[derive(Default)]
+pub struct SystemBackend {
+    clipboard: Option<Clipboard>,
+}
+
+impl SystemBackend {
+    fn clipboard(&mut self) -> Result<&mut Clipboard, CommandError> {
+        if self.clipboard.is_none() {
+            self.clipboard = Some(Clipboard::new().map_err(CommandError::clipboard)?);
+        }
+        Ok(self.clipboard.as_mut().expect("clipboard was just opened"))
+    }
+
+    fn with_enigo<T>(
+        &mut self,
+        f: impl FnOnce(&mut Enigo) -> Result<T, enigo::InputError>,
+    ) -> Result<T, CommandError> {
+        SHARED_ENIGO.with(|slot| {
+            with_cached(
+                slot,
+                || Enigo::new(&Settings::default()).map_err(CommandError::input),
+                |enigo| f(enigo).map_err(CommandError::input),
+            )
+        })
+    }
+}
+
+impl OutputBackend for SystemBackend {
+    fn clipboard_text(&mut self) -> Result<String, CommandError> {
+        self.clipboard()?.get_text().map_err(CommandError::clipboard)
+    }
+
+    fn set_clipboard_text(&mut self, text: &str) -> Result<(), CommandError> {
+        supersede_clipboard_restores();
+        self.clipboard()?.set_text(text).map_err(CommandError::clipboard)
+    }
+
+    fn set_concealed_clipboard_text(&mut self, text: &str) -> Result<(), CommandError> {
+        supersede_clipboard_restores();
+        set_concealed_text(self.clipboard()?, text).map_err(CommandError::clipboard)
+    }
+
+    fn clear_clipboard(&mut self) -> Result<(), CommandError> {
+        self.clipboard()?.clear().map_err(CommandError::clipboard)
+    }
+
+    fn clipboard_available(&mut self) -> bool {
+        self.clipboard().is_ok()
+    }
+
+    fn snapshot_clipboard(&mut self, max_bytes: usize) -> Option<ClipboardSnapshot> {
+        clipboard_snapshot::capture(max_bytes)
+    }
+
+    fn restore_clipboard_snapshot(
+        &mut self,
+        snapshot: &ClipboardSnapshot,
+        concealed: bool,
+    ) -> Result<(), String> {
+        restore_snapshot_within(snapshot, concealed)
+    }
+
+    fn restore_clipboard_text(&mut self, text: &str, concealed: bool) -> Result<(), CommandError> {
+        TimedClipboard::new(concealed).write_text(text)
+    }
+
+    fn key(&mut self, key: Key, direction: Direction) -> Result<(), CommandError> {
+        self.with_enigo(|enigo| enigo.key(key, direction))
+    }
+
+    fn text(&mut self, text: &str, unicode: bool) -> Result<(), CommandError> {
+        if !unicode || !unicode_input::is_supported() {
+            return self.with_enigo(|enigo| enigo.text(text));
+        }
+        // Symbols like '@' and '{' sit on different keys across layouts; send just those
+        // as Unicode events.
+        for (run, layout_sensitive) in unicode_input::split_layout_sensitive(text) {
+            if layout_sensitive {
+                unicode_input::send_text(run).map_err(CommandError::input)?;
+            } else {
+                self.with_enigo(|enigo| enigo.text(run))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn paste(&mut self, linux_paste: &LinuxPasteConfig) -> Result<(), CommandError> {
+        #[cfg(target_os = "linux")]
+        {
+            use crate::linux_paste::{self, InjectionPath, PasteShortcut};
+
+            let (path, shortcut) =
+                linux_paste::plan(linux_paste).map_err(CommandError::InputInjectionFailed)?;
+            match (path, shortcut) {
+                (InjectionPath::Wtype, shortcut) => linux_paste::send_with_wtype(shortcut)
+                    .map_err(CommandError::InputInjectionFailed),
+                (InjectionPath::Enigo, PasteShortcut::CtrlV) => {
+                    send_command_shortcut(self, 'v')
+                }
+                (InjectionPath::Enigo, PasteShortcut::CtrlShiftV) => {
+                    send_key_combo(self, &[Key::Control, Key::Shift], 'v')
+                }
+            }
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = linux_paste;
+            send_command_shortcut(self, 'v')
+        }
+    }
+
+    fn key_activity(&mut self) -> Option<KeyActivity> {
+        modifier_state::key_activity()
+    }
+
+    fn held_modifiers(&mut self) -> Option<Vec<Key>> {
+        modifier_state::held_modifiers()
+    }
+
+    fn output_paused(&mut self) -> bool {
+        OUTPUT_PAUSED.load(Ordering::SeqCst)
+    }
+
+    fn take_clipboard_only(&mut self) -> bool {
+        NEXT_OUTPUT_CLIPBOARD_ONLY.swap(false, Ordering::SeqCst)
+    }
+
+    fn secure_field_focused(&mut self) -> bool {
+        secure_input::is_secure_field_focused()
+    }
+
+    fn input_permitted(&mut self) -> bool {
+        accessibility::is_accessibility_trusted()
+    }
+}
+
+/// Send Ctrl+`key` (Cmd+`key` on macOS).
+pub(crate) fn send_command_shortcut(
+    backend: &mut impl OutputBackend,
+    key: char,
+) -> Result<(), CommandError> {
+    #[cfg(target_os = "macos")]
+    let modifier = Key::Meta;
+    #[cfg(not(target_os = "macos"))]
+    let modifier = Key::Control;
+
+    send_key_combo(backend, &[modifier], key)
+}
+
+/// Hold `modifiers` (in order), click `key`, then release them in reverse order.
+pub(crate) fn send_key_combo(
+    backend: &mut impl OutputBackend,
+    modifiers: &[Key],
+    key: char,
+) -> Result<(), CommandError> {
+    for modifier in modifiers {
+        backend.key(*modifier, Direction::Press)?;
+        backend.sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
+    }
+    backend.key(Key::Unicode(key), Direction::Click)?;
+    for modifier in modifiers.iter().rev() {
+        backend.sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
+        backend.key(*modifier, Direction::Release)?;
+    }
+    Ok(())
+}
+
+/// A call made through `MockOutputBackend`.
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum BackendCall {
+    GetClipboard,
+    SetClipboard(String),
+    SetConcealedClipboard(String),
+    ClearClipboard,
+    RestoreClipboard(String),
+    Key(Key, Direction),
+    Text(String),
+    Paste,
+    Sleep(Duration),
+}
+
+/// Records calls and keeps an in-memory clipboard. Sleeps are recorded, not slept, and no
+/// keys are ever held. The system checks report the fields below, never the real state.
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct MockOutputBackend {
+    pub clipboard: String,
+    pub calls: Vec<BackendCall>,
+    pub paused: bool,
+    pub clipboard_only: bool,
+    pub secure_field: bool,
+    pub input_denied: bool,
+}
+
+#[cfg(test)]
+impl MockOutputBackend {
+    pub fn with_clipboard(text: &str) -> Self {
+        Self {
+            clipboard: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Recorded calls without the sleeps, for asserting just the input sequence.
+    pub fn actions(&self) -> Vec<BackendCall> {
+        self.calls
+            .iter()
+            .filter(|call| !matches!(call, BackendCall::Sleep(_)))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+impl OutputBackend for MockOutputBackend {
+    fn clipboard_text(&mut self) -> Result<String, CommandError> {
+        self.calls.push(BackendCall::GetClipboard);
+        Ok(self.clipboard.clone())
+    }
+
+    fn set_clipboard_text(&mut self, text: &str) -> Result<(), CommandError> {
+        self.calls.push(BackendCall::SetClipboard(text.to_string()));
+        self.clipboard = text.to_string();
+        Ok(())
+    }
+
+    fn set_concealed_clipboard_text(&mut self, text: &str) -> Result<(), CommandError> {
+        self.calls.push(BackendCall::SetConcealedClipboard(text.to_string()));
+        self.clipboard = text.to_string();
+        Ok(())
+    }
+
+    fn clear_clipboard(&mut self) -> Result<(), CommandError> {
+        self.calls.push(BackendCall::ClearClipboard);
+        self.clipboard.clear();
+        Ok(())
+    }
+
+    fn clipboard_available(&mut self) -> bool {
+        true
+    }
+
+    fn restore_clipboard_text(&mut self, text: &str, _concealed: bool) -> Result<(), CommandError> {
+        self.calls.push(BackendCall::RestoreClipboard(text.to_string()));
+        self.clipboard = text.to_string();
+        Ok(())
+    }
+
+    fn key(&mut self, key: Key, direction: Direction) -> Result<(), CommandError> {
+        self.calls.push(BackendCall::Key(key, direction));
+        Ok(())
+    }
+
+    fn text(&mut self, text: &str, _unicode: bool) -> Result<(), CommandError> {
+        self.calls.push(BackendCall::Text(text.to_string()));
+        Ok(())
+    }
+
+    fn paste(&mut self, _linux_paste: &LinuxPasteConfig) -> Result<(), CommandError> {
+        self.calls.push(BackendCall::Paste);
+        Ok(())
+    }
+
+    fn key_activity(&mut self) -> Option<KeyActivity> {
+        Some(KeyActivity {
+            modifiers: Vec::new(),
+            other_keys: false,
+        })
+    }
+
+    fn held_modifiers(&mut self) -> Option<Vec<Key>> {
+        Some(Vec::new())
+    }
+
+    fn output_paused(&mut self) -> bool {
+        self.paused
+    }
+
+    fn take_clipboard_only(&mut self) -> bool {
+        std::mem::take(&mut self.clipboard_only)
+    }
+
+    fn secure_field_focused(&mut self) -> bool {
+        self.secure_field
+    }
+
+    fn input_permitted(&mut self) -> bool {
+        !self.input_denied
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        self.calls.push(BackendCall::Sleep(duration));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_cached_value_is_reused_and_rebuilt_after_input_errors() {
+        let slot = RefCell::new(None);
+        let created = Cell::new(0);
+        let create = || {
+            created.set(created.get() + 1);
+            Ok(created.get())
+        };
+        let run = |outcome: Result<(), CommandError>| {
+            with_cached(&slot, create, |id: &mut u32| outcome.map(|_| *id))
+        };
+
+        assert_eq!(run(Ok(())).unwrap(), 1);
+        assert_eq!(run(Ok(())).unwrap(), 1);
+        // Other failures keep the instance; input injection failures replace it.
+        assert!(run(Err(CommandError::clipboard("busy"))).is_err());
+        assert_eq!(run(Ok(())).unwrap(), 1);
+        assert!(run(Err(CommandError::input("rejected"))).is_err());
+        assert_eq!(run(Ok(())).unwrap(), 2);
+
+        // A nested use gets its own instance and leaves the cached one alone.
+        let nested = with_cached(&slot, create, |_| run(Ok(())));
+        assert_eq!(nested.unwrap(), 3);
+        assert_eq!(run(Ok(())).unwrap(), 2);
+    }
+}
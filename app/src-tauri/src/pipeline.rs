@@ -15,19 +15,19 @@
 //! - Multiple provider support (OpenAI, Anthropic, Ollama)
 //! - Configurable prompts for dictation cleanup
 
-use crate::audio_capture::{AudioCapture, AudioCaptureDiagnostics, AudioCaptureError, AudioCaptureEvent, AudioEncodeConfig, AudioLevelSnapshot, AudioLevelStats, VadAutoStopConfig};
+use crate::audio_capture::{AudioCapture, AudioCaptureDiagnostics, CaptureConfig, CapturePreference, AudioCaptureError, AudioCaptureEvent, AudioEncodeConfig, AudioLevelSnapshot, AudioLevelStats, RecordingStart, SilenceTrimConfig, VadAutoStopConfig};
 use crate::llm::{
     format_text, AnthropicLlmProvider, GeminiLlmProvider, GroqLlmProvider, LlmConfig, LlmError,
     LlmProvider, OllamaLlmProvider, OpenAiLlmProvider,
 };
 use crate::request_log::RequestLogStore;
-use crate::stt::{AudioFormat, RetryConfig, SttError, SttProvider, SttRegistry, with_retry};
+use crate::stt::{AudioFormat, PartialTranscriptListener, RetryConfig, SttError, SttProvider, SttRegistry, with_retry};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio_util::sync::CancellationToken;
 
-fn normalize_program_path(path: &str) -> String {
+pub(crate) fn normalize_program_path(path: &str) -> String {
     // Windows comparisons are case-insensitive, and we want to treat / and \ equivalently.
     path.replace('/', "\\").to_lowercase()
 }
@@ -159,6 +159,9 @@ pub enum PipelineError {
 
     #[error("Recording too large: {0} bytes exceeds limit of {1} bytes")]
     RecordingTooLarge(usize, usize),
+
+    #[error("STT server unreachable: {0}")]
+    Unreachable(String),
 }
 
 /// Pipeline state machine
@@ -196,6 +199,30 @@ impl PipelineState {
     }
 }
 
+/// Coarse recording state for the UI and integrations: what the user would call the
+/// pipeline's state. `Rewriting` counts as `Transcribing` and `Error` as `Idle`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingState {
+    Idle,
+    Recording,
+    Transcribing,
+}
+
+impl From<PipelineState> for RecordingState {
+    fn from(state: PipelineState) -> Self {
+        match state {
+            PipelineState::Idle | PipelineState::Error => RecordingState::Idle,
+            PipelineState::Recording => RecordingState::Recording,
+            PipelineState::Transcribing | PipelineState::Rewriting => RecordingState::Transcribing,
+        }
+    }
+}
+
+/// Called with the new `RecordingState` on every transition. Runs with the pipeline locked,
+/// so it must not call back into the pipeline.
+pub type RecordingStateListener = Arc<dyn Fn(RecordingState) + Send + Sync>;
+
 /// Events emitted by the pipeline
 #[cfg_attr(not(test), allow(dead_code))]
 #[derive(Debug, Clone)]
@@ -255,6 +282,61 @@ pub struct TranscriptionResult {
     pub llm_outcome: LlmOutcome,
 }
 
+/// Round-trip timing for one transcription request, from `measure_transcription_latency`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TranscriptionLatency {
+    pub provider: String,
+    pub model: Option<String>,
+    /// Whether the last captured recording was sent (`true`) or the generated sample.
+    pub used_last_audio: bool,
+    /// Length of the audio that was sent, in milliseconds.
+    pub audio_ms: u64,
+    pub audio_bytes: usize,
+    /// Time from sending the request to receiving the final transcript, in milliseconds.
+    pub total_ms: u64,
+    pub text_chars: usize,
+}
+
+/// Length of the generated latency sample.
+const LATENCY_SAMPLE_MS: u32 = 1_000;
+
+/// A short, quiet 16 kHz mono tone as WAV bytes, sent by `measure_transcription_latency`
+/// when nothing has been recorded yet.
+fn latency_sample_wav() -> Vec<u8> {
+    let sample_rate = 16_000;
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut cursor = std::io::Cursor::new(Vec::new());
+    {
+        let mut writer =
+            hound::WavWriter::new(&mut cursor, spec).expect("writing to memory can't fail");
+        for i in 0..sample_rate * LATENCY_SAMPLE_MS / 1_000 {
+            let t = i as f32 / sample_rate as f32;
+            let sample = (t * 440.0 * std::f32::consts::TAU).sin() * 0.1;
+            writer
+                .write_sample((sample * i16::MAX as f32) as i16)
+                .expect("writing to memory can't fail");
+        }
+        writer.finalize().expect("writing to memory can't fail");
+    }
+    cursor.into_inner()
+}
+
+/// Duration of WAV `bytes` in milliseconds, or 0 if they don't parse.
+fn wav_duration_ms(bytes: &[u8]) -> u64 {
+    hound::WavReader::new(std::io::Cursor::new(bytes))
+        .map(|reader| {
+            let spec = reader.spec();
+            let frames = reader.duration() as u64;
+            frames * 1_000 / spec.sample_rate.max(1) as u64
+        })
+        .unwrap_or(0)
+}
+
 impl TranscriptionResult {
     pub fn llm_attempted(&self) -> bool {
         !matches!(self.llm_outcome, LlmOutcome::NotAttempted)
@@ -302,6 +384,10 @@ pub struct PipelineConfig {
     /// Peak threshold (in dBFS) below which the audio is considered quiet.
     pub quiet_audio_peak_dbfs_threshold: f32,
 
+    /// Trim leading/trailing silence at stop-time, before any other processing. `None`
+    /// disables it.
+    pub silence_trim: Option<SilenceTrimConfig>,
+
     /// Optional noise gate threshold (dBFS), applied at stop-time before WAV encoding.
     ///
     /// Recommended range: -75..-30. `None` disables the noise gate.
@@ -320,6 +406,11 @@ pub struct PipelineConfig {
     pub audio_agc_enabled: bool,
     /// Apply a lightweight noise suppression.
     pub audio_noise_suppression_enabled: bool,
+    /// Preferred capture sample rate/channel count (e.g. 16kHz mono), falling back to the
+    /// device default when unsupported.
+    pub capture_preference: CapturePreference,
+    /// Lead-in dropped from, or pre-roll added to, the start of each recording.
+    pub recording_start: RecordingStart,
 
     // ------------------------------------------------------------------------
     // Extra hallucination protection
@@ -358,6 +449,7 @@ impl Default for PipelineConfig {
             quiet_audio_rms_dbfs_threshold: DEFAULT_QUIET_AUDIO_RMS_DBFS_THRESHOLD,
             quiet_audio_peak_dbfs_threshold: DEFAULT_QUIET_AUDIO_PEAK_DBFS_THRESHOLD,
 
+            silence_trim: None,
             noise_gate_threshold_dbfs: None,
 
             audio_downmix_to_mono: true,
@@ -365,6 +457,8 @@ impl Default for PipelineConfig {
             audio_highpass_enabled: true,
             audio_agc_enabled: false,
             audio_noise_suppression_enabled: false,
+            capture_preference: CapturePreference::default(),
+            recording_start: RecordingStart::default(),
 
             quiet_audio_require_speech: false,
 
@@ -393,11 +487,19 @@ struct PipelineInner {
 
     /// Last recording diagnostics (raw stats + optional speech detection).
     last_recording_diagnostics: Option<AudioCaptureDiagnostics>,
+
+    /// Gets partial transcripts from providers that stream them.
+    partial_listener: Option<PartialTranscriptListener>,
+
+    /// Told about every `RecordingState` transition.
+    state_listener: Option<RecordingStateListener>,
 }
 
 impl PipelineInner {
     fn new(config: PipelineConfig) -> Self {
-        let audio_capture = AudioCapture::with_vad_config(config.vad_config.clone());
+        let mut audio_capture = AudioCapture::with_vad_config(config.vad_config.clone());
+        audio_capture.set_capture_preference(config.capture_preference);
+        audio_capture.set_recording_start(config.recording_start);
         let mut inner = Self {
             audio_capture,
             stt_registry: SttRegistry::new(),
@@ -408,8 +510,11 @@ impl PipelineInner {
             cancel_token: None,
             last_wav_bytes: None,
             last_recording_diagnostics: None,
+            partial_listener: None,
+            state_listener: None,
         };
         inner.initialize_providers(&config);
+        inner.arm_pre_roll();
         inner
     }
 
@@ -568,16 +673,36 @@ impl PipelineInner {
         // Note: LLM providers are created on-demand per transcription based on the active profile.
     }
 
+    /// Change state, telling the state listener if the coarse `RecordingState` changed.
+    /// All state changes go through here.
+    fn set_state(&mut self, state: PipelineState) {
+        let previous = RecordingState::from(self.state);
+        self.state = state;
+        let current = RecordingState::from(state);
+        if current != previous {
+            if let Some(listener) = &self.state_listener {
+                listener(current);
+            }
+        }
+    }
+
     /// Reset to idle state, clearing any error condition
     fn reset_to_idle(&mut self) {
-        self.state = PipelineState::Idle;
+        self.set_state(PipelineState::Idle);
         self.cancel_token = None;
+        self.arm_pre_roll();
+    }
+
+    /// Keep the pre-roll buffer filling between recordings (no-op unless it's configured).
+    fn arm_pre_roll(&mut self) {
+        self.audio_capture
+            .arm_pre_roll(self.config.input_device_name.as_deref());
     }
 
     /// Transition to error state
     fn set_error(&mut self, msg: &str) {
         log::error!("Pipeline error: {}", msg);
-        self.state = PipelineState::Error;
+        self.set_state(PipelineState::Error);
         self.cancel_token = None;
     }
 }
@@ -667,6 +792,7 @@ pub struct SharedPipeline {
     inner: Arc<Mutex<PipelineInner>>,
     level_meter: crate::audio_capture::SharedAudioLevelMeter,
     waveform_meter: crate::audio_capture::SharedAudioWaveformMeter,
+    capture_failure: crate::audio_capture::SharedCaptureFailure,
 }
 
 impl SharedPipeline {
@@ -675,10 +801,12 @@ impl SharedPipeline {
         let inner = PipelineInner::new(config);
         let level_meter = inner.audio_capture.shared_level_meter();
         let waveform_meter = inner.audio_capture.shared_waveform_meter();
+        let capture_failure = inner.audio_capture.shared_capture_failure();
         Self {
             inner: Arc::new(Mutex::new(inner)),
             level_meter,
             waveform_meter,
+            capture_failure,
         }
     }
 
@@ -704,6 +832,30 @@ impl SharedPipeline {
         self.waveform_meter.snapshot()
     }
 
+    /// Receive the transcript so far while a recording is being transcribed, for providers
+    /// that stream partial results. Others only produce the final result.
+    pub fn set_partial_listener(&self, listener: PartialTranscriptListener) -> Result<(), PipelineError> {
+        let mut inner = self.inner.lock().map_err(|e| PipelineError::Lock(e.to_string()))?;
+        inner.partial_listener = Some(listener);
+        Ok(())
+    }
+
+    /// Be told about every `RecordingState` transition (see `RecordingStateListener`).
+    pub fn set_recording_state_listener(
+        &self,
+        listener: RecordingStateListener,
+    ) -> Result<(), PipelineError> {
+        let mut inner = self.inner.lock().map_err(|e| PipelineError::Lock(e.to_string()))?;
+        inner.state_listener = Some(listener);
+        Ok(())
+    }
+
+    /// Take the error that made audio capture give up mid-recording (after its reconnect
+    /// attempts), without locking the pipeline mutex.
+    pub fn take_capture_failure(&self) -> Option<String> {
+        self.capture_failure.take()
+    }
+
     /// Start recording
     ///
     /// Creates a new cancellation token for this recording session.
@@ -728,7 +880,7 @@ impl SharedPipeline {
             .start_with_device_name(max_duration, input_device_name.as_deref())
         {
             Ok(()) => {
-                inner.state = PipelineState::Recording;
+                inner.set_state(PipelineState::Recording);
                 log::info!("Pipeline: Recording started");
                 Ok(())
             }
@@ -739,6 +891,18 @@ impl SharedPipeline {
         }
     }
 
+    /// Capture format negotiated for the current/most recent recording.
+    pub fn capture_config(&self) -> Option<CaptureConfig> {
+        let inner = self.inner.lock().ok()?;
+        inner.audio_capture.capture_config().cloned()
+    }
+
+    /// Name of the input device used by the current/most recent recording.
+    pub fn active_input_device_name(&self) -> Option<String> {
+        let inner = self.inner.lock().ok()?;
+        inner.audio_capture.active_device_name().map(str::to_string)
+    }
+
     /// Stop recording and return the raw WAV audio
     #[cfg_attr(not(test), allow(dead_code))]
     pub fn stop_recording(&self) -> Result<Vec<u8>, PipelineError> {
@@ -749,6 +913,7 @@ impl SharedPipeline {
         }
 
         let cfg = AudioEncodeConfig {
+            silence_trim: inner.config.silence_trim,
             noise_gate_threshold_dbfs: inner.config.noise_gate_threshold_dbfs,
             downmix_to_mono: inner.config.audio_downmix_to_mono,
             resample_to_16khz: inner.config.audio_resample_to_16khz,
@@ -804,6 +969,7 @@ impl SharedPipeline {
         }
 
         let after_cfg = AudioEncodeConfig {
+            silence_trim: inner.config.silence_trim,
             noise_gate_threshold_dbfs: inner.config.noise_gate_threshold_dbfs,
             downmix_to_mono: inner.config.audio_downmix_to_mono,
             resample_to_16khz: inner.config.audio_resample_to_16khz,
@@ -979,6 +1145,68 @@ impl SharedPipeline {
         }
     }
 
+    /// Time one transcription round trip with the global STT provider and model.
+    ///
+    /// Sends the last captured audio, or a short generated sample if nothing has been
+    /// recorded, without retries so the figure is a single request. Bounded by the
+    /// transcription timeout; connection failures come back as `Unreachable`.
+    pub async fn measure_transcription_latency(
+        &self,
+    ) -> Result<TranscriptionLatency, PipelineError> {
+        let (wav_bytes, used_last_audio, stt_provider, provider, model, timeout, cancel_token) = {
+            let mut inner = self
+                .inner
+                .lock()
+                .map_err(|e| PipelineError::Lock(e.to_string()))?;
+
+            let (wav_bytes, used_last_audio) = match inner.last_wav_bytes.clone() {
+                Some(bytes) if !bytes.is_empty() => (bytes, true),
+                _ => (latency_sample_wav(), false),
+            };
+            let provider = canonicalize_stt_provider_id(&inner.config.stt_provider);
+            let model = inner.config.stt_model.clone();
+            let stt_provider = inner.get_or_create_stt_provider(&provider, model.clone())?;
+            let timeout = inner.config.transcription_timeout;
+            let cancel_token = inner
+                .cancel_token
+                .clone()
+                .unwrap_or_else(CancellationToken::new);
+
+            (wav_bytes, used_last_audio, stt_provider, provider, model, timeout, cancel_token)
+        };
+
+        let format = AudioFormat::default();
+        let start = std::time::Instant::now();
+        let result = tokio::select! {
+            biased;
+
+            _ = cancel_token.cancelled() => Err(PipelineError::Cancelled),
+
+            _ = tokio::time::sleep(timeout) => Err(PipelineError::Timeout(timeout)),
+
+            result = stt_provider.transcribe(&wav_bytes, &format) => {
+                result.map_err(|e| match e {
+                    SttError::Network(err) if err.is_connect() || err.is_timeout() => {
+                        PipelineError::Unreachable(format!("{} ({})", provider, err))
+                    }
+                    other => PipelineError::from(other),
+                })
+            }
+        };
+        let total_ms = start.elapsed().as_millis() as u64;
+        let text = normalize_stt_text(result?);
+
+        Ok(TranscriptionLatency {
+            provider,
+            model,
+            used_last_audio,
+            audio_ms: wav_duration_ms(&wav_bytes),
+            audio_bytes: wav_bytes.len(),
+            total_ms,
+            text_chars: text.chars().count(),
+        })
+    }
+
     /// Stop recording and transcribe the audio, returning a detailed result.
     ///
     /// This is the main end-to-end function for voice dictation.
@@ -992,7 +1220,7 @@ impl SharedPipeline {
         &self,
     ) -> Result<TranscriptionResult, PipelineError> {
         // Phase 1: Stop recording and prepare for transcription (synchronous, holds lock briefly)
-        let (wav_bytes, stt_provider, llm_provider, llm_prompts, llm_timeout, retry_config, timeout, cancel_token) = {
+        let (wav_bytes, stt_provider, llm_provider, llm_prompts, llm_timeout, retry_config, timeout, cancel_token, partial_listener) = {
             let mut inner = self.inner.lock().map_err(|e| PipelineError::Lock(e.to_string()))?;
 
             if !inner.state.can_stop_recording() {
@@ -1000,6 +1228,7 @@ impl SharedPipeline {
             }
 
             let encode_cfg = AudioEncodeConfig {
+                silence_trim: inner.config.silence_trim,
                 noise_gate_threshold_dbfs: inner.config.noise_gate_threshold_dbfs,
                 downmix_to_mono: inner.config.audio_downmix_to_mono,
                 resample_to_16khz: inner.config.audio_resample_to_16khz,
@@ -1089,7 +1318,7 @@ impl SharedPipeline {
                 return Err(PipelineError::RecordingTooLarge(wav_bytes.len(), max_bytes));
             }
 
-            inner.state = PipelineState::Transcribing;
+            inner.set_state(PipelineState::Transcribing);
 
             let llm_config = inner.config.llm_config.clone();
             let active_profile = select_profile_for_foreground_app(&llm_config);
@@ -1212,6 +1441,7 @@ impl SharedPipeline {
                 retry_config,
                 desired_timeout,
                 cancel_token,
+                inner.partial_listener.clone(),
             )
         };
 
@@ -1231,7 +1461,13 @@ impl SharedPipeline {
                 let provider = stt_provider.clone();
                 let wav_bytes = wav_bytes_for_retry.clone();
                 let format = format.clone();
-                async move { provider.transcribe(&wav_bytes, &format).await }
+                let partial_listener = partial_listener.clone();
+                async move {
+                    match partial_listener {
+                        Some(listener) => provider.transcribe_streaming(&wav_bytes, &format, listener).await,
+                        None => provider.transcribe(&wav_bytes, &format).await,
+                    }
+                }
             })
             .await
         };
@@ -1294,7 +1530,7 @@ impl SharedPipeline {
                     .lock()
                     .map_err(|e| PipelineError::Lock(e.to_string()))?;
                 if inner.state == PipelineState::Transcribing {
-                    inner.state = PipelineState::Rewriting;
+                    inner.set_state(PipelineState::Rewriting);
                 }
             }
 
@@ -1397,7 +1633,7 @@ impl SharedPipeline {
                 return Err(PipelineError::RecordingTooLarge(wav_bytes.len(), max_bytes));
             }
 
-            inner.state = PipelineState::Transcribing;
+            inner.set_state(PipelineState::Transcribing);
 
             // Ensure we have a cancellation token for this attempt.
             let cancel_token = CancellationToken::new();
@@ -1594,7 +1830,7 @@ impl SharedPipeline {
                     .lock()
                     .map_err(|e| PipelineError::Lock(e.to_string()))?;
                 if inner.state == PipelineState::Transcribing {
-                    inner.state = PipelineState::Rewriting;
+                    inner.set_state(PipelineState::Rewriting);
                 }
             }
 
@@ -1690,6 +1926,9 @@ impl SharedPipeline {
         inner.initialize_providers(&config);
         // Update VAD config on audio capture
         inner.audio_capture.set_vad_config(config.vad_config);
+        inner.audio_capture.set_capture_preference(config.capture_preference);
+        inner.audio_capture.set_recording_start(config.recording_start);
+        inner.arm_pre_roll();
         log::info!("Pipeline configuration updated");
         Ok(())
     }
@@ -1735,6 +1974,21 @@ impl SharedPipeline {
             .unwrap_or(false)
     }
 
+    /// Move a recording whose capture stream died (and couldn't be rebuilt) to `Error`,
+    /// so the UI stops showing it as recording and a new one can start.
+    pub fn fail_capture(&self, error: &str) {
+        if let Ok(mut inner) = self.inner.lock() {
+            if inner.state != PipelineState::Recording {
+                return;
+            }
+            if let Some(token) = inner.cancel_token.take() {
+                token.cancel();
+            }
+            inner.audio_capture.stop();
+            inner.set_error(&format!("Audio capture failed: {}", error));
+        }
+    }
+
     /// Cancel current operation
     ///
     /// This will:
@@ -1790,6 +2044,11 @@ impl SharedPipeline {
             .unwrap_or(PipelineState::Error)
     }
 
+    /// Current coarse recording state (see `RecordingState`).
+    pub fn recording_state(&self) -> RecordingState {
+        self.state().into()
+    }
+
     /// Get the most recent realtime audio input level snapshot.
     ///
     /// This is cheap and intended for UI metering (e.g., overlay waveform). The snapshot is
@@ -1862,6 +2121,7 @@ impl Clone for SharedPipeline {
             inner: self.inner.clone(),
             level_meter: self.level_meter.clone(),
             waveform_meter: self.waveform_meter.clone(),
+            capture_failure: self.capture_failure.clone(),
         }
     }
 }
@@ -1894,6 +2154,16 @@ mod tests {
         assert!(!pipeline.is_error());
     }
 
+    #[test]
+    fn test_latency_sample_wav() {
+        let wav = latency_sample_wav();
+        let reader = hound::WavReader::new(std::io::Cursor::new(&wav)).unwrap();
+        assert_eq!(reader.spec().channels, 1);
+        assert_eq!(reader.spec().sample_rate, 16_000);
+        assert_eq!(wav_duration_ms(&wav), LATENCY_SAMPLE_MS as u64);
+        assert_eq!(wav_duration_ms(b"not a wav"), 0);
+    }
+
     #[test]
     fn test_state_guards() {
         assert!(PipelineState::Idle.can_start_recording());
@@ -1921,4 +2191,33 @@ mod tests {
         pipeline.force_reset();
         assert_eq!(pipeline.state(), PipelineState::Idle);
     }
+
+    #[test]
+    fn test_recording_state_listener_sees_coarse_transitions() {
+        let pipeline = SharedPipeline::new(PipelineConfig::default());
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        pipeline
+            .set_recording_state_listener(Arc::new(move |state| sink.lock().unwrap().push(state)))
+            .unwrap();
+
+        {
+            let mut inner = pipeline.inner.lock().unwrap();
+            inner.set_state(PipelineState::Recording);
+            inner.set_state(PipelineState::Transcribing);
+            // Rewriting is still "transcribing" and Error is "idle": no events.
+            inner.set_state(PipelineState::Rewriting);
+            inner.set_error("boom");
+            inner.reset_to_idle();
+        }
+        assert_eq!(pipeline.recording_state(), RecordingState::Idle);
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                RecordingState::Recording,
+                RecordingState::Transcribing,
+                RecordingState::Idle,
+            ]
+        );
+    }
 }
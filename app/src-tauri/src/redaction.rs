@@ -0,0 +1,115 @@
+//! Redaction of sensitive text before it's stored (`redact_patterns`,
+//! `redact_builtin_patterns`).
+//!
+//! One `Redactor` is shared by everything that keeps transcripts around: history, request
+//! logs and the output audit log. Only stored copies are affected; output has already
+//! happened with the original text.
+
+use regex::Regex;
+use std::borrow::Cow;
+use std::sync::{Arc, RwLock};
+
+/// Stored in place of text matched by a redaction pattern.
+pub const REDACTION_PLACEHOLDER: &str = "[redacted]";
+
+/// Patterns applied when `redact_builtin_patterns` is on.
+const BUILTIN_REDACT_PATTERNS: &[&str] = &[
+    // Card and account numbers: 12+ digits, optionally grouped with spaces or dashes.
+    r"\b\d(?:[ -]?\d){11,}\b",
+    // API keys with well-known prefixes (OpenAI/Stripe, GitHub, Slack, AWS).
+    r"\b(?:sk|pk|rk|ghp|gho|ghs|xox[abprs]|AKIA)[-_A-Za-z0-9]{12,}",
+    // Any other long unbroken run of letters, digits, dashes and underscores.
+    r"\b[A-Za-z0-9_-]{32,}",
+];
+
+#[derive(Debug, Default)]
+struct Redaction {
+    custom: Vec<String>,
+    builtins: bool,
+    patterns: Vec<Regex>,
+}
+
+impl Redaction {
+    /// Compile the configured patterns. Invalid or empty patterns are logged and skipped.
+    fn new(custom: &[String], builtins: bool) -> Self {
+        let builtin_patterns = if builtins { BUILTIN_REDACT_PATTERNS } else { &[] };
+        let patterns = builtin_patterns
+            .iter()
+            .copied()
+            .chain(custom.iter().map(String::as_str))
+            .filter(|pattern| !pattern.trim().is_empty())
+            .filter_map(|pattern| match Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(e) => {
+                    log::warn!("Ignoring invalid redaction pattern {:?}: {}", pattern, e);
+                    None
+                }
+            })
+            .collect();
+        Self {
+            custom: custom.to_vec(),
+            builtins,
+            patterns,
+        }
+    }
+
+    fn apply(&self, mut text: String) -> String {
+        for pattern in &self.patterns {
+            if let Cow::Owned(redacted) = pattern.replace_all(&text, REDACTION_PLACEHOLDER) {
+                text = redacted;
+            }
+        }
+        text
+    }
+}
+
+/// Cheaply cloneable handle to the current redaction patterns. Clones share updates.
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    inner: Arc<RwLock<Redaction>>,
+}
+
+impl Redactor {
+    /// Set the regexes whose matches are replaced with `REDACTION_PLACEHOLDER`, plus the
+    /// built-in patterns (long digit runs, key-like tokens) if `builtins`. Patterns are only
+    /// recompiled when they change.
+    pub fn set(&self, custom: &[String], builtins: bool) {
+        if let Ok(current) = self.inner.read() {
+            if current.custom == custom && current.builtins == builtins {
+                return;
+            }
+        }
+        let redaction = Redaction::new(custom, builtins);
+        if let Ok(mut slot) = self.inner.write() {
+            *slot = redaction;
+        }
+    }
+
+    pub fn apply(&self, text: String) -> String {
+        match self.inner.read() {
+            Ok(redaction) => redaction.apply(text),
+            Err(_) => text,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redactor_clones_share_patterns() {
+        let redactor = Redactor::default();
+        let shared = redactor.clone();
+        assert_eq!(shared.apply("hunter22".to_string()), "hunter22");
+
+        redactor.set(&[r"hunter\d+".to_string(), "(".to_string()], true);
+        assert_eq!(
+            shared.apply("pw hunter22, card 4111 1111 1111 1111, on 2024-01-15".to_string()),
+            "pw [redacted], card [redacted], on 2024-01-15"
+        );
+
+        redactor.set(&[], false);
+        assert_eq!(shared.apply("hunter22".to_string()), "hunter22");
+    }
+}
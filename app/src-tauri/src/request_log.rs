@@ -7,6 +7,7 @@
 //! - Timing information
 //! - Errors if any
 
+use crate::redaction::Redactor;
 use chrono::{DateTime, Duration as ChronoDuration, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
@@ -246,6 +247,8 @@ pub struct RequestLogStore {
     logs: Arc<Mutex<VecDeque<RequestLog>>>,
     current: Arc<Mutex<Option<RequestLog>>>,
     retention: Arc<Mutex<RequestLogsRetentionConfig>>,
+    /// Applied to transcripts as logs are stored or read.
+    redactor: Redactor,
 }
 
 impl Default for RequestLogStore {
@@ -271,9 +274,22 @@ impl RequestLogStore {
             logs: Arc::new(Mutex::new(VecDeque::with_capacity(initial_capacity))),
             current: Arc::new(Mutex::new(None)),
             retention: Arc::new(Mutex::new(retention)),
+            redactor: Redactor::default(),
         }
     }
 
+    /// Redact transcripts with `redactor` (shared with history, so settings changes apply).
+    pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
+    fn redact(&self, mut log: RequestLog) -> RequestLog {
+        log.raw_transcript = log.raw_transcript.map(|t| self.redactor.apply(t));
+        log.formatted_transcript = log.formatted_transcript.map(|t| self.redactor.apply(t));
+        log
+    }
+
     pub fn set_retention(&self, retention: RequestLogsRetentionConfig) {
         {
             let mut cfg = self.retention.lock().unwrap();
@@ -352,6 +368,7 @@ impl RequestLogStore {
 
     /// Store a completed log
     fn store_log(&self, log: RequestLog) {
+        let log = self.redact(log);
         let mut logs = self.logs.lock().unwrap();
         logs.push_back(log);
 
@@ -370,7 +387,7 @@ impl RequestLogStore {
 
         // Add current request if exists
         if let Some(ref c) = *current {
-            result.push(c.clone());
+            result.push(self.redact(c.clone()));
         }
 
         // Reverse to get most recent first
@@ -439,4 +456,29 @@ mod tests {
         assert_eq!(logs[0].id, id2); // Most recent first
         assert_eq!(logs[1].id, id1);
     }
+
+    #[test]
+    fn test_log_store_redacts_transcripts() {
+        let redactor = Redactor::default();
+        let store = RequestLogStore::new().with_redactor(redactor.clone());
+        redactor.set(&[r"hunter\d+".to_string()], false);
+
+        store.start_request("groq".to_string(), None);
+        store.with_current(|log| {
+            log.raw_transcript = Some("password hunter22".to_string());
+            log.formatted_transcript = Some("Password hunter22.".to_string());
+        });
+        let in_progress = store.get_logs(None);
+        assert_eq!(in_progress[0].raw_transcript.as_deref(), Some("password [redacted]"));
+        store.complete_current();
+
+        let stored = store.get_logs(None);
+        assert_eq!(stored[0].raw_transcript.as_deref(), Some("password [redacted]"));
+        assert_eq!(
+            stored[0].formatted_transcript.as_deref(),
+            Some("Password [redacted].")
+        );
+        let json = serde_json::to_string(&stored).unwrap();
+        assert!(!json.contains("hunter"));
+    }
 }
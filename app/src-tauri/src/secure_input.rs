@@ -0,0 +1,68 @@
+//! Best-effort check for whether keyboard focus is in a password/secure input field.
+//!
+//! Output mode resolution types instead of pasting there, so secrets never pass through the
+//! clipboard (see `resolve_output_mode`). Platforms and fields we can't inspect report
+//! `false`.
+
+/// Whether the focused element is a password or other secure input field.
+pub fn is_secure_field_focused() -> bool {
+    platform::is_secure_field_focused()
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    #[link(name = "Carbon", kind = "framework")]
+    extern "C" {
+        fn IsSecureEventInputEnabled() -> u8;
+    }
+
+    /// Password fields (and apps like Terminal's Secure Keyboard Entry) turn on secure event
+    /// input while they have focus.
+    pub fn is_secure_field_focused() -> bool {
+        // SAFETY: pure query without arguments.
+        unsafe { IsSecureEventInputEnabled() != 0 }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetClassNameW, GetGUIThreadInfo, GetWindowLongW, GUITHREADINFO, GWL_STYLE,
+    };
+
+    /// `ES_PASSWORD` edit control style.
+    const ES_PASSWORD: i32 = 0x20;
+
+    /// Classic Win32 edit controls with the password style. Browser and custom-drawn
+    /// password fields don't expose this and report `false`.
+    pub fn is_secure_field_focused() -> bool {
+        let mut info = GUITHREADINFO {
+            cbSize: std::mem::size_of::<GUITHREADINFO>() as u32,
+            ..Default::default()
+        };
+        // SAFETY: `info` is a properly sized GUITHREADINFO; thread 0 is the foreground thread.
+        if unsafe { GetGUIThreadInfo(0, &mut info) }.is_err() || info.hwndFocus.is_invalid() {
+            return false;
+        }
+
+        let mut class = [0u16; 32];
+        // SAFETY: the buffer length is passed along with it.
+        let len = unsafe { GetClassNameW(info.hwndFocus, &mut class) };
+        let class = String::from_utf16_lossy(&class[..len.max(0) as usize]);
+        if !class.eq_ignore_ascii_case("edit") {
+            return false;
+        }
+
+        // SAFETY: pure query on a window handle.
+        let style = unsafe { GetWindowLongW(info.hwndFocus, GWL_STYLE) };
+        style & ES_PASSWORD != 0
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    /// No portable way to inspect the focused widget on Linux (X11 vs Wayland toolkits).
+    pub fn is_secure_field_focused() -> bool {
+        false
+    }
+}
@@ -46,6 +46,51 @@ pub const DEFAULT_VAD_PRE_ROLL_MS: u32 = 300;
 
 // ============================================================================
 
+/// Why `HotkeyConfig::parse` rejected a combo string.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HotkeyParseError {
+    /// Nothing but whitespace.
+    Empty,
+    /// A `+`-separated part was empty ("ctrl++a", trailing "+").
+    EmptyPart,
+    /// Only modifiers, no key.
+    MissingKey,
+    /// More than one non-modifier key ("ctrl+a+b").
+    MultipleKeys { keys: Vec<String> },
+    /// The same modifier twice, possibly under different names ("ctrl+control+a").
+    DuplicateModifier { modifier: String },
+}
+
+impl std::fmt::Display for HotkeyParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HotkeyParseError::Empty => write!(f, "Hotkey is empty"),
+            HotkeyParseError::EmptyPart => write!(f, "Hotkey has an empty part"),
+            HotkeyParseError::MissingKey => write!(f, "Hotkey has modifiers but no key"),
+            HotkeyParseError::MultipleKeys { keys } => {
+                write!(f, "Hotkey has more than one key: {}", keys.join(", "))
+            }
+            HotkeyParseError::DuplicateModifier { modifier } => {
+                write!(f, "Modifier {} appears more than once", modifier)
+            }
+        }
+    }
+}
+
+/// Canonical name of a modifier as accepted by the shortcut parser, or `None` if `part`
+/// isn't a modifier.
+fn canonical_modifier(part: &str) -> Option<&'static str> {
+    match part.to_lowercase().as_str() {
+        "ctrl" | "control" => Some("ctrl"),
+        "alt" | "option" => Some("alt"),
+        "shift" => Some("shift"),
+        "super" | "cmd" | "command" | "meta" | "win" => Some("super"),
+        "cmdorctrl" | "cmdorcontrol" | "commandorctrl" | "commandorcontrol" => Some("cmdorctrl"),
+        _ => None,
+    }
+}
+
 /// Configuration for a hotkey combination
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct HotkeyConfig {
@@ -101,6 +146,43 @@ impl HotkeyConfig {
         }
     }
 
+    /// Parse a combo string like "Ctrl+Alt+Space" into modifiers and a key.
+    ///
+    /// Checks the structure only: modifier names are canonicalized ("control" -> "ctrl",
+    /// "cmd" -> "super"), and there must be exactly one non-modifier key. Whether the key
+    /// name itself is recognized is up to the shortcut parser (`to_shortcut`).
+    pub fn parse(combo: &str) -> Result<Self, HotkeyParseError> {
+        if combo.trim().is_empty() {
+            return Err(HotkeyParseError::Empty);
+        }
+
+        let mut modifiers: Vec<String> = Vec::new();
+        let mut keys: Vec<String> = Vec::new();
+        for part in combo.split('+').map(str::trim) {
+            if part.is_empty() {
+                return Err(HotkeyParseError::EmptyPart);
+            }
+            match canonical_modifier(part) {
+                Some(modifier) if modifiers.iter().any(|m| m == modifier) => {
+                    return Err(HotkeyParseError::DuplicateModifier {
+                        modifier: modifier.to_string(),
+                    });
+                }
+                Some(modifier) => modifiers.push(modifier.to_string()),
+                None => keys.push(part.to_string()),
+            }
+        }
+
+        match keys.len() {
+            0 => Err(HotkeyParseError::MissingKey),
+            1 => Ok(Self {
+                modifiers,
+                key: keys.remove(0),
+            }),
+            _ => Err(HotkeyParseError::MultipleKeys { keys }),
+        }
+    }
+
     /// Convert to shortcut string format like "ctrl+alt+Space"
     /// Note: modifiers must be lowercase for the parser to recognize them
     pub fn to_shortcut_string(&self) -> String {
@@ -1,4 +1,8 @@
+use crate::commands::text::OutputMode;
+use crate::foreground_window::ForegroundWindow;
 use std::sync::atomic::AtomicBool;
+use std::sync::Mutex;
+use std::time::Instant;
 
 #[derive(Default)]
 pub struct AppState {
@@ -13,4 +17,17 @@ pub struct AppState {
     pub paste_key_held: AtomicBool,
     /// Tracks if toggle key is currently held down (for debouncing - action happens on release)
     pub toggle_key_held: AtomicBool,
+    /// Tracks if a mode-bound hotkey (`mode_hotkeys`) is held down (toggle semantics)
+    pub mode_key_held: AtomicBool,
+    /// Input device used by the last recording (to detect when the effective device switches)
+    pub last_input_device: Mutex<Option<String>>,
+    /// Window focused when recording started (only tracked with `refocus_before_paste`)
+    pub output_target_window: Mutex<Option<ForegroundWindow>>,
+    /// Output mode chosen by the hotkey that started the current recording
+    /// (`None` = the default `output_mode`)
+    pub recording_output_mode: Mutex<Option<OutputMode>>,
+    /// When the current recording started (for `min_recording_ms_for_stop_cue`)
+    pub recording_started_at: Mutex<Option<Instant>>,
+    /// Release time of a toggle press whose action is waiting out `double_press_window_ms`
+    pub pending_toggle_release: Mutex<Option<Instant>>,
 }
@@ -40,7 +40,7 @@ impl DeepgramSttProvider {
     /// * `api_key` - Deepgram API key
     /// * `model` - Model to use (e.g., "nova-2")
     pub fn new(api_key: String, model: Option<String>) -> Self {
-        let client = reqwest::Client::builder()
+        let client = crate::http_client::builder()
             .timeout(Duration::from_secs(60))
             .build()
             .expect("Failed to create HTTP client");
@@ -26,7 +26,7 @@ impl GroqSttProvider {
     /// * `model` - Model to use (e.g., "whisper-large-v3-turbo")
     /// * `default_prompt` - Optional transcription prompt (OpenAI-compatible `prompt` field)
     pub fn new(api_key: String, model: Option<String>, default_prompt: Option<String>) -> Self {
-        let client = reqwest::Client::builder()
+        let client = crate::http_client::builder()
             .timeout(Duration::from_secs(60))
             .build()
             .expect("Failed to create HTTP client");
@@ -72,6 +72,9 @@ pub enum SttError {
     Timeout,
 }
 
+/// Receives the transcript so far while a streaming transcription is in progress.
+pub type PartialTranscriptListener = Arc<dyn Fn(&str) + Send + Sync>;
+
 /// Trait for Speech-to-Text providers
 #[async_trait]
 pub trait SttProvider: Send + Sync {
@@ -85,6 +88,21 @@ pub trait SttProvider: Send + Sync {
     /// The transcribed text, or an error if transcription fails
     async fn transcribe(&self, audio: &[u8], format: &AudioFormat) -> Result<String, SttError>;
 
+    /// Transcribe audio data, calling `on_partial` with the transcript so far as partial
+    /// results arrive.
+    ///
+    /// Providers (or models) that can't stream only return the final text, without calling
+    /// `on_partial`.
+    async fn transcribe_streaming(
+        &self,
+        audio: &[u8],
+        format: &AudioFormat,
+        on_partial: PartialTranscriptListener,
+    ) -> Result<String, SttError> {
+        let _ = on_partial;
+        self.transcribe(audio, format).await
+    }
+
     /// Get the name of this provider
     #[cfg_attr(not(test), allow(dead_code))]
     fn name(&self) -> &'static str;
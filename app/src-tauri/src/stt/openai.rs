@@ -4,7 +4,7 @@
 //! - Legacy Whisper API (whisper-1) - uses /v1/audio/transcriptions
 //! - Audio chat models (e.g., gpt-4o-audio-preview) - uses /v1/responses with audio input
 
-use super::{AudioFormat, SttError, SttProvider};
+use super::{AudioFormat, PartialTranscriptListener, SttError, SttProvider};
 use async_trait::async_trait;
 use crate::request_log::RequestLogStore;
 use reqwest::multipart;
@@ -32,7 +32,7 @@ impl OpenAiSttProvider {
     ///   - "gpt-4o-mini-audio-preview" - Smaller/faster GPT-4o audio
     ///   - "whisper-1" - Legacy Whisper API
     pub fn new(api_key: String, model: Option<String>, default_prompt: Option<String>) -> Self {
-        let client = reqwest::Client::builder()
+        let client = crate::http_client::builder()
             .timeout(Duration::from_secs(120)) // Longer timeout for GPT-4o
             .build()
             .expect("Failed to create HTTP client");
@@ -84,6 +84,11 @@ impl OpenAiSttProvider {
         self.model == "whisper-1" || self.model.contains("transcribe")
     }
 
+    /// Whether the model streams partial transcripts (`stream=true`). `whisper-1` doesn't.
+    fn supports_streaming(&self) -> bool {
+        self.uses_transcriptions_endpoint() && self.model != "whisper-1"
+    }
+
     fn clamp_prompt_for_model(&self, prompt: Option<&str>) -> Option<String> {
         let prompt = prompt.map(str::trim).filter(|s| !s.is_empty())?;
 
@@ -108,11 +113,17 @@ impl OpenAiSttProvider {
     }
 
     /// Transcribe using the dedicated OpenAI transcription endpoint.
+    ///
+    /// With `on_partial` and a model that supports it, the response is streamed as
+    /// server-sent events and `on_partial` gets the transcript so far after each delta.
     async fn transcribe_audio_transcriptions(
         &self,
         audio: &[u8],
         prompt: Option<&str>,
+        on_partial: Option<&PartialTranscriptListener>,
     ) -> Result<String, SttError> {
+        let stream = on_partial.is_some() && self.supports_streaming();
+
         if let Some(store) = &self.request_log_store {
             let prompt = self.clamp_prompt_for_model(prompt);
             let request_json = json!({
@@ -122,6 +133,7 @@ impl OpenAiSttProvider {
                 "fields": {
                     "model": self.model,
                     "prompt": prompt,
+                    "stream": stream,
                 },
                 "file": {
                     "name": "audio.wav",
@@ -148,8 +160,11 @@ impl OpenAiSttProvider {
         if let Some(prompt) = self.clamp_prompt_for_model(prompt) {
             form = form.text("prompt", prompt);
         }
+        if stream {
+            form = form.text("stream", "true");
+        }
 
-        let response = self
+        let mut response = self
             .client
             .post("https://api.openai.com/v1/audio/transcriptions")
             .bearer_auth(&self.api_key)
@@ -170,6 +185,35 @@ impl OpenAiSttProvider {
             )));
         }
 
+        // The server may answer a streaming request with a plain JSON body; handle both.
+        let is_event_stream = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/event-stream"));
+        if let (true, Some(on_partial)) = (is_event_stream, on_partial) {
+            let mut events = SseBuffer::default();
+            let mut transcript = String::new();
+            let mut final_text: Option<String> = None;
+            while let Some(chunk) = response.chunk().await? {
+                for data in events.push(&chunk) {
+                    match apply_stream_event(&data, &mut transcript) {
+                        Some(done) => final_text = Some(done),
+                        None => on_partial(&transcript),
+                    }
+                }
+            }
+            let text = final_text.unwrap_or(transcript);
+
+            if let Some(store) = &self.request_log_store {
+                let result_for_log = json!({ "text": text, "streamed": true });
+                store.with_current(|log| {
+                    log.stt_response_json = Some(result_for_log);
+                });
+            }
+            return Ok(text);
+        }
+
         let result: serde_json::Value = response.json().await?;
 
         if let Some(store) = &self.request_log_store {
@@ -350,7 +394,7 @@ impl OpenAiSttProvider {
         prompt: Option<&str>,
     ) -> Result<String, SttError> {
         if self.uses_transcriptions_endpoint() {
-            self.transcribe_audio_transcriptions(audio, prompt).await
+            self.transcribe_audio_transcriptions(audio, prompt, None).await
         } else {
             self.transcribe_responses_audio(audio, prompt).await
         }
@@ -364,15 +408,108 @@ impl SttProvider for OpenAiSttProvider {
             .await
     }
 
+    async fn transcribe_streaming(
+        &self,
+        audio: &[u8],
+        format: &AudioFormat,
+        on_partial: PartialTranscriptListener,
+    ) -> Result<String, SttError> {
+        if !self.supports_streaming() {
+            return self.transcribe(audio, format).await;
+        }
+        self.transcribe_audio_transcriptions(audio, self.default_prompt.as_deref(), Some(&on_partial))
+            .await
+    }
+
     fn name(&self) -> &'static str {
         "openai"
     }
 }
 
+/// Incremental parser for `text/event-stream` bodies: feed it raw chunks as they arrive and
+/// get back the `data` payload of each complete event.
+#[derive(Default)]
+struct SseBuffer {
+    pending: Vec<u8>,
+}
+
+impl SseBuffer {
+    fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        // Normalize CRLF line endings; JSON payloads never contain a raw '\r'.
+        self.pending.extend(chunk.iter().filter(|&&b| b != b'\r'));
+
+        let mut events = Vec::new();
+        while let Some(end) = self.pending.windows(2).position(|w| w == b"\n\n") {
+            let event: Vec<u8> = self.pending.drain(..end + 2).collect();
+            let event = String::from_utf8_lossy(&event);
+            let data: Vec<&str> = event
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(|data| data.strip_prefix(' ').unwrap_or(data))
+                .collect();
+            if !data.is_empty() {
+                events.push(data.join("\n"));
+            }
+        }
+        events
+    }
+}
+
+/// Fold one streamed transcription event into the transcript so far.
+///
+/// Returns the final text on `transcript.text.done`; deltas (and unknown events) return
+/// `None`.
+fn apply_stream_event(data: &str, transcript: &mut String) -> Option<String> {
+    let event: serde_json::Value = serde_json::from_str(data).ok()?;
+    match event.get("type").and_then(|t| t.as_str()) {
+        Some("transcript.text.delta") => {
+            if let Some(delta) = event.get("delta").and_then(|d| d.as_str()) {
+                transcript.push_str(delta);
+            }
+            None
+        }
+        Some("transcript.text.done") => event
+            .get("text")
+            .and_then(|t| t.as_str())
+            .map(str::to_string),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_sse_buffer_splits_events_across_chunks() {
+        let mut buffer = SseBuffer::default();
+        assert!(buffer.push(b"data: {\"a\":").is_empty());
+        assert_eq!(
+            buffer.push(b"1}\r\n\r\ndata: {\"b\":2}\n\n: keep-alive\n\n"),
+            vec!["{\"a\":1}".to_string(), "{\"b\":2}".to_string()]
+        );
+        // Multi-byte UTF-8 split between chunks survives.
+        let event = "data: {\"delta\":\"café\"}\n\n".as_bytes();
+        assert!(buffer.push(&event[..20]).is_empty());
+        assert_eq!(buffer.push(&event[20..]), vec!["{\"delta\":\"café\"}".to_string()]);
+    }
+
+    #[test]
+    fn test_apply_stream_event() {
+        let mut transcript = String::new();
+        let delta = |text: &str| json!({ "type": "transcript.text.delta", "delta": text }).to_string();
+        assert_eq!(apply_stream_event(&delta("Hello"), &mut transcript), None);
+        assert_eq!(apply_stream_event(&delta(" world"), &mut transcript), None);
+        assert_eq!(transcript, "Hello world");
+        assert_eq!(apply_stream_event("not json", &mut transcript), None);
+
+        let done = json!({ "type": "transcript.text.done", "text": "Hello, world." }).to_string();
+        assert_eq!(
+            apply_stream_event(&done, &mut transcript).as_deref(),
+            Some("Hello, world.")
+        );
+    }
+
     #[test]
     fn test_provider_creation() {
         let provider = OpenAiSttProvider::new("test-key".to_string(), None, None);
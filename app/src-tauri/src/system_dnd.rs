@@ -0,0 +1,132 @@
+//! Best-effort query of the OS "Do Not Disturb" / Focus state.
+//!
+//! Every platform exposes this differently (and some not at all), so the helper returns
+//! `None` when the state can't be determined. Callers should treat `None` as "not in DND"
+//! and proceed normally.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long a queried DND state is reused. It's checked before every cue, and on Linux each
+/// query spawns `gsettings`.
+const DND_CACHE_TTL: Duration = Duration::from_secs(5);
+
+static DND_CACHE: Mutex<Option<(Instant, Option<bool>)>> = Mutex::new(None);
+
+/// Returns `Some(true)` if the OS reports DND/Focus active, `Some(false)` if it reports it
+/// inactive, and `None` if we can't tell on this platform/configuration. The answer may be
+/// up to `DND_CACHE_TTL` old.
+pub fn is_dnd_active() -> Option<bool> {
+    let mut cache = DND_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cached_state(&mut cache, Instant::now(), platform::is_dnd_active)
+}
+
+/// The cached state if it's younger than `DND_CACHE_TTL`, otherwise `query` it and cache that.
+fn cached_state(
+    cache: &mut Option<(Instant, Option<bool>)>,
+    now: Instant,
+    query: impl FnOnce() -> Option<bool>,
+) -> Option<bool> {
+    match *cache {
+        Some((at, state)) if now.saturating_duration_since(at) < DND_CACHE_TTL => state,
+        _ => {
+            let state = query();
+            *cache = Some((now, state));
+            state
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    /// macOS 12+ records active Focus assertions in a JSON file under the user's Library.
+    /// The file is absent on older releases (or before Focus has ever been used).
+    pub fn is_dnd_active() -> Option<bool> {
+        let home = std::env::var_os("HOME")?;
+        let path = std::path::Path::new(&home).join("Library/DoNotDisturb/DB/Assertions.json");
+        let contents = std::fs::read_to_string(path).ok()?;
+        let json: serde_json::Value = serde_json::from_str(&contents).ok()?;
+
+        let records = json
+            .get("data")?
+            .as_array()?
+            .first()?
+            .get("storeAssertionRecords");
+
+        Some(matches!(records, Some(serde_json::Value::Array(r)) if !r.is_empty()))
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::Win32::UI::Shell::{
+        SHQueryUserNotificationState, QUNS_ACCEPTS_NOTIFICATIONS, QUNS_APP, QUNS_BUSY,
+        QUNS_PRESENTATION_MODE, QUNS_QUIET_TIME, QUNS_RUNNING_D3D_FULL_SCREEN,
+    };
+
+    /// Uses the shell notification state, which covers quiet hours, presentation mode and
+    /// full-screen apps (the cases where Windows itself holds back toasts).
+    pub fn is_dnd_active() -> Option<bool> {
+        let state = unsafe { SHQueryUserNotificationState() }.ok()?;
+
+        if state == QUNS_ACCEPTS_NOTIFICATIONS || state == QUNS_APP {
+            Some(false)
+        } else if state == QUNS_QUIET_TIME
+            || state == QUNS_PRESENTATION_MODE
+            || state == QUNS_BUSY
+            || state == QUNS_RUNNING_D3D_FULL_SCREEN
+        {
+            Some(true)
+        } else {
+            // QUNS_NOT_PRESENT (locked/screen saver) or unknown future values.
+            None
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    /// GNOME exposes DND as "notification banners disabled". Other desktops: unknown.
+    pub fn is_dnd_active() -> Option<bool> {
+        let output = std::process::Command::new("gsettings")
+            .args(["get", "org.gnome.desktop.notifications", "show-banners"])
+            .output()
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+
+        match String::from_utf8_lossy(&output.stdout).trim() {
+            "false" => Some(true),
+            "true" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+mod platform {
+    pub fn is_dnd_active() -> Option<bool> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dnd_state_is_cached_for_the_ttl() {
+        let mut cache = None;
+        let start = Instant::now();
+        assert_eq!(cached_state(&mut cache, start, || Some(true)), Some(true));
+        // Within the TTL the OS isn't asked again.
+        let later = start + DND_CACHE_TTL / 2;
+        assert_eq!(cached_state(&mut cache, later, || panic!("queried")), Some(true));
+        // Unknown states are cached too.
+        let expired = start + DND_CACHE_TTL;
+        assert_eq!(cached_state(&mut cache, expired, || None), None);
+        assert_eq!(cached_state(&mut cache, expired, || Some(false)), None);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::settings::HotkeyConfig;
+use crate::settings::{HotkeyConfig, HotkeyParseError};
 
 // Tests for HotkeyConfig::to_shortcut_string()
 #[test]
@@ -28,3 +28,43 @@ fn test_to_shortcut_string_preserves_key_case() {
     // Modifiers should be lowercase, key should preserve case
     assert_eq!(hotkey.to_shortcut_string(), "ctrl+alt+Backquote");
 }
+
+// Tests for HotkeyConfig::parse()
+#[test]
+fn test_parse_canonicalizes_modifiers() {
+    let hotkey = HotkeyConfig::parse(" Control + Option + Space ").unwrap();
+    assert_eq!(hotkey.modifiers, vec!["ctrl", "alt"]);
+    assert_eq!(hotkey.key, "Space");
+    assert_eq!(hotkey.to_shortcut_string(), "ctrl+alt+Space");
+
+    let hotkey = HotkeyConfig::parse("Cmd+Shift+K").unwrap();
+    assert_eq!(hotkey.modifiers, vec!["super", "shift"]);
+    assert_eq!(hotkey.key, "K");
+}
+
+#[test]
+fn test_parse_key_without_modifiers() {
+    let hotkey = HotkeyConfig::parse("F13").unwrap();
+    assert!(hotkey.modifiers.is_empty());
+    assert_eq!(hotkey.key, "F13");
+}
+
+#[test]
+fn test_parse_rejects_malformed_combos() {
+    assert_eq!(HotkeyConfig::parse("  "), Err(HotkeyParseError::Empty));
+    assert_eq!(HotkeyConfig::parse("ctrl++a"), Err(HotkeyParseError::EmptyPart));
+    assert_eq!(HotkeyConfig::parse("ctrl+a+"), Err(HotkeyParseError::EmptyPart));
+    assert_eq!(HotkeyConfig::parse("ctrl+shift"), Err(HotkeyParseError::MissingKey));
+    assert_eq!(
+        HotkeyConfig::parse("ctrl+a+b"),
+        Err(HotkeyParseError::MultipleKeys {
+            keys: vec!["a".to_string(), "b".to_string()]
+        })
+    );
+    assert_eq!(
+        HotkeyConfig::parse("ctrl+control+a"),
+        Err(HotkeyParseError::DuplicateModifier {
+            modifier: "ctrl".to_string()
+        })
+    );
+}
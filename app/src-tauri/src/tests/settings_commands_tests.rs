@@ -37,3 +37,148 @@ fn test_to_shortcut_string() {
     assert!(result.contains("alt"));
     assert!(result.contains("Space"));
 }
+
+// Tests for default_settings (seeding + reset_settings_to_defaults)
+#[test]
+fn test_default_settings_keys_are_unique() {
+    let defaults = crate::default_settings().unwrap();
+    let mut keys: Vec<&str> = defaults.iter().map(|(key, _)| *key).collect();
+    let total = keys.len();
+    keys.sort_unstable();
+    keys.dedup();
+    assert_eq!(keys.len(), total);
+}
+
+#[test]
+fn test_default_settings_match_getter_defaults() {
+    let defaults = crate::default_settings().unwrap();
+    let get = |key: &str| {
+        defaults
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.clone())
+            .unwrap()
+    };
+    assert_eq!(get("max_saved_recordings"), serde_json::json!(1000));
+    assert_eq!(get("output_mode"), serde_json::json!("paste"));
+    assert_eq!(
+        get("toggle_hotkey"),
+        serde_json::to_value(HotkeyConfig::default_toggle()).unwrap()
+    );
+}
+
+#[test]
+fn test_reset_preserved_settings_are_seeded() {
+    use crate::commands::settings::RESET_PRESERVED_SETTINGS;
+
+    let defaults = crate::default_settings().unwrap();
+    for key in RESET_PRESERVED_SETTINGS {
+        assert!(
+            defaults.iter().any(|(known, _)| known == key),
+            "{} is not a seeded setting",
+            key
+        );
+    }
+}
+
+// Tests for validate_setting_value (import_settings)
+#[test]
+fn test_import_validation_accepts_matching_values() {
+    use crate::commands::settings::{validate_setting_value, SettingValidation};
+    use serde_json::json;
+
+    let defaults = crate::default_settings().unwrap();
+    for (key, value) in &defaults {
+        assert_eq!(
+            validate_setting_value(key, value, Some(value)),
+            SettingValidation::Valid,
+            "default for {} should validate",
+            key
+        );
+    }
+    assert_eq!(
+        validate_setting_value("max_saved_recordings", &json!(250), Some(&json!(1000))),
+        SettingValidation::Valid
+    );
+    assert_eq!(
+        validate_setting_value("audio_cue_stop", &json!("clave"), Some(&json!(null))),
+        SettingValidation::Valid
+    );
+    assert_eq!(
+        validate_setting_value("selected_mic_id", &json!("USB Mic"), None),
+        SettingValidation::Valid
+    );
+}
+
+#[test]
+fn test_import_validation_rejects_and_repairs() {
+    use crate::commands::settings::{validate_setting_value, SettingValidation};
+    use serde_json::json;
+
+    let invalid = |key: &str, value: serde_json::Value, default: Option<serde_json::Value>| {
+        matches!(
+            validate_setting_value(key, &value, default.as_ref()),
+            SettingValidation::Invalid(_)
+        )
+    };
+    assert!(invalid("output_mode", json!("teleport"), Some(json!("paste"))));
+    assert!(invalid("audio_cue", json!("gong"), None));
+    assert!(invalid("overlay_shape", json!("circle"), Some(json!("rounded"))));
+    assert!(invalid("toggle_hotkey", json!("ctrl+space"), None));
+    assert!(invalid("mode_hotkeys", json!({ "ctrl+alt+KeyT": "bogus" }), Some(json!({}))));
+    assert!(invalid("output_mode_cues", json!({ "paste": "gong" }), Some(json!({}))));
+    assert!(invalid("output_mode_cues", json!({ "teleport": "clave" }), Some(json!({}))));
+    assert!(invalid("keystroke_newline_key", json!("ctrl_enter"), Some(json!("enter"))));
+    assert!(invalid(
+        "keystroke_newline_key_overrides",
+        json!({ "/usr/bin/slack": "alt_enter" }),
+        Some(json!({}))
+    ));
+    assert!(invalid("sound_enabled", json!("yes"), Some(json!(true))));
+    assert!(invalid("max_saved_recordings", json!(-5), Some(json!(1000))));
+    assert!(invalid("max_saved_recordings", json!(2.5), Some(json!(1000))));
+
+    assert_eq!(
+        validate_setting_value("max_saved_recordings", &json!(200.0), Some(&json!(1000))),
+        SettingValidation::Repaired {
+            value: json!(200),
+            reason: "200.0 converted to an integer".to_string(),
+        }
+    );
+    assert!(matches!(
+        validate_setting_value("overlay_corner_radius", &json!(500.0), Some(&json!(16.0))),
+        SettingValidation::Repaired { value, .. } if value == json!(64.0)
+    ));
+    assert_eq!(
+        validate_setting_value("recording_start_delay_ms", &json!(-300), Some(&json!(0))),
+        SettingValidation::Valid
+    );
+    assert!(invalid("recording_start_delay_ms", json!(12.5), Some(json!(0))));
+    assert!(matches!(
+        validate_setting_value("recording_start_delay_ms", &json!(-9000), Some(&json!(0))),
+        SettingValidation::Repaired { value, .. } if value == json!(-2000)
+    ));
+    assert!(matches!(
+        validate_setting_value("overlay_min_size", &json!(-20.0), Some(&json!(48.0))),
+        SettingValidation::Repaired { value, .. } if value == json!(1.0)
+    ));
+    assert!(matches!(
+        validate_setting_value("output_retries", &json!(50), Some(&json!(1))),
+        SettingValidation::Repaired { value, .. } if value == json!(5)
+    ));
+    assert!(matches!(
+        validate_setting_value("max_saved_recordings", &json!(0.0), Some(&json!(1000))),
+        SettingValidation::Repaired { value, .. } if value == json!(1)
+    ));
+    assert_eq!(
+        validate_setting_value("proxy_url", &json!("http://corp:3128"), Some(&json!(null))),
+        SettingValidation::Valid
+    );
+    assert!(invalid("proxy_url", json!("corp:3128"), Some(json!(null))));
+    assert!(invalid("proxy_url", json!(3128), Some(json!(null))));
+    assert!(invalid("cue_volume", json!("loud"), Some(json!(1.0))));
+    assert!(matches!(
+        validate_setting_value("cue_volume", &json!(1.5), Some(&json!(1.0))),
+        SettingValidation::Repaired { value, .. } if value == json!(1.0)
+    ));
+}
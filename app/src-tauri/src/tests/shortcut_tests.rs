@@ -1,4 +1,7 @@
-use crate::normalize_shortcut_string;
+use crate::{
+    find_hotkey_conflict, is_double_press, normalize_shortcut_string, recording_countdown_ticks,
+};
+use std::time::{Duration, Instant};
 
 #[test]
 fn test_normalize_ctrl_to_control() {
@@ -56,3 +59,34 @@ fn test_normalize_empty_string() {
 fn test_normalize_single_key() {
     assert_eq!(normalize_shortcut_string("Space"), "space");
 }
+
+#[test]
+fn test_find_hotkey_conflict_matches_normalized_combo() {
+    let bound = vec![
+        ("toggle".to_string(), "ctrl+alt+Space".to_string()),
+        ("hold".to_string(), "cmd+KeyH".to_string()),
+    ];
+    assert_eq!(find_hotkey_conflict("Control+Alt+Space", &bound), Some("toggle"));
+    assert_eq!(find_hotkey_conflict("super+keyh", &bound), Some("hold"));
+    assert_eq!(find_hotkey_conflict("ctrl+alt+KeyT", &bound), None);
+}
+
+#[test]
+fn test_double_press_within_window() {
+    let window = Duration::from_millis(300);
+    let first = Instant::now();
+    assert!(is_double_press(Some(first), first + Duration::from_millis(200), window));
+    assert!(is_double_press(Some(first), first + window, window));
+    assert!(!is_double_press(Some(first), first + Duration::from_millis(301), window));
+    assert!(!is_double_press(None, first, window));
+    // A zero window disables double presses.
+    assert!(!is_double_press(Some(first), first, Duration::ZERO));
+}
+
+#[test]
+fn test_recording_countdown_ticks() {
+    assert_eq!(recording_countdown_ticks(60, 5), vec![5, 4, 3, 2, 1]);
+    // Shorter limit than the countdown: count down from the start of the recording.
+    assert_eq!(recording_countdown_ticks(3, 5), vec![3, 2, 1]);
+    assert!(recording_countdown_ticks(60, 0).is_empty());
+}
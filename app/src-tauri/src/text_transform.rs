@@ -0,0 +1,552 @@
+//! Lightweight post-processing transforms applied to the final transcript.
+//!
+//! Casing, punctuation spacing and number grouping are keyed on the `transform_locale`
+//! setting.
+//!
+//! Casing is locale-sensitive: Rust's `char::to_uppercase` implements the Unicode
+//! *default* mapping, which is wrong for Turkish/Azeri dotted/dotless i. The
+//! `transform_locale` setting selects the tailoring; an empty/unknown locale uses the
+//! Unicode default.
+
+/// Casing rules that differ from the Unicode default mapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CaseLocale {
+    /// Unicode default casing (`ß` -> `SS`, `i` -> `I`).
+    #[default]
+    Default,
+    /// Turkish/Azeri: `i` -> `İ` (U+0130), `ı` -> `I`.
+    Turkic,
+    /// German: a leading `ß` becomes capital sharp s `ẞ` (U+1E9E) rather than `SS`,
+    /// so the transcript length doesn't change.
+    German,
+}
+
+impl CaseLocale {
+    /// Parse a BCP 47-ish locale tag (`"tr"`, `"tr-TR"`, `"de_DE"`, ...).
+    pub fn from_tag(tag: &str) -> Self {
+        let lang = tag
+            .trim()
+            .split(['-', '_'])
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+
+        match lang.as_str() {
+            "tr" | "az" => CaseLocale::Turkic,
+            "de" => CaseLocale::German,
+            _ => CaseLocale::Default,
+        }
+    }
+
+    fn uppercase_char(self, c: char) -> String {
+        match (self, c) {
+            (CaseLocale::Turkic, 'i') => "\u{130}".to_string(),
+            (CaseLocale::Turkic, 'ı') => "I".to_string(),
+            (CaseLocale::German, 'ß') => "\u{1E9E}".to_string(),
+            _ => c.to_uppercase().collect(),
+        }
+    }
+}
+
+/// Uppercase the first alphabetic character of `text`, leaving everything else untouched.
+///
+/// Leading punctuation/quotes/whitespace are skipped so `"¿qué?"` and `"\"hello"` still
+/// get capitalized.
+pub fn capitalize_first_letter(text: &str, locale: CaseLocale) -> String {
+    let Some((idx, c)) = text.char_indices().find(|(_, c)| c.is_alphabetic()) else {
+        return text.to_string();
+    };
+
+    if !c.is_lowercase() {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push_str(&text[..idx]);
+    out.push_str(&locale.uppercase_char(c));
+    out.push_str(&text[idx + c.len_utf8()..]);
+    out
+}
+
+/// No-break space (U+00A0), used before `:` and inside guillemets in French.
+const NBSP: char = '\u{00A0}';
+
+/// Narrow no-break space (U+202F), used before `?`, `!` and `;` in French.
+const NNBSP: char = '\u{202F}';
+
+/// Typographic spacing conventions around punctuation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PunctuationLocale {
+    /// No changes (English and most other locales).
+    #[default]
+    Default,
+    /// French: space before `? ! ; :` and inside `« »`.
+    French,
+}
+
+impl PunctuationLocale {
+    pub fn from_tag(tag: &str) -> Self {
+        let lang = tag
+            .trim()
+            .split(['-', '_'])
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+
+        match lang.as_str() {
+            "fr" => PunctuationLocale::French,
+            _ => PunctuationLocale::Default,
+        }
+    }
+}
+
+/// Spaces we may replace with the locale's no-break space.
+fn is_inline_space(c: char) -> bool {
+    c == ' ' || c == '\t' || c == NBSP || c == NNBSP
+}
+
+fn trim_inline_spaces(out: &mut String) {
+    while out.ends_with(is_inline_space) {
+        out.pop();
+    }
+}
+
+/// Apply locale-specific spacing around punctuation.
+///
+/// French: a narrow no-break space before `?`, `!`, `;`, a no-break space before `:` and
+/// inside guillemets. Punctuation not followed by whitespace/end-of-text (times like
+/// `10:30`, URLs like `https://`) is left alone.
+pub fn apply_punctuation_spacing(text: &str, locale: PunctuationLocale) -> String {
+    if locale == PunctuationLocale::Default {
+        return text.to_string();
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len() + 8);
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '?' | '!' | ';' | ':' => {
+                let next = chars.get(i + 1).copied();
+                let ends_clause = match next {
+                    None => true,
+                    Some(n) => n.is_whitespace() || matches!(n, '?' | '!' | ';' | ':' | '»'),
+                };
+
+                if ends_clause {
+                    trim_inline_spaces(&mut out);
+                    let attach = out
+                        .chars()
+                        .last()
+                        .is_some_and(|p| !p.is_whitespace() && !matches!(p, '?' | '!' | ';' | ':' | '«' | '('));
+                    if attach {
+                        out.push(if c == ':' { NBSP } else { NNBSP });
+                    }
+                }
+                out.push(c);
+            }
+            '«' => {
+                out.push(c);
+                while chars.get(i + 1).copied().is_some_and(is_inline_space) {
+                    i += 1;
+                }
+                if chars.get(i + 1).is_some_and(|n| *n != '»' && !n.is_whitespace()) {
+                    out.push(NBSP);
+                }
+            }
+            '»' => {
+                trim_inline_spaces(&mut out);
+                if out.chars().last().is_some_and(|p| p != '«' && !p.is_whitespace()) {
+                    out.push(NBSP);
+                }
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+        i += 1;
+    }
+
+    out
+}
+
+/// Smallest number `format_numbers` groups by default. Four-digit numbers are left alone:
+/// they're usually years, PINs or times, and most style guides don't group them anyway.
+pub const DEFAULT_NUMBER_GROUPING_MIN: u64 = 10_000;
+
+/// Longest digit run `format_numbers` will group. Anything longer is far more likely a phone,
+/// account or card number than a dictated quantity.
+const MAX_GROUPED_DIGITS: usize = 9;
+
+/// Thousands separator conventions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumberLocale {
+    /// `12,300` (English and the default).
+    #[default]
+    Comma,
+    /// `12.300` (German, Spanish, Italian, Dutch, Portuguese, Turkish, ...).
+    Period,
+    /// `12 300` with a narrow no-break space (French).
+    NarrowSpace,
+    /// `12 300` with a no-break space (Russian, Polish, Czech, Swedish, ...).
+    Space,
+}
+
+impl NumberLocale {
+    pub fn from_tag(tag: &str) -> Self {
+        let lang = tag
+            .trim()
+            .split(['-', '_'])
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+
+        match lang.as_str() {
+            "de" | "es" | "it" | "nl" | "pt" | "tr" | "da" | "id" | "el" | "ro" => {
+                NumberLocale::Period
+            }
+            "fr" => NumberLocale::NarrowSpace,
+            "ru" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "no" | "uk" | "hu" => {
+                NumberLocale::Space
+            }
+            _ => NumberLocale::Comma,
+        }
+    }
+
+    fn separator(self) -> char {
+        match self {
+            NumberLocale::Comma => ',',
+            NumberLocale::Period => '.',
+            NumberLocale::NarrowSpace => NNBSP,
+            NumberLocale::Space => NBSP,
+        }
+    }
+}
+
+/// Characters that, directly next to a digit run, mark it as part of an identifier, phone
+/// number, time, path, version or already-formatted number rather than a plain quantity.
+fn is_number_joiner(c: char) -> bool {
+    matches!(c, '#' | '+' | '-' | '/' | '\\' | ':' | '.' | ',' | '_' | '@' | '&' | '=')
+}
+
+/// Whether the first non-space character from `idx` walking in `step` direction is a digit,
+/// i.e. this number is one group of a spaced-out sequence like "555 12345".
+fn has_digit_neighbor(chars: &[char], idx: isize, step: isize) -> bool {
+    let mut i = idx;
+    while i >= 0 && (i as usize) < chars.len() && is_inline_space(chars[i as usize]) {
+        i += step;
+    }
+    i >= 0 && (i as usize) < chars.len() && chars[i as usize].is_ascii_digit()
+}
+
+/// Whether the digit run `chars[start..end]` looks like a standalone quantity worth grouping.
+fn is_groupable_number(chars: &[char], start: usize, end: usize, min_value: u64) -> bool {
+    let digits = &chars[start..end];
+    if digits.len() > MAX_GROUPED_DIGITS || digits[0] == '0' {
+        return false;
+    }
+
+    let value: u64 = digits.iter().collect::<String>().parse().unwrap_or(0);
+    if value < min_value.max(DEFAULT_NUMBER_GROUPING_MIN) {
+        return false;
+    }
+
+    if let Some(&prev) = start.checked_sub(1).and_then(|i| chars.get(i)) {
+        if prev.is_alphanumeric() || is_number_joiner(prev) {
+            return false;
+        }
+    }
+
+    if let Some(&next) = chars.get(end) {
+        // "12345." ends a sentence; "12345.6" / "12345,6" are decimals.
+        let sentence_end = matches!(next, '.' | ',')
+            && !chars.get(end + 1).is_some_and(|c| c.is_alphanumeric());
+        if next.is_alphanumeric() || (is_number_joiner(next) && !sentence_end) {
+            return false;
+        }
+    }
+
+    !has_digit_neighbor(chars, start as isize - 1, -1) && !has_digit_neighbor(chars, end as isize, 1)
+}
+
+/// Insert locale thousands separators into bare integers of at least `min_value`
+/// (`12300` -> `12,300` / `12.300` / `12 300`).
+///
+/// Deliberately conservative; a number is left alone if it:
+/// - has a leading zero or more than 9 digits (zip codes, IDs, phone/card numbers),
+/// - is below `min_value` (which can't go under `DEFAULT_NUMBER_GROUPING_MIN`, so years
+///   and other four-digit numbers always stay as-is),
+/// - touches a letter or one of `# + - / \ : . , _ @ & =` (order #12345, A12345, 555-1234,
+///   10:30, 1.2.3, already-grouped or decimal numbers), except a sentence-ending `.`/`,`,
+/// - sits next to another number separated only by spaces (spaced-out phone numbers).
+///
+/// Decimal separators are never rewritten: converting `3.5` to `3,5` is too easy to get
+/// wrong for versions, scores and ranges.
+pub fn format_numbers(text: &str, locale: NumberLocale, min_value: u64) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let separator = locale.separator();
+    let mut out = String::with_capacity(text.len() + 8);
+    let mut i = 0;
+
+    while i < chars.len() {
+        if !chars[i].is_ascii_digit() {
+            out.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        while i < chars.len() && chars[i].is_ascii_digit() {
+            i += 1;
+        }
+        let digits = &chars[start..i];
+
+        if is_groupable_number(&chars, start, i, min_value) {
+            for (n, &d) in digits.iter().enumerate() {
+                if n > 0 && (digits.len() - n) % 3 == 0 {
+                    out.push(separator);
+                }
+                out.push(d);
+            }
+        } else {
+            out.extend(digits);
+        }
+    }
+
+    out
+}
+
+/// The configured transforms, read from settings. `apply` runs the enabled ones in a fixed
+/// order: number grouping, punctuation spacing, then capitalization.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransformConfig {
+    /// `transform_locale` tag.
+    pub locale: String,
+    /// `number_grouping_min` when `number_grouping` is on.
+    pub number_grouping_min: Option<u64>,
+    pub punctuation_spacing: bool,
+    pub capitalize_first_letter: bool,
+}
+
+impl TransformConfig {
+    /// Setting keys of the enabled transforms, in the order `apply` runs them.
+    pub fn order(&self) -> Vec<&'static str> {
+        [
+            ("number_grouping", self.number_grouping_min.is_some()),
+            ("punctuation_spacing", self.punctuation_spacing),
+            ("capitalize_first_letter", self.capitalize_first_letter),
+        ]
+        .into_iter()
+        .filter_map(|(name, enabled)| enabled.then_some(name))
+        .collect()
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        let mut text = text.to_string();
+
+        // Thousands separators for dictated quantities ("12300" -> "12,300"); IDs, phone
+        // numbers and years are left alone.
+        if let Some(min_value) = self.number_grouping_min {
+            text = format_numbers(&text, NumberLocale::from_tag(&self.locale), min_value);
+        }
+
+        // Locale typographic spacing (e.g. French "Oui ?"); a no-op for English.
+        if self.punctuation_spacing {
+            let locale = PunctuationLocale::from_tag(&self.locale);
+            text = apply_punctuation_spacing(&text, locale);
+        }
+
+        // Optional casing fix-up (useful when LLM formatting is off).
+        if self.capitalize_first_letter {
+            text = capitalize_first_letter(&text, CaseLocale::from_tag(&self.locale));
+        }
+
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capitalize_default_locale() {
+        assert_eq!(
+            capitalize_first_letter("hello world", CaseLocale::Default),
+            "Hello world"
+        );
+        assert_eq!(
+            capitalize_first_letter("  \"quoted\"", CaseLocale::Default),
+            "  \"Quoted\""
+        );
+        assert_eq!(capitalize_first_letter("Already", CaseLocale::Default), "Already");
+        assert_eq!(capitalize_first_letter("123", CaseLocale::Default), "123");
+        assert_eq!(capitalize_first_letter("", CaseLocale::Default), "");
+    }
+
+    #[test]
+    fn test_capitalize_turkish_dotted_and_dotless_i() {
+        // Unicode default maps 'i' to ASCII 'I', which is wrong in Turkish.
+        assert_eq!(capitalize_first_letter("istanbul", CaseLocale::Default), "Istanbul");
+        assert_eq!(capitalize_first_letter("istanbul", CaseLocale::Turkic), "İstanbul");
+        assert_eq!(capitalize_first_letter("ılık", CaseLocale::Turkic), "Ilık");
+    }
+
+    #[test]
+    fn test_capitalize_german_sharp_s() {
+        // Default mapping expands to two characters.
+        assert_eq!(capitalize_first_letter("ßa", CaseLocale::Default), "SSa");
+        assert_eq!(capitalize_first_letter("ßa", CaseLocale::German), "ẞa");
+        // Non-leading ß is untouched.
+        assert_eq!(capitalize_first_letter("straße", CaseLocale::German), "Straße");
+    }
+
+    #[test]
+    fn test_case_locale_from_tag() {
+        assert_eq!(CaseLocale::from_tag("tr"), CaseLocale::Turkic);
+        assert_eq!(CaseLocale::from_tag("tr-TR"), CaseLocale::Turkic);
+        assert_eq!(CaseLocale::from_tag("az_AZ"), CaseLocale::Turkic);
+        assert_eq!(CaseLocale::from_tag("DE"), CaseLocale::German);
+        assert_eq!(CaseLocale::from_tag("en-US"), CaseLocale::Default);
+        assert_eq!(CaseLocale::from_tag(""), CaseLocale::Default);
+    }
+
+    #[test]
+    fn test_punctuation_spacing_english_is_noop() {
+        let text = "Hello: world? Yes! \"quoted\" 10:30";
+        assert_eq!(
+            apply_punctuation_spacing(text, PunctuationLocale::from_tag("en-US")),
+            text
+        );
+    }
+
+    #[test]
+    fn test_punctuation_spacing_french_colon() {
+        let fr = PunctuationLocale::French;
+        assert_eq!(apply_punctuation_spacing("Note: ceci", fr), "Note\u{A0}: ceci");
+        // Existing plain space is replaced, not doubled.
+        assert_eq!(apply_punctuation_spacing("Note : ceci", fr), "Note\u{A0}: ceci");
+        // Times and URLs are untouched.
+        assert_eq!(apply_punctuation_spacing("à 10:30", fr), "à 10:30");
+        assert_eq!(
+            apply_punctuation_spacing("voir https://example.com", fr),
+            "voir https://example.com"
+        );
+    }
+
+    #[test]
+    fn test_punctuation_spacing_french_question_and_exclamation() {
+        let fr = PunctuationLocale::French;
+        assert_eq!(
+            apply_punctuation_spacing("Vraiment? Oui!", fr),
+            "Vraiment\u{202F}? Oui\u{202F}!"
+        );
+        assert_eq!(apply_punctuation_spacing("Quoi?!", fr), "Quoi\u{202F}?!");
+        assert_eq!(apply_punctuation_spacing("a; b", fr), "a\u{202F}; b");
+    }
+
+    #[test]
+    fn test_punctuation_spacing_french_guillemets() {
+        let fr = PunctuationLocale::French;
+        assert_eq!(
+            apply_punctuation_spacing("Il a dit «bonjour».", fr),
+            "Il a dit «\u{A0}bonjour\u{A0}»."
+        );
+        assert_eq!(
+            apply_punctuation_spacing("Il a dit « bonjour ».", fr),
+            "Il a dit «\u{A0}bonjour\u{A0}»."
+        );
+        assert_eq!(
+            apply_punctuation_spacing("«Vraiment?»", fr),
+            "«\u{A0}Vraiment\u{202F}?\u{A0}»"
+        );
+    }
+
+    #[test]
+    fn test_format_numbers_groups_by_locale() {
+        let min = DEFAULT_NUMBER_GROUPING_MIN;
+        assert_eq!(
+            format_numbers("about 12300 people", NumberLocale::Comma, min),
+            "about 12,300 people"
+        );
+        assert_eq!(format_numbers("1234567", NumberLocale::Comma, min), "1,234,567");
+        assert_eq!(format_numbers("12300", NumberLocale::Period, min), "12.300");
+        assert_eq!(format_numbers("12300", NumberLocale::NarrowSpace, min), "12\u{202F}300");
+        assert_eq!(format_numbers("12300", NumberLocale::Space, min), "12\u{A0}300");
+        // Sentence punctuation and currency symbols don't block grouping.
+        assert_eq!(
+            format_numbers("It cost $45000. Then 20000, or so.", NumberLocale::Comma, min),
+            "It cost $45,000. Then 20,000, or so."
+        );
+        assert_eq!(format_numbers("(25000)", NumberLocale::Comma, min), "(25,000)");
+    }
+
+    #[test]
+    fn test_format_numbers_leaves_ids_phones_and_years_alone() {
+        let min = DEFAULT_NUMBER_GROUPING_MIN;
+        for text in [
+            "in 2024",
+            "call 555-123-4567",
+            "call +1 5551234567",
+            "call 555 12345 678",
+            "order #12345",
+            "ticket A12345 or 12345B",
+            "zip 02134",
+            "pi is 3.14159",
+            "total 12345.67 or 12345,67",
+            "already 12,300",
+            "at 10:30:45",
+            "version 1.20000.3",
+            "see /tmp/12345/x",
+            "user_12345",
+            "9876543210",
+        ] {
+            assert_eq!(format_numbers(text, NumberLocale::Comma, min), text, "{}", text);
+        }
+        // Below the threshold.
+        assert_eq!(format_numbers("9999 items", NumberLocale::Comma, min), "9999 items");
+        // A lower threshold still never groups years; a higher one raises the bar.
+        assert_eq!(format_numbers("in 2024", NumberLocale::Comma, 0), "in 2024");
+        assert_eq!(format_numbers("50000 items", NumberLocale::Comma, 100_000), "50000 items");
+    }
+
+    #[test]
+    fn test_number_locale_from_tag() {
+        assert_eq!(NumberLocale::from_tag("en-US"), NumberLocale::Comma);
+        assert_eq!(NumberLocale::from_tag("de_DE"), NumberLocale::Period);
+        assert_eq!(NumberLocale::from_tag("fr-CA"), NumberLocale::NarrowSpace);
+        assert_eq!(NumberLocale::from_tag("ru"), NumberLocale::Space);
+        assert_eq!(NumberLocale::from_tag(""), NumberLocale::Comma);
+    }
+
+    #[test]
+    fn test_punctuation_locale_from_tag() {
+        assert_eq!(PunctuationLocale::from_tag("fr"), PunctuationLocale::French);
+        assert_eq!(PunctuationLocale::from_tag("fr-CA"), PunctuationLocale::French);
+        assert_eq!(PunctuationLocale::from_tag("en"), PunctuationLocale::Default);
+        assert_eq!(PunctuationLocale::from_tag(""), PunctuationLocale::Default);
+    }
+
+    #[test]
+    fn test_transform_config_order_and_apply() {
+        let off = TransformConfig::default();
+        assert!(off.order().is_empty());
+        assert_eq!(off.apply("das kostet 12300 euro: ja"), "das kostet 12300 euro: ja");
+
+        let config = TransformConfig {
+            locale: "fr".to_string(),
+            number_grouping_min: Some(DEFAULT_NUMBER_GROUPING_MIN),
+            punctuation_spacing: true,
+            capitalize_first_letter: true,
+        };
+        assert_eq!(
+            config.order(),
+            ["number_grouping", "punctuation_spacing", "capitalize_first_letter"]
+        );
+        assert_eq!(
+            config.apply("ça coûte 12300 euros ?"),
+            "Ça coûte 12\u{202F}300 euros\u{202F}?"
+        );
+    }
+}
@@ -0,0 +1,111 @@
+//! Best-effort spoken confirmations through the OS text-to-speech engine.
+//!
+//! For eyes-free use (`tts_confirmations`): a short phrase such as "pasted" after output.
+//! Each platform's stock speech tool is used (`say` on macOS, SAPI through PowerShell's
+//! System.Speech on Windows, speech-dispatcher or espeak on Linux), so nothing extra has to
+//! be bundled. Speech runs on its own thread and never holds up output.
+
+use std::process::Command;
+use std::thread;
+
+/// Longest phrase we'll speak. Confirmations are a word or two; this only guards against
+/// a caller passing a whole transcript.
+pub const MAX_SPOKEN_CHARS: usize = 200;
+
+/// Speak `text` on a background thread. Failures are logged.
+pub fn speak(text: String) {
+    thread::spawn(move || {
+        if let Err(e) = speak_blocking(&text) {
+            log::warn!("Text-to-speech failed: {}", e);
+        }
+    });
+}
+
+/// Speak `text` and wait until it has been spoken.
+pub fn speak_blocking(text: &str) -> Result<(), String> {
+    let text: String = text.trim().chars().take(MAX_SPOKEN_CHARS).collect();
+    if text.is_empty() {
+        return Ok(());
+    }
+    platform::speak(&text)
+}
+
+/// Run a speech command, mapping spawn failures and non-zero exits to an error.
+#[cfg_attr(not(any(target_os = "macos", target_os = "windows", target_os = "linux")), allow(dead_code))]
+fn run(command: &mut Command) -> Result<(), String> {
+    let status = command.status().map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("speech command exited with {}", status))
+    }
+}
+
+/// Quote `text` as a PowerShell single-quoted string literal.
+#[cfg_attr(not(any(target_os = "windows", test)), allow(dead_code))]
+fn powershell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::process::Command;
+
+    /// `say` speaks through the system voice (the NSSpeechSynthesizer default).
+    pub fn speak(text: &str) -> Result<(), String> {
+        super::run(Command::new("say").arg("--").arg(text))
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::powershell_quote;
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
+    /// Don't flash a console window for the PowerShell child.
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    /// SAPI through .NET's System.Speech, which ships with Windows PowerShell.
+    pub fn speak(text: &str) -> Result<(), String> {
+        let script = format!(
+            "Add-Type -AssemblyName System.Speech; \
+             (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak({})",
+            powershell_quote(text)
+        );
+        super::run(
+            Command::new("powershell")
+                .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+                .creation_flags(CREATE_NO_WINDOW),
+        )
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::process::Command;
+
+    /// speech-dispatcher if present, otherwise espeak.
+    pub fn speak(text: &str) -> Result<(), String> {
+        super::run(Command::new("spd-say").args(["--wait", "--", text]))
+            .or_else(|_| super::run(Command::new("espeak").arg("--").arg(text)))
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+mod platform {
+    pub fn speak(_text: &str) -> Result<(), String> {
+        Err("Text-to-speech is not supported on this platform".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_powershell_quote_escapes_single_quotes() {
+        assert_eq!(powershell_quote("pasted"), "'pasted'");
+        assert_eq!(powershell_quote("it's'; rm x"), "'it''s''; rm x'");
+    }
+}
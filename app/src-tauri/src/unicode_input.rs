@@ -0,0 +1,195 @@
+//! Layout-independent text input via Unicode key events.
+//!
+//! enigo types characters by simulating keys on the active keyboard layout, which produces
+//! wrong characters on some non-US layouts (dead keys, AltGr combinations). On Windows,
+//! `SendInput` with `KEYEVENTF_UNICODE` delivers the UTF-16 code units directly instead.
+//! Other platforms don't have this path; callers check `is_supported()` and use enigo.
+//!
+//! With `unicode_keystrokes` on, only layout-sensitive symbols (`is_layout_sensitive`) go
+//! through this path; the rest is typed with layout keys. With it off, nothing does.
+
+/// Whether `send_text` is available on this platform.
+pub fn is_supported() -> bool {
+    cfg!(target_os = "windows")
+}
+
+/// Type `text` as Unicode key events.
+pub fn send_text(text: &str) -> Result<(), String> {
+    platform::send_text(&plan_events(text))
+}
+
+/// Whether `c` is typed with a different physical key (often AltGr or a dead key) across
+/// common layouts, so layout key simulation tends to garble it on international keyboards.
+/// These are mostly the symbols programmers dictate: `@`, `#`, braces, brackets, quotes.
+pub fn is_layout_sensitive(c: char) -> bool {
+    matches!(
+        c,
+        '@' | '#' | '$' | '{' | '}' | '[' | ']' | '\\' | '|' | '~' | '^' | '`' | '<' | '>'
+            | '\'' | '"' | '€'
+    )
+}
+
+/// Split `text` into runs of layout-sensitive characters (`true`) and everything else
+/// (`false`), in order. Empty text gives no runs.
+#[cfg_attr(not(any(target_os = "windows", test)), allow(dead_code))]
+pub fn split_layout_sensitive(text: &str) -> Vec<(&str, bool)> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    let mut current = None;
+    for (index, c) in text.char_indices() {
+        let sensitive = is_layout_sensitive(c);
+        if current.is_some_and(|current| current != sensitive) {
+            runs.push((&text[start..index], !sensitive));
+            start = index;
+        }
+        current = Some(sensitive);
+    }
+    if let Some(sensitive) = current {
+        runs.push((&text[start..], sensitive));
+    }
+    runs
+}
+
+/// One key press (down + up) to send.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(any(target_os = "windows", test)), allow(dead_code))]
+enum UnicodeEvent {
+    /// A UTF-16 code unit. Characters outside the BMP become two events (a surrogate pair).
+    Unit(u16),
+    /// A real Return key: many apps ignore a Unicode '\n', so line breaks press Enter.
+    Return,
+}
+
+/// Translate text into key events. "\r\n" and lone '\r' produce a single Return.
+fn plan_events(text: &str) -> Vec<UnicodeEvent> {
+    let mut events = Vec::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\r' => {
+                chars.next_if_eq(&'\n');
+                events.push(UnicodeEvent::Return);
+            }
+            '\n' => events.push(UnicodeEvent::Return),
+            _ => {
+                let mut buf = [0u16; 2];
+                events.extend(c.encode_utf16(&mut buf).iter().map(|&u| UnicodeEvent::Unit(u)));
+            }
+        }
+    }
+    events
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::UnicodeEvent;
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS,
+        KEYEVENTF_KEYUP, KEYEVENTF_UNICODE, VIRTUAL_KEY, VK_RETURN,
+    };
+
+    fn key_input(vk: VIRTUAL_KEY, scan: u16, flags: KEYBD_EVENT_FLAGS) -> INPUT {
+        INPUT {
+            r#type: INPUT_KEYBOARD,
+            Anonymous: INPUT_0 {
+                ki: KEYBDINPUT {
+                    wVk: vk,
+                    wScan: scan,
+                    dwFlags: flags,
+                    time: 0,
+                    dwExtraInfo: 0,
+                },
+            },
+        }
+    }
+
+    pub fn send_text(events: &[UnicodeEvent]) -> Result<(), String> {
+        let mut inputs = Vec::with_capacity(events.len() * 2);
+        for event in events {
+            match *event {
+                UnicodeEvent::Unit(unit) => {
+                    inputs.push(key_input(VIRTUAL_KEY(0), unit, KEYEVENTF_UNICODE));
+                    inputs.push(key_input(
+                        VIRTUAL_KEY(0),
+                        unit,
+                        KEYEVENTF_UNICODE | KEYEVENTF_KEYUP,
+                    ));
+                }
+                UnicodeEvent::Return => {
+                    inputs.push(key_input(VK_RETURN, 0, KEYBD_EVENT_FLAGS(0)));
+                    inputs.push(key_input(VK_RETURN, 0, KEYEVENTF_KEYUP));
+                }
+            }
+        }
+
+        if inputs.is_empty() {
+            return Ok(());
+        }
+
+        // SAFETY: `inputs` is a valid slice of initialized INPUT structs and cbsize matches.
+        let sent = unsafe { SendInput(&inputs, std::mem::size_of::<INPUT>() as i32) };
+        if sent as usize != inputs.len() {
+            return Err(format!(
+                "SendInput injected {} of {} events: {}",
+                sent,
+                inputs.len(),
+                std::io::Error::last_os_error()
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use super::UnicodeEvent;
+
+    pub fn send_text(_events: &[UnicodeEvent]) -> Result<(), String> {
+        Err("Unicode input is only available on Windows".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_events_encodes_utf16_and_line_breaks() {
+        assert_eq!(
+            plan_events("é\r\nß\n"),
+            vec![
+                UnicodeEvent::Unit(0x00e9),
+                UnicodeEvent::Return,
+                UnicodeEvent::Unit(0x00df),
+                UnicodeEvent::Return,
+            ]
+        );
+        // Outside the BMP: a surrogate pair.
+        assert_eq!(
+            plan_events("😀"),
+            vec![UnicodeEvent::Unit(0xd83d), UnicodeEvent::Unit(0xde00)]
+        );
+        assert_eq!(plan_events("\r"), vec![UnicodeEvent::Return]);
+        assert!(plan_events("").is_empty());
+    }
+
+    #[test]
+    fn test_split_layout_sensitive_runs() {
+        assert_eq!(
+            split_layout_sensitive("mail me@x.com {ok}"),
+            vec![
+                ("mail me", false),
+                ("@", true),
+                ("x.com ", false),
+                ("{", true),
+                ("ok", false),
+                ("}", true),
+            ]
+        );
+        assert_eq!(split_layout_sensitive("#[]"), vec![("#[]", true)]);
+        assert_eq!(split_layout_sensitive("héllo"), vec![("héllo", false)]);
+        assert!(split_layout_sensitive("").is_empty());
+        assert!(is_layout_sensitive('\\'));
+        assert!(!is_layout_sensitive('a') && !is_layout_sensitive('.'));
+    }
+}
@@ -20,7 +20,7 @@ mod imp {
         pub process_path: String,
     }
 
-    fn query_process_path(pid: u32) -> Option<String> {
+    pub fn query_process_path(pid: u32) -> Option<String> {
         unsafe {
             let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
 
@@ -114,7 +114,7 @@ mod imp {
 }
 
 #[cfg(target_os = "windows")]
-pub use imp::{get_foreground_process_path, list_open_windows, OpenWindowInfo};
+pub use imp::{get_foreground_process_path, list_open_windows, query_process_path, OpenWindowInfo};
 
 #[cfg(not(target_os = "windows"))]
 mod imp_stub {
@@ -8,7 +8,7 @@ import {
   Title,
   Tooltip,
 } from "@mantine/core";
-import { useQuery } from "@tanstack/react-query";
+import { useQuery, useQueryClient } from "@tanstack/react-query";
 import {
   CircleHelp,
   Cog,
@@ -636,6 +636,33 @@ function SettingsViewWithGuideLauncher({
   );
 }
 
+// The backend writes settings.json directly on reset; reload it so the UI doesn't show
+// (and later write back) stale values.
+function SettingsResetSync() {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    tauriAPI
+      .onSettingsReset(async () => {
+        try {
+          await tauriAPI.reloadSettingsFromDisk();
+        } catch (error) {
+          console.error("Failed to reload settings after reset:", error);
+        }
+        queryClient.invalidateQueries({ queryKey: ["settings"] });
+      })
+      .then((fn) => {
+        unlisten = fn;
+      });
+    return () => {
+      unlisten?.();
+    };
+  }, [queryClient]);
+
+  return null;
+}
+
 function AccentColorSync() {
   const { data: settings } = useSettings();
 
@@ -691,6 +718,7 @@ export default function App() {
   return (
     <div className="app-layout">
       <AccentColorSync />
+      <SettingsResetSync />
       <Sidebar
         activeView={activeView}
         onViewChange={(view) => {
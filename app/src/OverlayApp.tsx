@@ -7,7 +7,7 @@ import { useDrag } from "@use-gesture/react";
 import { useCallback, useEffect, useRef, useState } from "react";
 import { applyAccentColor } from "./lib/accentColor";
 import { useSettings, useTypeText } from "./lib/queries";
-import { type ConnectionState, tauriAPI } from "./lib/tauri";
+import { commandErrorMessage, type ConnectionState, tauriAPI } from "./lib/tauri";
 import "./app.css";
 
 /**
@@ -50,13 +50,7 @@ interface ErrorInfo {
  * Parse error message to user-friendly format
  */
 function parseError(error: unknown): ErrorInfo {
-  // Handle object errors (e.g., CommandError from Tauri with { message, error_type })
-  let errorStr: string;
-  if (error && typeof error === "object" && "message" in error) {
-    errorStr = String((error as { message: unknown }).message);
-  } else {
-    errorStr = String(error);
-  }
+  const errorStr = commandErrorMessage(error);
 
   // Missing persisted audio (retry can't run)
   if (
@@ -1400,6 +1394,8 @@ function RecordingControl() {
   const [lastFailedRequestId, setLastFailedRequestId] = useState<string | null>(
     null
   );
+  // Seconds left before the max-duration auto-stop (backend `recording-countdown`).
+  const [countdown, setCountdown] = useState<number | null>(null);
   const [containerRef, rect] = useResizeObserver();
   const hasDragStartedRef = useRef(false);
   const [animState, setAnimState] = useState<"enter" | "visible" | "exit">(
@@ -1608,7 +1604,9 @@ function RecordingControl() {
     setExpanded(false);
   }, [pipelineState, settings?.overlay_mode]);
 
-  const requestAnimatedHide = useCallback(() => {
+  // `seq` comes from the backend's `overlay-hide-requested` event; passing it back lets the
+  // backend ignore this hide if a newer show happened during the exit animation.
+  const requestAnimatedHide = useCallback((seq?: number) => {
     if (exitTimerRef.current) {
       window.clearTimeout(exitTimerRef.current);
       exitTimerRef.current = null;
@@ -1617,7 +1615,7 @@ function RecordingControl() {
     setAnimState("exit");
     // Keep duration in sync with CSS transition (180ms) + a tiny buffer.
     exitTimerRef.current = window.setTimeout(() => {
-      invoke("hide_overlay").catch(console.error);
+      invoke("hide_overlay", { seq: seq ?? null }).catch(console.error);
       // Prep for next entrance.
       setAnimState("enter");
       // Clear held phase so the next show doesn't accidentally reuse it.
@@ -1679,8 +1677,8 @@ function RecordingControl() {
     let unlisten: (() => void) | undefined;
 
     const setup = async () => {
-      unlisten = await listen("overlay-hide-requested", () => {
-        requestAnimatedHide();
+      unlisten = await listen<number | null>("overlay-hide-requested", (event) => {
+        requestAnimatedHide(event.payload ?? undefined);
       });
     };
 
@@ -1690,6 +1688,22 @@ function RecordingControl() {
     };
   }, [requestAnimatedHide, settings?.overlay_mode]);
 
+  // Answer the backend's heartbeat so it knows this webview is still responsive
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+
+    const setup = async () => {
+      unlisten = await listen<number>("overlay-ping", (event) => {
+        invoke("overlay_pong", { seq: event.payload }).catch(console.error);
+      });
+    };
+
+    setup();
+    return () => {
+      unlisten?.();
+    };
+  }, []);
+
   // If the overlay itself was used to record (not hotkey path), honor recording-only by
   // animating out when we return to idle.
   const prevPipelineStateRef = useRef<PipelineState>("idle");
@@ -1739,7 +1753,7 @@ function RecordingControl() {
       console.error("[Pipeline] Failed to start recording:", error);
       const errorInfo = parseError(error);
       setLastError(errorInfo);
-      setLastErrorDetail(String(error));
+      setLastErrorDetail(commandErrorMessage(error));
 
       setPipelineState("error");
     }
@@ -1764,7 +1778,7 @@ function RecordingControl() {
           console.error("[Pipeline] Failed to type text:", error);
           const errorInfo = parseError(error);
           setLastError(errorInfo);
-          setLastErrorDetail(String(error));
+          setLastErrorDetail(commandErrorMessage(error));
         }
       }
 
@@ -1779,7 +1793,7 @@ function RecordingControl() {
       // Show error to user
       const errorInfo = parseError(error);
       setLastError(errorInfo);
-      setLastErrorDetail(String(error));
+      setLastErrorDetail(commandErrorMessage(error));
     }
   }, [pipelineState, typeTextMutation]);
 
@@ -1800,7 +1814,7 @@ function RecordingControl() {
         } catch (error) {
           console.error("[Pipeline] Failed to type retry transcript:", error);
           setLastError(parseError(error));
-          setLastErrorDetail(String(error));
+          setLastErrorDetail(commandErrorMessage(error));
         }
       }
 
@@ -1810,7 +1824,7 @@ function RecordingControl() {
       console.error("[Pipeline] Retry failed:", error);
       setPipelineState("error");
       setLastError(parseError(error));
-      setLastErrorDetail(String(error));
+      setLastErrorDetail(commandErrorMessage(error));
     }
   }, [lastFailedRequestId, typeTextMutation]);
 
@@ -1844,6 +1858,29 @@ function RecordingControl() {
     };
   }, []);
 
+  // Auto-stop countdown: only meaningful while recording.
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+
+    const setup = async () => {
+      unlisten = await tauriAPI.onRecordingCountdown((payload) => {
+        setCountdown(payload.seconds_remaining);
+      });
+    };
+
+    setup();
+
+    return () => {
+      unlisten?.();
+    };
+  }, []);
+
+  useEffect(() => {
+    if (pipelineState !== "recording") {
+      setCountdown(null);
+    }
+  }, [pipelineState]);
+
   // Listen for pipeline events from Rust
   useEffect(() => {
     const unlisteners: (() => void)[] = [];
@@ -1915,6 +1952,8 @@ function RecordingControl() {
   // Listen for settings changes from main window
   useEffect(() => {
     let unlisten: (() => void) | undefined;
+    let unlistenReset: (() => void) | undefined;
+    let unlistenDevice: (() => void) | undefined;
 
     const setup = async () => {
       unlisten = await tauriAPI.onSettingsChanged(async (payload) => {
@@ -1946,12 +1985,27 @@ function RecordingControl() {
         // Sync pipeline config when settings change
         invoke("sync_pipeline_config").catch(console.error);
       });
+      // A backend reset already re-applied runtime state; just pick up the new values.
+      unlistenReset = await tauriAPI.onSettingsReset(async () => {
+        try {
+          await tauriAPI.reloadSettingsFromDisk();
+        } catch (error) {
+          console.error("[Overlay] Failed to reload settings after reset:", error);
+        }
+        queryClient.invalidateQueries({ queryKey: ["settings"] });
+      });
+      // The cycle-input-device hotkey saves the new mic itself; refresh selectedMicId.
+      unlistenDevice = await tauriAPI.onInputDeviceSelected(() => {
+        queryClient.invalidateQueries({ queryKey: ["settings"] });
+      });
     };
 
     setup();
 
     return () => {
       unlisten?.();
+      unlistenReset?.();
+      unlistenDevice?.();
     };
   }, [queryClient]);
 
@@ -2131,6 +2185,17 @@ function RecordingControl() {
                 </>
               )}
             </div>
+            {isRecording && countdown !== null ? (
+              <div className="overlay-meta">
+                <div
+                  className="overlay-pill"
+                  aria-live="polite"
+                  title="Recording stops automatically"
+                >
+                  {countdown}s
+                </div>
+              </div>
+            ) : null}
             {isError ? (
               <div className="overlay-meta">
                 {lastFailedRequestId ? (
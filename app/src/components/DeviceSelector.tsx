@@ -1,7 +1,9 @@
 import { Loader, Select } from "@mantine/core";
 import { invoke } from "@tauri-apps/api/core";
+import { useQueryClient } from "@tanstack/react-query";
 import { useEffect, useState } from "react";
 import { useSettings, useUpdateSelectedMic } from "../lib/queries";
+import { tauriAPI } from "../lib/tauri";
 
 interface AudioDevice {
   deviceId: string;
@@ -11,6 +13,7 @@ interface AudioDevice {
 export function DeviceSelector() {
   const { data: settings, isLoading: settingsLoading } = useSettings();
   const updateSelectedMic = useUpdateSelectedMic();
+  const queryClient = useQueryClient();
   const [devices, setDevices] = useState<AudioDevice[]>([]);
   const [isLoading, setIsLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
@@ -44,6 +47,16 @@ export function DeviceSelector() {
     return;
   }, []);
 
+  // The cycle-input-device hotkey switches mics behind the settings query's back.
+  useEffect(() => {
+    const unlisten = tauriAPI.onInputDeviceSelected(() => {
+      queryClient.invalidateQueries({ queryKey: ["settings"] });
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [queryClient]);
+
   const handleChange = (value: string | null) => {
     // null or empty string means "default"
     const micId = value === "" || value === "default" ? null : value;
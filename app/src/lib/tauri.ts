@@ -15,11 +15,34 @@ export type ConnectionState =
   | "recording"
   | "processing";
 
+// Mirrors the backend OutputResult returned by type_text.
+export interface OutputResult {
+  status: "success" | "fallback" | "empty" | "paused" | "cancelled";
+  chars: number;
+  mode: string;
+  timing?: Record<string, number>;
+}
+
 interface TypeTextResult {
   success: boolean;
+  output?: OutputResult;
   error?: string;
 }
 
+// Backend commands reject with `{ code, message }`.
+export interface CommandError {
+  code: string;
+  message: string;
+}
+
+// Readable message for a rejected `invoke` (a CommandError, or a plain string).
+export function commandErrorMessage(error: unknown): string {
+  if (error && typeof error === "object" && "message" in error) {
+    return String((error as { message: unknown }).message);
+  }
+  return String(error);
+}
+
 export interface HotkeyConfig {
   modifiers: string[];
   key: string;
@@ -34,6 +57,8 @@ export const HotkeyConfigSchema = z.object({
 interface HistoryEntry {
   id: string;
   timestamp: string;
+  // Insertion order; breaks ties between equal timestamps (newest first).
+  seq?: number;
   text: string;
   status?: "in_progress" | "success" | "error";
   error_message?: string | null;
@@ -41,8 +66,51 @@ interface HistoryEntry {
   stt_model?: string | null;
   llm_provider?: string | null;
   llm_model?: string | null;
+  session_id?: string | null;
+  source_app?: string | null;
+}
+
+// Combined history filter for `queryHistory`; every field is optional (AND semantics).
+export interface HistoryFilter {
+  query?: string;
+  from?: string;
+  to?: string;
+  source_app?: string;
+  offset?: number;
+  limit?: number;
 }
 
+export interface HistoryPage {
+  entries: HistoryEntry[];
+  total: number;
+  offset: number;
+}
+
+// Entries grouped into one dictation session, newest first.
+export interface HistorySession {
+  id: string;
+  started_at: string;
+  ended_at: string;
+  entries: HistoryEntry[];
+}
+
+// Size of history.json on disk, from `getHistoryStorageInfo` / `compactHistory`.
+export interface HistoryStorageInfo {
+  entry_count: number;
+  file_bytes: number;
+  // Backup plus any leftover temp file.
+  backup_bytes: number;
+  average_entry_bytes: number;
+}
+
+// Payload of backend `history-changed` events. Windows re-emit an empty payload to
+// request a plain refetch.
+export type HistoryChange =
+  | { kind: "added"; entry: HistoryEntry }
+  | { kind: "updated"; entry: HistoryEntry }
+  | { kind: "deleted"; ids: string[] }
+  | { kind: "cleared" };
+
 export interface PromptSection {
   enabled: boolean;
   content: string | null;
@@ -95,6 +163,85 @@ export type PlayingAudioHandling = "none" | "mute" | "pause" | "mute_and_pause";
 
 export type AudioCue = "tangerine" | "maraca" | "clave" | "tambourine";
 
+/** A cue theme as returned by the `list_audio_cues` command. */
+export interface AudioCueInfo {
+  id: AudioCue;
+  display_name: string;
+  description: string;
+  start_ms: number;
+  stop_ms: number;
+  requires_user_files: boolean;
+}
+
+/** Why a cue did or didn't play; errors carry a `message`. */
+export type PlaybackOutcome =
+  | {
+      status:
+        | "played"
+        | "sound_disabled"
+        | "suppressed_dnd"
+        | "suppressed_paused"
+        | "debounced"
+        | "no_output_device";
+    }
+  | { status: "decode_error" | "stream_error"; message: string };
+
+/** Result of `diagnoseAudio`: the test cue's outcome and the state that decided it. */
+export interface AudioDiagnosis {
+  outcome: PlaybackOutcome;
+  output_device: string | null;
+  sound_enabled: boolean;
+  respect_system_dnd: boolean;
+  /** null where the platform can't report Do Not Disturb. */
+  system_dnd_active: boolean | null;
+  cue_theme: string | null;
+}
+
+/** An installed user cue theme (`list_cue_themes` / `install_cue_theme`). */
+export interface CueThemeInfo {
+  name: string;
+  /** Sounds the theme provides: "start", "stop" and optionally "error". */
+  sounds: string[];
+  active: boolean;
+}
+
+/** An installed theme folder and why it can't be played, if it can't. */
+export interface CueThemeCheck {
+  name: string;
+  error: string | null;
+  /** A required sound file is missing (only such folders are ever deleted by a prune). */
+  missing_required: boolean;
+}
+
+/** A cue setting that doesn't point at a playable sound. */
+export interface CueSettingIssue {
+  /** Setting key; `output_mode_cues.<mode>` for a per-mode override. */
+  key: string;
+  value: unknown;
+  reason: string;
+}
+
+/** Result of `validateCueConfig`. */
+export interface CueConfigReport {
+  themes: CueThemeCheck[];
+  settings: CueSettingIssue[];
+  healthy: boolean;
+}
+
+/** What `pruneMissingCueFiles` removed and reset. */
+export interface CuePruneReport {
+  removed_themes: string[];
+  /** Unplayable theme folders that were kept (deactivated if active). */
+  broken_themes: CueThemeCheck[];
+  reset_settings: string[];
+}
+
+/** Named snapshot of the cue selection, cue volume and cue output device settings. */
+export interface AudioProfile {
+  name: string;
+  settings: Record<string, unknown>;
+}
+
 export type OverlayMode = "always" | "never" | "recording_only";
 
 export type WidgetPosition =
@@ -493,13 +640,84 @@ export function validateHotkeyNotDuplicate(
 // Tauri API
 // ============================================================================
 
+/** Emitted when the capture stream died mid-recording and couldn't be rebuilt. */
+export interface CaptureFailedPayload {
+  error: string;
+  device: string | null;
+}
+
+/** Transcript so far, emitted while a streaming STT provider is transcribing. */
+export interface PartialTranscriptionPayload {
+  text: string;
+}
+
+/** Emitted when a recording was stopped automatically (e.g. `max_recording_seconds`). */
+export interface RecordingAutoStoppedPayload {
+  reason: "max_duration";
+  max_recording_seconds: number;
+}
+
+// Sent once a second over the last `recording_countdown_seconds` before an auto-stop.
+export interface RecordingCountdownPayload {
+  seconds_remaining: number;
+}
+
+export interface FileOutputResult {
+  path: string;
+  bytes_written: number;
+  appended: boolean;
+}
+
+// Result of `previewTextTransforms`: the sample after the configured transforms, and the
+// setting keys of the transforms that ran, in order.
+export interface TransformPreview {
+  text: string;
+  transforms: string[];
+}
+
+// What the clipboard-only modes last wrote, from `getLastClipboardWrite` (in-memory,
+// cleared on restart; only the latest write is kept).
+export interface LastClipboardWrite {
+  mode: "clipboard" | "clipboard_append";
+  // Full clipboard text after the write (for append, including the earlier text).
+  text: string;
+  chars: number;
+  written_at: string;
+}
+
+// Sent when `confirm_before_output` holds a transcript; answer with `confirmOutput` or
+// `cancelOutput` before `timeout_ms`, or it's cancelled (`output-pending-expired`).
+export interface PendingOutputEvent {
+  token: string;
+  text: string;
+  mode: string;
+  timeout_ms: number;
+}
+
 export const tauriAPI = {
+  async getLastClipboardWrite(): Promise<LastClipboardWrite | null> {
+    return invoke<LastClipboardWrite | null>("get_last_clipboard_write");
+  },
+
+  // `path` must be inside the file_output_dir setting (relative paths resolve against it).
+  async outputTextToFile(
+    text: string,
+    path: string,
+    append = true,
+  ): Promise<FileOutputResult> {
+    return invoke<FileOutputResult>("output_text_to_file", { text, path, append });
+  },
+
+  async previewTextTransforms(sample: string): Promise<TransformPreview> {
+    return invoke<TransformPreview>("preview_text_transforms", { sample });
+  },
+
   async typeText(text: string): Promise<TypeTextResult> {
     try {
-      await invoke("type_text", { text });
-      return { success: true };
+      const output = await invoke<OutputResult>("type_text", { text });
+      return { success: true, output };
     } catch (error) {
-      return { success: false, error: String(error) };
+      return { success: false, error: commandErrorMessage(error) };
     }
   },
 
@@ -511,6 +729,38 @@ export const tauriAPI = {
     return listen("recording-stop", callback);
   },
 
+  async onCaptureFailed(
+    callback: (payload: CaptureFailedPayload) => void
+  ): Promise<UnlistenFn> {
+    return listen<CaptureFailedPayload>("capture-failed", (event) => {
+      callback(event.payload);
+    });
+  },
+
+  async onPartialTranscription(
+    callback: (payload: PartialTranscriptionPayload) => void
+  ): Promise<UnlistenFn> {
+    return listen<PartialTranscriptionPayload>("partial-transcription", (event) => {
+      callback(event.payload);
+    });
+  },
+
+  async onRecordingAutoStopped(
+    callback: (payload: RecordingAutoStoppedPayload) => void
+  ): Promise<UnlistenFn> {
+    return listen<RecordingAutoStoppedPayload>("recording-auto-stopped", (event) => {
+      callback(event.payload);
+    });
+  },
+
+  async onRecordingCountdown(
+    callback: (payload: RecordingCountdownPayload) => void
+  ): Promise<UnlistenFn> {
+    return listen<RecordingCountdownPayload>("recording-countdown", (event) => {
+      callback(event.payload);
+    });
+  },
+
   // Settings API - using store plugin directly
   async getSettings(): Promise<AppSettings> {
     const store = await getStore();
@@ -1026,6 +1276,12 @@ export const tauriAPI = {
     await emit("settings-changed", {});
   },
 
+  /** Follow an app's window (by executable/process name), or `null` for the static position. */
+  async setOverlayFollowApp(appName: string | null): Promise<void> {
+    await invoke("set_overlay_follow_app", { appName });
+    await emit("settings-changed", {});
+  },
+
   async updateOutputMode(mode: OutputMode): Promise<void> {
     const store = await getStore();
     await store.set("output_mode", mode);
@@ -1263,6 +1519,24 @@ export const tauriAPI = {
     return invoke("get_history", { limit });
   },
 
+  async queryHistory(filter: HistoryFilter = {}): Promise<HistoryPage> {
+    return invoke("query_history", { filter });
+  },
+
+  async getHistorySessions(limit?: number): Promise<HistorySession[]> {
+    return invoke("get_history_sessions", { limit });
+  },
+
+  async copyHistoryEntry(id: string): Promise<void> {
+    return invoke("copy_history_entry", { id });
+  },
+
+  // Save one entry's text to a path inside file_output_dir (overwrites), using
+  // file_output_line_ending.
+  async exportHistoryEntry(id: string, path: string): Promise<FileOutputResult> {
+    return invoke<FileOutputResult>("export_history_entry", { id, path });
+  },
+
   async deleteHistoryEntry(id: string): Promise<boolean> {
     return invoke("delete_history_entry", { id });
   },
@@ -1271,16 +1545,140 @@ export const tauriAPI = {
     return invoke("clear_history");
   },
 
+  async getHistoryStorageInfo(): Promise<HistoryStorageInfo> {
+    return invoke("get_history_storage_info");
+  },
+
+  /** Rewrite history.json compactly and drop its backup. */
+  async compactHistory(): Promise<HistoryStorageInfo> {
+    return invoke("compact_history");
+  },
+
   // Overlay API
   async resizeOverlay(width: number, height: number): Promise<void> {
     return invoke("resize_overlay", { width, height });
   },
 
+  /** Rebuild the overlay window if it was closed. Resolves to false if it already existed. */
+  async recreateOverlay(): Promise<boolean> {
+    return invoke<boolean>("recreate_overlay");
+  },
+
   async startDragging(): Promise<void> {
+    // Lets the backend hold back resizes until the drag has settled.
+    invoke("overlay_drag_started").catch(console.error);
     const window = getCurrentWindow();
     return window.startDragging();
   },
 
+  // Plays the start cue as a recording would and reports why it did or didn't play.
+  async diagnoseAudio(): Promise<AudioDiagnosis> {
+    return invoke<AudioDiagnosis>("diagnose_audio");
+  },
+
+  // Reports broken cue theme folders and cue settings that point at nothing playable.
+  async validateCueConfig(): Promise<CueConfigReport> {
+    return invoke<CueConfigReport>("validate_cue_config");
+  },
+
+  // Deletes broken cue themes and reverts the affected cue settings to built-in cues.
+  async pruneMissingCueFiles(): Promise<CuePruneReport> {
+    return invoke<CuePruneReport>("prune_missing_cue_files");
+  },
+
+  async listAudioOutputDevices(): Promise<string[]> {
+    return invoke<string[]>("list_audio_output_devices");
+  },
+
+  // Saves the current cue settings as a named profile, replacing one of the same name.
+  async saveAudioProfile(name: string): Promise<AudioProfile> {
+    return invoke<AudioProfile>("save_audio_profile", { name });
+  },
+
+  // Writes all of a profile's settings at once; nothing changes if any value is invalid.
+  async applyAudioProfile(name: string): Promise<AudioProfile> {
+    return invoke<AudioProfile>("apply_audio_profile", { name });
+  },
+
+  async listAudioProfiles(): Promise<AudioProfile[]> {
+    return invoke<AudioProfile[]>("list_audio_profiles");
+  },
+
+  async onAudioProfileApplied(callback: (profile: AudioProfile) => void): Promise<UnlistenFn> {
+    return listen<AudioProfile>("audio-profile-applied", (event) => {
+      callback(event.payload);
+    });
+  },
+
+  // Mute the mic without stopping capture: a running recording captures silence until
+  // unmuted.
+  async setInputMuted(muted: boolean): Promise<void> {
+    return invoke("set_input_muted", { muted });
+  },
+
+  async isInputMuted(): Promise<boolean> {
+    return invoke<boolean>("is_input_muted");
+  },
+
+  async onInputMuteChanged(callback: (muted: boolean) => void): Promise<UnlistenFn> {
+    return listen<{ muted: boolean }>("input-mute-changed", (event) => {
+      callback(event.payload.muted);
+    });
+  },
+
+  // Trim leading/trailing audio quieter than `thresholdDbfs` (-75..-20) before
+  // transcription, at most `maxTrimMs` per end. Omitted values keep their setting.
+  async setSilenceTrim(
+    enabled: boolean,
+    thresholdDbfs?: number,
+    maxTrimMs?: number,
+  ): Promise<void> {
+    return invoke("set_silence_trim", { enabled, thresholdDbfs, maxTrimMs });
+  },
+
+  // Switch to the next input device (wrapping around); resolves to its name.
+  async cycleInputDevice(): Promise<string> {
+    return invoke<string>("cycle_input_device");
+  },
+
+  async onInputDeviceSelected(callback: (name: string) => void): Promise<UnlistenFn> {
+    return listen<string>("input-device-selected", (event) => {
+      callback(event.payload);
+    });
+  },
+
+  // Double-pressing the toggle hotkey flips the next output to clipboard-only (one-shot).
+  async onOutputTargetChanged(
+    callback: (clipboardOnly: boolean) => void
+  ): Promise<UnlistenFn> {
+    return listen<{ clipboard_only: boolean }>("output-target-changed", (event) => {
+      callback(event.payload.clipboard_only);
+    });
+  },
+
+  async onOutputPending(
+    callback: (pending: PendingOutputEvent) => void
+  ): Promise<UnlistenFn> {
+    return listen<PendingOutputEvent>("output-pending", (event) => {
+      callback(event.payload);
+    });
+  },
+
+  // The pending output with this token timed out and was cancelled.
+  async onOutputPendingExpired(callback: (token: string) => void): Promise<UnlistenFn> {
+    return listen<string>("output-pending-expired", (event) => {
+      callback(event.payload);
+    });
+  },
+
+  async confirmOutput(token: string): Promise<void> {
+    return invoke("confirm_output", { token });
+  },
+
+  async cancelOutput(token: string): Promise<void> {
+    return invoke("cancel_output", { token });
+  },
+
   // Connection state sync between windows
   async emitConnectionState(state: ConnectionState): Promise<void> {
     return emit("connection-state-changed", { state });
@@ -1302,10 +1700,15 @@ export const tauriAPI = {
     return emit("history-changed", {});
   },
 
-  async onHistoryChanged(callback: () => void): Promise<UnlistenFn> {
-    return listen("history-changed", () => {
-      callback();
-    });
+  async onHistoryChanged(
+    callback: (change: HistoryChange | null) => void
+  ): Promise<UnlistenFn> {
+    return listen<HistoryChange | Record<string, never>>(
+      "history-changed",
+      (event) => {
+        callback("kind" in event.payload ? event.payload : null);
+      }
+    );
   },
 
   // Settings sync between windows (main -> overlay)
@@ -1322,6 +1725,19 @@ export const tauriAPI = {
       callback(event.payload);
     });
   },
+
+  // Reset settings to defaults (all but saved collections when `keys` is omitted);
+  // resolves to the keys that were reset.
+  async resetSettingsToDefaults(keys?: string[]): Promise<string[]> {
+    return invoke<string[]>("reset_settings_to_defaults", { keys });
+  },
+
+  // Emitted by the backend after a reset, with the keys that were reset.
+  async onSettingsReset(callback: (keys: string[]) => void): Promise<UnlistenFn> {
+    return listen<string[]>("settings-reset", (event) => {
+      callback(event.payload);
+    });
+  },
 };
 
 export interface OpenWindowInfo {
@@ -1391,6 +1807,9 @@ export const sttAPI = {
 
   hasLastAudio: () => invoke<boolean>("pipeline_has_last_audio"),
 
+  measureTranscriptionLatency: () =>
+    invoke<TranscriptionLatency>("measure_transcription_latency"),
+
   getLastRecordingDiagnostics: () =>
     invoke<AudioCaptureDiagnostics | null>(
       "pipeline_get_last_recording_diagnostics"
@@ -1404,12 +1823,57 @@ export const sttAPI = {
     }),
 };
 
+// Coarse pipeline state: transcribing includes the optional LLM rewrite.
+export type RecordingState = "idle" | "recording" | "transcribing";
+
+export const recordingStateAPI = {
+  get: () => invoke<RecordingState>("get_recording_state"),
+
+  // Emitted on every transition.
+  onChanged: (callback: (state: RecordingState) => void): Promise<UnlistenFn> =>
+    listen<RecordingState>("recording-state-changed", (event) => {
+      callback(event.payload);
+    }),
+};
+
+// Payload of the app-wide `recording-level` event, sent while recording (up to ~60/s)
+// after `setLevelMeterEnabled(true)`, whether or not the overlay is visible. A final
+// frame with `recording: false` and zero levels follows the end of a recording.
+export interface RecordingLevelEvent {
+  // Increments per captured audio chunk.
+  seq: number;
+  // Amplitudes of the latest chunk, in [0, 1].
+  rms: number;
+  peak: number;
+  recording: boolean;
+}
+
+export const levelMeterAPI = {
+  setEnabled: (enabled: boolean) =>
+    invoke<void>("set_level_meter_enabled", { enabled }),
+
+  onLevel: (callback: (level: RecordingLevelEvent) => void): Promise<UnlistenFn> =>
+    listen<RecordingLevelEvent>("recording-level", (event) => {
+      callback(event.payload);
+    }),
+};
+
 export interface AudioLevelStats {
   duration_secs: number;
   rms: number;
   peak: number;
 }
 
+export interface TranscriptionLatency {
+  provider: string;
+  model: string | null;
+  used_last_audio: boolean;
+  audio_ms: number;
+  audio_bytes: number;
+  total_ms: number;
+  text_chars: number;
+}
+
 export interface AudioCaptureDiagnostics {
   stats: AudioLevelStats;
   // null when speech detection wasn't computed for the last recording.
@@ -1448,6 +1912,29 @@ interface AvailableProvidersResponse {
   llm: ProviderInfo[];
 }
 
+export interface BackendInfo {
+  version: string;
+  platform: string;
+  arch: string;
+  desktop: boolean;
+  features: string[];
+  output_modes: string[];
+  partial_transcription: boolean;
+}
+
+/** Privacy-safe snapshot of the effective configuration, for bug reports. */
+export interface DiagnosticsSnapshot {
+  backend: BackendInfo;
+  settings: Record<string, unknown>;
+  output_mode_overrides: number;
+  default_input_device: string | null;
+  default_output_device: string | null;
+  server_host: string | null;
+  proxy_host: string | null;
+  accessibility: "granted" | "denied";
+  audio_output_available: boolean;
+}
+
 export const configAPI = {
   // Default prompt sections (from Tauri)
   getDefaultSections: () =>
@@ -1457,6 +1944,12 @@ export const configAPI = {
   getAvailableProviders: () =>
     invoke<AvailableProvidersResponse>("get_available_providers"),
 
+  // Backend version, platform and supported features (handshake)
+  getBackendInfo: () => invoke<BackendInfo>("get_backend_info"),
+
+  // Effective settings, devices and permission status to attach to bug reports
+  dumpDiagnostics: () => invoke<DiagnosticsSnapshot>("dump_diagnostics"),
+
   // Sync pipeline config when settings change
   syncPipelineConfig: () => invoke<void>("sync_pipeline_config"),
 };
@@ -1504,11 +1997,21 @@ export interface RecordingsStats {
   bytes: number;
 }
 
+export interface RecentLogLine {
+  timestamp: string;
+  level: "error" | "warn" | "info" | "debug" | "trace";
+  target: string;
+  message: string;
+}
+
 export const logsAPI = {
   getRequestLogs: (limit?: number) =>
     invoke<RequestLog[]>("get_request_logs", { limit: limit ?? 100 }),
 
   clearRequestLogs: () => invoke<void>("clear_request_logs"),
+
+  getRecentLogs: (level?: string) =>
+    invoke<RecentLogLine[]>("get_recent_logs", { level: level ?? null }),
 };
 
 // ============================================================================